@@ -1,11 +1,16 @@
 use bevy::prelude::*;
 use common::structs::{AudioDecoderError, AudioSettings, PrimaryCamera, PrimaryUser};
-use comms::global_crdt::ForeignAudioSource;
+use comms::{
+    global_crdt::{BlockedPeers, ForeignAudioSource, ForeignPlayer},
+    Transport,
+};
 use kira::{manager::backend::DefaultBackend, sound::streaming::StreamingSoundData, tween::Tween};
 use scene_runner::{ContainingScene, SceneEntity};
 use tokio::sync::mpsc::error::TryRecvError;
 
-use crate::stream_processor::AVCommand;
+use crate::{
+    stream_processor::AVCommand, voice_attenuation::VoiceAttenuation, voice_gain::VoiceGains,
+};
 
 #[derive(Component)]
 pub struct AudioSink {
@@ -101,19 +106,25 @@ pub fn spawn_audio_streams(
     }
 }
 
-const MAX_CHAT_DISTANCE: f32 = 25.0;
-
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_and_locate_foreign_streams(
     mut commands: Commands,
     mut streams: Query<(
         Entity,
         &GlobalTransform,
+        &ForeignPlayer,
         &mut ForeignAudioSource,
         Option<&mut AudioSpawned>,
     )>,
     mut audio_manager: NonSendMut<bevy_kira_audio::audio_output::AudioOutput<DefaultBackend>>,
     receiver: Query<&GlobalTransform, With<PrimaryCamera>>,
+    listener: Query<&GlobalTransform, With<PrimaryUser>>,
+    transports: Query<(), With<Transport>>,
+    containing_scene: ContainingScene,
     settings: Res<AudioSettings>,
+    attenuation: Res<VoiceAttenuation>,
+    gains: Res<VoiceGains>,
+    blocked: Res<BlockedPeers>,
 ) {
     if audio_manager.manager.is_none() {
         return;
@@ -122,8 +133,14 @@ pub fn spawn_and_locate_foreign_streams(
     let Ok(receiver_transform) = receiver.get_single() else {
         return;
     };
+    // distance is measured from the player, panning from the camera
+    let listener_position = listener
+        .get_single()
+        .map(GlobalTransform::translation)
+        .unwrap_or_else(|_| receiver_transform.translation());
+    let listener_scene = containing_scene.get_parcel_position(listener_position);
 
-    for (ent, emitter_transform, mut stream, mut maybe_spawned) in streams.iter_mut() {
+    for (ent, emitter_transform, player, mut stream, mut maybe_spawned) in streams.iter_mut() {
         match stream.0.try_recv() {
             Ok(sound_data) => {
                 info!("{ent:?} received foreign sound data!");
@@ -140,10 +157,8 @@ pub fn spawn_and_locate_foreign_streams(
         }
 
         if let Some(handle) = maybe_spawned.as_mut().and_then(|a| a.0.as_mut()) {
-            let sound_path = emitter_transform.translation() - receiver_transform.translation();
-            let volume = (1. - sound_path.length() / MAX_CHAT_DISTANCE)
-                .clamp(0., 1.)
-                .powi(2);
+            let emitter_position = emitter_transform.translation();
+            let sound_path = emitter_position - receiver_transform.translation();
 
             let panning = if sound_path.length() > f32::EPSILON {
                 let right_ear_angle = receiver_transform.right().angle_between(sound_path);
@@ -152,7 +167,19 @@ pub fn spawn_and_locate_foreign_streams(
                 0.5
             };
 
-            let volume = volume * settings.voice();
+            // peers whose transport has closed are no longer on our island
+            let audible = !blocked.0.contains(&player.address)
+                && transports.contains(player.transport_id)
+                && (!attenuation.same_scene_only
+                    || containing_scene.get_parcel_position(emitter_position) == listener_scene);
+
+            let volume = if audible {
+                attenuation.gain_between(listener_position, emitter_position)
+                    * settings.voice()
+                    * gains.gain(&player.address)
+            } else {
+                0.0
+            };
 
             let _ = handle.set_volume(volume as f64, Tween::default());
             let _ = handle.set_panning(panning as f64, Tween::default());
@@ -4,7 +4,9 @@ use bevy::{
     prelude::*,
     utils::{HashMap, HashSet},
 };
-use bevy_kira_audio::{prelude::AudioEmitter, AudioControl, AudioInstance, AudioTween};
+use bevy_kira_audio::{
+    prelude::AudioEmitter, AudioControl, AudioInstance, AudioTween, PlaybackState,
+};
 use common::{
     sets::SetupSets,
     structs::{AudioSettings, PrimaryCameraRes, PrimaryUser, SystemAudio},
@@ -14,8 +16,10 @@ use dcl::interface::ComponentPosition;
 use dcl_component::{proto_components::sdk::components::PbAudioSource, SceneComponentId};
 use ipfs::IpfsAssetServer;
 use scene_runner::{
-    renderer_context::RendererSceneContext, update_world::AddCrdtInterfaceExt, ContainingScene,
-    SceneEntity,
+    features::{SceneFeature, SceneFeatures},
+    renderer_context::RendererSceneContext,
+    update_world::AddCrdtInterfaceExt,
+    ContainingScene, SceneEntity,
 };
 
 #[derive(Component, Debug)]
@@ -55,20 +59,38 @@ pub struct AudioSourceState {
     clip_url: String,
 }
 
+// what happens to a source when scene audio is switched back on. sources paused part way
+// through carry on and loops restart, but one-shots that already finished are not replayed
+#[derive(Debug, PartialEq, Eq)]
+pub enum ResumeAudio {
+    Resume,
+    Restart,
+    Skip,
+}
+
+pub fn resume_audio(source: &PbAudioSource, paused: bool) -> ResumeAudio {
+    if !source.playing() {
+        ResumeAudio::Skip
+    } else if paused {
+        ResumeAudio::Resume
+    } else if source.r#loop() {
+        ResumeAudio::Restart
+    } else {
+        ResumeAudio::Skip
+    }
+}
+
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn update_audio(
     mut commands: Commands,
-    mut query: Query<
-        (
-            Entity,
-            &SceneEntity,
-            &AudioSource,
-            Option<&mut AudioSourceState>,
-            Option<&mut AudioEmitter>,
-            &GlobalTransform,
-        ),
-        Changed<AudioSource>,
-    >,
+    mut query: Query<(
+        Entity,
+        &SceneEntity,
+        Ref<AudioSource>,
+        Option<&mut AudioSourceState>,
+        Option<&mut AudioEmitter>,
+        &GlobalTransform,
+    )>,
     scenes: Query<&RendererSceneContext>,
     audio: Res<bevy_kira_audio::Audio>,
     ipfas: IpfsAssetServer,
@@ -77,7 +99,13 @@ fn update_audio(
     player: Query<Entity, With<PrimaryUser>>,
     cam: Query<&GlobalTransform, With<AudioReceiver>>,
     settings: Res<AudioSettings>,
+    mut features: SceneFeatures,
+    mut prev_enabled: Local<Option<bool>>,
 ) {
+    // all sources are updated when the feature is switched on or off
+    let enabled = features.enabled(SceneFeature::SceneAudio);
+    let toggled = prev_enabled.replace(enabled) != Some(enabled);
+
     let current_scenes = player
         .get_single()
         .ok()
@@ -87,6 +115,48 @@ fn update_audio(
     let gt = cam.get_single().unwrap_or(&GlobalTransform::IDENTITY);
 
     for (ent, scene_ent, audio_source, maybe_source, maybe_emitter, egt) in query.iter_mut() {
+        if !audio_source.is_changed() && !toggled {
+            continue;
+        }
+
+        if !features.allowed(scene_ent.root, SceneFeature::SceneAudio) {
+            // paused rather than stopped, so they can carry on when audio is switched back on
+            if let Some(emitter) = maybe_emitter.as_ref() {
+                for h_instance in emitter.instances.iter() {
+                    if let Some(instance) = audio_instances.get_mut(h_instance) {
+                        instance.pause(AudioTween::default());
+                    }
+                }
+            }
+            continue;
+        }
+
+        // switched back on, without a change from the scene
+        if !audio_source.is_changed() {
+            let paused = maybe_emitter.as_ref().is_some_and(|emitter| {
+                emitter.instances.iter().any(|h_instance| {
+                    audio_instances.get(h_instance).is_some_and(|instance| {
+                        matches!(
+                            instance.state(),
+                            PlaybackState::Paused { .. } | PlaybackState::Pausing { .. }
+                        )
+                    })
+                })
+            });
+            match resume_audio(&audio_source.0, paused) {
+                ResumeAudio::Resume => {
+                    for h_instance in maybe_emitter.iter().flat_map(|e| e.instances.iter()) {
+                        if let Some(instance) = audio_instances.get_mut(h_instance) {
+                            instance.resume(AudioTween::default());
+                        }
+                    }
+                    continue;
+                }
+                ResumeAudio::Restart => (),
+                ResumeAudio::Skip => continue,
+            }
+        }
+
         let mut new_state = None;
         // preload clips
         let state = match maybe_source {
@@ -145,11 +215,7 @@ fn update_audio(
                 .and_then(|emitter| emitter.instances.first())
                 .and_then(|h_instance| {
                     let instance = audio_instances.get_mut(h_instance)?;
-                    matches!(
-                        instance.state(),
-                        bevy_kira_audio::PlaybackState::Playing { .. }
-                    )
-                    .then_some(instance)
+                    matches!(instance.state(), PlaybackState::Playing { .. }).then_some(instance)
                 });
 
             match maybe_playing_instance {
@@ -16,6 +16,8 @@ pub mod video_context;
 pub mod video_player;
 #[cfg(feature = "ffmpeg")]
 pub mod video_stream;
+pub mod voice_attenuation;
+pub mod voice_gain;
 
 #[cfg(feature = "ffmpeg")]
 use audio_sink::{spawn_and_locate_foreign_streams, spawn_audio_streams};
@@ -24,6 +26,8 @@ use bevy::prelude::*;
 use microphone::MicPlugin;
 #[cfg(feature = "ffmpeg")]
 use video_player::VideoPlayerPlugin;
+use voice_attenuation::VoiceAttenuation;
+use voice_gain::VoiceGainPlugin;
 
 pub struct AudioPlugin;
 
@@ -34,6 +38,8 @@ impl Plugin for AudioPlugin {
         app.add_plugins(VideoPlayerPlugin);
         app.add_plugins(MicPlugin);
         app.add_plugins(AudioSourcePlugin);
+        app.add_plugins(VoiceGainPlugin);
+        app.init_resource::<VoiceAttenuation>();
         #[cfg(feature = "ffmpeg")]
         app.add_systems(
             PostUpdate,
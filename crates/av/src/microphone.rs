@@ -1,13 +1,20 @@
 use bevy::prelude::*;
+use bevy_console::ConsoleCommand;
+use common::structs::{AppConfig, MicrophoneSetting, VoiceChatSetting};
 use comms::global_crdt::{LocalAudioFrame, LocalAudioSource};
+use console::DoAddConsoleCommand;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 
 pub struct MicPlugin;
 
+// the mic starts off. `enabled` is the ui / push-to-talk toggle, `capturing` is whether the
+// device is actually open and recording, which also requires the microphone and voice chat
+// settings to be enabled
 #[derive(Resource, Default)]
 pub struct MicState {
     pub available: bool,
     pub enabled: bool,
+    pub capturing: bool,
 }
 
 impl Plugin for MicPlugin {
@@ -15,6 +22,7 @@ impl Plugin for MicPlugin {
         app.init_non_send_resource::<MicStream>();
         app.init_resource::<MicState>();
         app.add_systems(Update, update_mic);
+        app.add_console_command::<CommsCommand, _>(comms_command);
     }
 }
 
@@ -26,21 +34,27 @@ pub fn update_mic(
     mut last_name: Local<String>,
     mut stream: NonSendMut<MicStream>,
     mut mic_state: ResMut<MicState>,
+    config: Res<AppConfig>,
 ) {
+    // the mic isn't opened at all while hard-muted or voice chat is disabled
+    let enabled = mic_state.enabled
+        && config.audio.microphone == MicrophoneSetting::Enabled
+        && config.network.voice_chat == VoiceChatSetting::Enabled;
+
     let default_host = cpal::default_host();
     let default_input = default_host.default_input_device();
     if let Some(input) = default_input {
         if let Ok(name) = input.name() {
             mic_state.available = true;
 
-            if name == *last_name && mic_state.enabled {
+            if name == *last_name && enabled {
                 return;
             }
 
             // drop old stream
-            stream.0 = None;
+            stop_capture(&mut stream, &mic, &mut mic_state);
 
-            if !mic_state.enabled {
+            if !enabled {
                 "disabled".clone_into(&mut last_name);
                 return;
             }
@@ -53,6 +67,10 @@ pub fn update_mic(
                 .build_input_stream(
                     &config.into(),
                     move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                        // empty frames mark the end of capture
+                        if data.is_empty() {
+                            return;
+                        }
                         if sender
                             .send(LocalAudioFrame {
                                 data: data.to_owned(),
@@ -74,6 +92,7 @@ pub fn update_mic(
             match new_stream.play() {
                 Ok(()) => {
                     stream.0 = Some(new_stream);
+                    mic_state.capturing = true;
                     info!("set mic to {name}");
                     *last_name = name;
                 }
@@ -87,7 +106,59 @@ pub fn update_mic(
     }
 
     // faild to find input - drop old stream
-    stream.0 = None;
+    stop_capture(&mut stream, &mic, &mut mic_state);
     "no device".clone_into(&mut last_name);
     mic_state.available = false;
 }
+
+fn stop_capture(stream: &mut MicStream, mic: &LocalAudioSource, mic_state: &mut MicState) {
+    if stream.0.take().is_some() {
+        let _ = mic.sender.send(LocalAudioFrame::end_of_capture());
+    }
+    mic_state.capturing = false;
+}
+
+/// comms options. `voice off` keeps chat and positions but stops sending and receiving voice
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/comms")]
+struct CommsCommand {
+    #[command(subcommand)]
+    option: CommsOption,
+}
+
+#[derive(clap::Subcommand)]
+enum CommsOption {
+    /// enable or disable voice chat, or show the current state
+    Voice { state: Option<OnOff> },
+}
+
+#[derive(clap::ValueEnum, Clone, Copy)]
+enum OnOff {
+    On,
+    Off,
+}
+
+fn comms_command(mut input: ConsoleCommand<CommsCommand>, mut config: ResMut<AppConfig>) {
+    if let Some(Ok(command)) = input.take() {
+        match command.option {
+            CommsOption::Voice { state } => {
+                let setting = match state {
+                    Some(OnOff::On) => VoiceChatSetting::Enabled,
+                    Some(OnOff::Off) => VoiceChatSetting::Disabled,
+                    None => config.network.voice_chat,
+                };
+                // leave the config unchanged (and unsaved) when the state is the same
+                if setting != config.network.voice_chat {
+                    config.network.voice_chat = setting;
+                }
+                input.reply_ok(format!(
+                    "voice chat {}",
+                    match setting {
+                        VoiceChatSetting::Enabled => "enabled",
+                        VoiceChatSetting::Disabled => "disabled",
+                    }
+                ));
+            }
+        }
+    }
+}
@@ -11,3 +11,69 @@ fn test_ffmpeg() {
     let (sx, _rx) = tokio::sync::mpsc::channel(1);
     VideoContext::init(&context, sx).unwrap();
 }
+
+#[test]
+fn test_voice_attenuation() {
+    use crate::voice_attenuation::{VoiceAttenuation, VoiceRolloff};
+    use bevy::math::Vec3;
+
+    let listener = Vec3::ZERO;
+    let near = Vec3::new(3.0, 0.0, 4.0);
+    let far = Vec3::new(0.0, 0.0, 20.0);
+    let beyond = Vec3::new(30.0, 0.0, 0.0);
+
+    for rolloff in [
+        VoiceRolloff::Linear,
+        VoiceRolloff::Quadratic,
+        VoiceRolloff::Inverse,
+    ] {
+        let attenuation = VoiceAttenuation {
+            rolloff,
+            ..Default::default()
+        };
+        let near_gain = attenuation.gain_between(listener, near);
+        let far_gain = attenuation.gain_between(listener, far);
+        assert!(near_gain > far_gain, "{rolloff:?}");
+        assert!(far_gain > 0.0, "{rolloff:?}");
+        assert_eq!(attenuation.gain_between(listener, beyond), 0.0);
+        assert_eq!(attenuation.gain_between(listener, listener), 1.0);
+    }
+
+    // full volume inside the minimum distance
+    let attenuation = VoiceAttenuation {
+        min_distance: 10.0,
+        ..Default::default()
+    };
+    assert_eq!(attenuation.gain_between(listener, near), 1.0);
+    assert!(attenuation.gain_between(listener, far) < 1.0);
+}
+
+#[test]
+fn test_resume_audio() {
+    use crate::audio_source::{resume_audio, ResumeAudio};
+    use dcl_component::proto_components::sdk::components::PbAudioSource;
+
+    let one_shot = PbAudioSource {
+        playing: Some(true),
+        ..Default::default()
+    };
+    let looped = PbAudioSource {
+        r#loop: Some(true),
+        ..one_shot.clone()
+    };
+
+    // a finished one-shot is not replayed, one paused part way through carries on
+    assert_eq!(resume_audio(&one_shot, false), ResumeAudio::Skip);
+    assert_eq!(resume_audio(&one_shot, true), ResumeAudio::Resume);
+
+    // loops carry on, or restart if they were never started
+    assert_eq!(resume_audio(&looped, true), ResumeAudio::Resume);
+    assert_eq!(resume_audio(&looped, false), ResumeAudio::Restart);
+
+    // stopped sources stay stopped
+    let stopped = PbAudioSource {
+        playing: Some(false),
+        ..looped
+    };
+    assert_eq!(resume_audio(&stopped, true), ResumeAudio::Skip);
+}
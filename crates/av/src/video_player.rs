@@ -1,4 +1,5 @@
 use crate::{
+    audio_sink::AudioSink,
     stream_processor::AVCommand,
     video_context::{VideoData, VideoInfo},
     video_stream::{av_sinks, VideoSink},
@@ -12,7 +13,7 @@ use bevy::{
         render_asset::RenderAssetUsages,
         render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
     },
-    utils::HashMap,
+    utils::{HashMap, HashSet},
 };
 use common::{
     sets::SceneSets,
@@ -25,6 +26,7 @@ use dcl_component::{
 };
 use ipfs::IpfsResource;
 use scene_runner::{
+    features::{SceneFeature, SceneFeatures},
     renderer_context::RendererSceneContext,
     update_world::{material::VideoTextureOutput, AddCrdtInterfaceExt},
     ContainerEntity, ContainingScene,
@@ -52,11 +54,26 @@ impl Plugin for VideoPlayerPlugin {
 pub struct AVPlayer {
     // note we reuse PbVideoPlayer for audio as well
     pub source: PbVideoPlayer,
+    // false for audio streams
+    pub video: bool,
+}
+
+impl AVPlayer {
+    pub fn feature(&self) -> SceneFeature {
+        if self.video {
+            SceneFeature::Video
+        } else {
+            SceneFeature::SceneAudio
+        }
+    }
 }
 
 impl From<PbVideoPlayer> for AVPlayer {
     fn from(value: PbVideoPlayer) -> Self {
-        Self { source: value }
+        Self {
+            source: value,
+            video: true,
+        }
     }
 }
 
@@ -69,10 +86,27 @@ impl From<PbAudioStream> for AVPlayer {
                 volume: value.volume,
                 ..Default::default()
             },
+            video: false,
         }
     }
 }
 
+fn video_image(color: Srgba) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: 8,
+            height: 8,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &color.to_u8_array(),
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::all(),
+    );
+    image.texture_descriptor.usage = TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING;
+    image
+}
+
 fn init_ffmpeg() {
     ffmpeg_next::init().unwrap();
     ffmpeg_next::log::set_level(ffmpeg_next::log::Level::Error);
@@ -115,11 +149,11 @@ fn play_videos(
 
         if let Some(frame) = last_frame_received {
             debug!("set frame on {:?}", sink.image);
-            images
-                .get_mut(&sink.image)
-                .unwrap()
-                .data
-                .copy_from_slice(frame.data(0));
+            let image = images.get_mut(&sink.image).unwrap();
+            // the image is replaced with a placeholder when video is disabled
+            if image.data.len() == frame.data(0).len() {
+                image.data.copy_from_slice(frame.data(0));
+            }
         }
 
         const VIDEO_REPORT_FREQUENCY: f64 = 1.0;
@@ -170,28 +204,38 @@ pub fn update_video_players(
     mut system_paused: Local<HashMap<Entity, Option<tokio::sync::mpsc::Sender<AVCommand>>>>,
     containing_scene: ContainingScene,
     user: Query<&GlobalTransform, With<PrimaryUser>>,
+    mut features: SceneFeatures,
 ) {
     let mut previously_stopped = std::mem::take(&mut *system_paused);
+    let mut disabled = HashSet::default();
 
     for (ent, container, player, maybe_sink, maybe_texture, _) in video_players.iter() {
-        if maybe_sink.map(|sink| &sink.source) != Some(&player.source.src) {
-            let image_handle = match maybe_texture {
+        if !features.allowed(container.root, player.feature()) {
+            // stop the decoder and show a placeholder until the feature is enabled again
+            disabled.insert(ent);
+            previously_stopped.remove(&ent);
+            if let Some(sink) = maybe_sink {
+                let _ = sink.command_sender.try_send(AVCommand::Dispose);
+                commands.entity(ent).remove::<(VideoSink, AudioSink)>();
+            }
+            let placeholder = video_image(basic::GRAY);
+            match maybe_texture {
+                Some(texture) if maybe_sink.is_some() => {
+                    images.insert(texture.0.id(), placeholder);
+                }
+                Some(_) => (),
                 None => {
-                    let mut image = Image::new_fill(
-                        bevy::render::render_resource::Extent3d {
-                            width: 8,
-                            height: 8,
-                            depth_or_array_layers: 1,
-                        },
-                        TextureDimension::D2,
-                        &basic::FUCHSIA.to_u8_array(),
-                        TextureFormat::Rgba8UnormSrgb,
-                        RenderAssetUsages::all(),
-                    );
-                    image.texture_descriptor.usage =
-                        TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING;
-                    images.add(image)
+                    commands
+                        .entity(ent)
+                        .try_insert(VideoTextureOutput(images.add(placeholder)));
                 }
+            }
+            continue;
+        }
+
+        if maybe_sink.map(|sink| &sink.source) != Some(&player.source.src) {
+            let image_handle = match maybe_texture {
+                None => images.add(video_image(basic::FUCHSIA)),
                 Some(texture) => texture.0.clone(),
             };
 
@@ -244,7 +288,7 @@ pub fn update_video_players(
     let mut sorted_players = video_players
         .iter()
         .filter_map(|(ent, container, player, _, _, transform)| {
-            if player.source.playing.unwrap_or(true) {
+            if player.source.playing.unwrap_or(true) && !disabled.contains(&ent) {
                 let in_scene = containing_scenes.contains(&container.root);
                 let distance = transform.translation().distance(user.translation());
                 Some((in_scene, distance, ent))
@@ -0,0 +1,57 @@
+// distance attenuation for other players' voices, recomputed every frame from the primary
+// user to each speaker in `audio_sink::spawn_and_locate_foreign_streams`. voices from peers
+// whose transport has gone (an island we left) are muted, and optionally so are peers in a
+// different scene.
+
+use bevy::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VoiceRolloff {
+    Linear,
+    Quadratic,
+    // falls quickly close up, then tails off
+    Inverse,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct VoiceAttenuation {
+    // full volume within this distance
+    pub min_distance: f32,
+    // silent from this distance
+    pub max_distance: f32,
+    pub rolloff: VoiceRolloff,
+    // mute players outside the listener's scene
+    pub same_scene_only: bool,
+}
+
+impl Default for VoiceAttenuation {
+    fn default() -> Self {
+        Self {
+            min_distance: 0.0,
+            max_distance: 25.0,
+            rolloff: VoiceRolloff::Quadratic,
+            same_scene_only: false,
+        }
+    }
+}
+
+impl VoiceAttenuation {
+    // gain from 0 to 1 for a speaker at `distance`
+    pub fn gain(&self, distance: f32) -> f32 {
+        if distance >= self.max_distance {
+            return 0.0;
+        }
+
+        let range = (self.max_distance - self.min_distance).max(f32::EPSILON);
+        let falloff = ((distance - self.min_distance) / range).clamp(0.0, 1.0);
+        match self.rolloff {
+            VoiceRolloff::Linear => 1.0 - falloff,
+            VoiceRolloff::Quadratic => (1.0 - falloff).powi(2),
+            VoiceRolloff::Inverse => (1.0 - falloff) / (1.0 + 4.0 * falloff),
+        }
+    }
+
+    pub fn gain_between(&self, listener: Vec3, speaker: Vec3) -> f32 {
+        self.gain(listener.distance(speaker))
+    }
+}
@@ -0,0 +1,125 @@
+// per-player voice gain, applied on top of distance attenuation and the global voice volume.
+// gains set with `/voice` last for the session unless `--save` is given, in which case they
+// are stored in the audio settings and restored on startup.
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_console::ConsoleCommand;
+use common::{structs::AppConfig, util::AsH160};
+use console::DoAddConsoleCommand;
+use ethers_core::types::Address;
+
+const MAX_VOICE_GAIN: u32 = 200;
+
+pub struct VoiceGainPlugin;
+
+impl Plugin for VoiceGainPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VoiceGains>();
+        app.add_systems(Startup, load_voice_gains);
+        app.add_console_command::<VoiceCommand, _>(voice_command);
+    }
+}
+
+// voice gain in percent by player address. unknown addresses play at 100%
+#[derive(Resource, Default, Debug)]
+pub struct VoiceGains(pub HashMap<Address, u32>);
+
+impl VoiceGains {
+    pub fn percent(&self, address: &Address) -> u32 {
+        self.0.get(address).copied().unwrap_or(100)
+    }
+
+    pub fn gain(&self, address: &Address) -> f32 {
+        self.percent(address) as f32 / 100.0
+    }
+}
+
+fn load_voice_gains(config: Res<AppConfig>, mut gains: ResMut<VoiceGains>) {
+    for (address, percent) in config.audio.voice_gains.iter() {
+        let Some(h160) = address.as_h160() else {
+            warn!("ignoring saved voice gain for invalid address `{address}`");
+            continue;
+        };
+        gains.0.insert(h160, (*percent).min(MAX_VOICE_GAIN));
+    }
+}
+
+/// set the voice volume of a player in percent (0-200), or reset it with `--reset`
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/voice")]
+struct VoiceCommand {
+    address: Option<String>,
+    percent: Option<u32>,
+    /// reset the given address, or all addresses if none is given
+    #[arg(long)]
+    reset: bool,
+    /// persist the change across sessions
+    #[arg(long)]
+    save: bool,
+}
+
+fn voice_command(
+    mut input: ConsoleCommand<VoiceCommand>,
+    mut gains: ResMut<VoiceGains>,
+    mut config: ResMut<AppConfig>,
+) {
+    if let Some(Ok(command)) = input.take() {
+        let address = match command.address.as_deref() {
+            Some(address) => {
+                let Some(h160) = address.as_h160() else {
+                    input.reply_failed(format!("invalid address `{address}`"));
+                    return;
+                };
+                Some(h160)
+            }
+            None => None,
+        };
+
+        match (address, command.percent) {
+            (None, _) if command.reset => {
+                gains.0.clear();
+                if command.save {
+                    config.audio.voice_gains.clear();
+                }
+                input.reply_ok("reset all voice volumes");
+            }
+            (Some(address), _) if command.reset => {
+                gains.0.remove(&address);
+                if command.save {
+                    config.audio.voice_gains.remove(&format!("{address:#x}"));
+                }
+                input.reply_ok(format!("reset voice volume for {address:#x}"));
+            }
+            (Some(address), Some(percent)) => {
+                let percent = percent.min(MAX_VOICE_GAIN);
+                gains.0.insert(address, percent);
+                if command.save {
+                    config
+                        .audio
+                        .voice_gains
+                        .insert(format!("{address:#x}"), percent);
+                }
+                input.reply_ok(format!("voice volume for {address:#x} set to {percent}%"));
+            }
+            (Some(address), None) => {
+                input.reply_ok(format!(
+                    "voice volume for {address:#x} is {}%",
+                    gains.percent(&address)
+                ));
+            }
+            (None, _) => {
+                if gains.0.is_empty() {
+                    input.reply_ok("no voice volumes set");
+                    return;
+                }
+                let mut lines = gains
+                    .0
+                    .iter()
+                    .map(|(address, percent)| format!("{address:#x}: {percent}%"))
+                    .collect::<Vec<_>>();
+                lines.sort();
+                input.reply_ok(lines.join("\n"));
+            }
+        }
+    }
+}
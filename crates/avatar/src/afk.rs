@@ -0,0 +1,239 @@
+// marks the player as away after `afk.timeout_secs` without any input. while away the player
+// plays a looping emote, peers are told with a chat marker and show it on the player's name
+// tag, and the app runs at the background frame rate if `afk.throttle` is set. any input
+// brings the player back immediately.
+
+use bevy::{
+    input::mouse::{MouseMotion, MouseWheel},
+    prelude::*,
+};
+use common::structs::{AfkState, AppConfig};
+use comms::{chat_marker_things, global_crdt::ChatEvent, NetworkMessage, Transport};
+use dcl_component::proto_components::kernel::comms::rfc4;
+
+use crate::{animate::PlayerEmote, Nametag};
+
+// the closest default emote to an away pose
+pub const AFK_EMOTE: &str = "dontsee";
+// the away status is repeated for peers that arrive later
+const REBROADCAST_SECONDS: f32 = 30.0;
+
+pub struct AfkPlugin;
+
+impl Plugin for AfkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AfkState>();
+        app.init_resource::<AfkTimer>();
+        app.add_systems(
+            Update,
+            (
+                (detect_afk, broadcast_afk).chain(),
+                receive_afk,
+                update_afk_nametags,
+            ),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct AfkTimer {
+    idle: f32,
+    afk: bool,
+}
+
+impl AfkTimer {
+    // returns the new state when it changes. a timeout of 0 never goes away
+    pub fn update(&mut self, active: bool, delta: f32, timeout: f32) -> Option<bool> {
+        if active {
+            self.idle = 0.0;
+        } else {
+            self.idle += delta;
+        }
+        let afk = timeout > 0.0 && self.idle >= timeout;
+        (afk != self.afk).then(|| {
+            self.afk = afk;
+            afk
+        })
+    }
+}
+
+// a foreign player that is away
+#[derive(Component)]
+pub struct Afk;
+
+#[allow(clippy::too_many_arguments)]
+fn detect_afk(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    time: Res<Time>,
+    config: Res<AppConfig>,
+    mut timer: ResMut<AfkTimer>,
+    mut state: ResMut<AfkState>,
+    mut emote: PlayerEmote,
+) {
+    // read both, so old events don't count next frame
+    let moved = motion.read().count() > 0;
+    let scrolled = wheel.read().count() > 0;
+    let active = moved
+        || scrolled
+        || keys.get_pressed().next().is_some()
+        || mouse.get_pressed().next().is_some();
+
+    let Some(afk) = timer.update(active, time.delta_seconds(), config.afk.timeout_secs as f32)
+    else {
+        return;
+    };
+
+    state.afk = afk;
+    if afk {
+        info!("player is away");
+        emote.play(AFK_EMOTE, true);
+    } else {
+        info!("player is back");
+        if emote
+            .current()
+            .is_some_and(|current| current.emote_urn == AFK_EMOTE)
+        {
+            emote.stop();
+        }
+    }
+}
+
+fn broadcast_afk(
+    state: Res<AfkState>,
+    transports: Query<&Transport>,
+    time: Res<Time>,
+    mut last_sent: Local<f32>,
+) {
+    let now = time.elapsed_seconds();
+    if !state.is_changed() && !(state.afk && now - *last_sent >= REBROADCAST_SECONDS) {
+        return;
+    }
+
+    let packet = rfc4::Packet {
+        message: Some(rfc4::packet::Message::Chat(rfc4::Chat {
+            message: format!("{}{}", chat_marker_things::AFK, state.afk as u8),
+            timestamp: time.elapsed_seconds_f64(),
+        })),
+        protocol_version: 999,
+    };
+    for transport in transports.iter() {
+        if let Err(e) = transport.sender.try_send(NetworkMessage::reliable(&packet)) {
+            warn!("failed to send afk status: {e}");
+        }
+    }
+    *last_sent = now;
+}
+
+fn receive_afk(mut commands: Commands, mut chat_events: EventReader<ChatEvent>) {
+    for ev in chat_events.read() {
+        let Some(status) = ev.message.strip_prefix(chat_marker_things::AFK) else {
+            continue;
+        };
+        let Some(mut commands) = commands.get_entity(ev.sender) else {
+            continue;
+        };
+        match status {
+            "1" => {
+                commands.try_insert(Afk);
+            }
+            "0" => {
+                commands.remove::<Afk>();
+            }
+            _ => warn!("unrecognised afk status `{status}`"),
+        }
+    }
+}
+
+fn update_afk_nametags(
+    nametags: Query<&Nametag>,
+    afk: Query<(), With<Afk>>,
+    mut styles: Query<&mut Style>,
+) {
+    for nametag in nametags.iter() {
+        let display = if afk.contains(nametag.player) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if let Ok(mut style) = styles.get_mut(nametag.afk_label) {
+            if style.display != display {
+                style.display = display;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+    use common::structs::PrimaryUser;
+
+    use super::*;
+
+    #[test]
+    fn test_afk_timer_fires_and_clears() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world.init_resource::<Events<MouseMotion>>();
+        world.init_resource::<Events<MouseWheel>>();
+        world.init_resource::<Time>();
+        world.init_resource::<AfkTimer>();
+        world.init_resource::<AfkState>();
+        let mut config = AppConfig::default();
+        config.afk.timeout_secs = 10;
+        world.insert_resource(config);
+        world.spawn(PrimaryUser::default());
+
+        let step = |world: &mut World, seconds: f32| {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(seconds));
+            world.run_system_once(detect_afk);
+            world.resource::<AfkState>().afk
+        };
+        let emote = |world: &mut World| {
+            world.run_system_once(|emote: PlayerEmote| {
+                emote.current().map(|current| current.emote_urn.clone())
+            })
+        };
+
+        assert!(!step(&mut world, 9.0));
+        assert!(step(&mut world, 2.0));
+        assert_eq!(emote(&mut world).as_deref(), Some(AFK_EMOTE));
+
+        // any input clears it immediately
+        world.send_event(MouseMotion {
+            delta: Vec2::new(1.0, 0.0),
+        });
+        assert!(!step(&mut world, 0.5));
+        assert_eq!(emote(&mut world), None);
+        let mut motion = world.resource_mut::<Events<MouseMotion>>();
+        motion.update();
+        motion.update();
+
+        // and the timer restarts from then
+        assert!(!step(&mut world, 9.0));
+        assert!(step(&mut world, 1.0));
+
+        // held keys count as activity
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyW);
+        assert!(!step(&mut world, 1.0));
+        assert!(!step(&mut world, 20.0));
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .release(KeyCode::KeyW);
+        assert!(step(&mut world, 10.0));
+
+        // a zero timeout disables it
+        world.resource_mut::<AppConfig>().afk.timeout_secs = 0;
+        assert!(!step(&mut world, 100.0));
+    }
+}
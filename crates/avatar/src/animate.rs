@@ -3,6 +3,7 @@ use std::{collections::VecDeque, time::Duration};
 
 use bevy::{
     animation::RepeatAnimation,
+    ecs::system::SystemParam,
     gltf::Gltf,
     math::Vec3Swizzles,
     prelude::*,
@@ -71,6 +72,18 @@ pub enum EmoteBroadcast {
     Omit(Entity),
 }
 
+// stop an avatar's emote. after a transient interrupt (such as a jump) a looping emote that is
+// still requested resumes once the avatar is back on the ground, other emotes are cleared
+#[derive(Event, Debug, Clone, Copy)]
+pub struct InterruptEmote {
+    pub avatar: Entity,
+    pub transient: bool,
+}
+
+// a looping emote paused by a transient interrupt
+#[derive(Component)]
+pub struct EmoteSuspended;
+
 #[derive(Component, Default, Deref, DerefMut, Debug, Clone)]
 pub struct EmotesFromScene(pub(crate) VecDeque<PbAvatarEmoteCommand>);
 
@@ -90,6 +103,49 @@ impl EmoteList {
     }
 }
 
+// get or set the primary user's emote. emotes played here are broadcast to peers and to the
+// scenes around the player by `broadcast_emote` and `animate`
+#[derive(SystemParam)]
+pub struct PlayerEmote<'w, 's> {
+    commands: Commands<'w, 's>,
+    player: Query<'w, 's, (Entity, Option<&'static EmoteList>), With<PrimaryUser>>,
+}
+
+impl PlayerEmote<'_, '_> {
+    pub fn entity(&self) -> Option<Entity> {
+        self.player.get_single().ok().map(|(player, _)| player)
+    }
+
+    // the requested emote, if any
+    pub fn current(&self) -> Option<&PbAvatarEmoteCommand> {
+        let (_, list) = self.player.get_single().ok()?;
+        list?.back().map(|command| &command.emote)
+    }
+
+    pub fn play(&mut self, urn: impl Into<String>, r#loop: bool) {
+        self.play_commands(EmoteList(VecDeque::from_iter([EmoteCommand {
+            emote: PbAvatarEmoteCommand {
+                emote_urn: urn.into(),
+                r#loop,
+                timestamp: 0,
+            },
+            broadcast: EmoteBroadcast::All,
+        }])));
+    }
+
+    // play the last of a list of commands, with their own broadcast settings
+    pub fn play_commands(&mut self, list: EmoteList) {
+        if let Some(player) = self.entity() {
+            self.commands.entity(player).try_insert(list);
+        }
+    }
+
+    // an empty list rather than removing it, so the next emote is broadcast even if it's the same
+    pub fn stop(&mut self) {
+        self.play_commands(EmoteList::default());
+    }
+}
+
 impl Plugin for AvatarAnimationPlugin {
     fn build(&self, app: &mut App) {
         app.add_crdt_go_component::<PbAvatarEmoteCommand, EmotesFromScene>(
@@ -99,11 +155,17 @@ impl Plugin for AvatarAnimationPlugin {
         app.add_systems(
             Update,
             (
-                (read_player_emotes, broadcast_emote, receive_emotes).before(animate),
+                (
+                    (read_player_emotes, interrupt_emotes).chain(),
+                    broadcast_emote,
+                    receive_emotes,
+                )
+                    .before(animate),
                 (animate, play_current_emote).chain().after(process_avatar),
             )
                 .in_set(SceneSets::PostLoop),
         );
+        app.add_event::<InterruptEmote>();
         app.add_console_command::<EmoteConsoleCommand, _>(emote_console_command);
     }
 }
@@ -121,10 +183,11 @@ fn read_player_emotes(
         ),
         Without<PrimaryUser>,
     >,
-    player: Query<Entity, With<PrimaryUser>>,
+    mut player_emote: PlayerEmote,
+    mut interrupts: EventWriter<InterruptEmote>,
     mut perms: Permission<EmoteList>,
 ) {
-    let Ok(player) = player.get_single() else {
+    let Some(player) = player_emote.entity() else {
         return;
     };
 
@@ -133,6 +196,19 @@ fn read_player_emotes(
             continue;
         }
 
+        // an empty urn stops the player's emote, which doesn't need permission
+        if emotes
+            .0
+            .back()
+            .is_some_and(|emote| emote.emote_urn.is_empty())
+        {
+            interrupts.send(InterruptEmote {
+                avatar: player,
+                transient: false,
+            });
+            continue;
+        }
+
         let mut list = EmoteList::default();
         for emote in &emotes.0 {
             list.0.push_back(EmoteCommand {
@@ -151,17 +227,55 @@ fn read_player_emotes(
                 false,
             );
         } else {
-            commands.entity(player).insert(list);
+            player_emote.play_commands(list);
         }
     }
 
     for list in perms.drain_success(common::structs::PermissionType::PlayEmote) {
-        commands.entity(player).insert(list.clone());
+        player_emote.play_commands(list);
     }
 
     for _ in perms.drain_fail(common::structs::PermissionType::PlayEmote) {}
 }
 
+// stop emotes on interrupt events and when the avatar jumps. a suspended looping emote is
+// resumed by `animate`
+fn interrupt_emotes(
+    mut commands: Commands,
+    mut events: EventReader<InterruptEmote>,
+    mut avatars: Query<(Entity, &AvatarDynamicState, &mut EmoteList)>,
+    time: Res<Time>,
+) {
+    // transient only if all interrupts this frame are
+    let mut interrupts = HashMap::<Entity, bool>::default();
+    for ev in events.read() {
+        *interrupts.entry(ev.avatar).or_insert(true) &= ev.transient;
+    }
+
+    for (avatar_ent, dynamic_state, mut emotes) in avatars.iter_mut() {
+        let jumped = dynamic_state.jump_time > time.elapsed_seconds() - time.delta_seconds();
+        let Some(transient) = interrupts
+            .get(&avatar_ent)
+            .copied()
+            .or(jumped.then_some(true))
+        else {
+            continue;
+        };
+        let Some(requested) = emotes.back() else {
+            continue;
+        };
+
+        if transient && requested.emote.r#loop {
+            debug!("suspend emote {:?}", requested.emote.emote_urn);
+            commands.entity(avatar_ent).try_insert(EmoteSuspended);
+        } else {
+            debug!("interrupt emote {:?}", requested.emote.emote_urn);
+            emotes.clear();
+            commands.entity(avatar_ent).remove::<EmoteSuspended>();
+        }
+    }
+}
+
 fn broadcast_emote(
     q: Query<&EmoteList, With<PrimaryUser>>,
     transports: Query<&Transport>,
@@ -273,6 +387,7 @@ fn animate(
         &GlobalTransform,
         Option<&mut ActiveEmote>,
         Option<&ForeignPlayer>,
+        Option<&EmoteSuspended>,
     )>,
     mut velocities: Local<HashMap<Entity, Vec3>>,
     mut current_emote_min_velocities: Local<HashMap<Entity, f32>>,
@@ -281,6 +396,7 @@ fn animate(
     containing_scene: ContainingScene,
     mut scenes: Query<&mut RendererSceneContext>,
 ) {
+    let _span = debug_span!("avatar::animate").entered();
     let (gravity, jump_height) = player
         .get_single()
         .map(|(p, m)| m.map(|m| m.combine(p)).unwrap_or(p.clone()))
@@ -292,7 +408,7 @@ fn animate(
     let prior_velocities = std::mem::take(&mut *velocities);
     let prior_min_velocities = std::mem::take(&mut *current_emote_min_velocities);
 
-    for (avatar_ent, dynamic_state, mut emotes, gt, active_emote, maybe_foreign) in
+    for (avatar_ent, dynamic_state, mut emotes, gt, active_emote, maybe_foreign, suspended) in
         avatars.iter_mut()
     {
         let Some(mut active_emote) = active_emote else {
@@ -343,6 +459,20 @@ fn animate(
             (None, None, false, EmoteBroadcast::None)
         };
 
+        // a suspended emote waits until the avatar lands, or is replaced by a new request
+        let mut resumed = false;
+        if suspended.is_some() && requested_emote.is_some() {
+            let airborne = dynamic_state.ground_height > 0.2 || dynamic_state.velocity.y > 0.0;
+            if airborne && !emotes_changed {
+                requested_emote = None;
+            } else {
+                commands.entity(avatar_ent).remove::<EmoteSuspended>();
+                resumed = true;
+            }
+        } else if suspended.is_some() {
+            commands.entity(avatar_ent).remove::<EmoteSuspended>();
+        }
+
         // check / cancel requested emote
         if Some(&active_emote.urn) == requested_emote.as_ref() {
             let playing_min_vel = prior_min_velocities
@@ -413,7 +543,7 @@ fn animate(
             }
             ActiveEmote {
                 urn: requested_emote,
-                restart: emotes_changed,
+                restart: emotes_changed || resumed,
                 repeat: request_loop,
                 ..Default::default()
             }
@@ -942,31 +1072,85 @@ struct EmoteConsoleCommand {
 }
 
 fn emote_console_command(
-    mut commands: Commands,
     mut input: ConsoleCommand<EmoteConsoleCommand>,
-    player: Query<Entity, With<PrimaryUser>>,
+    mut player_emote: PlayerEmote,
     profile: Res<CurrentUserProfile>,
 ) {
     if let Some(Ok(command)) = input.take() {
-        if let Ok(player) = player.get_single() {
-            let mut urn = &command.urn;
-            if let Ok(slot) = command.urn.parse::<u32>() {
-                if let Some(emote) = profile
-                    .profile
-                    .as_ref()
-                    .and_then(|p| p.content.avatar.emotes.as_ref())
-                    .and_then(|es| es.iter().find(|e| e.slot == slot))
-                {
-                    urn = &emote.urn;
-                }
+        let mut urn = &command.urn;
+        if let Ok(slot) = command.urn.parse::<u32>() {
+            if let Some(emote) = profile
+                .profile
+                .as_ref()
+                .and_then(|p| p.content.avatar.emotes.as_ref())
+                .and_then(|es| es.iter().find(|e| e.slot == slot))
+            {
+                urn = &emote.urn;
             }
+        }
 
-            info!("anim {} -> {}", command.urn, urn);
+        info!("anim {} -> {}", command.urn, urn);
 
-            commands
-                .entity(player)
-                .try_insert(EmoteList::new(urn.clone(), EmoteBroadcast::All));
-        };
+        player_emote.play(urn.clone(), false);
         input.ok();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn test_play_then_stop_clears_emote() {
+        let mut world = World::new();
+        world.spawn(PrimaryUser::default());
+
+        world.run_system_once(|mut emote: PlayerEmote| emote.play("wave", true));
+        let current = world.run_system_once(|emote: PlayerEmote| emote.current().cloned());
+        assert_eq!(
+            current.map(|emote| (emote.emote_urn, emote.r#loop)),
+            Some(("wave".to_owned(), true))
+        );
+
+        world.run_system_once(|mut emote: PlayerEmote| emote.stop());
+        let current = world.run_system_once(|emote: PlayerEmote| emote.current().cloned());
+        assert!(current.is_none());
+    }
+
+    #[test]
+    fn test_interrupt_clears_emote() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<Events<InterruptEmote>>();
+        let one_shot = world
+            .spawn((
+                AvatarDynamicState::default(),
+                EmoteList::new("wave", EmoteBroadcast::All),
+            ))
+            .id();
+        let mut looping = EmoteList::new("dance", EmoteBroadcast::All);
+        looping[0].emote.r#loop = true;
+        let looping = world.spawn((AvatarDynamicState::default(), looping)).id();
+
+        // a transient interrupt only suspends looping emotes
+        world.send_event_batch([one_shot, looping].map(|avatar| InterruptEmote {
+            avatar,
+            transient: true,
+        }));
+        world.run_system_once(interrupt_emotes);
+        assert!(world.get::<EmoteList>(one_shot).unwrap().is_empty());
+        assert_eq!(world.get::<EmoteList>(looping).unwrap().len(), 1);
+        assert!(world.get::<EmoteSuspended>(looping).is_some());
+
+        // otherwise the emote is cleared
+        world.send_event(InterruptEmote {
+            avatar: looping,
+            transient: false,
+        });
+        world.run_system_once(interrupt_emotes);
+        assert!(world.get::<EmoteList>(looping).unwrap().is_empty());
+        assert!(world.get::<EmoteSuspended>(looping).is_none());
+    }
+}
@@ -10,10 +10,14 @@ use dcl_component::{
     proto_components::sdk::components::{AvatarAnchorPointType, PbAvatarAttach},
     SceneComponentId,
 };
-use scene_runner::update_world::{
-    mesh_collider::DisableCollisions,
-    transform_and_parent::{AvatarAttachStage, ParentPositionSync},
-    AddCrdtInterfaceExt,
+use scene_runner::{
+    features::{SceneFeature, SceneFeatures},
+    update_world::{
+        mesh_collider::DisableCollisions,
+        transform_and_parent::{AvatarAttachStage, ParentPositionSync},
+        AddCrdtInterfaceExt,
+    },
+    SceneEntity,
 };
 
 pub struct AttachPlugin;
@@ -39,10 +43,12 @@ impl From<PbAvatarAttach> for AvatarAttachment {
 
 pub fn update_attached(
     mut commands: Commands,
-    attachments: Query<(Entity, &AvatarAttachment), Changed<AvatarAttachment>>,
+    attachments: Query<(Entity, &SceneEntity, Ref<AvatarAttachment>)>,
     mut removed_attachments: RemovedComponents<AvatarAttachment>,
     primary_user: Query<&AttachPoints, With<PrimaryUser>>,
     all_users: Query<(&AttachPoints, &UserProfile)>,
+    mut features: SceneFeatures,
+    mut prev_enabled: Local<Option<bool>>,
 ) {
     for removed in removed_attachments.read() {
         if let Some(mut commands) = commands.get_entity(removed) {
@@ -50,7 +56,22 @@ pub fn update_attached(
         }
     }
 
-    for (ent, attach) in attachments.iter() {
+    // all attachments are updated when the feature is switched on or off
+    let enabled = features.enabled(SceneFeature::AvatarAttach);
+    let toggled = prev_enabled.replace(enabled) != Some(enabled);
+
+    for (ent, scene_ent, attach) in attachments.iter() {
+        if !attach.is_changed() && !toggled {
+            continue;
+        }
+
+        if !features.allowed(scene_ent.root, SceneFeature::AvatarAttach) {
+            commands
+                .entity(ent)
+                .remove::<(ParentPositionSync<AvatarAttachStage>, DisableCollisions)>();
+            continue;
+        }
+
         let attach_points = match attach.0.avatar_id.as_ref() {
             None => {
                 let Ok(data) = primary_user.get_single() else {
@@ -8,7 +8,7 @@ use common::{
     dynamics::{PLAYER_COLLIDER_HEIGHT, PLAYER_COLLIDER_OVERLAP, PLAYER_COLLIDER_RADIUS},
     rpc::{RpcCall, RpcEventSender},
     sets::SceneSets,
-    structs::{PrimaryCamera, ShowProfileEvent, ToolTips, TooltipSource},
+    structs::{PrimaryCamera, ShowAvatarMenuEvent, ToolTips, TooltipSource},
     util::{AsH160, FireEventEx},
 };
 use comms::{global_crdt::ForeignPlayer, profile::UserProfile};
@@ -210,7 +210,7 @@ fn update_avatar_collider_actions(
 
         tooltips.0.insert(
             TooltipSource::Label("avatar_pointer"),
-            vec![("Middle Click : Profile".to_owned(), true)],
+            vec![("Middle Click : Options".to_owned(), true)],
         );
 
         if mouse_input.just_pressed(MouseButton::Left) {
@@ -229,9 +229,13 @@ fn update_avatar_collider_actions(
         }
 
         if mouse_input.just_pressed(MouseButton::Middle) {
-            // display profile
+            // display context menu
             if let Some(address) = profile.content.eth_address.as_h160() {
-                commands.fire_event(ShowProfileEvent(address));
+                commands.fire_event(ShowAvatarMenuEvent {
+                    address,
+                    name: profile.content.name.clone(),
+                    position: cursor_position,
+                });
             } else {
                 warn!("Profile has a bad address {}", profile.content.eth_address);
             }
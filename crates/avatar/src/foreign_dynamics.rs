@@ -1,8 +1,11 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 
-use common::util::QuatNormalizeExt;
+use common::{structs::AppConfig, util::QuatNormalizeExt};
 
-use comms::global_crdt::{ForeignPlayer, PlayerPositionEvent};
+use comms::{
+    global_crdt::{ForeignPlayer, PlayerPositionEvent},
+    jitter_buffer::{PositionJitterBuffer, PositionSample, TELEPORT_DISTANCE},
+};
 use dcl_component::{transform_and_parent::DclTransformAndParent, SceneEntityId};
 use scene_runner::{
     renderer_context::RendererSceneContext, update_world::mesh_collider::SceneColliderData,
@@ -26,19 +29,16 @@ impl Plugin for PlayerMovementPlugin {
     }
 }
 
-#[derive(Component)]
-struct PlayerTargetPosition {
-    time: f32,
-    translation: Vec3,
-    rotation: Quat,
-    index: u32,
-}
-
 fn update_foreign_user_target_position(
     mut commands: Commands,
     mut move_events: EventReader<PlayerPositionEvent>,
-    mut players: Query<(&ForeignPlayer, Option<&mut PlayerTargetPosition>)>,
+    mut players: Query<(&ForeignPlayer, Option<&mut PositionJitterBuffer>)>,
+    config: Res<AppConfig>,
 ) {
+    let delay = config.network.jitter_buffer_ms as f32 / 1000.0;
+    let mode = config.network.avatar_motion;
+    let mut new_buffers = HashMap::<Entity, PositionJitterBuffer>::default();
+
     for ev in move_events.read() {
         let dcl_transform = DclTransformAndParent {
             translation: ev.translation,
@@ -48,36 +48,36 @@ fn update_foreign_user_target_position(
         };
 
         let bevy_trans = dcl_transform.to_bevy_transform();
+        let sample = PositionSample {
+            index: ev.index,
+            time: ev.time,
+            translation: bevy_trans.translation,
+            rotation: bevy_trans.rotation.normalize_or_identity(),
+        };
 
-        if let Ok((_player, maybe_pos)) = players.get_mut(ev.player) {
-            if let Some(mut pos) = maybe_pos {
-                if pos.index < ev.index {
-                    *pos = PlayerTargetPosition {
-                        time: ev.time,
-                        translation: bevy_trans.translation,
-                        rotation: bevy_trans.rotation.normalize_or_identity(),
-                        index: ev.index,
-                    }
-                }
+        if let Ok((_player, maybe_buffer)) = players.get_mut(ev.player) {
+            if let Some(mut buffer) = maybe_buffer {
+                buffer.push(sample);
             } else {
-                commands.entity(ev.player).try_insert((
-                    PlayerTargetPosition {
-                        time: ev.time,
-                        translation: bevy_trans.translation,
-                        rotation: bevy_trans.rotation,
-                        index: ev.index,
-                    },
-                    AvatarDynamicState::default(),
-                ));
+                new_buffers
+                    .entry(ev.player)
+                    .or_insert_with(|| PositionJitterBuffer::new(delay, mode))
+                    .push(sample);
             }
         }
     }
+
+    for (player, buffer) in new_buffers {
+        commands
+            .entity(player)
+            .try_insert((buffer, AvatarDynamicState::default()));
+    }
 }
 
 fn update_foreign_user_actual_position(
     mut avatars: Query<(
         Entity,
-        &PlayerTargetPosition,
+        &mut PositionJitterBuffer,
         &mut Transform,
         &mut AvatarDynamicState,
     )>,
@@ -88,28 +88,28 @@ fn update_foreign_user_actual_position(
     )>,
     containing_scene: ContainingScene,
     time: Res<Time>,
+    config: Res<AppConfig>,
 ) {
-    for (foreign_ent, target, mut actual, mut dynamic_state) in avatars.iter_mut() {
-        // arrive at target position by time + 0.5
-        let walk_time_left = target.time + 0.5 - time.elapsed_seconds();
-        if walk_time_left <= 0.0 || (actual.translation - target.translation).length() > 125.0 {
-            actual.translation = target.translation;
-            dynamic_state.velocity = Vec3::ZERO;
-        } else {
-            let walk_fraction = (time.delta_seconds() / walk_time_left).min(1.0);
-            let delta = (target.translation - actual.translation) * walk_fraction;
-            dynamic_state.velocity = delta / time.delta_seconds();
-            actual.translation += delta;
-        }
+    let delay = config.network.jitter_buffer_ms as f32 / 1000.0;
+    let mode = config.network.avatar_motion;
 
-        // turn a bit faster
-        let turn_time_left = target.time + 0.2 - time.elapsed_seconds();
-        if turn_time_left <= 0.0 {
-            actual.rotation = target.rotation;
-        } else {
-            let turn_fraction = (time.delta_seconds() / turn_time_left).min(1.0);
-            actual.rotation = actual.rotation.lerp(target.rotation, turn_fraction);
-        }
+    for (foreign_ent, mut buffer, mut actual, mut dynamic_state) in avatars.iter_mut() {
+        buffer.delay = delay;
+        buffer.mode = mode;
+        let Some((target_translation, target_rotation)) = buffer.sample(time.elapsed_seconds())
+        else {
+            continue;
+        };
+
+        let delta = target_translation - actual.translation;
+        dynamic_state.velocity =
+            if time.delta_seconds() > 0.0 && delta.length() <= TELEPORT_DISTANCE {
+                delta / time.delta_seconds()
+            } else {
+                Vec3::ZERO
+            };
+        actual.translation = target_translation;
+        actual.rotation = target_rotation;
 
         // update ground height
         dynamic_state.ground_height = actual.translation.y;
@@ -131,18 +131,6 @@ fn update_foreign_user_actual_position(
                 }
             });
 
-        // fall
-        if actual.translation.y > target.translation.y && dynamic_state.ground_height > 0.0 {
-            let updated_y = target
-                .translation
-                .y
-                .max(actual.translation.y - 15.0 * time.delta_seconds())
-                .max(actual.translation.y - dynamic_state.ground_height);
-
-            dynamic_state.ground_height += updated_y - actual.translation.y;
-            actual.translation.y = updated_y;
-        }
-
         dynamic_state.force = dynamic_state.velocity.xz();
     }
 }
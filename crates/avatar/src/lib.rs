@@ -1,5 +1,6 @@
 use std::{collections::VecDeque, f32::consts::PI, path::PathBuf, str::FromStr, time::Duration};
 
+use afk::AfkPlugin;
 use attach::AttachPlugin;
 use avatar_texture::AvatarTexturePlugin;
 use bevy::{
@@ -27,6 +28,7 @@ use console::DoAddConsoleCommand;
 use npc_dynamics::NpcMovementPlugin;
 use scene_material::{BoundRegion, SceneBound, SceneMaterial};
 
+pub mod afk;
 pub mod animate;
 pub mod attach;
 pub mod avatar_texture;
@@ -37,11 +39,11 @@ pub mod npc_dynamics;
 
 use common::{
     sets::SetupSets,
-    structs::{AppConfig, AttachPoints, PrimaryUser},
+    structs::{AppConfig, AttachPoints, PhotoMode, PrimaryUser, RealmSettings},
     util::{DespawnWith, TryPushChildrenEx},
 };
 use comms::{
-    global_crdt::{ForeignPlayer, GlobalCrdtState},
+    global_crdt::{BlockedPeers, ForeignPlayer, GlobalCrdtState, SpeakingPeers},
     profile::UserProfile,
 };
 use dcl::interface::{ComponentPosition, CrdtType};
@@ -62,6 +64,7 @@ use scene_runner::{
     util::ConsoleRelay,
     ContainingScene, SceneEntity,
 };
+use social::{FriendshipState, SocialClient};
 use world_ui::{spawn_world_ui_view, WorldUi};
 
 use crate::animate::AvatarAnimPlayer;
@@ -81,6 +84,7 @@ impl Plugin for AvatarPlugin {
         app.add_plugins(NpcMovementPlugin);
         app.add_plugins(AvatarAnimationPlugin);
         app.add_plugins(AttachPlugin);
+        app.add_plugins(AfkPlugin);
         app.add_plugins(AvatarColliderPlugin);
         app.add_plugins(AvatarTexturePlugin);
         app.add_systems(
@@ -93,6 +97,7 @@ impl Plugin for AvatarPlugin {
                 spawn_scenes,
                 process_avatar,
                 set_avatar_visibility,
+                set_nametag_visibility,
             ),
         );
 
@@ -1280,15 +1285,15 @@ fn process_avatar(
         // add nametag
         if let Some(label) = def.label.as_ref() {
             debug!("spawn avatar label for {label}");
-            let label_ui = commands
+            let label_components = commands
                 .entity(ui_view.ui_root)
                 .spawn_template(
                     &dui,
                     "avatar-nametag",
                     DuiProps::new().with_prop("name", label.to_string()),
                 )
-                .unwrap()
-                .root;
+                .unwrap();
+            let label_ui = label_components.root;
 
             debug!("{:?} as child of {:?}", label_ui, ui_view.view);
             commands.entity(label_ui).insert(DespawnWith(avatar_ent));
@@ -1310,16 +1315,68 @@ fn process_avatar(
                         ui_node: label_ui,
                     },
                     Billboard::Y,
+                    Nametag {
+                        player: root_player_entity.get(),
+                        afk_label: label_components.named("afk"),
+                    },
                 ));
             });
         }
     }
 }
 
+// avatar label, shown according to the `NametagSetting` for foreign players
+#[derive(Component)]
+pub struct Nametag {
+    player: Entity,
+    // shown while the player is away
+    afk_label: Entity,
+}
+
+fn set_nametag_visibility(
+    mut nametags: Query<(&Nametag, &mut Visibility)>,
+    players: Query<&ForeignPlayer>,
+    config: Res<AppConfig>,
+    social: Res<SocialClient>,
+    speaking: Res<SpeakingPeers>,
+    photo_mode: Res<PhotoMode>,
+) {
+    for (nametag, mut vis) in nametags.iter_mut() {
+        // labels on scene-created avatars are always shown, except in photo mode
+        let show = !photo_mode.active
+            && players.get(nametag.player).map_or(true, |player| {
+                config.nametags.visible(
+                    social.get_state(player.address) == FriendshipState::Friends,
+                    speaking.0.contains(&player.address),
+                )
+            });
+        let target = if show {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *vis != target {
+            *vis = target;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
 fn set_avatar_visibility(
-    mut q: Query<(&GlobalTransform, &mut Visibility, Option<&RenderLayers>), With<AvatarProcessed>>,
+    mut q: Query<
+        (
+            &GlobalTransform,
+            &mut Visibility,
+            Option<&RenderLayers>,
+            &Parent,
+        ),
+        With<AvatarProcessed>,
+    >,
     player: Query<&GlobalTransform, With<PrimaryUser>>,
+    foreign_players: Query<&ForeignPlayer>,
+    blocked: Res<BlockedPeers>,
     config: Res<AppConfig>,
+    realm_settings: Res<RealmSettings>,
 ) {
     let Ok(player_pos) = player.get_single().map(|gt| gt.translation()) else {
         return;
@@ -1335,13 +1392,23 @@ fn set_avatar_visibility(
         .collect::<Vec<_>>();
     distances.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Less));
     let cutoff = distances
-        .get(config.max_avatars)
+        .get(
+            realm_settings
+                .overrides
+                .max_avatars
+                .unwrap_or(config.max_avatars),
+        )
         .copied()
         .unwrap_or(f32::MAX);
 
-    for (t, mut vis, maybe_layer) in q.iter_mut() {
+    for (t, mut vis, maybe_layer, parent) in q.iter_mut() {
         let is_root_layer = maybe_layer.map_or(true, |layer| layer.intersects(&default_layer));
-        *vis = if is_root_layer && (t.translation() - player_pos).length_squared() >= cutoff {
+        let is_blocked = foreign_players
+            .get(parent.get())
+            .is_ok_and(|player| blocked.0.contains(&player.address));
+        *vis = if is_blocked
+            || (is_root_layer && (t.translation() - player_pos).length_squared() >= cutoff)
+        {
             Visibility::Hidden
         } else {
             Visibility::Inherited
@@ -0,0 +1,390 @@
+// the `AppConfig` resource is the source of truth for the config file. systems mutate the
+// resource and changes are written to disk shortly afterwards and on exit, rather than each
+// site reading, modifying and rewriting the file itself. values that change continuously
+// (e.g. the camera zoom) can be written bypassing change detection, and are saved on exit.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::{app::AppExit, prelude::*, utils::Instant};
+
+use serde_json::{Map, Value};
+
+use crate::{structs::AppConfig, util::config_file};
+
+// current config schema version. bump this and add a migration to `MIGRATIONS`
+// when a change to `AppConfig` can't be handled by filling missing fields from defaults
+pub const CONFIG_VERSION: u32 = 1;
+
+// migrations from each version to the next, indexed by the version they upgrade from
+const MIGRATIONS: [fn(&mut Map<String, Value>); CONFIG_VERSION as usize] = [migrate_v0];
+
+// v0 configs predate the version field. fields they lack are filled from defaults
+fn migrate_v0(_: &mut Map<String, Value>) {}
+
+// delay between the first unsaved change and writing the file, so bursts of changes
+// (e.g. dragging a slider) result in a single write
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(1);
+
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConfigFile>();
+        app.add_systems(Last, autosave_config);
+    }
+}
+
+// path the config is saved to
+#[derive(Resource)]
+pub struct ConfigFile(pub PathBuf);
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        Self(config_file())
+    }
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    path.with_extension("json.bak")
+}
+
+// write the config via a temp file and rename, so a crash mid-write can't leave a partial
+// file. the previous config is kept as a backup if it is readable
+pub fn save_config(path: &Path, config: &AppConfig) -> Result<(), std::io::Error> {
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder)?;
+    }
+
+    let temp_path = path.with_extension("json.tmp");
+    let mut temp = std::fs::File::create(&temp_path)?;
+    temp.write_all(serde_json::to_string(config)?.as_bytes())?;
+    temp.sync_all()?;
+    drop(temp);
+
+    // don't overwrite a good backup with a corrupt primary
+    if std::fs::read(path)
+        .ok()
+        .is_some_and(|data| serde_json::from_slice::<Map<String, Value>>(&data).is_ok())
+    {
+        std::fs::copy(path, backup_path(path))?;
+    }
+
+    std::fs::rename(temp_path, path)
+}
+
+// read and load the config at `path`, falling back to the backup if the primary is
+// unreadable. returns None if neither can be read
+pub fn read_config(path: &Path, warnings: &mut Vec<String>) -> Option<AppConfig> {
+    let mut read = |path: &Path| -> Result<AppConfig, String> {
+        let data = std::fs::read(path).map_err(|e| e.to_string())?;
+        let (config, load_warnings) = load_config(&data).map_err(|e| e.to_string())?;
+        warnings.extend(load_warnings);
+        Ok(config)
+    };
+
+    let primary_error = match read(path) {
+        Ok(config) => return Some(config),
+        Err(e) => e,
+    };
+
+    let backup = backup_path(path);
+    match read(&backup) {
+        Ok(config) => {
+            warnings.push(format!(
+                "failed to read config at {path:?} ({primary_error}), recovered from {backup:?}"
+            ));
+            Some(config)
+        }
+        Err(_) => {
+            warnings.push(format!(
+                "failed to read config at {path:?} ({primary_error}), generating default"
+            ));
+            None
+        }
+    }
+}
+
+// load a config, migrating from older versions. fields that can't be read are reset to
+// defaults individually so that e.g. `previous_login` survives an incompatible setting.
+// returns the config and a list of warnings, or an error if the data is not a json object
+pub fn load_config(data: &[u8]) -> Result<(AppConfig, Vec<String>), serde_json::Error> {
+    let mut warnings = Vec::default();
+    let mut stored: Map<String, Value> = serde_json::from_slice(data)?;
+
+    let version = stored.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if version > CONFIG_VERSION {
+        warnings.push(format!(
+            "config version {version} is newer than supported version {CONFIG_VERSION}, loading what we can"
+        ));
+    }
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        migration(&mut stored);
+    }
+    stored.insert("version".to_owned(), CONFIG_VERSION.into());
+
+    let Value::Object(mut merged) = serde_json::to_value(AppConfig::default())? else {
+        unreachable!("AppConfig serializes to an object");
+    };
+    let fallback = merged.clone();
+
+    // fast path: everything reads
+    for (key, value) in stored.iter() {
+        overlay(
+            merged.entry(key.clone()).or_insert(Value::Null),
+            value.clone(),
+        );
+    }
+    if let Ok(config) = serde_json::from_value(Value::Object(merged)) {
+        return Ok((config, warnings));
+    }
+
+    // otherwise keep each top-level field only if it reads
+    let mut merged = fallback;
+    for (key, value) in stored {
+        let prev = merged.get(&key).cloned();
+        overlay(merged.entry(key.clone()).or_insert(Value::Null), value);
+        if serde_json::from_value::<AppConfig>(Value::Object(merged.clone())).is_err() {
+            warnings.push(format!(
+                "config field `{key}` could not be read, using default"
+            ));
+            match prev {
+                Some(prev) => merged.insert(key, prev),
+                None => merged.remove(&key),
+            };
+        }
+    }
+
+    Ok((serde_json::from_value(Value::Object(merged))?, warnings))
+}
+
+// recursively replace values in `target` with those in `source`, keeping fields of
+// `target` that `source` doesn't have
+fn overlay(target: &mut Value, source: Value) {
+    match (target, source) {
+        (Value::Object(target), Value::Object(source)) => {
+            for (key, value) in source {
+                overlay(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (target, source) => *target = source,
+    }
+}
+
+pub fn autosave_config(
+    config: Option<Res<AppConfig>>,
+    file: Res<ConfigFile>,
+    mut exit: EventReader<AppExit>,
+    mut dirty_since: Local<Option<Instant>>,
+) {
+    let Some(config) = config else {
+        return;
+    };
+
+    if config.is_changed() && dirty_since.is_none() {
+        *dirty_since = Some(Instant::now());
+    }
+
+    // exiting always saves, to keep values written without change detection
+    let exiting = exit.read().last().is_some();
+    let due = dirty_since.is_some_and(|since| since.elapsed() >= AUTOSAVE_DELAY);
+    if !exiting && !due {
+        return;
+    }
+
+    *dirty_since = None;
+    if let Err(e) = save_config(&file.0, &config) {
+        warn!("failed to save config to {:?}: {e}", file.0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::structs::{PermissionType, PermissionValue, PreviousLogin};
+
+    use super::*;
+
+    // a config as written before versioning, missing fields added since
+    fn v0_config() -> Map<String, Value> {
+        let config = AppConfig {
+            location: IVec2::new(-5, 7),
+            previous_login: Some(PreviousLogin {
+                root_address: Default::default(),
+                ephemeral_key: vec![1, 2, 3],
+                auth: Default::default(),
+            }),
+            max_avatars: 17,
+            ..Default::default()
+        };
+        let Value::Object(mut config) = serde_json::to_value(config).unwrap() else {
+            panic!();
+        };
+        config.remove("version");
+        config.remove("despawn_workaround");
+        config.remove("performance");
+        config
+            .get_mut("graphics")
+            .and_then(Value::as_object_mut)
+            .unwrap()
+            .remove("fps_target");
+        config
+    }
+
+    #[test]
+    fn test_load_v0_config() {
+        let data = serde_json::to_vec(&v0_config()).unwrap();
+        let (config, warnings) = load_config(&data).unwrap();
+
+        assert!(warnings.is_empty());
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.location, IVec2::new(-5, 7));
+        assert_eq!(config.max_avatars, 17);
+        assert_eq!(
+            config.previous_login.map(|login| login.ephemeral_key),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(
+            config.graphics.fps_target,
+            AppConfig::default().graphics.fps_target
+        );
+    }
+
+    #[test]
+    fn test_load_config_with_unreadable_field() {
+        let mut stored = v0_config();
+        stored.insert("max_avatars".to_owned(), "lots".into());
+        let data = serde_json::to_vec(&stored).unwrap();
+        let (config, warnings) = load_config(&data).unwrap();
+
+        // the broken field is reset, everything else survives
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(config.max_avatars, AppConfig::default().max_avatars);
+        assert_eq!(config.location, IVec2::new(-5, 7));
+        assert!(config.previous_login.is_some());
+
+        assert!(load_config(b"not json").is_err());
+    }
+
+    #[test]
+    fn test_recover_from_partial_write() {
+        let folder = std::env::temp_dir().join(format!("config_test_{}", std::process::id()));
+        let path = folder.join("config.json");
+
+        let first = AppConfig {
+            location: IVec2::new(1, 1),
+            ..Default::default()
+        };
+        let second = AppConfig {
+            location: IVec2::new(2, 2),
+            ..Default::default()
+        };
+        save_config(&path, &first).unwrap();
+        save_config(&path, &second).unwrap();
+
+        let mut warnings = Vec::default();
+        let loaded = read_config(&path, &mut warnings).unwrap();
+        assert_eq!(loaded.location, IVec2::new(2, 2));
+        assert!(warnings.is_empty());
+
+        // simulate a crash part way through writing the primary
+        let data = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &data[..data.len() / 2]).unwrap();
+        let loaded = read_config(&path, &mut warnings).unwrap();
+        assert_eq!(loaded.location, IVec2::new(1, 1));
+        assert_eq!(warnings.len(), 1);
+
+        // saving again keeps the good backup
+        save_config(&path, &second).unwrap();
+        std::fs::write(&path, "").unwrap();
+        let loaded = read_config(&path, &mut warnings).unwrap();
+        assert_eq!(loaded.location, IVec2::new(1, 1));
+
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn test_independent_mutations_persist() {
+        let path = std::env::temp_dir().join(format!("config_test_{}.json", std::process::id()));
+
+        let mut app = App::new();
+        app.add_event::<AppExit>()
+            .insert_resource(AppConfig::default())
+            .insert_resource(ConfigFile(path.clone()))
+            .add_systems(Last, autosave_config)
+            .add_systems(
+                Update,
+                (
+                    |mut config: ResMut<AppConfig>| config.location = IVec2::new(12, -34),
+                    |mut config: ResMut<AppConfig>| {
+                        config
+                            .scene_permissions
+                            .entry("scene".to_owned())
+                            .or_default()
+                            .insert(PermissionType::Teleport, PermissionValue::Allow);
+                    },
+                ),
+            );
+
+        app.update();
+        app.world_mut().send_event(AppExit::Success);
+        app.update();
+
+        let saved: AppConfig = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(saved.location, IVec2::new(12, -34));
+        assert_eq!(
+            saved
+                .scene_permissions
+                .get("scene")
+                .and_then(|p| p.get(&PermissionType::Teleport)),
+            Some(&PermissionValue::Allow)
+        );
+    }
+
+    #[test]
+    fn test_exit_saves_unflagged_changes() {
+        let path =
+            std::env::temp_dir().join(format!("config_test_exit_{}.json", std::process::id()));
+
+        let mut app = App::new();
+        app.add_event::<AppExit>()
+            .insert_resource(AppConfig::default())
+            .insert_resource(ConfigFile(path.clone()))
+            .add_systems(Last, autosave_config)
+            .add_systems(
+                Update,
+                |mut config: ResMut<AppConfig>, mut frame: Local<usize>| {
+                    *frame += 1;
+                    if *frame == 3 {
+                        config.bypass_change_detection().camera.zoom = 12.0;
+                    }
+                },
+            );
+        let saved_zoom = || {
+            let saved: AppConfig = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+            saved.camera.zoom
+        };
+
+        // the initial insert is saved after the delay
+        app.update();
+        std::thread::sleep(AUTOSAVE_DELAY);
+        app.update();
+        assert_eq!(saved_zoom(), AppConfig::default().camera.zoom);
+
+        // the zoom isn't flagged as a change, so waits for exit
+        app.update();
+        std::thread::sleep(AUTOSAVE_DELAY);
+        app.update();
+        assert_eq!(saved_zoom(), AppConfig::default().camera.zoom);
+        app.world_mut().send_event(AppExit::Success);
+        app.update();
+        assert_eq!(saved_zoom(), 12.0);
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(backup_path(&path)).unwrap();
+    }
+}
@@ -1,3 +1,4 @@
+pub mod config;
 pub mod dynamics;
 pub mod profile;
 pub mod rpc;
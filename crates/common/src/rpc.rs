@@ -102,6 +102,11 @@ pub enum RpcCall {
         url: String,
         response: RpcResultSender<Result<(), String>>,
     },
+    ShowToast {
+        scene: Entity,
+        message: String,
+        response: RpcResultSender<Result<(), String>>,
+    },
     MovePlayer {
         scene: Entity,
         to: Vec3,
@@ -188,6 +193,16 @@ pub enum RpcCall {
         hash: String,
         sender: tokio::sync::mpsc::UnboundedSender<(String, Vec<u8>)>,
     },
+    // messages between scenes running in this client, never sent over the network.
+    // received messages are (sender scene hash, message)
+    SubscribeLocalMessages {
+        scene: Entity,
+        sender: tokio::sync::mpsc::UnboundedSender<(String, String)>,
+    },
+    SendLocalMessage {
+        scene: Entity,
+        message: String,
+    },
     TestPlan {
         scene: Entity,
         plan: Vec<String>,
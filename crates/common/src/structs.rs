@@ -1,10 +1,20 @@
-use std::{f32::consts::PI, num::ParseIntError, ops::Range, str::FromStr, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    f32::consts::PI,
+    num::ParseIntError,
+    ops::Range,
+    str::FromStr,
+    sync::Arc,
+};
 
 use bevy::{
+    core_pipeline::tonemapping::Tonemapping,
     prelude::*,
-    render::view::RenderLayers,
+    render::view::{ColorGrading, ColorGradingGlobal, ColorGradingSection, RenderLayers},
     utils::{HashMap, HashSet},
 };
+use dcl_component::proto_components::ColorSpace;
 use ethers_core::abi::Address;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
@@ -81,6 +91,9 @@ impl AttachPoints {
     }
 }
 
+// meters of boom per unit of `PrimaryCamera::distance`
+pub const CAMERA_BOOM: f32 = 5.0;
+
 // main camera entity
 #[derive(Component)]
 pub struct PrimaryCamera {
@@ -140,6 +153,14 @@ impl Default for PrimaryCamera {
     }
 }
 
+impl PrimaryCamera {
+    // re-read yaw, pitch and roll from the camera's transform on the next update, after something
+    // other than the camera controller has moved it
+    pub fn request_resync(&mut self) {
+        self.initialized = false;
+    }
+}
+
 #[derive(Resource)]
 pub struct PrimaryPlayerRes(pub Entity);
 
@@ -179,9 +200,15 @@ pub struct PreviousLogin {
 // app configuration
 #[derive(Serialize, Deserialize, Resource, Clone)]
 pub struct AppConfig {
+    // missing in configs written before versioning
+    #[serde(default)]
+    pub version: u32,
     pub server: String,
     pub location: IVec2,
     pub previous_login: Option<PreviousLogin>,
+    // how long a new login remains valid before signing in again is required, at least a day
+    #[serde(default = "default_login_lifetime_days")]
+    pub login_lifetime_days: u32,
     pub graphics: GraphicsSettings,
     pub audio: AudioSettings,
     pub scene_threads: usize,
@@ -202,18 +229,60 @@ pub struct AppConfig {
     pub default_permissions: HashMap<PermissionType, PermissionValue>,
     pub realm_permissions: HashMap<String, HashMap<PermissionType, PermissionValue>>,
     pub scene_permissions: HashMap<String, HashMap<PermissionType, PermissionValue>>,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    #[serde(default)]
+    pub pause_on_focus_loss: FocusLossSetting,
+    #[serde(default)]
+    pub performance: PerformanceSettings,
+    #[serde(default)]
+    pub metrics: MetricsSettings,
+    #[serde(default)]
+    pub nametags: NametagSetting,
+    // addresses of blocked players
+    #[serde(default)]
+    pub blocked_users: BTreeSet<String>,
+    #[serde(default)]
+    pub cursor_toggle_key: CursorToggleKey,
+    // local overrides per realm address, taking precedence over what the realm suggests
+    #[serde(default)]
+    pub realm_settings: HashMap<String, RealmSettingOverrides>,
+    #[serde(default)]
+    pub features: FeatureSettings,
+    #[serde(default)]
+    pub afk: AfkSettings,
+    #[serde(default)]
+    pub output_log: OutputLogSettings,
+    // keep the third-person camera over loaded scenes
+    #[serde(default)]
+    pub constrain_camera: bool,
+    #[serde(default)]
+    pub camera: CameraSettings,
+}
+
+// half the available cores, leaving the rest for the main and render threads
+fn default_scene_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|cores| (cores.get() / 2).clamp(2, 8))
+        .unwrap_or(4)
+}
+
+fn default_login_lifetime_days() -> u32 {
+    30
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            version: crate::config::CONFIG_VERSION,
             server: "https://sdk-team-cdn.decentraland.org/ipfs/goerli-plaza-main-latest"
                 .to_owned(),
             location: IVec2::new(78, -7),
             previous_login: None,
+            login_lifetime_days: default_login_lifetime_days(),
             graphics: Default::default(),
             audio: Default::default(),
-            scene_threads: 4,
+            scene_threads: default_scene_threads(),
             scene_load_distance: 50.0,
             scene_unload_extra_distance: 15.0,
             scene_imposter_distances: vec![150.0, 300.0, 600.0, 1200.0, 2400.0, 4800.0],
@@ -234,6 +303,150 @@ impl Default for AppConfig {
             default_permissions: Default::default(),
             realm_permissions: Default::default(),
             scene_permissions: Default::default(),
+            network: Default::default(),
+            pause_on_focus_loss: Default::default(),
+            performance: Default::default(),
+            metrics: Default::default(),
+            nametags: Default::default(),
+            blocked_users: Default::default(),
+            cursor_toggle_key: Default::default(),
+            realm_settings: Default::default(),
+            features: Default::default(),
+            afk: Default::default(),
+            output_log: Default::default(),
+            constrain_camera: false,
+            camera: Default::default(),
+        }
+    }
+}
+
+// settings a realm suggests (in its `about` configuration) or the user sets locally for a
+// realm. unset fields keep the user's base setting
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RealmSettingOverrides {
+    #[serde(default, alias = "sceneLoadDistance")]
+    pub scene_load_distance: Option<f32>,
+    #[serde(default, alias = "sceneUnloadExtraDistance")]
+    pub scene_unload_extra_distance: Option<f32>,
+    #[serde(default, alias = "maxAvatars")]
+    pub max_avatars: Option<usize>,
+    #[serde(default, alias = "shadowSettings")]
+    pub shadow_settings: Option<ShadowSetting>,
+}
+
+impl RealmSettingOverrides {
+    // the overridable values of the config, all set
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            scene_load_distance: Some(config.scene_load_distance),
+            scene_unload_extra_distance: Some(config.scene_unload_extra_distance),
+            max_avatars: Some(config.max_avatars),
+            shadow_settings: Some(config.graphics.shadow_settings),
+        }
+    }
+
+    // fields of self, falling back to those of `other`
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            scene_load_distance: self.scene_load_distance.or(other.scene_load_distance),
+            scene_unload_extra_distance: self
+                .scene_unload_extra_distance
+                .or(other.scene_unload_extra_distance),
+            max_avatars: self.max_avatars.or(other.max_avatars),
+            shadow_settings: self.shadow_settings.or(other.shadow_settings),
+        }
+    }
+
+    pub fn apply(&self, config: &mut AppConfig) {
+        if let Some(distance) = self.scene_load_distance {
+            config.scene_load_distance = distance;
+        }
+        if let Some(distance) = self.scene_unload_extra_distance {
+            config.scene_unload_extra_distance = distance;
+        }
+        if let Some(max_avatars) = self.max_avatars {
+            config.max_avatars = max_avatars;
+        }
+        if let Some(shadows) = self.shadow_settings {
+            config.graphics.shadow_settings = shadows;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+// the realm overrides in effect. they are applied on top of `AppConfig` when settings are
+// applied, and never written to it, so the user's base settings are kept when leaving
+#[derive(Resource, Default, Debug)]
+pub struct RealmSettings {
+    pub realm: String,
+    pub overrides: RealmSettingOverrides,
+}
+
+impl RealmSettings {
+    pub fn set_realm(&mut self, realm: &str, config: &AppConfig, suggested: RealmSettingOverrides) {
+        let local = config
+            .realm_settings
+            .get(realm)
+            .cloned()
+            .unwrap_or_default();
+        self.realm = realm.to_owned();
+        self.overrides = local.or(suggested);
+    }
+
+    // the user's explicit changes win, so drop overrides for settings that changed between
+    // `before` and `after` (from `RealmSettingOverrides::from_config`). returns true if any
+    // were dropped
+    pub fn user_changed(
+        &mut self,
+        before: &RealmSettingOverrides,
+        after: &RealmSettingOverrides,
+    ) -> bool {
+        fn drop_changed<T: PartialEq>(
+            value: &mut Option<T>,
+            before: &Option<T>,
+            after: &Option<T>,
+        ) -> bool {
+            before != after && value.take().is_some()
+        }
+
+        let overrides = &mut self.overrides;
+        // evaluated in full, not short-circuited
+        [
+            drop_changed(
+                &mut overrides.scene_load_distance,
+                &before.scene_load_distance,
+                &after.scene_load_distance,
+            ),
+            drop_changed(
+                &mut overrides.scene_unload_extra_distance,
+                &before.scene_unload_extra_distance,
+                &after.scene_unload_extra_distance,
+            ),
+            drop_changed(
+                &mut overrides.max_avatars,
+                &before.max_avatars,
+                &after.max_avatars,
+            ),
+            drop_changed(
+                &mut overrides.shadow_settings,
+                &before.shadow_settings,
+                &after.shadow_settings,
+            ),
+        ]
+        .contains(&true)
+    }
+
+    // the config with overrides applied
+    pub fn effective<'a>(&self, config: &'a AppConfig) -> Cow<'a, AppConfig> {
+        if self.overrides.is_empty() {
+            Cow::Borrowed(config)
+        } else {
+            let mut config = config.clone();
+            self.overrides.apply(&mut config);
+            Cow::Owned(config)
         }
     }
 }
@@ -263,6 +476,16 @@ impl AppConfig {
             .unwrap_or_else(|| Self::default_permission(ty))
     }
 
+    // imposter distances limited by the imposter setting
+    pub fn active_imposter_distances(&self) -> &[f32] {
+        let levels = self
+            .graphics
+            .scene_imposters
+            .max_levels()
+            .min(self.scene_imposter_distances.len());
+        &self.scene_imposter_distances[..levels]
+    }
+
     pub const fn default_permission(ty: PermissionType) -> PermissionValue {
         match ty {
             PermissionType::MovePlayer
@@ -289,10 +512,45 @@ pub struct GraphicsSettings {
     // removed until bevy window resizing bugs are fixed
     // pub fullscreen_res: FullscreenResSetting,
     pub fog: FogSetting,
+    #[serde(default)]
+    pub fog_params: FogParams,
     pub bloom: BloomSetting,
     pub ssao: SsaoSetting,
     pub oob: f32,
     pub ambient_brightness: i32,
+    #[serde(default)]
+    pub loading_skeleton: LoadingSkeletonSetting,
+    // how far static imposters of unloaded scenes are drawn
+    #[serde(default)]
+    pub scene_imposters: SceneImposterSetting,
+    // how scene-provided colors are interpreted
+    #[serde(default)]
+    pub color_space: ColorSpace,
+    #[serde(default)]
+    pub post_processing: PostProcessingSettings,
+    #[serde(default)]
+    pub skybox: SkyboxSetting,
+    // camera near clip distance in meters. larger values improve depth precision
+    #[serde(default = "default_camera_near")]
+    pub camera_near: f32,
+    // camera far distance in meters, beyond which nothing is drawn
+    #[serde(default = "default_camera_far")]
+    pub camera_far: f32,
+    // memory for scene textures in megabytes. once exceeded, new textures are downscaled. 0 for unlimited
+    #[serde(default = "default_texture_budget_mb")]
+    pub texture_budget_mb: usize,
+}
+
+fn default_camera_near() -> f32 {
+    0.1
+}
+
+fn default_camera_far() -> f32 {
+    100000.0
+}
+
+fn default_texture_budget_mb() -> usize {
+    2048
 }
 
 impl Default for GraphicsSettings {
@@ -308,14 +566,366 @@ impl Default for GraphicsSettings {
             window: WindowSetting::Windowed,
             // fullscreen_res: FullscreenResSetting(UVec2::new(1280,720)),
             fog: FogSetting::Atmospheric,
+            fog_params: Default::default(),
             bloom: BloomSetting::Low,
             ssao: SsaoSetting::Off,
             oob: 2.0,
             ambient_brightness: 50,
+            loading_skeleton: Default::default(),
+            scene_imposters: Default::default(),
+            color_space: Default::default(),
+            post_processing: Default::default(),
+            skybox: Default::default(),
+            camera_near: default_camera_near(),
+            camera_far: default_camera_far(),
+            texture_budget_mb: default_texture_budget_mb(),
         }
     }
 }
 
+// the sky drawn behind the world
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub enum SkyboxSetting {
+    // procedural atmosphere following the sun, unless the realm specifies a cubemap
+    #[default]
+    Procedural,
+    // path or url of a cubemap image with the six faces stacked vertically. overrides the realm
+    Cubemap(String),
+}
+
+// adjustments applied to the rendered 3d view. ui is drawn afterwards and is unaffected
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct PostProcessingSettings {
+    // exposure offset in stops
+    #[serde(default)]
+    pub brightness: f32,
+    // multiplier, 1.0 for unchanged
+    #[serde(default = "one")]
+    pub contrast: f32,
+    // multiplier, 1.0 for unchanged. higher values darken the midtones
+    #[serde(default = "one")]
+    pub gamma: f32,
+    #[serde(default)]
+    pub tonemapping: TonemappingSetting,
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+impl Default for PostProcessingSettings {
+    fn default() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 1.0,
+            gamma: 1.0,
+            tonemapping: Default::default(),
+        }
+    }
+}
+
+impl PostProcessingSettings {
+    pub fn color_grading(&self) -> ColorGrading {
+        // base grading for the default look, which the user adjustments are relative to
+        let section = ColorGradingSection {
+            gamma: 0.75 * self.gamma,
+            contrast: self.contrast,
+            ..Default::default()
+        };
+        ColorGrading {
+            global: ColorGradingGlobal {
+                exposure: -0.5 + self.brightness,
+                ..default()
+            },
+            shadows: section,
+            midtones: section,
+            highlights: section,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TonemappingSetting {
+    Off,
+    Basic,
+    #[default]
+    High,
+}
+
+impl From<TonemappingSetting> for Tonemapping {
+    fn from(value: TonemappingSetting) -> Self {
+        match value {
+            TonemappingSetting::Off => Tonemapping::None,
+            TonemappingSetting::Basic => Tonemapping::Reinhard,
+            TonemappingSetting::High => Tonemapping::TonyMcMapface,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NetworkSettings {
+    // delay applied to remote avatar positions to smooth out irregular packet arrival
+    pub jitter_buffer_ms: u32,
+    #[serde(default)]
+    pub avatar_motion: AvatarMotionMode,
+    #[serde(default)]
+    pub voice_chat: VoiceChatSetting,
+    #[serde(default)]
+    pub island_change: IslandChangeSetting,
+    // send positions quantized and delta encoded between full precision keyframes. only
+    // this explorer decodes them, other clients see the keyframes
+    #[serde(default)]
+    pub compress_positions: bool,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            jitter_buffer_ms: 100,
+            avatar_motion: Default::default(),
+            voice_chat: Default::default(),
+            island_change: Default::default(),
+            compress_positions: false,
+        }
+    }
+}
+
+// when disabled, comms still connect for chat and positions, but no voice tracks are
+// published or subscribed
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum VoiceChatSetting {
+    #[default]
+    Enabled,
+    Disabled,
+}
+
+// when the position reported to archipelago follows the player, which decides the comms island
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum IslandChangeSetting {
+    #[default]
+    Immediate,
+    // only once the player stops moving, to avoid changing island repeatedly during fast travel
+    WhenStopped,
+}
+
+// how remote avatar positions are displayed between packets
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AvatarMotionMode {
+    // smooth, delayed by the jitter buffer
+    #[default]
+    Interpolate,
+    // predicted from the last known velocity, lower latency but may overshoot
+    Extrapolate,
+}
+
+// operator metrics export, disabled by default
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct MetricsSettings {
+    // serve metrics as json at `http://127.0.0.1:<port>/`, 0 to disable
+    pub http_port: u16,
+    // log metrics every this many seconds, 0 to disable
+    pub log_interval: f32,
+}
+
+impl MetricsSettings {
+    pub fn enabled(&self) -> bool {
+        self.http_port != 0 || self.log_interval > 0.0
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PerformanceSettings {
+    // max crdt updates applied from scenes per frame, 0 for unlimited
+    pub scene_update_budget: usize,
+    // how strongly scene update frequency falls off with distance, 0 to update all scenes equally
+    #[serde(default = "default_scene_priority_exponent")]
+    pub scene_priority_exponent: f32,
+    // scenes further than this from the player tick at `scene_throttle_rate`, 0 to disable
+    #[serde(default = "default_scene_throttle_distance")]
+    pub scene_throttle_distance: f32,
+    // ticks per second for throttled scenes
+    #[serde(default = "default_scene_throttle_rate")]
+    pub scene_throttle_rate: f32,
+    // max ui nodes per scene, further nodes are ignored. 0 for unlimited
+    #[serde(default = "default_max_scene_ui_nodes")]
+    pub max_scene_ui_nodes: usize,
+}
+
+fn default_scene_priority_exponent() -> f32 {
+    2.0
+}
+
+fn default_scene_throttle_distance() -> f32 {
+    64.0
+}
+
+fn default_scene_throttle_rate() -> f32 {
+    10.0
+}
+
+fn default_max_scene_ui_nodes() -> usize {
+    5000
+}
+
+impl Default for PerformanceSettings {
+    fn default() -> Self {
+        Self {
+            scene_update_budget: 2000,
+            scene_priority_exponent: default_scene_priority_exponent(),
+            max_scene_ui_nodes: default_max_scene_ui_nodes(),
+            scene_throttle_distance: default_scene_throttle_distance(),
+            scene_throttle_rate: default_scene_throttle_rate(),
+        }
+    }
+}
+
+// expensive sdk features that can be switched off for all scenes, regardless of what the
+// scenes request
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeatureSettings {
+    // video players, textures show a placeholder instead
+    pub video: bool,
+    // audio sources and audio streams
+    pub scene_audio: bool,
+    // attaching scene entities to avatars
+    pub avatar_attach: bool,
+}
+
+impl Default for FeatureSettings {
+    fn default() -> Self {
+        Self {
+            video: true,
+            scene_audio: true,
+            avatar_attach: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AfkSettings {
+    // seconds without input before the player is marked as away, 0 to disable
+    pub timeout_secs: u32,
+    // run at the background frame rate while away
+    pub throttle: bool,
+}
+
+impl Default for AfkSettings {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 300,
+            throttle: true,
+        }
+    }
+}
+
+// how far the third-person camera can zoom, in meters from the player's head
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CameraSettings {
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    // zooming in closer than this goes to first person, and hides the player
+    pub first_person_zoom: f32,
+    // the current zoom, kept between sessions
+    pub zoom: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            min_zoom: 0.0,
+            max_zoom: 100.0,
+            first_person_zoom: 0.5,
+            zoom: CAMERA_BOOM,
+        }
+    }
+}
+
+// chat, console output and optionally scene logs written to rotating files on disk, see
+// `system_ui::output_log`. disabled by default
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct OutputLogSettings {
+    pub enabled: bool,
+    pub scene_logs: SceneLogFilter,
+    // a new file is started each day and when the current one reaches this size
+    pub max_file_mb: u32,
+    // the oldest files are removed when all of them exceed this size
+    pub max_total_mb: u32,
+}
+
+impl Default for OutputLogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            scene_logs: SceneLogFilter::Off,
+            max_file_mb: 10,
+            max_total_mb: 100,
+        }
+    }
+}
+
+// which scene log messages to include in the output log
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SceneLogFilter {
+    #[default]
+    Off,
+    SystemErrors,
+    Errors,
+    All,
+}
+
+// whether the primary user is currently away
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AfkState {
+    pub afk: bool,
+}
+
+// photo mode detaches the camera from the player and hides the ui, see `user_input::photo_mode`
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PhotoMode {
+    pub active: bool,
+}
+
+// what to do when the window is not focused
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FocusLossSetting {
+    #[default]
+    Run,
+    PauseScenes,
+    PauseScenesAndComms,
+}
+
+// which other players' name tags are shown
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum NametagSetting {
+    All,
+    #[default]
+    FriendsAndSpeaking,
+    Friends,
+    Speaking,
+    Off,
+}
+
+impl NametagSetting {
+    pub fn visible(&self, is_friend: bool, is_speaking: bool) -> bool {
+        match self {
+            NametagSetting::All => true,
+            NametagSetting::FriendsAndSpeaking => is_friend || is_speaking,
+            NametagSetting::Friends => is_friend,
+            NametagSetting::Speaking => is_speaking,
+            NametagSetting::Off => false,
+        }
+    }
+}
+
+// current background pause state, derived from window focus and `FocusLossSetting`
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BackgroundPause {
+    pub scenes: bool,
+    pub comms: bool,
+}
+
 #[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct AudioSettings {
     pub master: i32, // 0-100
@@ -323,6 +933,11 @@ pub struct AudioSettings {
     pub scene: i32,
     pub system: i32,
     pub avatar: i32,
+    // saved per-player voice gains in percent (0-200), keyed by address
+    #[serde(default)]
+    pub voice_gains: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub microphone: MicrophoneSetting,
 }
 
 impl Default for AudioSettings {
@@ -333,10 +948,20 @@ impl Default for AudioSettings {
             scene: 100,
             system: 100,
             avatar: 100,
+            voice_gains: Default::default(),
+            microphone: Default::default(),
         }
     }
 }
 
+// disabled is a hard mute: the mic device is never opened, whatever the mic button state
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum MicrophoneSetting {
+    #[default]
+    Enabled,
+    Disabled,
+}
+
 impl AudioSettings {
     pub fn voice(&self) -> f32 {
         (self.voice * self.master) as f32 / 10_000.0
@@ -352,6 +977,28 @@ impl AudioSettings {
     }
 }
 
+// limits the imposter detail levels from `scene_imposter_distances` that are drawn, which
+// bounds the distance, and so the number of imposters kept in memory
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SceneImposterSetting {
+    Off,
+    Near,
+    Medium,
+    #[default]
+    Far,
+}
+
+impl SceneImposterSetting {
+    pub fn max_levels(&self) -> usize {
+        match self {
+            SceneImposterSetting::Off => 0,
+            SceneImposterSetting::Near => 3,
+            SceneImposterSetting::Medium => 4,
+            SceneImposterSetting::Far => usize::MAX,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ShadowSetting {
     Off,
@@ -379,6 +1026,14 @@ pub enum WindowSetting {
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub struct FullscreenResSetting(pub UVec2);
 
+// placeholder shown over scenes that are still loading
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LoadingSkeletonSetting {
+    Off,
+    #[default]
+    On,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum FogSetting {
     Off,
@@ -386,6 +1041,40 @@ pub enum FogSetting {
     Atmospheric,
 }
 
+// fog tuning, used when fog is enabled
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct FogParams {
+    // multiplier on the default density, which fades scenes in around the load distance
+    #[serde(default = "one")]
+    pub density: f32,
+    // srgb color. when unset the color follows the scene lighting
+    #[serde(default)]
+    pub color: Option<[f32; 3]>,
+    // distances in meters for a linear fade, replacing the density based falloff when set.
+    // start defaults to 0 and end to the visibility distance
+    #[serde(default)]
+    pub start: Option<f32>,
+    #[serde(default)]
+    pub end: Option<f32>,
+}
+
+impl FogParams {
+    // density multiplier range offered by the fog density setting
+    pub const MIN_DENSITY: f32 = 0.1;
+    pub const MAX_DENSITY: f32 = 4.0;
+}
+
+impl Default for FogParams {
+    fn default() -> Self {
+        Self {
+            density: 1.0,
+            color: None,
+            start: None,
+            end: None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum BloomSetting {
     Off,
@@ -511,6 +1200,8 @@ pub struct SceneDisplay {
 #[serde(rename_all = "camelCase")]
 pub struct SceneMeta {
     pub display: Option<SceneDisplay>,
+    // empty for scenes with no script
+    #[serde(default)]
     pub main: String,
     pub scene: SceneMetaScene,
     pub runtime_version: Option<String>,
@@ -540,6 +1231,10 @@ pub enum PermissionType {
     Fetch,
     Websocket,
     OpenUrl,
+    // scene-initiated mic access. the player's own voice chat mic doesn't need it
+    Microphone,
+    SceneMessages,
+    Notifications,
 }
 
 #[derive(Resource)]
@@ -596,6 +1291,14 @@ pub struct ShowSettingsEvent(pub SettingsTab);
 #[derive(Event, Clone)]
 pub struct ShowProfileEvent(pub Address);
 
+// open the context menu for another player at the given window position
+#[derive(Event, Clone)]
+pub struct ShowAvatarMenuEvent {
+    pub address: Address,
+    pub name: String,
+    pub position: Vec2,
+}
+
 #[derive(Event, Clone)]
 pub struct SystemAudio(pub String);
 
@@ -666,3 +1369,42 @@ pub struct CursorLocked(pub bool);
 
 #[derive(Resource, Default)]
 pub struct CursorLocks(pub HashSet<&'static str>);
+
+// in look mode the cursor is locked and mouse motion turns the camera, in ui mode the cursor
+// is free. dialogs and the console suspend look mode, which resumes when they close.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CursorMode {
+    #[default]
+    Ui,
+    Look,
+}
+
+impl CursorMode {
+    pub fn toggle(&mut self) {
+        *self = match self {
+            CursorMode::Ui => CursorMode::Look,
+            CursorMode::Look => CursorMode::Ui,
+        };
+    }
+}
+
+// key that switches between look mode and ui mode
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CursorToggleKey {
+    Off,
+    #[default]
+    Tab,
+    CapsLock,
+    Backslash,
+}
+
+impl CursorToggleKey {
+    pub fn key(&self) -> Option<KeyCode> {
+        match self {
+            CursorToggleKey::Off => None,
+            CursorToggleKey::Tab => Some(KeyCode::Tab),
+            CursorToggleKey::CapsLock => Some(KeyCode::CapsLock),
+            CursorToggleKey::Backslash => Some(KeyCode::Backslash),
+        }
+    }
+}
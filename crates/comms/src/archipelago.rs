@@ -1,9 +1,20 @@
 use anyhow::{anyhow, bail};
-use async_tungstenite::tungstenite::{client::IntoClientRequest, http::HeaderValue};
+use async_tungstenite::tungstenite::{
+    client::IntoClientRequest,
+    http::{HeaderValue, Uri},
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
 use bevy::{
     prelude::*,
     tasks::{IoTaskPool, Task},
-    utils::HashMap,
+    utils::{HashMap, Instant},
 };
 use futures_lite::future;
 use futures_util::{pin_mut, select, stream::StreamExt, FutureExt, SinkExt};
@@ -12,10 +23,16 @@ use prost::Message;
 use serde_json::json;
 use tokio::sync::mpsc::{Receiver, Sender};
 
-use common::rpc::{RpcCall, RpcEventSender};
+use common::{
+    rpc::{RpcCall, RpcEventSender},
+    structs::{AppConfig, IslandChangeSetting},
+};
 use wallet::Wallet;
 
-use crate::{AdapterManager, Transport, TransportType};
+use crate::{
+    packet_log::{log_packet, PacketDirection},
+    recv_until, AdapterManager, Outbound, Transport, TransportStats, TransportType, ROUNDTRIP_FREQ,
+};
 
 use super::NetworkMessage;
 
@@ -44,6 +61,7 @@ impl Plugin for ArchipelagoPlugin {
                 reconnect_websocket,
                 start_archipelago,
                 manage_islands,
+                update_island_change_setting,
             ),
         );
 
@@ -67,13 +85,91 @@ pub struct StartIsland {
 pub struct IslandChannel {
     sender: tokio::sync::mpsc::Sender<StartIsland>,
     receiver: tokio::sync::mpsc::Receiver<StartIsland>,
+    // `IslandChangeSetting::WhenStopped`, shared with running connections
+    settle: Arc<AtomicBool>,
 }
 
 impl Default for IslandChannel {
     fn default() -> Self {
         let (sender, receiver) = tokio::sync::mpsc::channel(100);
-        Self { sender, receiver }
+        Self {
+            sender,
+            receiver,
+            settle: Default::default(),
+        }
+    }
+}
+
+fn update_island_change_setting(config: Res<AppConfig>, channel: Res<IslandChannel>) {
+    if config.is_changed() {
+        channel.settle.store(
+            config.network.island_change == IslandChangeSetting::WhenStopped,
+            Ordering::Relaxed,
+        );
+    }
+}
+
+// the player must stay within this distance for `SETTLE_TIME` to count as stopped
+const SETTLE_DISTANCE: f32 = 16.0;
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+// the position reported in heartbeats, which archipelago uses to assign the island. the
+// first position is reported straight away, so the initial island matches where the player
+// spawned. when settling, later positions are only reported once the player has stopped
+#[derive(Default)]
+pub struct HeartbeatPosition {
+    reported: Option<Vec3>,
+    // where the player has been since when, while waiting to settle
+    candidate: Option<(Vec3, Instant)>,
+}
+
+impl HeartbeatPosition {
+    pub fn update(&mut self, position: Vec3, now: Instant, settle: bool) -> Vec3 {
+        let Some(reported) = self.reported.filter(|_| settle) else {
+            self.candidate = None;
+            return *self.reported.insert(position);
+        };
+
+        match self.candidate {
+            Some((anchor, since)) if anchor.distance(position) < SETTLE_DISTANCE => {
+                if now.duration_since(since) >= SETTLE_TIME {
+                    self.reported = Some(position);
+                    return position;
+                }
+            }
+            _ => self.candidate = Some((position, now)),
+        }
+        reported
+    }
+}
+
+// convert an archipelago address into a websocket url.
+// http/https map to ws/wss, bare hosts default to wss, anything else is rejected
+pub fn archipelago_ws_url(address: &str) -> Result<String, anyhow::Error> {
+    let address = address.trim();
+    if address.is_empty() {
+        bail!("empty address");
+    }
+
+    let (scheme, remainder) = match address.split_once("://") {
+        Some((scheme, remainder)) => {
+            let scheme = match scheme.to_lowercase().as_str() {
+                "http" | "ws" => "ws",
+                "https" | "wss" => "wss",
+                other => bail!("unsupported scheme `{other}`"),
+            };
+            (scheme, remainder)
+        }
+        None => ("wss", address),
+    };
+
+    let url = format!("{scheme}://{remainder}");
+    let uri = Uri::try_from(url.as_str())?;
+    if uri.host().unwrap_or_default().is_empty() {
+        bail!("missing host");
     }
+
+    Ok(url)
 }
 
 #[derive(Component)]
@@ -87,7 +183,7 @@ pub struct ArchipelagoTransport {
 pub struct ArchipelagoConnection(Task<(Receiver<NetworkMessage>, anyhow::Error)>);
 
 pub fn start_archipelago(mut commands: Commands, mut archi_events: EventReader<StartArchipelago>) {
-    if let Some(ev) = archi_events.read().last() {
+    for ev in archi_events.read() {
         info!("starting archipelago protocol");
         let (sender, receiver) = tokio::sync::mpsc::channel(1000);
 
@@ -97,6 +193,7 @@ pub fn start_archipelago(mut commands: Commands, mut archi_events: EventReader<S
                 sender,
                 foreign_aliases: Default::default(),
             },
+            TransportStats::default(),
             ArchipelagoTransport {
                 address: ev.address.to_owned(),
                 receiver: Some(receiver),
@@ -152,11 +249,14 @@ fn manage_islands(
 #[allow(clippy::type_complexity)]
 fn connect_websocket(
     mut commands: Commands,
-    mut new_websockets: Query<(Entity, &mut ArchipelagoTransport), Without<ArchipelagoConnection>>,
+    mut new_websockets: Query<
+        (Entity, &mut ArchipelagoTransport, &TransportStats),
+        Without<ArchipelagoConnection>,
+    >,
     wallet: Res<Wallet>,
     island_channel: Res<IslandChannel>,
 ) {
-    for (transport_id, mut new_transport) in new_websockets.iter_mut() {
+    for (transport_id, mut new_transport, stats) in new_websockets.iter_mut() {
         let remote_address = new_transport.address.to_owned();
         let wallet = wallet.clone();
         let receiver = new_transport.receiver.take().unwrap();
@@ -167,6 +267,8 @@ fn connect_websocket(
             wallet,
             receiver,
             sender,
+            island_channel.settle.clone(),
+            stats.clone(),
         ));
         commands
             .entity(transport_id)
@@ -179,11 +281,12 @@ fn reconnect_websocket(
         Entity,
         &mut ArchipelagoTransport,
         &mut ArchipelagoConnection,
+        &TransportStats,
     )>,
     wallet: Res<Wallet>,
     island_channel: Res<IslandChannel>,
 ) {
-    for (transport_id, mut transport, mut conn) in websockets.iter_mut() {
+    for (transport_id, mut transport, mut conn, stats) in websockets.iter_mut() {
         if transport.retries < 3 {
             if conn.0.is_finished() {
                 transport.retries += 1;
@@ -198,6 +301,8 @@ fn reconnect_websocket(
                     wallet,
                     receiver,
                     sender,
+                    island_channel.settle.clone(),
+                    stats.clone(),
                 ));
                 conn.0 = task;
             }
@@ -215,10 +320,20 @@ async fn archipelago_handler(
     wallet: Wallet,
     mut receiver: Receiver<NetworkMessage>,
     sender: Sender<StartIsland>,
+    settle: Arc<AtomicBool>,
+    stats: TransportStats,
 ) -> (Receiver<NetworkMessage>, anyhow::Error) {
-    let res =
-        archipelago_handler_inner(transport_id, remote_address, wallet, &mut receiver, sender)
-            .await;
+    let res = archipelago_handler_inner(
+        transport_id,
+        remote_address,
+        wallet,
+        &mut receiver,
+        sender,
+        settle,
+        stats.clone(),
+    )
+    .await;
+    stats.set_connected(false);
     (receiver, res.err().unwrap_or(anyhow!("connection closed")))
 }
 
@@ -228,16 +343,12 @@ async fn archipelago_handler_inner(
     wallet: Wallet,
     receiver: &mut Receiver<NetworkMessage>,
     sender: Sender<StartIsland>,
+    settle: Arc<AtomicBool>,
+    stats: TransportStats,
 ) -> Result<(), anyhow::Error> {
     debug!(">> stream connect async : {remote_address}");
 
-    let remote_address = if remote_address.starts_with("ws:") || remote_address.starts_with("wss:")
-    {
-        remote_address
-    } else {
-        format!("wss://{remote_address}")
-    };
-
+    // address is validated and normalized in `AdapterManager::connect`
     let mut request = remote_address.into_client_request()?;
     request.headers_mut().append(
         "Sec-WebSocket-Protocol",
@@ -260,6 +371,8 @@ async fn archipelago_handler_inner(
     };
     stream.send(ident.encode_to_vec().into()).await?;
     debug!(">> challeng request sent: {ident:?}");
+    // the server's first reply gives a roundtrip estimate
+    stats.start_roundtrip();
 
     // challenge / welcome
     loop {
@@ -267,6 +380,7 @@ async fn archipelago_handler_inner(
             bail!("stream closed unexpectedly awaiting challenge")
         };
         let response = response?;
+        stats.end_roundtrip();
         let response = ServerPacket::decode(response.into_data().as_slice())?;
         let Some(message) = response.message else {
             bail!("received empty packet")
@@ -308,32 +422,73 @@ async fn archipelago_handler_inner(
         }
     }
 
+    stats.set_connected(true);
+
     let (mut write, mut read) = stream.split();
+    let write_stats = stats.clone();
+
+    // until the first island is assigned, the reported position always follows the player, so
+    // a teleport straight after connecting isn't held back by settling
+    let assigned = Arc::new(AtomicBool::new(false));
+    let assigned_write = assigned.clone();
 
     // wrap and transmit outbound heartbeat
     let f_write = async move {
-        while let Some(next) = receiver.recv().await {
-            let Ok(rfc4::Packet {
-                message: Some(rfc4::packet::Message::Position(pos)),
-                ..
-            }) = DclReader::new(&next.data).read()
-            else {
+        let read_position = |next: &NetworkMessage| {
+            log_packet(PacketDirection::Outbound, "archipelago", None, &next.data);
+            match DclReader::new(&next.data).read() {
+                Ok(rfc4::Packet {
+                    message: Some(rfc4::packet::Message::Position(pos)),
+                    ..
+                }) => Some(Vec3::new(pos.position_x, pos.position_y, pos.position_z)),
                 // skip non-position messages
+                _ => None,
+            }
+        };
+
+        let mut heartbeat = HeartbeatPosition::default();
+        // pings keep the roundtrip current
+        let mut ping_at = Instant::now() + ROUNDTRIP_FREQ;
+        loop {
+            let next = match recv_until(receiver, ping_at).await {
+                Outbound::Message(next) => next,
+                Outbound::Due => {
+                    write_stats.start_roundtrip();
+                    write
+                        .send(async_tungstenite::tungstenite::Message::Ping(
+                            Default::default(),
+                        ))
+                        .await?;
+                    ping_at = Instant::now() + ROUNDTRIP_FREQ;
+                    continue;
+                }
+                Outbound::Closed => break,
+            };
+            // only the latest position matters. positions queue up while connecting, and the
+            // oldest may be from before spawning or teleporting
+            let mut latest = read_position(&next);
+            while let Ok(queued) = receiver.try_recv() {
+                latest = read_position(&queued).or(latest);
+            }
+            let Some(position) = latest else {
                 continue;
             };
 
+            let settling = settle.load(Ordering::Relaxed) && assigned_write.load(Ordering::Relaxed);
+            let position = heartbeat.update(position, Instant::now(), settling);
             let packet = ClientPacket {
                 message: Some(client_packet::Message::Heartbeat(Heartbeat {
                     position: Some(Position {
-                        x: pos.position_x,
-                        y: pos.position_y,
-                        z: pos.position_z,
+                        x: position.x,
+                        y: position.y,
+                        z: position.z,
                     }),
                     desired_room: None,
                 })),
             };
             let mut buf = Vec::default();
             packet.encode(&mut buf)?;
+            write_stats.record_sent(buf.len(), false);
             write.send(buf.into()).await?;
         }
 
@@ -347,7 +502,13 @@ async fn archipelago_handler_inner(
 
         while let Some(next) = read.next().await {
             let next = next?;
-            let next = ServerPacket::decode(next.into_data().as_slice())?;
+            if next.is_pong() {
+                stats.end_roundtrip();
+                continue;
+            }
+            let next = next.into_data();
+            stats.record_received(next.len(), false);
+            let next = ServerPacket::decode(next.as_slice())?;
             let Some(message) = next.message else {
                 bail!("received empty packet")
             };
@@ -359,6 +520,7 @@ async fn archipelago_handler_inner(
                     continue;
                 }
                 server_packet::Message::IslandChanged(change) => {
+                    assigned.store(true, Ordering::Relaxed);
                     sender
                         .send(StartIsland {
                             owner: transport_id,
@@ -1,75 +1,151 @@
 use bevy::prelude::*;
 
-use common::structs::PrimaryUser;
+use common::structs::{AppConfig, BackgroundPause, PrimaryUser};
 use dcl_component::{
     proto_components::kernel::comms::rfc4,
     transform_and_parent::{DclQuat, DclTranslation},
 };
 
-use super::{NetworkMessage, Transport};
+use super::{
+    position_compression::{PositionEncoder, PositionGrid},
+    NetworkMessage, Transport,
+};
 
 pub struct BroadcastPositionPlugin;
 
 impl Plugin for BroadcastPositionPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<BackgroundPause>();
+        app.init_resource::<PositionBroadcastRate>();
+        app.init_resource::<PositionGrid>();
         app.add_systems(Update, broadcast_position);
     }
 }
 
+// unchanged positions are repeated this often, for peers that missed them
 const STATIC_FREQ: f64 = 1.0;
-const DYNAMIC_FREQ: f64 = 0.1;
 
+// positions are sent at most `max_hz` times a second, updates in between are coalesced so only
+// the latest is sent. 0 sends every update
+#[derive(Resource)]
+pub struct PositionBroadcastRate {
+    pub max_hz: f64,
+}
+
+impl Default for PositionBroadcastRate {
+    fn default() -> Self {
+        Self { max_hz: 10.0 }
+    }
+}
+
+// holds the latest position until the rate allows another send
+#[derive(Default)]
+pub struct PositionCoalescer {
+    pending: Option<(rfc4::Position, bool)>,
+    last_flush: Option<f64>,
+}
+
+impl PositionCoalescer {
+    // replaces any waiting position. the send stays reliable if any update it replaces was
+    pub fn submit(&mut self, position: rfc4::Position, unreliable: bool) {
+        let unreliable = unreliable
+            && self
+                .pending
+                .as_ref()
+                .is_none_or(|(_, unreliable)| *unreliable);
+        self.pending = Some((position, unreliable));
+    }
+
+    // the waiting position and its unreliable flag, if the rate allows a send at `now`
+    pub fn flush(&mut self, now: f64, max_hz: f64) -> Option<(rfc4::Position, bool)> {
+        if max_hz > 0.0
+            && self
+                .last_flush
+                .is_some_and(|last| now - last < 1.0 / max_hz)
+        {
+            return None;
+        }
+        let pending = self.pending.take()?;
+        self.last_flush = Some(now);
+        Some(pending)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn broadcast_position(
     player: Query<&GlobalTransform, With<PrimaryUser>>,
     transports: Query<&Transport>,
+    // the last position submitted for sending
     mut last_position: Local<(Vec3, Quat)>,
     mut last_sent: Local<f64>,
     mut last_index: Local<u32>,
     time: Res<Time>,
+    pause: Res<BackgroundPause>,
+    config: Res<AppConfig>,
+    rate: Res<PositionBroadcastRate>,
+    grid: Res<PositionGrid>,
+    mut encoder: Local<PositionEncoder>,
+    mut coalescer: Local<PositionCoalescer>,
 ) {
+    // transports stay connected so we can resume immediately
+    if pause.comms {
+        return;
+    }
+
     let Ok(player) = player.get_single() else {
         return;
     };
     let time = time.elapsed_seconds_f64();
-    let elapsed = time - *last_sent;
-    if elapsed < DYNAMIC_FREQ {
-        return;
-    }
 
     let (_, rotation, translation) = player.to_scale_rotation_translation();
-    if elapsed < STATIC_FREQ && (translation, rotation) == *last_position {
-        return;
+    if (translation, rotation) != *last_position || time - *last_sent >= STATIC_FREQ {
+        let dcl_position = DclTranslation::from_bevy_translation(translation);
+        let dcl_rotation = DclQuat::from_bevy_quat(rotation);
+        coalescer.submit(
+            rfc4::Position {
+                index: 0,
+                position_x: dcl_position.0[0],
+                position_y: dcl_position.0[1],
+                position_z: dcl_position.0[2],
+                rotation_x: dcl_rotation.0[0],
+                rotation_y: dcl_rotation.0[1],
+                rotation_z: dcl_rotation.0[2],
+                rotation_w: dcl_rotation.0[3],
+            },
+            true,
+        );
+        *last_position = (translation, rotation);
     }
 
-    let dcl_position = DclTranslation::from_bevy_translation(translation);
-    let dcl_rotation = DclQuat::from_bevy_quat(rotation);
-    let position_packet = rfc4::Position {
-        index: *last_index,
-        position_x: dcl_position.0[0],
-        position_y: dcl_position.0[1],
-        position_z: dcl_position.0[2],
-        rotation_x: dcl_rotation.0[0],
-        rotation_y: dcl_rotation.0[1],
-        rotation_z: dcl_rotation.0[2],
-        rotation_w: dcl_rotation.0[3],
+    let Some((mut position_packet, unreliable)) = coalescer.flush(time, rate.max_hz) else {
+        return;
     };
+    // indexes are only used by sent positions
+    position_packet.index = *last_index;
 
     debug!("sending position: {position_packet:?}");
+    let (message, unreliable) = if config.network.compress_positions {
+        let (message, keyframe) = encoder.encode(position_packet, grid.grid_mm, time);
+        (message, unreliable && !keyframe)
+    } else {
+        (rfc4::packet::Message::Position(position_packet), unreliable)
+    };
     let packet = rfc4::Packet {
-        message: Some(rfc4::packet::Message::Position(position_packet)),
+        message: Some(message),
         protocol_version: 999,
     };
 
     for transport in transports.iter() {
-        if let Err(e) = transport
-            .sender
-            .try_send(NetworkMessage::unreliable(&packet))
-        {
+        let message = if unreliable {
+            NetworkMessage::unreliable(&packet)
+        } else {
+            NetworkMessage::reliable(&packet)
+        };
+        if let Err(e) = transport.sender.try_send(message) {
             warn!("failed to update to transport: {e}");
         }
     }
 
-    *last_position = (translation, rotation);
     *last_index += 1;
     *last_sent = time;
 }
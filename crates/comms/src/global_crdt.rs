@@ -1,4 +1,10 @@
-use std::ops::RangeInclusive;
+use std::{
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
 use bevy::{
     prelude::*,
@@ -8,7 +14,8 @@ use bevy::{
 use bimap::BiMap;
 use common::{
     rpc::{RpcCall, RpcEventSender},
-    structs::{AttachPoints, AudioDecoderError},
+    structs::{AppConfig, AttachPoints, AudioDecoderError},
+    util::AsH160,
 };
 use ethers_core::types::Address;
 use kira::sound::streaming::StreamingSoundData;
@@ -30,8 +37,34 @@ use dcl_component::{
     DclReader, DclWriter, SceneComponentId, SceneEntityId, ToDclWriter,
 };
 
+use crate::position_compression::decompress;
+
 const FOREIGN_PLAYER_RANGE: RangeInclusive<u16> = 6..=406;
 
+// a position index this far behind the last applied index is assumed to come from
+// a restarted sender rather than being a late packet
+pub(crate) const STALE_INDEX_WINDOW: u32 = 100;
+
+// tracks the last applied index of a peer's unreliable updates, so that
+// duplicate or out-of-order packets don't overwrite newer state
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SequenceTracker {
+    last: Option<u32>,
+}
+
+impl SequenceTracker {
+    // returns true and records the index if it is newer than the last accepted index
+    pub fn accept(&mut self, index: u32) -> bool {
+        if let Some(last) = self.last {
+            if last.wrapping_sub(index) < STALE_INDEX_WINDOW {
+                return false;
+            }
+        }
+        self.last = Some(index);
+        true
+    }
+}
+
 pub struct GlobalCrdtPlugin;
 
 impl Plugin for GlobalCrdtPlugin {
@@ -43,6 +76,7 @@ impl Plugin for GlobalCrdtPlugin {
         app.insert_resource(GlobalCrdtState {
             ext_receiver,
             ext_sender,
+            intercept_sender: None,
             int_sender,
             context: CrdtContext::new(SceneId::DUMMY, "Global Crdt".into(), false, false),
             store: Default::default(),
@@ -52,7 +86,13 @@ impl Plugin for GlobalCrdtPlugin {
         let (sender, receiver) = tokio::sync::broadcast::channel(1_000);
         // leak the receiver so it never gets dropped
         Box::leak(Box::new(receiver));
-        app.insert_resource(LocalAudioSource { sender });
+        app.insert_resource(LocalAudioSource {
+            sender,
+            publishing: Default::default(),
+        });
+        app.init_resource::<SpeakingPeers>();
+        app.init_resource::<BlockedPeers>();
+        app.add_systems(Startup, load_blocked_peers);
 
         app.add_systems(Update, process_transport_updates);
         app.add_systems(Update, despawn_players);
@@ -65,6 +105,8 @@ impl Plugin for GlobalCrdtPlugin {
 pub enum PlayerMessage {
     PlayerData(rfc4::packet::Message),
     AudioStream(Box<StreamingSoundData<AudioDecoderError>>),
+    // the peer started or stopped speaking on voice chat
+    Speaking(bool),
 }
 
 impl std::fmt::Debug for PlayerMessage {
@@ -72,6 +114,7 @@ impl std::fmt::Debug for PlayerMessage {
         match self {
             Self::PlayerData(arg0) => f.debug_tuple("PlayerData").field(arg0).finish(),
             Self::AudioStream(_) => f.debug_tuple("AudioStream").finish(),
+            Self::Speaking(arg0) => f.debug_tuple("Speaking").field(arg0).finish(),
         }
     }
 }
@@ -83,12 +126,45 @@ pub struct PlayerUpdate {
     pub address: Address,
 }
 
+// addresses of remote players currently speaking on voice chat
+#[derive(Resource, Default, Debug)]
+pub struct SpeakingPeers(pub HashSet<Address>);
+
+// addresses of players the user has blocked. their chat is dropped, their voice is muted
+// and their avatar is hidden
+#[derive(Resource, Default, Debug)]
+pub struct BlockedPeers(pub HashSet<Address>);
+
+impl BlockedPeers {
+    // block or unblock an address, keeping the saved list in the config up to date
+    pub fn set_blocked(&mut self, address: Address, blocked: bool, config: &mut AppConfig) {
+        let key = format!("{address:#x}");
+        if blocked {
+            self.0.insert(address);
+            config.blocked_users.insert(key);
+        } else {
+            self.0.remove(&address);
+            config.blocked_users.remove(&key);
+        }
+    }
+}
+
+fn load_blocked_peers(config: Res<AppConfig>, mut blocked: ResMut<BlockedPeers>) {
+    blocked.0 = config
+        .blocked_users
+        .iter()
+        .flat_map(|address| address.as_h160())
+        .collect();
+}
+
 #[derive(Resource)]
 pub struct GlobalCrdtState {
     // receiver from sockets
     ext_receiver: mpsc::Receiver<PlayerUpdate>,
     // sender for sockets to post to
     ext_sender: mpsc::Sender<PlayerUpdate>,
+    // replaces `ext_sender` for sockets while inbound packets are intercepted
+    intercept_sender: Option<mpsc::Sender<PlayerUpdate>>,
     // sender for broadcast updates
     int_sender: broadcast::Sender<Vec<u8>>,
     // receiver for broadcast updates (we keep it to ensure it doesn't get closed)
@@ -100,7 +176,19 @@ pub struct GlobalCrdtState {
 impl GlobalCrdtState {
     // get a channel to which updates can be sent
     pub fn get_sender(&self) -> mpsc::Sender<PlayerUpdate> {
-        self.ext_sender.clone()
+        self.intercept_sender
+            .clone()
+            .unwrap_or_else(|| self.ext_sender.clone())
+    }
+
+    // route updates from sockets connected after this call to the returned receiver instead.
+    // the caller is responsible for passing them on to the returned sender
+    pub fn intercept_inbound(
+        &mut self,
+    ) -> (mpsc::Receiver<PlayerUpdate>, mpsc::Sender<PlayerUpdate>) {
+        let (sender, receiver) = mpsc::channel(1000);
+        self.intercept_sender = Some(sender);
+        (receiver, self.ext_sender.clone())
     }
 
     // get a channel from which crdt updates can be received
@@ -160,15 +248,53 @@ pub struct LocalAudioFrame {
     pub samples_per_channel: u32,
 }
 
+impl LocalAudioFrame {
+    // sent when capture stops, so transports can stop publishing
+    pub fn end_of_capture() -> Self {
+        Self {
+            data: Vec::default(),
+            sample_rate: 0,
+            num_channels: 0,
+            samples_per_channel: 0,
+        }
+    }
+
+    pub fn is_end_of_capture(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
 #[derive(Resource)]
 pub struct LocalAudioSource {
     pub sender: tokio::sync::broadcast::Sender<LocalAudioFrame>,
+    // number of connections currently publishing a mic track
+    pub publishing: Arc<AtomicUsize>,
 }
 
 impl LocalAudioSource {
     pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<LocalAudioFrame> {
         self.sender.subscribe()
     }
+
+    pub fn is_publishing(&self) -> bool {
+        self.publishing.load(Ordering::Relaxed) > 0
+    }
+}
+
+// counts towards `LocalAudioSource::publishing` while alive
+pub struct PublishedMic(Arc<AtomicUsize>);
+
+impl PublishedMic {
+    pub fn new(publishing: &Arc<AtomicUsize>) -> Self {
+        publishing.fetch_add(1, Ordering::Relaxed);
+        Self(publishing.clone())
+    }
+}
+
+impl Drop for PublishedMic {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 #[derive(Event)]
@@ -214,7 +340,12 @@ pub fn process_transport_updates(
         HashMap<String, tokio::sync::mpsc::UnboundedSender<(String, Vec<u8>)>>,
     >,
     mut subscribers: EventReader<RpcCall>,
+    mut position_sequences: Local<HashMap<Address, SequenceTracker>>,
+    mut position_keyframes: Local<HashMap<Address, rfc4::Position>>,
+    mut speaking: ResMut<SpeakingPeers>,
+    blocked: Res<BlockedPeers>,
 ) {
+    let _span = debug_span!("comms::process_updates").entered();
     // gather any event receivers
     for ev in subscribers.read() {
         match ev {
@@ -229,6 +360,8 @@ pub fn process_transport_updates(
     }
     string_senders.retain(|_, s| !s.is_closed());
     binary_senders.retain(|_, s| !s.is_closed());
+    position_sequences.retain(|address, _| state.lookup.contains_left(address));
+    position_keyframes.retain(|address, _| state.lookup.contains_left(address));
 
     let mut created_this_frame: HashMap<
         Address,
@@ -304,13 +437,63 @@ pub fn process_transport_updates(
                 (new_entity, next_free, audio_sender)
             };
 
+        // keep full positions as keyframes for compressed positions, and expand compressed
+        // positions against their keyframe
+        let message = match update.message {
+            PlayerMessage::PlayerData(Message::Position(pos)) => {
+                if position_keyframes
+                    .get(&update.address)
+                    .map_or(true, |keyframe| {
+                        keyframe.index.wrapping_sub(pos.index) >= STALE_INDEX_WINDOW
+                    })
+                {
+                    position_keyframes.insert(update.address, pos.clone());
+                }
+                PlayerMessage::PlayerData(Message::Position(pos))
+            }
+            PlayerMessage::PlayerData(Message::CompressedPosition(compressed)) => {
+                let Some(keyframe) = position_keyframes
+                    .get(&update.address)
+                    .filter(|keyframe| keyframe.index == compressed.keyframe_index)
+                else {
+                    debug!(
+                        "ignoring compressed position {} from {:#x}: missing keyframe {}",
+                        compressed.index, update.address, compressed.keyframe_index
+                    );
+                    continue;
+                };
+                PlayerMessage::PlayerData(Message::Position(decompress(keyframe, &compressed)))
+            }
+            message => message,
+        };
+
         // process update
-        match update.message {
+        match message {
             PlayerMessage::AudioStream(audio) => {
                 // pass through
                 let _ = audio_channel.blocking_send(*audio);
             }
+            PlayerMessage::Speaking(is_speaking) => {
+                if is_speaking {
+                    speaking.0.insert(update.address);
+                } else {
+                    speaking.0.remove(&update.address);
+                }
+            }
             PlayerMessage::PlayerData(Message::Position(pos)) => {
+                // positions are unreliable, ignore anything older than what we've applied
+                if !position_sequences
+                    .entry(update.address)
+                    .or_default()
+                    .accept(pos.index)
+                {
+                    debug!(
+                        "ignoring stale position {} from {:#x}",
+                        pos.index, update.address
+                    );
+                    continue;
+                }
+
                 let dcl_transform = DclTransformAndParent {
                     translation: DclTranslation([pos.position_x, pos.position_y, pos.position_z]),
                     rotation: DclQuat([
@@ -368,6 +551,9 @@ pub fn process_transport_updates(
                 });
             }
             PlayerMessage::PlayerData(Message::Chat(chat)) => {
+                if blocked.0.contains(&update.address) {
+                    continue;
+                }
                 chat_events.send(ChatEvent {
                     sender: entity,
                     timestamp: chat.timestamp,
@@ -421,6 +607,8 @@ pub fn process_transport_updates(
             PlayerMessage::PlayerData(Message::Movement(_)) => (),
             PlayerMessage::PlayerData(Message::PlayerEmote(_)) => (),
             PlayerMessage::PlayerData(Message::SceneEmote(_)) => (),
+            // expanded to positions above
+            PlayerMessage::PlayerData(Message::CompressedPosition(_)) => (),
         }
     }
 }
@@ -429,6 +617,7 @@ fn despawn_players(
     mut commands: Commands,
     players: Query<(Entity, &ForeignPlayer)>,
     mut state: ResMut<GlobalCrdtState>,
+    mut speaking: ResMut<SpeakingPeers>,
     time: Res<Time>,
 ) {
     for (entity, player) in players.iter() {
@@ -440,6 +629,7 @@ fn despawn_players(
 
             state.delete_entity(player.scene_id);
             state.lookup.remove_by_right(&entity);
+            speaking.0.remove(&player.address);
         }
     }
 }
@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use common::structs::AvatarMotionMode;
+
+use crate::global_crdt::STALE_INDEX_WINDOW;
+
+// max samples retained per peer
+const MAX_SAMPLES: usize = 32;
+// consecutive samples further apart than this are treated as a teleport and snapped
+pub const TELEPORT_DISTANCE: f32 = 25.0;
+// max time to extrapolate past the newest sample, when packets are late or in extrapolation mode
+pub const MAX_EXTRAPOLATION: f32 = 0.25;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PositionSample {
+    pub index: u32,
+    // local receive time
+    pub time: f32,
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+// per-peer buffer of received positions, played back with a fixed delay
+// so that irregular packet arrival doesn't cause jitter
+#[derive(Component, Debug, Default)]
+pub struct PositionJitterBuffer {
+    samples: VecDeque<PositionSample>,
+    // index of the newest sample that has been discarded, anything at or below this is stale
+    discarded_index: Option<u32>,
+    pub delay: f32,
+    pub mode: AvatarMotionMode,
+}
+
+impl PositionJitterBuffer {
+    pub fn new(delay: f32, mode: AvatarMotionMode) -> Self {
+        Self {
+            delay,
+            mode,
+            ..Default::default()
+        }
+    }
+
+    // add a sample. duplicates and samples older than already played-out data are ignored.
+    // returns true if the sample was accepted
+    pub fn push(&mut self, mut sample: PositionSample) -> bool {
+        // indices wrap, so they are compared relative to each other. a sample too far behind the
+        // newest to be a late packet means the sender restarted, so start again from it
+        if let Some(newest) = self
+            .samples
+            .back()
+            .map(|s| s.index)
+            .or(self.discarded_index)
+        {
+            let behind = newest.wrapping_sub(sample.index);
+            if (STALE_INDEX_WINDOW..=u32::MAX / 2).contains(&behind) {
+                self.samples.clear();
+                self.discarded_index = None;
+            }
+        }
+
+        if self
+            .discarded_index
+            .is_some_and(|ix| ix.wrapping_sub(sample.index) < STALE_INDEX_WINDOW)
+        {
+            return false;
+        }
+
+        let position = self
+            .samples
+            .partition_point(|s| (s.index.wrapping_sub(sample.index) as i32) < 0);
+        if self
+            .samples
+            .get(position)
+            .is_some_and(|s| s.index == sample.index)
+        {
+            return false;
+        }
+
+        // keep sample times monotonic with respect to index. late arrivals are
+        // placed between their neighbours since their receive time is meaningless
+        let prev = position.checked_sub(1).and_then(|ix| self.samples.get(ix));
+        match (prev, self.samples.get(position)) {
+            (Some(prev), Some(next)) => sample.time = (prev.time + next.time) * 0.5,
+            (None, Some(next)) => sample.time = sample.time.min(next.time),
+            (Some(prev), None) => sample.time = sample.time.max(prev.time),
+            (None, None) => (),
+        }
+
+        self.samples.insert(position, sample);
+        while self.samples.len() > MAX_SAMPLES {
+            self.discard_front();
+        }
+        true
+    }
+
+    fn discard_front(&mut self) {
+        if let Some(sample) = self.samples.pop_front() {
+            self.discarded_index = Some(sample.index);
+        }
+    }
+
+    pub fn newest(&self) -> Option<&PositionSample> {
+        self.samples.back()
+    }
+
+    // get the position to display at time `now`
+    pub fn sample(&mut self, now: f32) -> Option<(Vec3, Quat)> {
+        match self.mode {
+            AvatarMotionMode::Interpolate => self.interpolate(now - self.delay),
+            AvatarMotionMode::Extrapolate => {
+                while self.samples.len() > 2 {
+                    self.discard_front();
+                }
+                self.extrapolate(now)
+            }
+        }
+    }
+
+    fn interpolate(&mut self, render_time: f32) -> Option<(Vec3, Quat)> {
+        // drop samples that can no longer be used, keeping one before the render time
+        // and at least two overall for extrapolation
+        while self.samples.len() > 2 && self.samples[1].time <= render_time {
+            self.discard_front();
+        }
+
+        let first = self.samples.front()?;
+        if self.samples.len() == 1 {
+            return Some((first.translation, first.rotation));
+        }
+
+        match self.samples.iter().position(|s| s.time > render_time) {
+            Some(0) => Some((first.translation, first.rotation)),
+            Some(ix) => {
+                let a = &self.samples[ix - 1];
+                let b = &self.samples[ix];
+                if a.translation.distance(b.translation) > TELEPORT_DISTANCE {
+                    return Some((b.translation, b.rotation));
+                }
+                let span = b.time - a.time;
+                let fraction = if span > 0.0 {
+                    ((render_time - a.time) / span).clamp(0.0, 1.0)
+                } else {
+                    1.0
+                };
+                Some((
+                    a.translation.lerp(b.translation, fraction),
+                    a.rotation.slerp(b.rotation, fraction),
+                ))
+            }
+            // late packet: extrapolate briefly
+            None => self.extrapolate(render_time),
+        }
+    }
+
+    // predict the position at `time` from the last known velocity, limited to
+    // `MAX_EXTRAPOLATION` past the newest sample
+    fn extrapolate(&self, time: f32) -> Option<(Vec3, Quat)> {
+        let last = self.samples.back()?;
+        let Some(prev) = self.samples.iter().rev().nth(1) else {
+            return Some((last.translation, last.rotation));
+        };
+        let span = last.time - prev.time;
+        if span <= 0.0 || prev.translation.distance(last.translation) > TELEPORT_DISTANCE {
+            return Some((last.translation, last.rotation));
+        }
+        let velocity = (last.translation - prev.translation) / span;
+        let overshoot = (time - last.time).clamp(0.0, MAX_EXTRAPOLATION);
+        Some((last.translation + velocity * overshoot, last.rotation))
+    }
+}
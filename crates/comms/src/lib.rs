@@ -1,10 +1,14 @@
 pub mod archipelago;
 pub mod broadcast_position;
 pub mod global_crdt;
+pub mod jitter_buffer;
 
 #[cfg(feature = "livekit")]
 pub mod livekit_room;
 
+pub mod network_sim;
+pub mod packet_log;
+pub mod position_compression;
 pub mod preview;
 pub mod profile;
 pub mod signed_login;
@@ -12,21 +16,30 @@ pub mod signed_login;
 mod test;
 pub mod websocket_room;
 
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 use bevy::{ecs::system::SystemParam, prelude::*};
 use bimap::BiMap;
 use ethers_core::types::Address;
 use preview::PreviewPlugin;
 use signed_login::{SignedLoginPlugin, StartSignedLogin};
-use tokio::sync::mpsc::Sender;
+use tokio::sync::mpsc::{Receiver, Sender};
 
 use dcl_component::{DclWriter, ToDclWriter};
 use ipfs::CurrentRealm;
+use network_sim::NetworkSimPlugin;
+use packet_log::PacketLogPlugin;
 use wallet::Wallet;
 
 use self::{
-    archipelago::{ArchipelagoPlugin, StartArchipelago},
+    archipelago::{archipelago_ws_url, ArchipelagoPlugin, StartArchipelago},
     broadcast_position::BroadcastPositionPlugin,
     global_crdt::GlobalCrdtPlugin,
     profile::UserProfilePlugin,
@@ -38,8 +51,10 @@ use self::livekit_room::{LivekitPlugin, StartLivekit};
 
 pub mod chat_marker_things {
     pub const EMOTE: char = '␐';
+    // followed by 1 when the sender goes away, 0 when they return
+    pub const AFK: char = '␚';
 
-    pub const ALL: [char; 3] = [EMOTE, '␑', '␆'];
+    pub const ALL: [char; 4] = [EMOTE, '␑', '␆', AFK];
 }
 
 pub struct CommsPlugin;
@@ -54,6 +69,8 @@ impl Plugin for CommsPlugin {
             GlobalCrdtPlugin,
             UserProfilePlugin,
             PreviewPlugin,
+            PacketLogPlugin,
+            NetworkSimPlugin,
         ));
 
         #[cfg(feature = "livekit")]
@@ -68,7 +85,7 @@ pub struct TransportAlias {
     pub alias: u32,
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TransportType {
     WebsocketRoom,
     Livekit,
@@ -100,6 +117,33 @@ impl NetworkMessage {
     }
 }
 
+// the next outbound message, or `Due` once `deadline` passes first
+pub(crate) enum Outbound {
+    Message(NetworkMessage),
+    Due,
+    Closed,
+}
+
+pub(crate) async fn recv_until(
+    receiver: &mut Receiver<NetworkMessage>,
+    deadline: Instant,
+) -> Outbound {
+    let due = async_std::task::sleep(deadline.saturating_duration_since(Instant::now()));
+    futures_lite::future::or(
+        async {
+            receiver
+                .recv()
+                .await
+                .map_or(Outbound::Closed, Outbound::Message)
+        },
+        async {
+            due.await;
+            Outbound::Due
+        },
+    )
+    .await
+}
+
 #[derive(Component)]
 pub struct Transport {
     pub transport_type: TransportType,
@@ -107,37 +151,226 @@ pub struct Transport {
     pub foreign_aliases: BiMap<u32, Address>,
 }
 
+// how often adapters measure the roundtrip again while connected
+pub const ROUNDTRIP_FREQ: Duration = Duration::from_secs(5);
+
+// live connection counters for a transport, shared with the adapter's network task which
+// updates them as messages are sent and received. byte counts exclude the transport's framing
+#[derive(Component, Clone, Default)]
+pub struct TransportStats(Arc<TransportCounters>);
+
+#[derive(Default)]
+struct TransportCounters {
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    reliable_messages: AtomicU64,
+    unreliable_messages: AtomicU64,
+    // microseconds, 0 if not measured
+    roundtrip_us: AtomicU64,
+    // unix time in milliseconds, 0 while disconnected
+    connected_since_ms: AtomicU64,
+    // when the measurement in progress started
+    roundtrip_start: Mutex<Option<Instant>>,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct TransportStatsSnapshot {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    // sent and received
+    pub reliable_messages: u64,
+    pub unreliable_messages: u64,
+    pub roundtrip: Option<Duration>,
+    pub connected_since: Option<SystemTime>,
+}
+
+impl TransportStats {
+    pub fn record_sent(&self, bytes: usize, unreliable: bool) {
+        self.0.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.count_message(unreliable);
+    }
+
+    pub fn record_received(&self, bytes: usize, unreliable: bool) {
+        self.0
+            .bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+        self.count_message(unreliable);
+    }
+
+    fn count_message(&self, unreliable: bool) {
+        let messages = if unreliable {
+            &self.0.unreliable_messages
+        } else {
+            &self.0.reliable_messages
+        };
+        messages.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set_roundtrip(&self, roundtrip: Duration) {
+        self.0
+            .roundtrip_us
+            .store((roundtrip.as_micros() as u64).max(1), Ordering::Relaxed);
+    }
+
+    // start a roundtrip measurement, completed by `end_roundtrip` when the reply arrives
+    pub fn start_roundtrip(&self) {
+        *self.0.roundtrip_start.lock().unwrap() = Some(Instant::now());
+    }
+
+    pub fn end_roundtrip(&self) {
+        let start = self.0.roundtrip_start.lock().unwrap().take();
+        if let Some(start) = start {
+            self.set_roundtrip(start.elapsed());
+        }
+    }
+
+    pub fn set_connected(&self, connected: bool) {
+        let now_ms = connected
+            .then(|| SystemTime::now().duration_since(UNIX_EPOCH).ok())
+            .flatten()
+            .map_or(0, |since_epoch| (since_epoch.as_millis() as u64).max(1));
+        self.0.connected_since_ms.store(now_ms, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> TransportStatsSnapshot {
+        let counters = &self.0;
+        let roundtrip_us = counters.roundtrip_us.load(Ordering::Relaxed);
+        let connected_since_ms = counters.connected_since_ms.load(Ordering::Relaxed);
+        TransportStatsSnapshot {
+            bytes_sent: counters.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: counters.bytes_received.load(Ordering::Relaxed),
+            reliable_messages: counters.reliable_messages.load(Ordering::Relaxed),
+            unreliable_messages: counters.unreliable_messages.load(Ordering::Relaxed),
+            roundtrip: (roundtrip_us != 0).then(|| Duration::from_micros(roundtrip_us)),
+            connected_since: (connected_since_ms != 0)
+                .then(|| UNIX_EPOCH + Duration::from_millis(connected_since_ms)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct NetworkHealthSummary {
+    pub transports: usize,
+    pub connected: usize,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub reliable_messages: u64,
+    pub unreliable_messages: u64,
+    // the slowest measured transport
+    pub worst_roundtrip: Option<Duration>,
+}
+
+// connection stats aggregated over all live transports
+#[derive(SystemParam)]
+pub struct NetworkHealth<'w, 's> {
+    transports: Query<'w, 's, &'static TransportStats, With<Transport>>,
+}
+
+impl NetworkHealth<'_, '_> {
+    pub fn summary(&self) -> NetworkHealthSummary {
+        self.transports.iter().map(TransportStats::snapshot).fold(
+            NetworkHealthSummary::default(),
+            |summary, stats| NetworkHealthSummary {
+                transports: summary.transports + 1,
+                connected: summary.connected + stats.connected_since.is_some() as usize,
+                bytes_sent: summary.bytes_sent + stats.bytes_sent,
+                bytes_received: summary.bytes_received + stats.bytes_received,
+                reliable_messages: summary.reliable_messages + stats.reliable_messages,
+                unreliable_messages: summary.unreliable_messages + stats.unreliable_messages,
+                worst_roundtrip: summary.worst_roundtrip.max(stats.roundtrip),
+            },
+        )
+    }
+}
+
+// the realm's adapters, which may list several to run at once
+fn realm_adapters(realm: &CurrentRealm) -> Vec<String> {
+    let Some(comms) = realm.comms.as_ref() else {
+        debug!("missing comms!");
+        return Vec::default();
+    };
+
+    if let Some(adapter) = comms.adapter.as_ref() {
+        split_adapters(adapter)
+            .into_iter()
+            .map(|adapter| {
+                parse_adapter(adapter)
+                    .map(|(_, tail)| tail)
+                    .unwrap_or(adapter)
+                    .to_owned()
+            })
+            .collect()
+    } else if let Some(adapter) = comms.fixed_adapter.as_ref() {
+        split_adapters(adapter)
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect()
+    } else {
+        Vec::default()
+    }
+}
+
 fn process_realm_change(
     mut commands: Commands,
     realm: Res<CurrentRealm>,
     adapters: Query<Entity, With<Transport>>,
     mut manager: AdapterManager,
     wallet: Res<Wallet>,
+    mut current: Local<Option<(Address, Vec<String>)>>,
 ) {
-    if realm.is_changed() || wallet.is_changed() {
-        for adapter in adapters.iter() {
-            commands.entity(adapter).despawn_recursive();
-        }
+    if !realm.is_changed() && !wallet.is_changed() {
+        return;
+    }
 
-        if wallet.address().is_none() {
-            info!("disconnecting comms, no identity");
-            return;
-        }
+    let next = wallet
+        .address()
+        .map(|address| (address, realm_adapters(&realm)));
 
-        if let Some(comms) = realm.comms.as_ref() {
-            if let Some(adapter) = comms.adapter.as_ref() {
-                let real_adapter = adapter
-                    .split_once(':')
-                    .map(|(_, tail)| tail)
-                    .unwrap_or(adapter.as_str());
-                manager.connect(real_adapter);
-            } else if let Some(adapter) = comms.fixed_adapter.as_ref() {
-                manager.connect(adapter);
-            }
-        } else {
-            debug!("missing comms!");
+    // keep running connections when nothing comms-related changed
+    if next.is_some() && *current == next && !adapters.is_empty() {
+        debug!("comms adapters unchanged");
+        return;
+    }
+
+    for adapter in adapters.iter() {
+        commands.entity(adapter).despawn_recursive();
+    }
+
+    *current = next;
+    let Some((_, requested)) = current.as_ref() else {
+        info!("disconnecting comms, no identity");
+        return;
+    };
+
+    for adapter in requested {
+        manager.connect(adapter);
+    }
+}
+
+// split a list of adapters separated by `,` or `;`, dropping blanks and repeats
+pub fn split_adapters(adapters: &str) -> Vec<&str> {
+    let mut list = Vec::default();
+    for adapter in adapters.split([',', ';']).map(str::trim) {
+        if !adapter.is_empty() && !list.contains(&adapter) {
+            list.push(adapter);
         }
     }
+    list
+}
+
+// split an adapter string of the form `protocol:address`.
+// only the first colon is significant, so addresses containing schemes, ports,
+// ipv6 literals or query strings are passed through to the adapter unmodified.
+pub fn parse_adapter(adapter: &str) -> Option<(&str, &str)> {
+    let (protocol, address) = adapter.split_once(':')?;
+    if protocol.is_empty()
+        || !protocol
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return None;
+    }
+    Some((protocol, address))
 }
 
 #[derive(SystemParam)]
@@ -155,8 +388,16 @@ pub struct AdapterManager<'w, 's> {
 }
 
 impl AdapterManager<'_, '_> {
+    // connect each adapter in a `,` or `;` separated list
+    pub fn connect_all(&mut self, adapters: &str) -> Vec<Entity> {
+        split_adapters(adapters)
+            .into_iter()
+            .filter_map(|adapter| self.connect(adapter))
+            .collect()
+    }
+
     pub fn connect(&mut self, adapter: &str) -> Option<Entity> {
-        let Some((protocol, address)) = adapter.split_once(':') else {
+        let Some((protocol, address)) = parse_adapter(adapter) else {
             warn!("unrecognised adapter string: {adapter}");
             return None;
         };
@@ -189,10 +430,15 @@ impl AdapterManager<'_, '_> {
                 info!("comms offline");
             }
             "archipelago" => {
+                let address = match archipelago_ws_url(address) {
+                    Ok(address) => address,
+                    Err(e) => {
+                        warn!("invalid archipelago address `{address}`: {e}");
+                        return None;
+                    }
+                };
                 debug!("arch starting: {address}");
-                self.archipelago_events.send(StartArchipelago {
-                    address: address.to_owned(),
-                });
+                self.archipelago_events.send(StartArchipelago { address });
             }
             "fixed-adapter" => {
                 // fixed-adapter should be ignored and we use the tail as the full protocol:address
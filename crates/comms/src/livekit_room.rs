@@ -1,9 +1,12 @@
 // --server https://worlds-content-server.decentraland.org/world/shibu.dcl.eth --location 1,1
 
-use std::sync::Arc;
+use std::sync::{atomic::AtomicUsize, Arc};
 
 use async_tungstenite::tungstenite::http::Uri;
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use futures_lite::StreamExt;
 use livekit::{
     id::TrackSid,
@@ -13,7 +16,7 @@ use livekit::{
         audio_source::native::NativeAudioSource,
         prelude::{AudioFrame, AudioSourceOptions, RtcAudioSource},
     },
-    RoomOptions,
+    DataPacketKind, RoomOptions,
 };
 use prost::Message;
 use tokio::sync::{
@@ -21,13 +24,17 @@ use tokio::sync::{
     Mutex,
 };
 
-use common::{structs::AudioDecoderError, util::AsH160};
+use common::{
+    structs::{AppConfig, AudioDecoderError, VoiceChatSetting},
+    util::AsH160,
+};
 use dcl_component::proto_components::kernel::comms::rfc4;
 
 use crate::{
-    global_crdt::{LocalAudioFrame, LocalAudioSource, PlayerMessage},
+    global_crdt::{LocalAudioFrame, LocalAudioSource, PlayerMessage, PublishedMic},
+    packet_log::{log_packet, PacketDirection},
     profile::CurrentUserProfile,
-    Transport, TransportType,
+    Transport, TransportStats, TransportType, ROUNDTRIP_FREQ,
 };
 
 use super::{
@@ -39,7 +46,10 @@ pub struct LivekitPlugin;
 
 impl Plugin for LivekitPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (connect_livekit, start_livekit));
+        app.add_systems(
+            Update,
+            (connect_livekit, start_livekit, reconnect_on_voice_change),
+        );
         app.add_event::<StartLivekit>();
     }
 }
@@ -57,15 +67,18 @@ pub struct LivekitTransport {
     pub retries: usize,
 }
 
+// the connection task is running. `voice` is whether it was started with voice tracks
 #[derive(Component)]
-pub struct LivekitConnection;
+pub struct LivekitConnection {
+    pub voice: bool,
+}
 
 pub fn start_livekit(
     mut commands: Commands,
     mut room_events: EventReader<StartLivekit>,
     current_profile: Res<CurrentUserProfile>,
 ) {
-    if let Some(ev) = room_events.read().last() {
+    for ev in room_events.read() {
         info!("starting livekit protocol");
         let (sender, receiver) = tokio::sync::mpsc::channel(1000);
 
@@ -90,6 +103,7 @@ pub fn start_livekit(
                 sender,
                 foreign_aliases: Default::default(),
             },
+            TransportStats::default(),
             LivekitTransport {
                 address: ev.address.to_owned(),
                 receiver: Some(receiver),
@@ -102,32 +116,88 @@ pub fn start_livekit(
 #[allow(clippy::type_complexity)]
 fn connect_livekit(
     mut commands: Commands,
-    mut new_livekits: Query<(Entity, &mut LivekitTransport), Without<LivekitConnection>>,
+    mut new_livekits: Query<
+        (Entity, &mut LivekitTransport, &TransportStats),
+        Without<LivekitConnection>,
+    >,
     player_state: Res<GlobalCrdtState>,
     mic: Res<LocalAudioSource>,
+    config: Res<AppConfig>,
 ) {
-    for (transport_id, mut new_transport) in new_livekits.iter_mut() {
+    for (transport_id, mut new_transport, stats) in new_livekits.iter_mut() {
         debug!("spawn lk connect");
         let remote_address = new_transport.address.to_owned();
-        let receiver = new_transport.receiver.take().unwrap();
+        // taken already when restarting, until `start_livekit` replaces the transport
+        let Some(receiver) = new_transport.receiver.take() else {
+            continue;
+        };
         let sender = player_state.get_sender();
 
         let subscription = mic.subscribe();
+        let publishing = mic.publishing.clone();
+        let voice = config.network.voice_chat == VoiceChatSetting::Enabled;
+        if !voice {
+            info!("voice chat disabled, connecting livekit without audio");
+        }
+        let stats = stats.clone();
 
         std::thread::spawn(move || {
-            livekit_handler(transport_id, remote_address, receiver, sender, subscription)
+            livekit_handler(
+                transport_id,
+                remote_address,
+                receiver,
+                sender,
+                subscription,
+                voice,
+                publishing,
+                stats,
+            )
         });
 
-        commands.entity(transport_id).try_insert(LivekitConnection);
+        commands
+            .entity(transport_id)
+            .try_insert(LivekitConnection { voice });
     }
 }
 
+// restart connections when voice is toggled. the transport entity is kept, and replacing its
+// `Transport` closes the old channel, which ends the old connection
+fn reconnect_on_voice_change(
+    mut commands: Commands,
+    config: Res<AppConfig>,
+    connections: Query<(Entity, &LivekitTransport, &LivekitConnection)>,
+    mut start: EventWriter<StartLivekit>,
+    current_profile: Res<CurrentUserProfile>,
+) {
+    // `start_livekit` needs a profile, keep the current connection until there is one
+    if !config.is_changed() || current_profile.profile.is_none() {
+        return;
+    }
+
+    let voice = config.network.voice_chat == VoiceChatSetting::Enabled;
+    for (entity, transport, connection) in connections.iter() {
+        if connection.voice == voice {
+            continue;
+        }
+        info!("voice chat toggled, reconnecting livekit");
+        commands.entity(entity).remove::<LivekitConnection>();
+        start.send(StartLivekit {
+            entity,
+            address: transport.address.clone(),
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn livekit_handler(
     transport_id: Entity,
     remote_address: String,
     receiver: Receiver<NetworkMessage>,
     sender: Sender<PlayerUpdate>,
     mic: tokio::sync::broadcast::Receiver<LocalAudioFrame>,
+    voice: bool,
+    publishing: Arc<AtomicUsize>,
+    stats: TransportStats,
 ) {
     let receiver = Arc::new(Mutex::new(receiver));
 
@@ -138,9 +208,13 @@ fn livekit_handler(
             receiver.clone(),
             sender.clone(),
             mic.resubscribe(),
+            voice,
+            publishing.clone(),
+            stats.clone(),
         ) {
             warn!("livekit error: {e}");
         }
+        stats.set_connected(false);
         if receiver.blocking_lock().is_closed() {
             // caller closed the channel
             return;
@@ -149,12 +223,16 @@ fn livekit_handler(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn livekit_handler_inner(
     transport_id: Entity,
     remote_address: &str,
     app_rx: Arc<Mutex<Receiver<NetworkMessage>>>,
     sender: Sender<PlayerUpdate>,
     mut mic: tokio::sync::broadcast::Receiver<LocalAudioFrame>,
+    voice: bool,
+    publishing: Arc<AtomicUsize>,
+    stats: TransportStats,
 ) -> Result<(), anyhow::Error> {
     debug!(">> lk connect async : {remote_address}");
 
@@ -183,18 +261,35 @@ fn livekit_handler_inner(
     let rt2 = rt.clone();
 
     let task = rt.spawn(async move {
-        let (room, mut network_rx) = livekit::prelude::Room::connect(&address, &token, RoomOptions{ auto_subscribe: true, adaptive_stream: false, dynacast: false, ..Default::default() }).await.unwrap();
+        let (room, mut network_rx) = livekit::prelude::Room::connect(&address, &token, RoomOptions{ auto_subscribe: voice, adaptive_stream: false, dynacast: false, ..Default::default() }).await.unwrap();
+        stats.set_connected(true);
         let local_participant = room.local_participant();
 
         let mut native_source: Option<NativeAudioSource> = None;
-        let mut mic_sid: Option<TrackSid> = None;
+        // the published track, counted in `LocalAudioSource::publishing` until unpublished or
+        // the connection ends
+        let mut mic_sid: Option<(TrackSid, PublishedMic)> = None;
 
+        // without voice the mic track is never published
         rt2.spawn(async move {
+            if !voice {
+                return;
+            }
             while let Ok(frame) = mic.recv().await {
+                if frame.is_end_of_capture() {
+                    // don't leave a silent track published while the mic is off
+                    if let Some((sid, _)) = mic_sid.take() {
+                        if let Err(e) = local_participant.unpublish_track(&sid).await {
+                            warn!("error unpublishing mic track: {e}");
+                        }
+                    }
+                    native_source = None;
+                    continue;
+                }
                 let data = frame.data.iter().map(|f| (f * i16::MAX as f32) as i16).collect();
                 if native_source.as_ref().map_or(true, |ns| ns.sample_rate() != frame.sample_rate || ns.num_channels() != frame.num_channels) {
                     // update track
-                    if let Some(sid) = mic_sid.take() {
+                    if let Some((sid, _)) = mic_sid.take() {
                         if let Err(e) = local_participant.unpublish_track(&sid).await {
                             warn!("error unpublishing previous mic track: {e}");
                         }
@@ -211,7 +306,8 @@ fn livekit_handler_inner(
                         None
                     ));
                     let mic_track = LocalTrack::Audio(LocalAudioTrack::create_audio_track("mic", RtcAudioSource::Native(new_source.clone())));
-                    mic_sid = Some(local_participant.publish_track(mic_track, TrackPublishOptions{ source: TrackSource::Microphone, ..Default::default() }).await.unwrap().sid());
+                    let sid = local_participant.publish_track(mic_track, TrackPublishOptions{ source: TrackSource::Microphone, ..Default::default() }).await.unwrap().sid();
+                    mic_sid = Some((sid, PublishedMic::new(&publishing)));
                     warn!("set sid");
                 }
                 if let Err(e) = native_source.as_mut().unwrap().capture_frame(&AudioFrame {
@@ -226,6 +322,11 @@ fn livekit_handler_inner(
         });
 
         let mut app_rx = app_rx.lock().await;
+        // remote participants currently speaking
+        let local_identity = room.local_participant().identity();
+        let mut speakers = HashSet::default();
+        // the roundtrip comes from the peer connections' active candidate pairs
+        let mut roundtrip_interval = tokio::time::interval(ROUNDTRIP_FREQ);
         'stream: loop {
             tokio::select!(
                 incoming = network_rx.recv() => {
@@ -236,8 +337,10 @@ fn livekit_handler_inner(
                     };
 
                     match incoming {
-                        livekit::RoomEvent::DataReceived { payload, participant, .. } => {
+                        livekit::RoomEvent::DataReceived { payload, participant, kind, .. } => {
                             if let Some(address) = participant.and_then(|p| p.identity().0.as_str().as_h160()) {
+                                log_packet(PacketDirection::Inbound, "livekit", Some(address), &payload);
+                                stats.record_received(payload.len(), matches!(kind, DataPacketKind::Lossy));
                                 let packet = match rfc4::Packet::decode(payload.as_slice()) {
                                     Ok(packet) => packet,
                                     Err(e) => {
@@ -314,6 +417,26 @@ fn livekit_handler_inner(
                                 }
                             }
                         }
+                        livekit::RoomEvent::ActiveSpeakersChanged { speakers: active } => {
+                            let active: HashSet<_> = active.iter()
+                                .map(|p| p.identity())
+                                .filter(|identity| identity != &local_identity)
+                                .flat_map(|identity| identity.0.as_str().as_h160())
+                                .collect();
+                            let started = active.difference(&speakers).map(|address| (*address, true));
+                            let stopped = speakers.difference(&active).map(|address| (*address, false));
+                            for (address, speaking) in started.chain(stopped).collect::<Vec<_>>() {
+                                if let Err(e) = sender.send(PlayerUpdate {
+                                    transport_id,
+                                    message: PlayerMessage::Speaking(speaking),
+                                    address,
+                                }).await {
+                                    warn!("app pipe broken ({e}), existing loop");
+                                    break 'stream;
+                                }
+                            }
+                            speakers = active;
+                        }
                         _ => { debug!("Event: {:?}", incoming); }
                     };
                 }
@@ -323,12 +446,28 @@ fn livekit_handler_inner(
                         break 'stream;
                     };
 
+                    log_packet(PacketDirection::Outbound, "livekit", None, &outgoing.data);
+                    stats.record_sent(outgoing.data.len(), outgoing.unreliable);
                     let packet = livekit::DataPacket { payload: outgoing.data, topic: None, reliable: !outgoing.unreliable, destination_identities: Default::default() };
                     if let Err(_e) = room.local_participant().publish_data(packet).await {
                         // debug!("outgoing failed: {_e}; not exiting loop though since it often fails at least once or twice at the start...");
                         break 'stream;
                     };
                 }
+                _ = roundtrip_interval.tick() => {
+                    let Ok(session) = room.get_stats().await else {
+                        continue;
+                    };
+                    let roundtrip = session.subscriber_stats.iter().chain(session.publisher_stats.iter()).find_map(|stats| match stats {
+                        livekit::webrtc::stats::RtcStats::CandidatePair(pair) if pair.candidate_pair.nominated && pair.candidate_pair.current_round_trip_time > 0.0 => {
+                            Some(pair.candidate_pair.current_round_trip_time)
+                        }
+                        _ => None,
+                    });
+                    if let Some(roundtrip) = roundtrip {
+                        stats.set_roundtrip(std::time::Duration::from_secs_f64(roundtrip));
+                    }
+                }
             );
         }
 
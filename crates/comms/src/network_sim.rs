@@ -0,0 +1,175 @@
+// developer tool: simulate a poor connection on inbound comms, to exercise the jitter buffer
+// and avatar smoothing without a bad network. enabled with
+// `--comms_sim loss=10,latency=100,jitter=50,reorder=5` (percentages and milliseconds).
+// only packets sent unreliably (positions, movement and profile requests) are affected,
+// reliable traffic such as chat passes straight through. when not enabled transports post
+// directly to the global crdt as usual.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tokio::sync::mpsc;
+
+use dcl_component::proto_components::kernel::comms::rfc4;
+
+use crate::global_crdt::{process_transport_updates, GlobalCrdtState, PlayerMessage, PlayerUpdate};
+
+// extra delay for reordered packets, enough for the next position update to overtake it
+const REORDER_DELAY: f32 = 0.1;
+
+pub struct NetworkSimPlugin;
+
+impl Plugin for NetworkSimPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            simulate_network
+                .run_if(resource_exists::<NetworkSimulator>)
+                .before(process_transport_updates),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NetworkConditions {
+    // chance of dropping a packet, 0-1
+    pub loss: f32,
+    // fixed delay in seconds
+    pub latency: f32,
+    // random additional delay up to this many seconds
+    pub jitter: f32,
+    // chance of holding a packet back so that later packets overtake it, 0-1
+    pub reorder: f32,
+}
+
+impl FromStr for NetworkConditions {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut conditions = Self::default();
+        for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected `name=value`, got `{part}`"))?;
+            let value = value.trim().parse::<f32>()?;
+            if !value.is_finite() || value < 0.0 {
+                bail!("invalid value for `{key}`: {value}");
+            }
+            match key.trim() {
+                "loss" => conditions.loss = (value / 100.0).min(1.0),
+                "latency" => conditions.latency = value / 1000.0,
+                "jitter" => conditions.jitter = value / 1000.0,
+                "reorder" => conditions.reorder = (value / 100.0).min(1.0),
+                other => bail!("unknown condition `{other}`"),
+            }
+        }
+        Ok(conditions)
+    }
+}
+
+// packets in flight over a simulated unreliable link
+pub struct SimulatedLink<T> {
+    pub conditions: NetworkConditions,
+    rng: StdRng,
+    // release time, send order (to keep equal times in order) and packet
+    in_flight: Vec<(f64, u64, T)>,
+    sent: u64,
+}
+
+impl<T> SimulatedLink<T> {
+    pub fn new(conditions: NetworkConditions, seed: u64) -> Self {
+        Self {
+            conditions,
+            rng: StdRng::seed_from_u64(seed),
+            in_flight: Vec::default(),
+            sent: 0,
+        }
+    }
+
+    // send a packet at time `now`. it may be dropped
+    pub fn send(&mut self, packet: T, now: f64) {
+        if self.rng.gen::<f32>() < self.conditions.loss {
+            return;
+        }
+
+        let mut delay = self.conditions.latency + self.rng.gen::<f32>() * self.conditions.jitter;
+        if self.rng.gen::<f32>() < self.conditions.reorder {
+            delay += self.conditions.jitter + REORDER_DELAY;
+        }
+
+        self.in_flight.push((now + delay as f64, self.sent, packet));
+        self.sent += 1;
+    }
+
+    // packets that have arrived by time `now`, in arrival order
+    pub fn receive(&mut self, now: f64) -> Vec<T> {
+        self.in_flight
+            .sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        let arrived = self.in_flight.partition_point(|(time, ..)| *time <= now);
+        self.in_flight
+            .drain(..arrived)
+            .map(|(_, _, packet)| packet)
+            .collect()
+    }
+}
+
+#[derive(Resource)]
+pub struct NetworkSimulator {
+    link: SimulatedLink<PlayerUpdate>,
+    // receiver for transport updates and the sender to forward them to, set up on first run
+    channel: Option<(mpsc::Receiver<PlayerUpdate>, mpsc::Sender<PlayerUpdate>)>,
+}
+
+impl NetworkSimulator {
+    pub fn new(conditions: NetworkConditions) -> Self {
+        Self {
+            link: SimulatedLink::new(conditions, rand::random()),
+            channel: None,
+        }
+    }
+}
+
+fn is_unreliable(update: &PlayerUpdate) -> bool {
+    matches!(
+        update.message,
+        PlayerMessage::PlayerData(
+            rfc4::packet::Message::Position(_)
+                | rfc4::packet::Message::CompressedPosition(_)
+                | rfc4::packet::Message::Movement(_)
+                | rfc4::packet::Message::ProfileRequest(_)
+        )
+    )
+}
+
+fn simulate_network(
+    mut sim: ResMut<NetworkSimulator>,
+    mut state: ResMut<GlobalCrdtState>,
+    time: Res<Time>,
+) {
+    let sim = &mut *sim;
+    let (receiver, forward) = sim.channel.get_or_insert_with(|| {
+        info!("simulating comms conditions: {:?}", sim.link.conditions);
+        state.intercept_inbound()
+    });
+
+    let forward = |update: PlayerUpdate| {
+        if let Err(e) = forward.try_send(update) {
+            warn!("failed to forward simulated packet: {e}");
+        }
+    };
+
+    let now = time.elapsed_seconds_f64();
+    while let Ok(update) = receiver.try_recv() {
+        if is_unreliable(&update) {
+            sim.link.send(update, now);
+        } else {
+            forward(update);
+        }
+    }
+
+    for update in sim.link.receive(now) {
+        forward(update);
+    }
+}
@@ -0,0 +1,243 @@
+// developer tool for recording and replaying comms traffic.
+// enabled with `--comms_log <file>` (record) and `--comms_replay <file>` (replay).
+// when recording is disabled the only cost is updating the traffic counters and a check
+// of an unset OnceLock per packet.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, LineWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    time::Instant,
+};
+
+use bevy::prelude::*;
+use ethers_core::types::Address;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use common::util::AsH160;
+use dcl_component::proto_components::kernel::comms::rfc4;
+
+use crate::global_crdt::{GlobalCrdtState, PlayerMessage, PlayerUpdate};
+
+pub struct PacketLogPlugin;
+
+impl Plugin for PacketLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            replay_packets.run_if(resource_exists::<PacketReplay>),
+        );
+    }
+}
+
+struct PacketLog {
+    start: Instant,
+    writer: Mutex<LineWriter<File>>,
+}
+
+static PACKET_LOG: OnceLock<PacketLog> = OnceLock::new();
+
+// start recording all comms traffic to the given file
+pub fn init_packet_log(path: impl Into<PathBuf>) -> Result<(), anyhow::Error> {
+    let file = File::create(path.into())?;
+    PACKET_LOG
+        .set(PacketLog {
+            start: Instant::now(),
+            writer: Mutex::new(LineWriter::new(file)),
+        })
+        .map_err(|_| anyhow::anyhow!("packet log already initialized"))
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PacketDirection {
+    Inbound,
+    Outbound,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PacketLogEntry {
+    pub time: f64,
+    pub direction: PacketDirection,
+    pub transport: String,
+    pub peer: Option<String>,
+    pub packet_type: String,
+    pub size: usize,
+    pub data: Vec<u8>,
+}
+
+fn packet_type(data: &[u8]) -> &'static str {
+    let Ok(rfc4::Packet {
+        message: Some(message),
+        ..
+    }) = rfc4::Packet::decode(data)
+    else {
+        return "Invalid";
+    };
+
+    match message {
+        rfc4::packet::Message::Position(_) => "Position",
+        rfc4::packet::Message::ProfileVersion(_) => "ProfileVersion",
+        rfc4::packet::Message::ProfileRequest(_) => "ProfileRequest",
+        rfc4::packet::Message::ProfileResponse(_) => "ProfileResponse",
+        rfc4::packet::Message::Chat(_) => "Chat",
+        rfc4::packet::Message::Scene(_) => "Scene",
+        rfc4::packet::Message::Voice(_) => "Voice",
+        rfc4::packet::Message::Movement(_) => "Movement",
+        rfc4::packet::Message::PlayerEmote(_) => "PlayerEmote",
+        rfc4::packet::Message::SceneEmote(_) => "SceneEmote",
+        rfc4::packet::Message::CompressedPosition(_) => "CompressedPosition",
+    }
+}
+
+// comms traffic totals since startup
+pub struct PacketCounters {
+    pub packets_sent: AtomicU64,
+    pub bytes_sent: AtomicU64,
+    pub packets_received: AtomicU64,
+    pub bytes_received: AtomicU64,
+}
+
+pub static PACKET_COUNTERS: PacketCounters = PacketCounters {
+    packets_sent: AtomicU64::new(0),
+    bytes_sent: AtomicU64::new(0),
+    packets_received: AtomicU64::new(0),
+    bytes_received: AtomicU64::new(0),
+};
+
+// count an rfc4 packet, and record it if logging is enabled
+pub fn log_packet(direction: PacketDirection, transport: &str, peer: Option<Address>, data: &[u8]) {
+    let (packets, bytes) = match direction {
+        PacketDirection::Inbound => (
+            &PACKET_COUNTERS.packets_received,
+            &PACKET_COUNTERS.bytes_received,
+        ),
+        PacketDirection::Outbound => (&PACKET_COUNTERS.packets_sent, &PACKET_COUNTERS.bytes_sent),
+    };
+    packets.fetch_add(1, Ordering::Relaxed);
+    bytes.fetch_add(data.len() as u64, Ordering::Relaxed);
+
+    let Some(log) = PACKET_LOG.get() else {
+        return;
+    };
+
+    let entry = PacketLogEntry {
+        time: log.start.elapsed().as_secs_f64(),
+        direction,
+        transport: transport.to_owned(),
+        peer: peer.map(|peer| format!("{peer:#x}")),
+        packet_type: packet_type(data).to_owned(),
+        size: data.len(),
+        data: data.to_vec(),
+    };
+
+    let Ok(line) = serde_json::to_string(&entry) else {
+        return;
+    };
+
+    let Ok(mut writer) = log.writer.lock() else {
+        return;
+    };
+    if let Err(e) = writeln!(writer, "{line}") {
+        warn!("failed to write packet log: {e}");
+    }
+}
+
+// feeds inbound packets from a recorded log back through the global crdt
+#[derive(Resource)]
+pub struct PacketReplay {
+    pub path: PathBuf,
+}
+
+struct ReplayState {
+    transport_id: Entity,
+    start: f64,
+    entries: std::vec::IntoIter<PacketLogEntry>,
+    next: Option<PacketLogEntry>,
+}
+
+fn load_replay(path: &Path) -> Result<Vec<PacketLogEntry>, anyhow::Error> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::default();
+    for line in reader.lines() {
+        let entry: PacketLogEntry = serde_json::from_str(&line?)?;
+        if entry.direction == PacketDirection::Inbound {
+            entries.push(entry);
+        }
+    }
+    entries.sort_by(|a, b| a.time.total_cmp(&b.time));
+    Ok(entries)
+}
+
+fn replay_packets(
+    mut commands: Commands,
+    replay: Res<PacketReplay>,
+    state: Res<GlobalCrdtState>,
+    time: Res<Time>,
+    mut replay_state: Local<Option<ReplayState>>,
+    mut done: Local<bool>,
+) {
+    if *done {
+        return;
+    }
+
+    if replay_state.is_none() {
+        let entries = match load_replay(&replay.path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("failed to load packet replay {:?}: {e}", replay.path);
+                *done = true;
+                return;
+            }
+        };
+        info!(
+            "replaying {} inbound packets from {:?}",
+            entries.len(),
+            replay.path
+        );
+        let mut entries = entries.into_iter();
+        *replay_state = Some(ReplayState {
+            transport_id: commands.spawn_empty().id(),
+            start: time.elapsed_seconds_f64(),
+            next: entries.next(),
+            entries,
+        });
+    }
+    let replay_state = replay_state.as_mut().unwrap();
+
+    let sender = state.get_sender();
+    let elapsed = time.elapsed_seconds_f64() - replay_state.start;
+    while let Some(entry) = replay_state.next.take() {
+        if entry.time > elapsed {
+            replay_state.next = Some(entry);
+            return;
+        }
+        replay_state.next = replay_state.entries.next();
+
+        let Some(address) = entry.peer.as_deref().and_then(|peer| peer.as_h160()) else {
+            continue;
+        };
+        let Ok(rfc4::Packet {
+            message: Some(message),
+            ..
+        }) = rfc4::Packet::decode(entry.data.as_slice())
+        else {
+            continue;
+        };
+
+        if let Err(e) = sender.try_send(PlayerUpdate {
+            transport_id: replay_state.transport_id,
+            message: PlayerMessage::PlayerData(message),
+            address,
+        }) {
+            warn!("failed to replay packet: {e}");
+        }
+    }
+
+    info!("packet replay complete");
+    *done = true;
+}
@@ -0,0 +1,144 @@
+// explorer extension: optionally (`network.compress_positions`) send position broadcasts as
+// `CompressedPosition`s, quantized to a grid scaled to the realm and delta encoded against the
+// last keyframe. a keyframe is a normal full precision `Position`, sent reliably at least every
+// `KEYFRAME_FREQ` seconds so new subscribers and other clients can follow. deltas are against
+// the keyframe rather than the previous packet so that a lost packet only affects itself.
+
+use bevy::prelude::*;
+use dcl_component::proto_components::kernel::comms::rfc4;
+
+pub const KEYFRAME_FREQ: f64 = 1.0;
+// quaternion components are quantized to steps of 1 / ROTATION_STEPS
+pub const ROTATION_STEPS: f32 = 1024.0;
+// the grid covers the realm's extent in this many steps
+pub const REALM_GRID_STEPS: f32 = 1048576.0;
+
+const PARCEL_SIZE: f32 = 16.0;
+const LOG_FREQ: f64 = 60.0;
+
+// quantization grid for compressed positions, set from the current realm's bounds
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionGrid {
+    pub grid_mm: u32,
+}
+
+impl PositionGrid {
+    // the grid for a realm spanning the parcels from `min` to `max` inclusive
+    pub fn for_realm(min: IVec2, max: IVec2) -> Self {
+        let parcels = (max - min + IVec2::ONE).max_element().max(1);
+        let extent_mm = parcels as f32 * PARCEL_SIZE * 1000.0;
+        Self {
+            grid_mm: (extent_mm / REALM_GRID_STEPS).ceil().max(1.0) as u32,
+        }
+    }
+}
+
+impl Default for PositionGrid {
+    // genesis city
+    fn default() -> Self {
+        Self::for_realm(IVec2::splat(-152), IVec2::splat(152))
+    }
+}
+
+fn grid_size(grid_mm: u32) -> f32 {
+    grid_mm.max(1) as f32 / 1000.0
+}
+
+pub fn compress(
+    keyframe: &rfc4::Position,
+    position: &rfc4::Position,
+    grid_mm: u32,
+) -> rfc4::CompressedPosition {
+    let grid = grid_size(grid_mm);
+    let delta = |from: f32, to: f32| ((to - from) / grid).round() as i32;
+    let rotation = |value: f32| (value * ROTATION_STEPS).round() as i32;
+
+    rfc4::CompressedPosition {
+        index: position.index,
+        keyframe_index: keyframe.index,
+        grid_mm,
+        delta_x: delta(keyframe.position_x, position.position_x),
+        delta_y: delta(keyframe.position_y, position.position_y),
+        delta_z: delta(keyframe.position_z, position.position_z),
+        rotation_x: rotation(position.rotation_x),
+        rotation_y: rotation(position.rotation_y),
+        rotation_z: rotation(position.rotation_z),
+        rotation_w: rotation(position.rotation_w),
+    }
+}
+
+// the caller checks that `keyframe` is the one the position was compressed against
+pub fn decompress(
+    keyframe: &rfc4::Position,
+    compressed: &rfc4::CompressedPosition,
+) -> rfc4::Position {
+    let grid = grid_size(compressed.grid_mm);
+    let rotation = Vec4::new(
+        compressed.rotation_x as f32,
+        compressed.rotation_y as f32,
+        compressed.rotation_z as f32,
+        compressed.rotation_w as f32,
+    )
+    .try_normalize()
+    .unwrap_or(Vec4::W);
+
+    rfc4::Position {
+        index: compressed.index,
+        position_x: keyframe.position_x + compressed.delta_x as f32 * grid,
+        position_y: keyframe.position_y + compressed.delta_y as f32 * grid,
+        position_z: keyframe.position_z + compressed.delta_z as f32 * grid,
+        rotation_x: rotation.x,
+        rotation_y: rotation.y,
+        rotation_z: rotation.z,
+        rotation_w: rotation.w,
+    }
+}
+
+#[derive(Default)]
+pub struct PositionEncoder {
+    keyframe: Option<(rfc4::Position, f64)>,
+    // encoded size of the messages sent, and of the same positions sent uncompressed
+    sent_bytes: usize,
+    full_bytes: usize,
+    last_log: f64,
+}
+
+impl PositionEncoder {
+    // the message to send for the position at `time`, a keyframe or a delta against the last
+    // one, and whether it is a keyframe. keyframes should be sent reliably, as the deltas
+    // until the next one can't be decoded without it
+    pub fn encode(
+        &mut self,
+        position: rfc4::Position,
+        grid_mm: u32,
+        time: f64,
+    ) -> (rfc4::packet::Message, bool) {
+        let full_bytes = rfc4::packet::Message::Position(position.clone()).encoded_len();
+        let (message, keyframe) = match &self.keyframe {
+            Some((keyframe, keyframe_time)) if time - keyframe_time < KEYFRAME_FREQ => (
+                rfc4::packet::Message::CompressedPosition(compress(keyframe, &position, grid_mm)),
+                false,
+            ),
+            _ => {
+                self.keyframe = Some((position.clone(), time));
+                (rfc4::packet::Message::Position(position), true)
+            }
+        };
+
+        self.full_bytes += full_bytes;
+        self.sent_bytes += message.encoded_len();
+        if time - self.last_log >= LOG_FREQ {
+            info!(
+                "position broadcasts: sent {} bytes instead of {} ({:.0}% saved)",
+                self.sent_bytes,
+                self.full_bytes,
+                100.0 * (1.0 - self.sent_bytes as f32 / self.full_bytes as f32)
+            );
+            self.sent_bytes = 0;
+            self.full_bytes = 0;
+            self.last_log = time;
+        }
+
+        (message, keyframe)
+    }
+}
@@ -62,7 +62,7 @@ pub fn start_signed_login(
                     ..
                 }) => {
                     info!("signed login ok, connecting to inner {adapter}");
-                    manager.connect(adapter.as_str());
+                    manager.connect_all(adapter.as_str());
                 }
                 otherwise => warn!("signed login failed: {otherwise:?}"),
             }
@@ -15,6 +15,741 @@ use livekit::{
 };
 use wallet::{signed_login::signed_login, SignedLoginMeta, Wallet};
 
+#[test]
+fn test_archipelago_ws_url() {
+    use crate::archipelago::archipelago_ws_url;
+
+    assert_eq!(
+        archipelago_ws_url("http://archipelago.org/ws").unwrap(),
+        "ws://archipelago.org/ws"
+    );
+    assert_eq!(
+        archipelago_ws_url("https://archipelago.org/ws").unwrap(),
+        "wss://archipelago.org/ws"
+    );
+    assert_eq!(
+        archipelago_ws_url("archipelago.org/ws").unwrap(),
+        "wss://archipelago.org/ws"
+    );
+    assert_eq!(
+        archipelago_ws_url("ws://localhost:5000/ws").unwrap(),
+        "ws://localhost:5000/ws"
+    );
+    assert_eq!(
+        archipelago_ws_url("wss://archipelago.org").unwrap(),
+        "wss://archipelago.org"
+    );
+
+    assert!(archipelago_ws_url("").is_err());
+    assert!(archipelago_ws_url("ftp://archipelago.org").is_err());
+    assert!(archipelago_ws_url("https://").is_err());
+    assert!(archipelago_ws_url("not a host").is_err());
+}
+
+#[test]
+fn test_parse_adapter() {
+    use crate::parse_adapter;
+
+    assert_eq!(
+        parse_adapter("livekit:wss://host:7880/path?access_token=abc:def&x=1"),
+        Some(("livekit", "wss://host:7880/path?access_token=abc:def&x=1"))
+    );
+    assert_eq!(
+        parse_adapter("ws-room:host.org:5000/rooms/room-1?a=b"),
+        Some(("ws-room", "host.org:5000/rooms/room-1?a=b"))
+    );
+    assert_eq!(
+        parse_adapter("ws-room:[::1]:5000/rooms/room-1"),
+        Some(("ws-room", "[::1]:5000/rooms/room-1"))
+    );
+    assert_eq!(
+        parse_adapter("ws-room:wss://[2001:db8::1]:443/rooms/room-1"),
+        Some(("ws-room", "wss://[2001:db8::1]:443/rooms/room-1"))
+    );
+    assert_eq!(
+        parse_adapter("fixed-adapter:livekit:wss://host:7880/path"),
+        Some(("fixed-adapter", "livekit:wss://host:7880/path"))
+    );
+    assert_eq!(
+        parse_adapter("offline:offline"),
+        Some(("offline", "offline"))
+    );
+
+    assert_eq!(parse_adapter("no-protocol"), None);
+    assert_eq!(parse_adapter(":host:1234"), None);
+    assert_eq!(parse_adapter("[::1]:5000"), None);
+}
+
+#[test]
+fn test_multiple_adapters() {
+    use bevy::prelude::*;
+    use ipfs::{CommsConfig, CurrentRealm};
+
+    use crate::{
+        archipelago::{start_archipelago, StartArchipelago},
+        process_realm_change,
+        profile::{CurrentUserProfile, UserProfile},
+        signed_login::StartSignedLogin,
+        split_adapters,
+        websocket_room::{start_ws_room, StartWsRoom},
+        Transport, TransportType,
+    };
+
+    assert_eq!(
+        split_adapters(" ws-room:a.org/room ;livekit:wss://b.org?x=1,, ws-room:a.org/room"),
+        vec!["ws-room:a.org/room", "livekit:wss://b.org?x=1"]
+    );
+
+    let realm = |adapter: &str| CurrentRealm {
+        comms: Some(CommsConfig {
+            healthy: true,
+            protocol: "v3".to_owned(),
+            fixed_adapter: None,
+            adapter: Some(adapter.to_owned()),
+        }),
+        ..Default::default()
+    };
+
+    let mut app = App::new();
+    app.add_event::<StartWsRoom>()
+        .add_event::<StartArchipelago>()
+        .add_event::<StartSignedLogin>();
+    #[cfg(feature = "livekit")]
+    app.add_event::<crate::livekit_room::StartLivekit>();
+    let mut wallet = Wallet::default();
+    wallet.finalize_as_guest();
+    app.insert_resource(wallet);
+    app.insert_resource(CurrentUserProfile {
+        profile: Some(UserProfile::default()),
+        ..Default::default()
+    });
+    app.insert_resource(realm(
+        "ws-room:ws-room:wss://rooms.org/room-1;archipelago:archipelago:https://archipelago.org/ws",
+    ));
+    app.add_systems(
+        Update,
+        (process_realm_change, (start_ws_room, start_archipelago)).chain(),
+    );
+
+    let transports = |app: &mut App| {
+        let world = app.world_mut();
+        world
+            .query::<(Entity, &Transport)>()
+            .iter(world)
+            .map(|(entity, transport)| (entity, transport.transport_type.clone()))
+            .collect::<HashMap<_, _>>()
+    };
+
+    app.update();
+    let first = transports(&mut app);
+    assert_eq!(first.len(), 2);
+    assert!(first.values().any(|ty| *ty == TransportType::WebsocketRoom));
+    assert!(first.values().any(|ty| *ty == TransportType::Archipelago));
+
+    // an unrelated realm change keeps the same connections
+    app.world_mut().resource_mut::<CurrentRealm>().public_url = "https://realm.org".to_owned();
+    app.update();
+    assert_eq!(transports(&mut app), first);
+
+    // a different list replaces them
+    *app.world_mut().resource_mut::<CurrentRealm>() =
+        realm("ws-room:ws-room:wss://rooms.org/room-2");
+    app.update();
+    let second = transports(&mut app);
+    assert_eq!(second.len(), 1);
+    assert!(second.keys().all(|entity| !first.contains_key(entity)));
+}
+
+#[test]
+fn test_logout_drops_transports() {
+    use bevy::prelude::*;
+    use ipfs::{CommsConfig, CurrentRealm};
+
+    use crate::{
+        archipelago::StartArchipelago,
+        process_realm_change,
+        profile::{CurrentUserProfile, UserProfile},
+        signed_login::StartSignedLogin,
+        websocket_room::{start_ws_room, StartWsRoom},
+        Transport,
+    };
+
+    let mut app = App::new();
+    app.add_event::<StartWsRoom>()
+        .add_event::<StartArchipelago>()
+        .add_event::<StartSignedLogin>();
+    #[cfg(feature = "livekit")]
+    app.add_event::<crate::livekit_room::StartLivekit>();
+    let mut wallet = Wallet::default();
+    wallet.finalize_as_guest();
+    app.insert_resource(wallet);
+    app.insert_resource(CurrentUserProfile {
+        profile: Some(UserProfile::default()),
+        ..Default::default()
+    });
+    app.insert_resource(CurrentRealm {
+        comms: Some(CommsConfig {
+            healthy: true,
+            protocol: "v3".to_owned(),
+            fixed_adapter: None,
+            adapter: Some("ws-room:ws-room:wss://rooms.org/room-1".to_owned()),
+        }),
+        ..Default::default()
+    });
+    app.add_systems(Update, (process_realm_change, start_ws_room).chain());
+
+    let transport_count = |app: &mut App| {
+        let world = app.world_mut();
+        world.query::<&Transport>().iter(world).count()
+    };
+
+    app.update();
+    assert_eq!(transport_count(&mut app), 1);
+
+    app.world_mut().resource_mut::<Wallet>().disconnect();
+    app.update();
+    assert_eq!(app.world().resource::<Wallet>().address(), None);
+    assert_eq!(transport_count(&mut app), 0);
+
+    // and nothing reconnects without an identity
+    app.update();
+    assert_eq!(transport_count(&mut app), 0);
+}
+
+#[test]
+fn test_websocket_room_backoff() {
+    use crate::websocket_room::{ReconnectBackoff, WebsocketRoomTransport};
+
+    let mut transport = WebsocketRoomTransport {
+        address: Default::default(),
+        receiver: None,
+        retries: 0,
+        backoff: ReconnectBackoff {
+            base: 0.5,
+            cap: 1.5,
+            jitter: 0.5,
+            max_retries: 3,
+            stable_after: 10.0,
+        },
+        connected_at: 0.0,
+        retry_at: None,
+    };
+
+    // the connection failed at `now`, having started at 0
+    let fail = |transport: &mut WebsocketRoomTransport, now: f64| transport.on_disconnect(now, 0.0);
+
+    // three quick failures back off exponentially up to the cap, with no jitter at random = 0
+    assert_eq!(fail(&mut transport, 1.0), Some(0.5));
+    assert_eq!(fail(&mut transport, 1.0), Some(1.0));
+    assert_eq!(fail(&mut transport, 1.0), Some(1.5));
+    assert_eq!(transport.retries, 3);
+
+    // jitter adds up to the configured fraction
+    assert_eq!(transport.backoff.delay(1, 1.0), 1.5);
+    assert_eq!(transport.backoff.delay(5, 0.5), 1.875);
+
+    // a connection that stayed up resets the schedule
+    assert_eq!(fail(&mut transport, 10.0), Some(0.5));
+    assert_eq!(transport.retries, 1);
+
+    // and after max_retries failures in a row it gives up
+    assert_eq!(fail(&mut transport, 1.0), Some(1.0));
+    assert_eq!(fail(&mut transport, 1.0), Some(1.5));
+    assert!(!transport.gave_up());
+    assert_eq!(fail(&mut transport, 1.0), None);
+    assert!(transport.gave_up());
+}
+
+#[test]
+fn test_transport_stats() {
+    use std::time::Duration;
+
+    use async_tungstenite::tungstenite::{
+        handshake::server::{Request, Response},
+        Message,
+    };
+    use bevy::{ecs::system::RunSystemOnce, prelude::*};
+    use common::util::AsH160;
+    use dcl_component::proto_components::kernel::comms::{
+        rfc4,
+        rfc5::{ws_packet, WsPacket, WsPeerUpdate, WsWelcome},
+    };
+    use futures_util::{SinkExt, StreamExt};
+    use prost::Message as _;
+
+    use crate::{
+        websocket_room::websocket_room_handler, NetworkHealth, NetworkMessage, Transport,
+        TransportStats, TransportType,
+    };
+
+    let transport = |sender, transport_type| Transport {
+        transport_type,
+        sender,
+        foreign_aliases: Default::default(),
+    };
+
+    let (sender, receiver) = tokio::sync::mpsc::channel(10);
+    for i in 0..5 {
+        sender
+            .try_send(NetworkMessage {
+                data: vec![0; 10 * (i + 1)],
+                unreliable: i % 2 == 1,
+            })
+            .unwrap();
+    }
+    let peer = "0x0000000000000000000000000000000000000002";
+    let body = rfc4::Packet {
+        message: Some(rfc4::packet::Message::Position(Default::default())),
+    }
+    .encode_to_vec();
+
+    let stats = TransportStats::default();
+    async_std::task::block_on(async {
+        // a mock ws-room server which welcomes the client, collects its messages until it
+        // pings, then forwards one peer update
+        let listener = async_std::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server_body = body.clone();
+        let server = async_std::task::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws =
+                async_tungstenite::accept_hdr_async(tcp, |_: &Request, mut response: Response| {
+                    response
+                        .headers_mut()
+                        .append("Sec-WebSocket-Protocol", "rfc5".parse().unwrap());
+                    Ok(response)
+                })
+                .await
+                .unwrap();
+
+            let ident =
+                WsPacket::decode(ws.next().await.unwrap().unwrap().into_data().as_slice()).unwrap();
+            assert!(matches!(
+                ident.message,
+                Some(ws_packet::Message::PeerIdentification(_))
+            ));
+            let welcome = WsPacket {
+                message: Some(ws_packet::Message::WelcomeMessage(WsWelcome {
+                    alias: 1,
+                    peer_identities: HashMap::from_iter([(2, peer.to_owned())]),
+                })),
+            };
+            ws.send(Message::Binary(welcome.encode_to_vec()))
+                .await
+                .unwrap();
+
+            let mut received = 0;
+            let mut pinged = false;
+            while received < 5 || !pinged {
+                match ws.next().await.unwrap().unwrap() {
+                    Message::Binary(_) => received += 1,
+                    // answered by tungstenite with the next send
+                    Message::Ping(_) => pinged = true,
+                    message => panic!("unexpected message {message:?}"),
+                }
+            }
+
+            let update = WsPacket {
+                message: Some(ws_packet::Message::PeerUpdateMessage(WsPeerUpdate {
+                    from_alias: 2,
+                    body: server_body,
+                    unreliable: false,
+                })),
+            };
+            ws.send(Message::Binary(update.encode_to_vec()))
+                .await
+                .unwrap();
+            // keep the connection open until the client closes it
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let mut wallet = wallet::Wallet::default();
+        wallet.finalize_as_guest();
+        let (player_sender, mut player_receiver) = tokio::sync::mpsc::channel(10);
+        let client = async_std::task::spawn(websocket_room_handler(
+            Entity::PLACEHOLDER,
+            format!("ws://127.0.0.1:{port}"),
+            wallet,
+            receiver,
+            player_sender,
+            stats.clone(),
+        ));
+
+        let update = player_receiver.recv().await.unwrap();
+        assert_eq!(Some(update.address), peer.as_h160());
+        // the handshake's and the ping's roundtrips are measured
+        let snapshot = stats.snapshot();
+        assert!(snapshot.roundtrip.is_some());
+        assert!(snapshot.connected_since.is_some());
+
+        // closing the channel ends the adapter's loops
+        drop(sender);
+        let (_, error) = client.await;
+        assert_eq!(error.to_string(), "connection closed");
+        server.await;
+    });
+
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.bytes_sent, 150);
+    assert_eq!(snapshot.bytes_received, body.len() as u64);
+    assert_eq!(snapshot.reliable_messages, 4);
+    assert_eq!(snapshot.unreliable_messages, 2);
+    assert!(snapshot.roundtrip.unwrap() < Duration::from_millis(80));
+    assert_eq!(snapshot.connected_since, None);
+
+    let other = TransportStats::default();
+    other.record_sent(100, true);
+    other.set_roundtrip(Duration::from_millis(80));
+    other.set_connected(true);
+
+    let (sender, _) = tokio::sync::mpsc::channel(1);
+    let mut world = World::new();
+    world.spawn((
+        transport(sender.clone(), TransportType::WebsocketRoom),
+        stats,
+    ));
+    world.spawn((transport(sender, TransportType::Livekit), other.clone()));
+    // stats without a transport aren't counted
+    world.spawn(TransportStats::default());
+
+    let summary = world.run_system_once(|health: NetworkHealth| health.summary());
+    assert_eq!(summary.transports, 2);
+    assert_eq!(summary.connected, 1);
+    assert_eq!(summary.bytes_sent, 250);
+    assert_eq!(summary.bytes_received, body.len() as u64);
+    assert_eq!(summary.reliable_messages, 4);
+    assert_eq!(summary.unreliable_messages, 3);
+    assert_eq!(summary.worst_roundtrip, Some(Duration::from_millis(80)));
+
+    // the adapter's task shares the counters with the component
+    other.set_connected(false);
+    let summary = world.run_system_once(|health: NetworkHealth| health.summary());
+    assert_eq!(summary.connected, 0);
+}
+
+#[test]
+fn test_position_coalescer() {
+    use crate::broadcast_position::PositionCoalescer;
+    use dcl_component::proto_components::kernel::comms::rfc4;
+
+    let position = |x: f32| rfc4::Position {
+        position_x: x,
+        ..Default::default()
+    };
+    let mut coalescer = PositionCoalescer::default();
+
+    // nothing to send
+    assert_eq!(coalescer.flush(0.0, 10.0), None);
+
+    // the first position goes straight away
+    coalescer.submit(position(0.0), true);
+    assert_eq!(coalescer.flush(0.0, 10.0), Some((position(0.0), true)));
+
+    // five in one tick only send the latest, once the rate allows
+    for i in 1..=5 {
+        coalescer.submit(position(i as f32), true);
+    }
+    assert_eq!(coalescer.flush(0.05, 10.0), None);
+    assert_eq!(coalescer.flush(0.1, 10.0), Some((position(5.0), true)));
+    assert_eq!(coalescer.flush(0.2, 10.0), None);
+
+    // a reliable update stays reliable when replaced
+    coalescer.submit(position(6.0), false);
+    coalescer.submit(position(7.0), true);
+    assert_eq!(coalescer.flush(0.3, 10.0), Some((position(7.0), false)));
+
+    // with no rate limit every update is sent
+    coalescer.submit(position(8.0), true);
+    assert_eq!(coalescer.flush(0.3, 0.0), Some((position(8.0), true)));
+}
+
+#[test]
+fn test_jitter_buffer() {
+    use crate::jitter_buffer::{PositionJitterBuffer, PositionSample, MAX_EXTRAPOLATION};
+    use bevy::math::{Quat, Vec3};
+    use common::structs::AvatarMotionMode;
+
+    let sample = |index: u32, time: f32, x: f32| PositionSample {
+        index,
+        time,
+        translation: Vec3::new(x, 0.0, 0.0),
+        rotation: Quat::IDENTITY,
+    };
+
+    let mut buffer = PositionJitterBuffer::new(0.1, AvatarMotionMode::Interpolate);
+
+    // moving at 1m/s, samples every 0.1s, delivered out of order with a duplicate
+    assert!(buffer.push(sample(0, 0.0, 0.0)));
+    assert!(buffer.push(sample(2, 0.2, 0.2)));
+    assert!(buffer.push(sample(1, 0.25, 0.1)));
+    assert!(!buffer.push(sample(1, 0.25, 0.1)));
+    assert!(buffer.push(sample(3, 0.3, 0.3)));
+
+    // output advances smoothly and monotonically
+    let mut prev = f32::MIN;
+    for step in 0..=20 {
+        let now = 0.1 + step as f32 * 0.01;
+        let (translation, _) = buffer.sample(now).unwrap();
+        assert!(translation.x >= prev, "{} < {prev} at {now}", translation.x);
+        assert!((translation.x - (now - 0.1)).abs() < 0.06);
+        prev = translation.x;
+    }
+
+    // a late packet older than the playback point is ignored
+    assert!(!buffer.push(sample(0, 0.35, 5.0)));
+
+    // late packets: extrapolate from last velocity, but only briefly
+    let (translation, _) = buffer.sample(0.45).unwrap();
+    assert!((translation.x - 0.35).abs() < 0.01);
+    let (translation, _) = buffer.sample(10.0).unwrap();
+    assert!((translation.x - (0.3 + MAX_EXTRAPOLATION)).abs() < 0.01);
+
+    // teleports snap rather than interpolate
+    let mut buffer = PositionJitterBuffer::new(0.1, AvatarMotionMode::Interpolate);
+    buffer.push(sample(0, 0.0, 0.0));
+    buffer.push(sample(1, 0.1, 1000.0));
+    buffer.push(sample(2, 0.2, 1000.1));
+    let (translation, _) = buffer.sample(0.15).unwrap();
+    assert_eq!(translation.x, 1000.0);
+
+    // extrapolation predicts ahead of the newest sample without delay, clamped
+    let mut buffer = PositionJitterBuffer::new(0.1, AvatarMotionMode::Extrapolate);
+    buffer.push(sample(0, 0.0, 0.0));
+    buffer.push(sample(1, 0.1, 0.1));
+    let (translation, _) = buffer.sample(0.1).unwrap();
+    assert!((translation.x - 0.1).abs() < 0.001);
+    let (translation, _) = buffer.sample(0.2).unwrap();
+    assert!((translation.x - 0.2).abs() < 0.001);
+    let (translation, _) = buffer.sample(10.0).unwrap();
+    assert!((translation.x - (0.1 + MAX_EXTRAPOLATION)).abs() < 0.001);
+    // and snaps on teleport
+    buffer.push(sample(2, 0.3, 1000.0));
+    let (translation, _) = buffer.sample(0.4).unwrap();
+    assert_eq!(translation.x, 1000.0);
+
+    // indices wrap
+    let mut buffer = PositionJitterBuffer::new(0.1, AvatarMotionMode::Interpolate);
+    assert!(buffer.push(sample(u32::MAX - 1, 0.0, 0.0)));
+    assert!(buffer.push(sample(0, 0.2, 0.2)));
+    assert!(buffer.push(sample(u32::MAX, 0.25, 0.1)));
+    let (translation, _) = buffer.sample(0.2).unwrap();
+    assert!((translation.x - 0.1).abs() < 0.01);
+    assert_eq!(buffer.newest().unwrap().index, 0);
+
+    // a restarted sender's samples are accepted once they fall too far behind to be late packets
+    let mut buffer = PositionJitterBuffer::new(0.1, AvatarMotionMode::Interpolate);
+    for index in 0..40 {
+        assert!(buffer.push(sample(500 + index, index as f32 * 0.1, 0.0)));
+    }
+    assert!(!buffer.push(sample(501, 4.0, 0.0)));
+    assert!(buffer.push(sample(0, 4.0, 5.0)));
+    assert_eq!(buffer.newest().unwrap().index, 0);
+    let (translation, _) = buffer.sample(5.0).unwrap();
+    assert_eq!(translation.x, 5.0);
+}
+
+#[test]
+fn test_simulated_network() {
+    use crate::jitter_buffer::{PositionJitterBuffer, PositionSample};
+    use crate::network_sim::{NetworkConditions, SimulatedLink};
+    use bevy::math::{Quat, Vec3};
+    use common::structs::AvatarMotionMode;
+
+    let conditions = "loss=10,latency=100,jitter=50,reorder=5"
+        .parse::<NetworkConditions>()
+        .unwrap();
+    assert_eq!(
+        conditions,
+        NetworkConditions {
+            loss: 0.1,
+            latency: 0.1,
+            jitter: 0.05,
+            reorder: 0.05,
+        }
+    );
+    assert!("loss=ten".parse::<NetworkConditions>().is_err());
+    assert!("speed=10".parse::<NetworkConditions>().is_err());
+
+    // moving at 1m/s, samples every 0.1s, sampled every frame at 60fps
+    let mut link = SimulatedLink::new(conditions, 0);
+    let mut buffer = PositionJitterBuffer::new(0.3, AvatarMotionMode::Interpolate);
+    let mut prev = f32::MIN;
+    for frame in 0..600u32 {
+        let now = frame as f32 / 60.0;
+        if frame % 6 == 0 {
+            let sample = PositionSample {
+                index: frame / 6,
+                time: now,
+                translation: Vec3::new(now, 0.0, 0.0),
+                rotation: Quat::IDENTITY,
+            };
+            link.send(sample, now as f64);
+        }
+        for mut sample in link.receive(now as f64) {
+            sample.time = now;
+            buffer.push(sample);
+        }
+
+        let Some((translation, _)) = buffer.sample(now) else {
+            continue;
+        };
+        // no large steps backwards, and trailing by about the latency plus the buffer delay
+        assert!(
+            translation.x > prev - 0.15,
+            "{} < {prev} at {now}",
+            translation.x
+        );
+        if now > 1.0 {
+            let lag = now - translation.x;
+            assert!((lag - 0.425).abs() < 0.25, "lag {lag} at {now}");
+        }
+        prev = translation.x;
+    }
+}
+
+#[test]
+fn test_stale_positions() {
+    use crate::global_crdt::SequenceTracker;
+
+    let mut tracker = SequenceTracker::default();
+    assert!(tracker.accept(5));
+    // newer packet applied, then an older one arrives late
+    assert!(tracker.accept(7));
+    assert!(!tracker.accept(6));
+    // duplicates are dropped
+    assert!(!tracker.accept(7));
+    assert!(tracker.accept(500));
+    // a large jump backwards is a restarted sender, not a stale packet
+    assert!(tracker.accept(0));
+    assert!(tracker.accept(1));
+    // indices wrap
+    let mut tracker = SequenceTracker::default();
+    assert!(tracker.accept(u32::MAX));
+    assert!(tracker.accept(0));
+    assert!(!tracker.accept(u32::MAX));
+}
+
+#[test]
+fn test_position_compression_round_trip() {
+    use bevy::math::{EulerRot, Quat, Vec3};
+    use dcl_component::proto_components::kernel::comms::rfc4;
+
+    use bevy::math::IVec2;
+
+    use crate::position_compression::{
+        compress, decompress, PositionEncoder, PositionGrid, KEYFRAME_FREQ,
+    };
+
+    let position = |index: u32, translation: Vec3, rotation: Quat| rfc4::Position {
+        index,
+        position_x: translation.x,
+        position_y: translation.y,
+        position_z: translation.z,
+        rotation_x: rotation.x,
+        rotation_y: rotation.y,
+        rotation_z: rotation.z,
+        rotation_w: rotation.w,
+    };
+
+    // the grid scales with the realm, a few mm for genesis city and the minimum for a small world
+    let grid_mm = PositionGrid::default().grid_mm;
+    assert_eq!(grid_mm, 5);
+    assert_eq!(
+        PositionGrid::for_realm(IVec2::ZERO, IVec2::new(3, 1)).grid_mm,
+        1
+    );
+
+    let keyframe = position(3, Vec3::new(-1234.567, 2.5, 987.654), Quat::IDENTITY);
+    for (ix, offset) in [
+        Vec3::ZERO,
+        Vec3::new(0.004, -0.006, 0.5),
+        Vec3::new(-12.345, 0.678, 31.001),
+        Vec3::new(250.0, -3.0, -160.123),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let rotation = Quat::from_euler(EulerRot::YXZ, ix as f32 * 1.7 - 2.0, 0.1, -0.2);
+        let sent = position(
+            4 + ix as u32,
+            Vec3::new(
+                keyframe.position_x,
+                keyframe.position_y,
+                keyframe.position_z,
+            ) + offset,
+            rotation,
+        );
+        let received = decompress(&keyframe, &compress(&keyframe, &sent, grid_mm));
+
+        assert_eq!(received.index, sent.index);
+        // within half a grid step, allowing for f32 precision at these coordinates
+        for (a, b) in [
+            (sent.position_x, received.position_x),
+            (sent.position_y, received.position_y),
+            (sent.position_z, received.position_z),
+        ] {
+            assert!((a - b).abs() <= 0.0025 + 1e-3, "{a} vs {b}");
+        }
+        let received_rotation = Quat::from_xyzw(
+            received.rotation_x,
+            received.rotation_y,
+            received.rotation_z,
+            received.rotation_w,
+        );
+        assert!(received_rotation.is_normalized());
+        assert!(received_rotation.angle_between(rotation) < 0.005);
+    }
+
+    // a keyframe first and then periodically, deltas in between
+    let mut encoder = PositionEncoder::default();
+    let is_keyframe = |(message, keyframe): &(rfc4::packet::Message, bool)| {
+        assert_eq!(
+            *keyframe,
+            matches!(message, rfc4::packet::Message::Position(_))
+        );
+        *keyframe
+    };
+    let sent = position(0, Vec3::ONE, Quat::IDENTITY);
+    assert!(is_keyframe(&encoder.encode(sent.clone(), grid_mm, 0.0)));
+    let delta = encoder.encode(position(1, Vec3::ONE, Quat::IDENTITY), grid_mm, 0.1);
+    assert!(!is_keyframe(&delta));
+    // and smaller
+    assert!(delta.0.encoded_len() < rfc4::packet::Message::Position(sent).encoded_len());
+    let later = position(2, Vec3::ONE, Quat::IDENTITY);
+    assert!(is_keyframe(&encoder.encode(later, grid_mm, KEYFRAME_FREQ)));
+}
+
+#[test]
+fn test_heartbeat_position_settles() {
+    use std::time::Duration;
+
+    use bevy::{math::Vec3, utils::Instant};
+
+    use crate::archipelago::HeartbeatPosition;
+
+    let start = Instant::now();
+    let at = |secs: f32| start + Duration::from_secs_f32(secs);
+    let spawn = Vec3::new(0.0, 0.0, 0.0);
+    let far = Vec3::new(500.0, 0.0, 0.0);
+    let further = Vec3::new(1000.0, 0.0, 0.0);
+
+    // the first position is reported straight away, even when settling
+    let mut heartbeat = HeartbeatPosition::default();
+    assert_eq!(heartbeat.update(spawn, at(0.0), true), spawn);
+
+    // travelling quickly keeps the spawn position
+    assert_eq!(heartbeat.update(far, at(0.1), true), spawn);
+    assert_eq!(heartbeat.update(further, at(1.0), true), spawn);
+    assert_eq!(heartbeat.update(further, at(2.5), true), spawn);
+    // until the player has stayed in one place
+    assert_eq!(heartbeat.update(further, at(3.0), true), further);
+
+    // without settling the position follows immediately
+    assert_eq!(heartbeat.update(far, at(3.1), false), far);
+}
+
 #[test]
 fn test_tls() {
     let _ = isahc::get("https://www.google.com/").unwrap();
@@ -1,5 +1,7 @@
+use std::time::Instant;
+
 use anyhow::{anyhow, bail};
-use async_tungstenite::tungstenite::{client::IntoClientRequest, http::HeaderValue};
+use async_tungstenite::tungstenite::{self, client::IntoClientRequest, http::HeaderValue};
 use bevy::{
     prelude::*,
     tasks::{IoTaskPool, Task},
@@ -19,7 +21,12 @@ use dcl_component::proto_components::kernel::comms::{
 };
 use wallet::Wallet;
 
-use crate::{global_crdt::PlayerMessage, profile::CurrentUserProfile, Transport, TransportType};
+use crate::{
+    global_crdt::PlayerMessage,
+    packet_log::{log_packet, PacketDirection},
+    profile::CurrentUserProfile,
+    recv_until, Outbound, Transport, TransportStats, TransportType, ROUNDTRIP_FREQ,
+};
 
 use super::{
     global_crdt::{GlobalCrdtState, PlayerUpdate},
@@ -48,6 +55,66 @@ pub struct WebsocketRoomTransport {
     pub address: String,
     pub receiver: Option<Receiver<NetworkMessage>>,
     pub retries: usize,
+    pub backoff: ReconnectBackoff,
+    // when the current connection attempt started
+    pub connected_at: f64,
+    // when to reconnect after a failure
+    pub retry_at: Option<f64>,
+}
+
+impl WebsocketRoomTransport {
+    // the delay before reconnecting after the connection fails at `now`, or `None` to give up.
+    // `random` is in 0-1 and picks the jitter
+    pub fn on_disconnect(&mut self, now: f64, random: f32) -> Option<f32> {
+        if now - self.connected_at >= self.backoff.stable_after as f64 {
+            self.retries = 0;
+        }
+
+        if self.retries >= self.backoff.max_retries {
+            self.retries += 1;
+            return None;
+        }
+
+        let delay = self.backoff.delay(self.retries, random);
+        self.retries += 1;
+        Some(delay)
+    }
+
+    pub fn gave_up(&self) -> bool {
+        self.retries > self.backoff.max_retries
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ReconnectBackoff {
+    // seconds before the first retry, doubling with each further failure up to `cap`
+    pub base: f32,
+    pub cap: f32,
+    // up to this fraction of the delay is added at random, so that clients dropped at the same
+    // time don't all come back at the same time
+    pub jitter: f32,
+    pub max_retries: usize,
+    // seconds a connection must stay up to count as a success and reset the retries
+    pub stable_after: f32,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self {
+            base: 1.0,
+            cap: 30.0,
+            jitter: 0.5,
+            max_retries: 10,
+            stable_after: 30.0,
+        }
+    }
+}
+
+impl ReconnectBackoff {
+    pub fn delay(&self, retries: usize, random: f32) -> f32 {
+        let delay = (self.base * 2f32.powi(retries.min(30) as i32)).min(self.cap);
+        delay * (1.0 + self.jitter * random.clamp(0.0, 1.0))
+    }
 }
 
 #[derive(Component)]
@@ -58,7 +125,7 @@ pub fn start_ws_room(
     mut room_events: EventReader<StartWsRoom>,
     current_profile: Res<CurrentUserProfile>,
 ) {
-    if let Some(ev) = room_events.read().last() {
+    for ev in room_events.read() {
         info!("starting ws-room protocol");
         let (sender, receiver) = tokio::sync::mpsc::channel(1000);
 
@@ -83,10 +150,14 @@ pub fn start_ws_room(
                 sender,
                 foreign_aliases: Default::default(),
             },
+            TransportStats::default(),
             WebsocketRoomTransport {
                 address: ev.address.to_owned(),
                 receiver: Some(receiver),
                 retries: 0,
+                backoff: ReconnectBackoff::default(),
+                connected_at: 0.0,
+                retry_at: None,
             },
         ));
     }
@@ -95,11 +166,16 @@ pub fn start_ws_room(
 #[allow(clippy::type_complexity)]
 fn connect_websocket(
     mut commands: Commands,
-    mut new_websockets: Query<(Entity, &mut WebsocketRoomTransport), Without<WebSocketConnection>>,
+    mut new_websockets: Query<
+        (Entity, &mut WebsocketRoomTransport, &TransportStats),
+        Without<WebSocketConnection>,
+    >,
     wallet: Res<Wallet>,
     player_state: Res<GlobalCrdtState>,
+    time: Res<Time>,
 ) {
-    for (transport_id, mut new_transport) in new_websockets.iter_mut() {
+    for (transport_id, mut new_transport, stats) in new_websockets.iter_mut() {
+        new_transport.connected_at = time.elapsed_seconds_f64();
         let remote_address = new_transport.address.to_owned();
         let wallet = wallet.clone();
         let receiver = new_transport.receiver.take().unwrap();
@@ -110,6 +186,7 @@ fn connect_websocket(
             wallet,
             receiver,
             sender,
+            stats.clone(),
         ));
         commands
             .entity(transport_id)
@@ -122,49 +199,74 @@ fn reconnect_websocket(
         Entity,
         &mut WebsocketRoomTransport,
         &mut WebSocketConnection,
+        &TransportStats,
     )>,
     wallet: Res<Wallet>,
     player_state: Res<GlobalCrdtState>,
+    time: Res<Time>,
 ) {
-    for (transport_id, mut transport, mut conn) in websockets.iter_mut() {
-        if transport.retries < 3 {
-            if conn.0.is_finished() {
-                transport.retries += 1;
-                let (receiver, err) = future::block_on(future::poll_once(&mut conn.0)).unwrap();
+    let now = time.elapsed_seconds_f64();
+    for (transport_id, mut transport, mut conn, stats) in websockets.iter_mut() {
+        if let Some(retry_at) = transport.retry_at {
+            if now < retry_at {
+                continue;
+            }
+
+            transport.retry_at = None;
+            transport.connected_at = now;
+            let remote_address = transport.address.to_owned();
+            let wallet = wallet.clone();
+            let receiver = transport.receiver.take().unwrap();
+            let sender = player_state.get_sender();
+            let task = IoTaskPool::get().spawn(websocket_room_handler(
+                transport_id,
+                remote_address,
+                wallet,
+                receiver,
+                sender,
+                stats.clone(),
+            ));
+            conn.0 = task;
+            continue;
+        }
+
+        if transport.gave_up() || !conn.0.is_finished() {
+            continue;
+        }
+
+        let (receiver, err) = future::block_on(future::poll_once(&mut conn.0)).unwrap();
+        match transport.on_disconnect(now, rand::random()) {
+            Some(delay) => {
                 warn!(
-                    "websocket room error: {err}, retrying [{}]",
+                    "websocket room error: {err}, retrying in {delay:.1}s [{}]",
                     transport.address
                 );
-                let remote_address = transport.address.to_owned();
-                let wallet = wallet.clone();
-                let sender = player_state.get_sender();
-                let task = IoTaskPool::get().spawn(websocket_room_handler(
-                    transport_id,
-                    remote_address,
-                    wallet,
-                    receiver,
-                    sender,
-                ));
-                conn.0 = task;
+                transport.receiver = Some(receiver);
+                transport.retry_at = Some(now + delay as f64);
             }
-        } else if transport.retries == 3 && conn.0.is_finished() {
-            transport.retries += 1;
-            let (_, err) = future::block_on(future::poll_once(&mut conn.0)).unwrap();
-            warn!("websocket room error: {err}, giving up");
+            None => warn!("websocket room error: {err}, giving up"),
         }
     }
 }
 
-async fn websocket_room_handler(
+pub(crate) async fn websocket_room_handler(
     transport_id: Entity,
     remote_address: String,
     wallet: Wallet,
     mut receiver: Receiver<NetworkMessage>,
     sender: Sender<PlayerUpdate>,
+    stats: TransportStats,
 ) -> (Receiver<NetworkMessage>, anyhow::Error) {
-    let res =
-        websocket_room_handler_inner(transport_id, remote_address, wallet, &mut receiver, sender)
-            .await;
+    let res = websocket_room_handler_inner(
+        transport_id,
+        remote_address,
+        wallet,
+        &mut receiver,
+        sender,
+        stats.clone(),
+    )
+    .await;
+    stats.set_connected(false);
     (receiver, res.err().unwrap_or(anyhow!("connection closed")))
 }
 
@@ -174,6 +276,7 @@ async fn websocket_room_handler_inner(
     wallet: Wallet,
     receiver: &mut Receiver<NetworkMessage>,
     sender: Sender<PlayerUpdate>,
+    stats: TransportStats,
 ) -> Result<(), anyhow::Error> {
     debug!(">> stream connect async : {remote_address}");
 
@@ -203,6 +306,8 @@ async fn websocket_room_handler_inner(
     };
     stream.send(ident.encode_to_vec().into()).await?;
     debug!(">> ident sent: {ident:?}");
+    // the server's first reply gives a roundtrip estimate
+    stats.start_roundtrip();
 
     // challenge / welcome
     let from_alias;
@@ -212,6 +317,7 @@ async fn websocket_room_handler_inner(
             bail!("stream closed unexpectedly awaiting challenge")
         };
         let response = response?;
+        stats.end_roundtrip();
         let response = WsPacket::decode(response.into_data().as_slice())?;
         let Some(message) = response.message else {
             bail!("received empty packet")
@@ -263,12 +369,30 @@ async fn websocket_room_handler_inner(
         }
     }
     dcl_assert!(from_alias != u32::MAX);
+    stats.set_connected(true);
 
     let (mut write, mut read) = stream.split();
+    let write_stats = stats.clone();
 
     // wrap and transmit outbound messages
     let f_write = async move {
-        while let Some(next) = receiver.recv().await {
+        // pings keep the roundtrip current
+        let mut ping_at = Instant::now() + ROUNDTRIP_FREQ;
+        loop {
+            let next = match recv_until(receiver, ping_at).await {
+                Outbound::Message(next) => next,
+                Outbound::Due => {
+                    write_stats.start_roundtrip();
+                    write
+                        .send(tungstenite::Message::Ping(Default::default()))
+                        .await?;
+                    ping_at = Instant::now() + ROUNDTRIP_FREQ;
+                    continue;
+                }
+                Outbound::Closed => break,
+            };
+            log_packet(PacketDirection::Outbound, "ws-room", None, &next.data);
+            write_stats.record_sent(next.data.len(), next.unreliable);
             let packet = WsPacket {
                 message: Some(ws_packet::Message::PeerUpdateMessage(WsPeerUpdate {
                     from_alias,
@@ -289,6 +413,10 @@ async fn websocket_room_handler_inner(
     let f_read = async move {
         while let Some(next) = read.next().await {
             let next = next?;
+            if next.is_pong() {
+                stats.end_roundtrip();
+                continue;
+            }
             let next = WsPacket::decode(next.into_data().as_slice())?;
             let Some(message) = next.message else {
                 bail!("received empty packet")
@@ -319,6 +447,13 @@ async fn websocket_room_handler_inner(
                     foreign_aliases.remove_by_left(&peer.alias);
                 }
                 ws_packet::Message::PeerUpdateMessage(update) => {
+                    log_packet(
+                        PacketDirection::Inbound,
+                        "ws-room",
+                        foreign_aliases.get_by_left(&update.from_alias).cloned(),
+                        &update.body,
+                    );
+                    stats.record_received(update.body.len(), update.unreliable);
                     let packet = match rfc4::Packet::decode(update.body.as_slice()) {
                         Ok(packet) => packet,
                         Err(e) => {
@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
+
 use bevy::{prelude::*, scene::scene_spawner_system};
 use bevy_console::{
     Command, ConsoleCommand, ConsoleCommandEntered, ConsoleConfiguration, ConsoleSet,
     PrintConsoleLine,
 };
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 
 use common::sets::SceneSets;
 
@@ -21,6 +23,7 @@ impl DoAddConsoleCommand for App {
         &mut self,
         system: impl IntoSystemConfigs<U>,
     ) -> &mut Self {
+        register_command::<T>(self);
         bevy_console::AddConsoleCommand::add_console_command::<T, U>(self, system)
     }
 }
@@ -31,17 +34,69 @@ impl DoAddConsoleCommand for App {
         &mut self,
         _: impl IntoSystemConfigs<U>,
     ) -> &mut Self {
-        // do nothing
+        // don't run the system, but keep the registry complete
+        register_command::<T>(self);
         self
     }
 }
 
+// definitions of all commands added with `add_console_command`, for `/help`
+#[derive(Resource, Default)]
+pub struct ConsoleCommandRegistry {
+    commands: BTreeMap<String, clap::Command>,
+}
+
+impl ConsoleCommandRegistry {
+    pub fn get(&self, name: &str) -> Option<&clap::Command> {
+        let name = name.trim();
+        self.commands.get(name).or_else(|| {
+            // allow the leading slash to be omitted
+            self.commands
+                .get(format!("/{}", name.trim_start_matches('/')).as_str())
+        })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.commands.keys().map(String::as_str)
+    }
+
+    // one line per command, with the first line of its description
+    pub fn summary(&self) -> Vec<String> {
+        self.commands
+            .iter()
+            .map(|(name, command)| {
+                match command
+                    .get_about()
+                    .and_then(|about| about.to_string().lines().next().map(ToOwned::to_owned))
+                {
+                    Some(about) => format!("{name} - {about}"),
+                    None => name.clone(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn usage(&self, name: &str) -> Option<String> {
+        self.get(name)
+            .map(|command| command.clone().render_long_help().to_string())
+    }
+}
+
+fn register_command<T: Command>(app: &mut App) {
+    let command = T::command();
+    app.world_mut()
+        .get_resource_or_insert_with(ConsoleCommandRegistry::default)
+        .commands
+        .insert(command.get_name().to_owned(), command);
+}
+
 pub struct ConsolePlugin {
     pub add_egui: bool,
 }
 
 impl Plugin for ConsolePlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<ConsoleCommandRegistry>();
         app.insert_resource(ConsoleConfiguration {
             // keys: vec![KeyCode::Backquote],
             // we don't want people using the actual console, just the command / print interface which gets piped to chat
@@ -87,10 +142,9 @@ impl Plugin for ConsolePlugin {
 }
 
 fn remove_default_commands(mut config: ResMut<ConsoleConfiguration>) {
-    for command_name in ["/help", "/clear", "/exit"] {
-        if let Some(res) = config.commands.remove(&command_name[1..]) {
-            config.commands.insert(command_name, res);
-        }
+    // the slash-prefixed replacements below forward to these
+    for command_name in ["help", "clear", "exit"] {
+        config.commands.remove(command_name);
     }
 }
 
@@ -98,6 +152,7 @@ fn remove_default_commands(mut config: ResMut<ConsoleConfiguration>) {
 pub struct PendingCommands(Vec<String>);
 
 //re-add default commands, unfortunately have to copy/paste
+/// clear the console
 #[derive(Parser, ConsoleCommand)]
 #[command(name = "/clear")]
 pub(crate) struct ClearCommand;
@@ -111,21 +166,35 @@ pub(crate) fn clear_command(
     }
 }
 
-//re-add default commands, unfortunately have to copy/paste
+/// list console commands, or show usage for one command
 #[derive(Parser, ConsoleCommand)]
 #[command(name = "/help")]
-pub(crate) struct HelpCommand;
+pub(crate) struct HelpCommand {
+    command: Option<String>,
+}
 
 pub(crate) fn help_command(
     mut cmd: ConsoleCommand<HelpCommand>,
-    mut pending: ResMut<PendingCommands>,
+    registry: Res<ConsoleCommandRegistry>,
 ) {
-    if let Some(Ok(_)) = cmd.take() {
-        pending.0.push("help".to_owned());
+    if let Some(Ok(HelpCommand { command })) = cmd.take() {
+        match command {
+            Some(name) => match registry.usage(&name) {
+                Some(usage) => cmd.reply_ok(usage),
+                None => cmd.reply_failed(format!("unknown command `{name}`, try /help")),
+            },
+            None => {
+                for line in registry.summary() {
+                    cmd.reply(line);
+                }
+                cmd.reply_ok("/help <command> for details");
+            }
+        }
     }
 }
 
 //re-add default commands, unfortunately have to copy/paste
+/// exit the app
 #[derive(Parser, ConsoleCommand)]
 #[command(name = "/exit")]
 pub(crate) struct ExitCommand;
@@ -150,3 +219,38 @@ pub fn send_pending(
         });
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// move the thing
+    #[derive(Parser, ConsoleCommand)]
+    #[command(name = "/move")]
+    struct MoveCommand {
+        distance: f32,
+    }
+
+    fn move_command(_: ConsoleCommand<MoveCommand>) {}
+
+    #[test]
+    fn test_command_registry() {
+        let mut app = App::new();
+        app.add_console_command::<HelpCommand, _>(help_command)
+            .add_console_command::<MoveCommand, _>(move_command);
+        let registry = app.world().resource::<ConsoleCommandRegistry>();
+
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["/help", "/move"]);
+        assert_eq!(
+            registry.summary(),
+            [
+                "/help - list console commands, or show usage for one command",
+                "/move - move the thing"
+            ]
+        );
+        // with or without the slash
+        assert!(registry.usage("move").unwrap().contains("<DISTANCE>"));
+        assert!(registry.usage("/move").is_some());
+        assert!(registry.usage("/jump").is_none());
+    }
+}
@@ -46,6 +46,17 @@ impl CrdtContext {
             return false;
         }
 
+        // a newer generation implies all older generations of the id are dead
+        let (gen, live) = &mut self.live_entities[entity.id as usize];
+        if *gen < entity.generation {
+            if *live {
+                self.death_row.insert(SceneEntityId::new(entity.id, *gen));
+            }
+            *gen = entity.generation;
+            *live = false;
+            self.nascent.retain(|nascent| nascent.id != entity.id);
+        }
+
         if !self.is_born(entity) {
             debug!("scene added {entity:?}");
             self.nascent.insert(entity);
@@ -72,18 +83,21 @@ impl CrdtContext {
         // update entity table and death row
         match &mut self.live_entities[scene_entity.id as usize] {
             (gen, live) if *gen <= scene_entity.generation => {
-                *gen = scene_entity.generation + 1;
-
+                // the live entity may be an older generation than the one killed
                 if *live {
-                    self.death_row.insert(scene_entity);
+                    self.death_row
+                        .insert(SceneEntityId::new(scene_entity.id, *gen));
                 }
+                *gen = scene_entity.generation + 1;
                 *live = false;
             }
             _ => (),
         }
 
-        // remove from nascent
-        self.nascent.remove(&scene_entity);
+        // remove from nascent, including any older generations
+        self.nascent.retain(|nascent| {
+            nascent.id != scene_entity.id || nascent.generation > scene_entity.generation
+        });
         debug!("scene killed {scene_entity:?}");
     }
 
@@ -121,3 +135,83 @@ impl CrdtContext {
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::Entity;
+
+    use super::*;
+
+    fn context() -> CrdtContext {
+        CrdtContext::new(SceneId(Entity::PLACEHOLDER), String::default(), true, false)
+    }
+
+    fn sorted(set: HashSet<SceneEntityId>) -> Vec<SceneEntityId> {
+        let mut list = set.into_iter().collect::<Vec<_>>();
+        list.sort();
+        list
+    }
+
+    #[test]
+    fn test_recreate_in_one_batch() {
+        let mut context = context();
+        let id = |generation| SceneEntityId::new(512, generation);
+
+        assert!(context.init(id(0)));
+        let census = context.take_census();
+        assert_eq!(sorted(census.born), vec![id(0)]);
+
+        // kill and recreate several times before the next census
+        context.kill(id(0));
+        assert!(context.init(id(1)));
+        context.kill(id(1));
+        assert!(context.init(id(2)));
+        context.kill(id(2));
+        assert!(context.init(id(3)));
+
+        // old generations can't be recreated
+        assert!(!context.init(id(1)));
+
+        let census = context.take_census();
+        assert_eq!(sorted(census.born), vec![id(3)]);
+        assert_eq!(sorted(census.died), vec![id(0)]);
+        for generation in 0..3 {
+            assert!(context.is_dead(id(generation)));
+            assert!(!context.is_born(id(generation)));
+        }
+        assert!(!context.is_dead(id(3)));
+        assert!(context.is_born(id(3)));
+    }
+
+    #[test]
+    fn test_implicit_generation_kill() {
+        let mut context = context();
+        let id = |generation| SceneEntityId::new(512, generation);
+
+        context.init(id(0));
+        context.take_census();
+
+        // a newer generation appearing without an explicit delete replaces the live entity
+        assert!(context.init(id(2)));
+        let census = context.take_census();
+        assert_eq!(sorted(census.born), vec![id(2)]);
+        assert_eq!(sorted(census.died), vec![id(0)]);
+        assert!(context.is_dead(id(1)));
+
+        // multiple new generations in one batch result in only the latest
+        context.init(id(3));
+        context.init(id(5));
+        context.init(id(4));
+        let census = context.take_census();
+        assert_eq!(sorted(census.born), vec![id(5)]);
+        assert_eq!(sorted(census.died), vec![id(2)]);
+
+        // killing a later generation than the live one reports the live generation as dead
+        context.kill(id(7));
+        let census = context.take_census();
+        assert!(census.born.is_empty());
+        assert_eq!(sorted(census.died), vec![id(5)]);
+        assert!(context.is_dead(id(7)));
+        assert!(context.init(id(8)));
+    }
+}
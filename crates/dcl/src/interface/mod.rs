@@ -173,6 +173,20 @@ impl CrdtStore {
         }
     }
 
+    // number of entity-component updates contained
+    pub fn update_count(&self) -> usize {
+        self.lww
+            .values()
+            .map(|state| state.updates.len())
+            .sum::<usize>()
+            + self
+                .go
+                .values()
+                .flat_map(|state| state.0.values())
+                .map(|entries| entries.len())
+                .sum::<usize>()
+    }
+
     pub fn take_updates(&mut self) -> CrdtStore {
         let lww =
             self.lww.iter_mut().map(|(component_id, state)| {
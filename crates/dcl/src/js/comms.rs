@@ -27,10 +27,13 @@ pub fn ops() -> Vec<OpDecl> {
         op_comms_send_binary(),
         op_comms_send_binary_single(),
         op_comms_recv_binary(),
+        op_local_bus_send(),
+        op_local_bus_recv(),
     ]
 }
 
 struct BinaryBusReceiver(tokio::sync::mpsc::UnboundedReceiver<(String, Vec<u8>)>);
+struct LocalBusReceiver(tokio::sync::mpsc::UnboundedReceiver<(String, String)>);
 
 #[op2(async)]
 async fn op_comms_send_string(state: Rc<RefCell<OpState>>, #[string] message: String) {
@@ -134,3 +137,43 @@ async fn op_comms_recv_binary(state: Rc<RefCell<OpState>>) -> Result<Vec<Vec<u8>
 
     Ok(results)
 }
+
+fn subscribe_local_bus(state: &mut OpState) {
+    if !state.has::<LocalBusReceiver>() {
+        let scene = state.borrow::<CrdtContext>().scene_id.0;
+        let (sx, rx) = tokio::sync::mpsc::unbounded_channel::<(String, String)>();
+        state
+            .borrow_mut::<RpcCalls>()
+            .push(RpcCall::SubscribeLocalMessages { scene, sender: sx });
+        state.put(LocalBusReceiver(rx));
+    }
+}
+
+#[op2(async)]
+async fn op_local_bus_send(state: Rc<RefCell<OpState>>, #[string] message: String) {
+    debug!("op_local_bus_send");
+    let mut state = state.borrow_mut();
+    subscribe_local_bus(&mut state);
+    let scene = state.borrow::<CrdtContext>().scene_id.0;
+    state
+        .borrow_mut::<RpcCalls>()
+        .push(RpcCall::SendLocalMessage { scene, message });
+}
+
+// (sender scene hash, message)
+#[op2(async)]
+#[serde]
+async fn op_local_bus_recv(
+    state: Rc<RefCell<OpState>>,
+) -> Result<Vec<(String, String)>, anyhow::Error> {
+    debug!("op_local_bus_recv");
+    let mut state = state.borrow_mut();
+    subscribe_local_bus(&mut state);
+
+    let rx = state.borrow_mut::<LocalBusReceiver>();
+    let mut results = Vec::default();
+    while let Ok(message) = rx.0.try_recv() {
+        results.push(message);
+    }
+    Ok(results)
+}
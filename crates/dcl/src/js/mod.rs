@@ -3,6 +3,7 @@ use std::{
     collections::HashMap,
     rc::Rc,
     sync::{mpsc::SyncSender, Arc},
+    time::Duration,
 };
 
 use bevy::utils::tracing::{debug, error, info_span};
@@ -33,6 +34,7 @@ use self::{
 };
 
 use super::{
+    fixed_scene_dt,
     interface::{crdt_context::CrdtContext, CrdtComponentInterfaces, CrdtStore},
     RendererResponse, SceneElapsedTime, SceneId, SceneLogLevel, SceneLogMessage, SceneResponse,
     VM_HANDLES,
@@ -59,6 +61,9 @@ pub mod websocket;
 // marker to indicate shutdown has been triggered
 pub struct ShuttingDown;
 
+// max dt passed to `onUpdate`
+const MAX_UPDATE_DT: Duration = Duration::from_secs(1);
+
 pub struct RendererStore(pub CrdtStore);
 
 pub fn create_runtime(
@@ -328,12 +333,21 @@ pub(crate) fn scene_thread(
 
     let start_time = std::time::Instant::now();
     let mut prev_time = start_time;
-    let mut elapsed;
+    let mut elapsed = Duration::ZERO;
     let mut reported_errors = 0;
     loop {
         let now = std::time::Instant::now();
-        let dt = now.saturating_duration_since(prev_time);
-        elapsed = now.saturating_duration_since(start_time);
+        let dt = match fixed_scene_dt() {
+            Some(fixed_dt) => {
+                elapsed += fixed_dt;
+                fixed_dt
+            }
+            None => {
+                elapsed = now.saturating_duration_since(start_time);
+                // clamp so scenes don't receive a huge delta after being paused
+                now.saturating_duration_since(prev_time).min(MAX_UPDATE_DT)
+            }
+        };
         prev_time = now;
 
         state
@@ -470,6 +484,7 @@ fn op_require(
         "~system/EthereumController" => {
             Ok(include_str!("modules/EthereumController.js").to_owned())
         }
+        "~system/LocalMessageBus" => Ok(include_str!("modules/LocalMessageBus.js").to_owned()),
         "~system/Players" => Ok(include_str!("modules/Players.js").to_owned()),
         "~system/PortableExperiences" => {
             Ok(include_str!("modules/PortableExperiences.js").to_owned())
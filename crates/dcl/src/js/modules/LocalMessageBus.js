@@ -0,0 +1,17 @@
+// messages between scenes running in this client. messages never leave the client, and
+// are only delivered to other scenes in the same realm that have also been granted
+// the scene messages permission
+
+// (message: string)
+module.exports.send = async function (body) {
+    await Deno.core.ops.op_local_bus_send(body.message);
+    return {}
+}
+
+// (messages: [{ sender: string, message: string }]), sender is the sending scene's hash
+module.exports.receive = async function () {
+    const messages = await Deno.core.ops.op_local_bus_recv();
+    return {
+        messages: messages.map(([sender, message]) => ({ sender, message }))
+    }
+}
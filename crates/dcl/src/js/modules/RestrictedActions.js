@@ -35,6 +35,14 @@ module.exports.openExternalUrl = async function (body) {
 module.exports.openNftDialog = async function (body) { 
     return await Deno.core.ops.op_open_nft_dialog(body.urn) 
 }
+
+// show a short notification to the user, labelled with the scene's title. requires the
+// notifications permission, and is rate limited per scene
+// (message: string) -> { success: boolean }
+module.exports.showToast = async function (body) { 
+    const success = await Deno.core.ops.op_show_toast(body.message);
+    return { success }
+}
 module.exports.setCommunicationsAdapter = async function (body) { 
     console.error("RestrictedActions::setCommunicationsAdapter not implemented");
     return {} 
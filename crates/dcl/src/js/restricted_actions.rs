@@ -30,6 +30,7 @@ pub fn ops() -> Vec<OpDecl> {
         op_emote(),
         op_scene_emote(),
         op_open_nft_dialog(),
+        op_show_toast(),
     ]
 }
 
@@ -161,6 +162,23 @@ async fn op_external_url(state: Rc<RefCell<OpState>>, #[string] url: String) ->
     matches!(rx.await, Ok(Ok(_)))
 }
 
+#[op2(async)]
+async fn op_show_toast(state: Rc<RefCell<OpState>>, #[string] message: String) -> bool {
+    debug!("op_show_toast");
+    let (sx, rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
+    let scene = state.borrow().borrow::<CrdtContext>().scene_id.0;
+    state
+        .borrow_mut()
+        .borrow_mut::<RpcCalls>()
+        .push(RpcCall::ShowToast {
+            scene,
+            message,
+            response: sx.into(),
+        });
+
+    matches!(rx.await, Ok(Ok(_)))
+}
+
 #[op2(fast)]
 fn op_emote(op_state: &mut OpState, #[string] emote: String) {
     debug!("op_emote");
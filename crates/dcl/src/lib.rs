@@ -1,10 +1,15 @@
 use std::{
     panic::{self, AssertUnwindSafe},
-    sync::{mpsc::SyncSender, Mutex},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        mpsc::SyncSender,
+        Mutex,
+    },
+    time::{Duration, Instant},
 };
 
 use bevy::{
-    log::error,
+    log::{error, warn},
     prelude::Entity,
     utils::{HashMap, HashSet},
 };
@@ -87,6 +92,53 @@ pub struct SceneLogMessage {
 pub(crate) static VM_HANDLES: Lazy<Mutex<HashMap<SceneId, IsolateHandle>>> =
     Lazy::new(Default::default);
 
+// number of scene threads that have not yet exited.
+// each scene owns a dedicated os thread since its v8 isolate is bound to the thread that created it.
+// threads are idle while waiting for the renderer, the number of scenes executing at once is
+// limited by `AppConfig::scene_threads` in the scene runner
+static SCENE_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn scene_thread_count() -> usize {
+    SCENE_THREADS.load(Ordering::SeqCst)
+}
+
+// fixed scene tick duration in nanoseconds, 0 for wall time
+static FIXED_SCENE_DT: AtomicU64 = AtomicU64::new(0);
+
+// when set, every scene tick advances scene time by exactly `dt` regardless of wall time,
+// so runs are reproducible. applies to existing and new scenes from their next tick
+pub fn set_fixed_scene_dt(dt: Option<Duration>) {
+    let nanos = dt.map_or(0, |dt| dt.as_nanos().clamp(1, u64::MAX as u128) as u64);
+    FIXED_SCENE_DT.store(nanos, Ordering::SeqCst);
+}
+
+pub fn fixed_scene_dt() -> Option<Duration> {
+    match FIXED_SCENE_DT.load(Ordering::SeqCst) {
+        0 => None,
+        nanos => Some(Duration::from_nanos(nanos)),
+    }
+}
+
+// wait for all scene threads to exit, up to the given timeout.
+// scene threads exit when their renderer channel is closed, so the scene thread handles
+// must be dropped before calling this. threads still running at the timeout are asked to
+// terminate and the number remaining is returned.
+pub fn wait_for_scene_threads(timeout: Duration) -> usize {
+    let deadline = Instant::now() + timeout;
+    while SCENE_THREADS.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let remaining = SCENE_THREADS.load(Ordering::SeqCst);
+    if remaining > 0 {
+        for (id, handle) in VM_HANDLES.lock().unwrap().iter() {
+            warn!("[{id:?}] scene thread did not exit, terminating");
+            handle.terminate_execution();
+        }
+    }
+    remaining
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_scene(
     scene_hash: String,
@@ -104,6 +156,7 @@ pub fn spawn_scene(
 ) -> Sender<RendererResponse> {
     let (main_sx, thread_rx) = tokio::sync::mpsc::channel::<RendererResponse>(1);
 
+    SCENE_THREADS.fetch_add(1, Ordering::SeqCst);
     std::thread::Builder::new()
         .name(format!("scene thread {:?}", id.0))
         .stack_size(8388608)
@@ -129,6 +182,9 @@ pub fn spawn_scene(
             if let Err(e) = thread_result {
                 error!("[{id:?}] caught scene thread panic: {e:?}");
             }
+
+            VM_HANDLES.lock().unwrap().remove(&id);
+            SCENE_THREADS.fetch_sub(1, Ordering::SeqCst);
         })
         .unwrap();
 
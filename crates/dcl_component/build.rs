@@ -44,6 +44,7 @@ fn gen_sdk_components() -> Result<()> {
         "nft_shape",
         "tween",
         "tween_state",
+        "tween_completed",
         "light",
         "global_light",
         "spotlight",
@@ -58,6 +59,7 @@ fn gen_sdk_components() -> Result<()> {
         .map(|component| format!("src/proto/decentraland/sdk/components/{component}.proto"))
         .collect::<Vec<_>>();
 
+    sources.push("src/proto/decentraland/explorer/tween.proto".into());
     sources.push("src/proto/decentraland/kernel/comms/rfc5/ws_comms.proto".into());
     sources.push("src/proto/decentraland/kernel/comms/rfc4/comms.proto".into());
     sources.push("src/proto/decentraland/kernel/comms/v3/archipelago.proto".into());
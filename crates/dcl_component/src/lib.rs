@@ -116,6 +116,8 @@ impl SceneComponentId {
 
     pub const TWEEN: SceneComponentId = SceneComponentId(1102);
     pub const TWEEN_STATE: SceneComponentId = SceneComponentId(1103);
+    // explorer extension
+    pub const TWEEN_COMPLETED: SceneComponentId = SceneComponentId(1500);
 
     pub const LIGHT: SceneComponentId = SceneComponentId(1204);
     pub const SPOTLIGHT: SceneComponentId = SceneComponentId(1205);
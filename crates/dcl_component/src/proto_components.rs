@@ -1,3 +1,6 @@
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
 use super::{FromDclReader, ToDclWriter};
 
 pub mod sdk {
@@ -14,6 +17,12 @@ pub mod sdk {
     }
 }
 
+// explorer extensions to sdk components
+#[allow(clippy::all)]
+pub mod explorer {
+    include!(concat!(env!("OUT_DIR"), "/decentraland.explorer.rs"));
+}
+
 pub mod kernel {
     #[allow(clippy::all)]
     pub mod comms {
@@ -105,8 +114,9 @@ impl DclProtoComponent for sdk::components::PbVideoEvent {}
 impl DclProtoComponent for sdk::components::PbVisibilityComponent {}
 impl DclProtoComponent for sdk::components::PbAvatarModifierArea {}
 impl DclProtoComponent for sdk::components::PbNftShape {}
-impl DclProtoComponent for sdk::components::PbTween {}
+impl DclProtoComponent for explorer::PbTween {}
 impl DclProtoComponent for sdk::components::PbTweenState {}
+impl DclProtoComponent for sdk::components::PbTweenCompleted {}
 impl DclProtoComponent for sdk::components::PbLight {}
 impl DclProtoComponent for sdk::components::PbSpotlight {}
 impl DclProtoComponent for sdk::components::PbGlobalLight {}
@@ -195,23 +205,58 @@ impl From<common::Quaternion> for bevy::math::Quat {
 }
 
 // COLOR conversions
+// sdk colors are srgb: the sdk's hex and named colors are srgb, and the reference client
+// passes the components straight to unity `Color`s, which are gamma space. the conversions
+// below always use srgb, systems that apply scene colors adjust them with the `ColorSpace`
+// resource so users can choose how scene colors are interpreted
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    Linear,
+}
+
+impl ColorSpace {
+    // reinterpret a color converted from sdk components in this color space
+    pub fn from_sdk(self, color: bevy::prelude::Color) -> bevy::prelude::Color {
+        match self {
+            ColorSpace::Srgb => color,
+            ColorSpace::Linear => {
+                let c = color.to_srgba();
+                bevy::prelude::Color::linear_rgba(c.red, c.green, c.blue, c.alpha)
+            }
+        }
+    }
+
+    // inverse of `from_sdk`, for colors reported back to scenes
+    pub fn to_sdk(self, color: bevy::prelude::Color) -> bevy::prelude::Color {
+        match self {
+            ColorSpace::Srgb => color,
+            ColorSpace::Linear => {
+                let c = color.to_linear();
+                bevy::prelude::Color::srgba(c.red, c.green, c.blue, c.alpha)
+            }
+        }
+    }
+}
+
 impl Copy for common::Color3 {}
 impl Copy for common::Color4 {}
 impl From<common::Color4> for bevy::prelude::Color {
     fn from(value: common::Color4) -> Self {
-        bevy::prelude::Color::linear_rgba(value.r, value.g, value.b, value.a)
+        bevy::prelude::Color::srgba(value.r, value.g, value.b, value.a)
     }
 }
 
 impl From<common::Color3> for bevy::prelude::Color {
     fn from(value: common::Color3) -> Self {
-        bevy::prelude::Color::linear_rgb(value.r, value.g, value.b)
+        bevy::prelude::Color::srgb(value.r, value.g, value.b)
     }
 }
 
 impl From<bevy::prelude::Color> for common::Color4 {
     fn from(value: bevy::prelude::Color) -> Self {
-        let rgba = value.to_linear();
+        let rgba = value.to_srgba();
         common::Color4 {
             r: rgba.red,
             g: rgba.green,
@@ -223,17 +268,18 @@ impl From<bevy::prelude::Color> for common::Color4 {
 
 impl From<bevy::prelude::Color> for common::Color3 {
     fn from(value: bevy::prelude::Color) -> Self {
-        value.to_linear().into()
+        let rgba = value.to_srgba();
+        common::Color3 {
+            r: rgba.red,
+            g: rgba.green,
+            b: rgba.blue,
+        }
     }
 }
 
 impl From<bevy::prelude::LinearRgba> for common::Color3 {
     fn from(value: bevy::prelude::LinearRgba) -> Self {
-        common::Color3 {
-            r: value.red,
-            g: value.green,
-            b: value.blue,
-        }
+        bevy::prelude::Color::from(value).into()
     }
 }
 
@@ -259,3 +305,34 @@ impl RoughRoundExt for bevy::math::Vec3 {
         (self * 2f32.powf(-pow2 as f32)).round() * 2f32.powf(pow2 as f32)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_color_space_mid_grey() {
+        let sdk_grey = common::Color4 {
+            r: 0.5,
+            g: 0.5,
+            b: 0.5,
+            a: 1.0,
+        };
+
+        // srgb mid grey is ~0.214 in linear
+        let grey = ColorSpace::Srgb.from_sdk(sdk_grey.into());
+        let linear = grey.to_linear();
+        assert!((linear.red - 0.2140).abs() < 0.001);
+        assert_eq!(linear.alpha, 1.0);
+        let back = common::Color4::from(ColorSpace::Srgb.to_sdk(grey));
+        assert!((back.r - 0.5).abs() < 0.0001 && (back.g - 0.5).abs() < 0.0001);
+        assert_eq!(back.a, 1.0);
+
+        // linear values pass through unchanged
+        let grey = ColorSpace::Linear.from_sdk(sdk_grey.into());
+        assert!((grey.to_linear().red - 0.5).abs() < 0.0001);
+        let back = common::Color4::from(ColorSpace::Linear.to_sdk(grey));
+        assert!((back.r - 0.5).abs() < 0.0001 && (back.b - 0.5).abs() < 0.0001);
+        assert_eq!(back.a, 1.0);
+    }
+}
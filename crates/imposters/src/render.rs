@@ -305,79 +305,78 @@ pub fn spawn_imposters(
     let live_min = live_parcels.iter().fold(IVec2::MAX, |x, y| x.min(*y));
     let live_max = live_parcels.iter().fold(IVec2::MIN, |x, y| x.max(*y));
 
-    let max_distance = config
-        .scene_imposter_distances
-        .last()
-        .copied()
-        .unwrap_or(0.0);
-    let mut level = config.scene_imposter_distances.len() - 1;
+    // with imposters off only baking ingredients are required
+    let distances = config.active_imposter_distances();
+    if let Some(&max_distance) = distances.last() {
+        let mut level = distances.len() - 1;
 
-    let tile_size = 1 << level;
+        let tile_size = 1 << level;
 
-    let min_tile = ((origin - max_distance) / 16.0 / tile_size as f32)
-        .floor()
-        .as_ivec2();
-    let max_tile = ((origin + max_distance) / 16.0 / tile_size as f32)
-        .ceil()
-        .as_ivec2();
+        let min_tile = ((origin - max_distance) / 16.0 / tile_size as f32)
+            .floor()
+            .as_ivec2();
+        let max_tile = ((origin + max_distance) / 16.0 / tile_size as f32)
+            .ceil()
+            .as_ivec2();
 
-    let min_tile = min_tile.max(pointers.min() >> level as u32);
-    let max_tile = max_tile.min(pointers.max() >> level as u32);
+        let min_tile = min_tile.max(pointers.min() >> level as u32);
+        let max_tile = max_tile.min(pointers.max() >> level as u32);
 
-    let mut required_tiles = (min_tile.x..=max_tile.x)
-        .flat_map(|x| (min_tile.y..=max_tile.y).map(move |y| IVec2::new(x, y)))
-        .collect::<HashSet<_>>();
+        let mut required_tiles = (min_tile.x..=max_tile.x)
+            .flat_map(|x| (min_tile.y..=max_tile.y).map(move |y| IVec2::new(x, y)))
+            .collect::<HashSet<_>>();
 
-    // take the largest permitted tile to fill the area
-    while level > 0 {
-        let tile_size = 1 << level;
-        let tile_size_world = (tile_size * 16) as f32;
+        // take the largest permitted tile to fill the area
+        while level > 0 {
+            let tile_size = 1 << level;
+            let tile_size_world = (tile_size * 16) as f32;
 
-        for tile in std::mem::take(&mut required_tiles).into_iter() {
-            let tile_origin_parcel = tile * tile_size;
-            let tile_origin_world = tile_origin_parcel.as_vec2() * 16.0;
+            for tile in std::mem::take(&mut required_tiles).into_iter() {
+                let tile_origin_parcel = tile * tile_size;
+                let tile_origin_world = tile_origin_parcel.as_vec2() * 16.0;
 
-            let closest_point =
-                origin.clamp(tile_origin_world, tile_origin_world + tile_size_world);
-            let closest_distance = (closest_point - origin).length();
+                let closest_point =
+                    origin.clamp(tile_origin_world, tile_origin_world + tile_size_world);
+                let closest_distance = (closest_point - origin).length();
 
-            // check it's not too far
-            if closest_distance > max_distance {
-                continue;
-            }
+                // check it's not too far
+                if closest_distance > max_distance {
+                    continue;
+                }
 
-            let mut render_tile = true;
-            // check it's not too close
-            render_tile &= closest_distance > config.scene_imposter_distances[level - 1];
-            // ensure no live scenes intersect the tile
-            render_tile &= {
-                live_max.cmplt(tile_origin_parcel).any()
-                    || live_min.cmpge(tile_origin_parcel + tile_size).any()
-                    || live_parcels.iter().all(|p| {
-                        p.cmplt(tile_origin_parcel).any()
-                            || p.cmpge(tile_origin_parcel + tile_size).any()
-                    })
-            };
+                let mut render_tile = true;
+                // check it's not too close
+                render_tile &= closest_distance > distances[level - 1];
+                // ensure no live scenes intersect the tile
+                render_tile &= {
+                    live_max.cmplt(tile_origin_parcel).any()
+                        || live_min.cmpge(tile_origin_parcel + tile_size).any()
+                        || live_parcels.iter().all(|p| {
+                            p.cmplt(tile_origin_parcel).any()
+                                || p.cmpge(tile_origin_parcel + tile_size).any()
+                        })
+                };
 
-            if render_tile {
-                debug!("adding {}:{} == {}", tile, level, tile_origin_parcel);
-                required.insert((tile_origin_parcel, level, false));
-            } else {
-                // add to next level requirements
-                debug!("cant' add {}:{} == {}", tile, level, tile_origin_parcel);
-                for offset in [IVec2::ZERO, IVec2::X, IVec2::Y, IVec2::ONE] {
-                    debug!("maybe the child {}:{}", tile * 2 + offset, level - 1);
-                    required_tiles.insert(tile * 2 + offset);
+                if render_tile {
+                    debug!("adding {}:{} == {}", tile, level, tile_origin_parcel);
+                    required.insert((tile_origin_parcel, level, false));
+                } else {
+                    // add to next level requirements
+                    debug!("cant' add {}:{} == {}", tile, level, tile_origin_parcel);
+                    for offset in [IVec2::ZERO, IVec2::X, IVec2::Y, IVec2::ONE] {
+                        debug!("maybe the child {}:{}", tile * 2 + offset, level - 1);
+                        required_tiles.insert(tile * 2 + offset);
+                    }
                 }
             }
-        }
 
-        level -= 1;
-    }
+            level -= 1;
+        }
 
-    for remaining_parcel in required_tiles {
-        if !live_parcels.contains(&remaining_parcel) {
-            required.insert((remaining_parcel, 0, false));
+        for remaining_parcel in required_tiles {
+            if !live_parcels.contains(&remaining_parcel) {
+                required.insert((remaining_parcel, 0, false));
+            }
         }
     }
 
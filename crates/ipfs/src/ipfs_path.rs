@@ -38,7 +38,7 @@ use std::{
     str::FromStr,
 };
 
-use bevy::log::error;
+use bevy::{log::error, math::IVec2};
 use urn::Urn;
 
 use crate::ServerAbout;
@@ -274,6 +274,56 @@ impl TryFrom<&str> for IpfsKey {
     }
 }
 
+// query parameters of a scene urn. realms list urns as `urn:decentraland:entity:<hash>?baseUrl=<url>`,
+// while the rfc 8141 form is `...?=baseUrl=<url>`. both forms are accepted, with parameters
+// separated by `&`. unrecognised parameters are ignored.
+// - `baseUrl`: endpoint the entity is sourced from
+// - `position`: `x,y` parcel to place the scene's base parcel at, overriding its scene.json
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UrnQuery {
+    pub base_url: Option<String>,
+    pub position: Option<IVec2>,
+}
+
+impl UrnQuery {
+    // split an urn into the urn without its query, and the query parameters
+    pub fn parse(urn: &str) -> (&str, Self) {
+        let Some((urn, query)) = urn.split_once('?') else {
+            return (urn, Self::default());
+        };
+
+        let mut params = Self::default();
+        for (key, value) in query
+            .strip_prefix('=')
+            .unwrap_or(query)
+            .split('&')
+            .flat_map(|piece| piece.split_once('='))
+        {
+            let value = urlencoding::decode(value)
+                .map(|value| value.into_owned())
+                .unwrap_or_else(|_| value.to_owned());
+            match key {
+                "baseUrl" => params.base_url = Some(value),
+                "position" => params.position = parse_parcel(&value),
+                _ => (),
+            }
+        }
+
+        (urn, params)
+    }
+
+    // offset to apply to the parcels of a scene with the given base
+    pub fn parcel_offset(&self, base: IVec2) -> IVec2 {
+        self.position
+            .map_or(IVec2::ZERO, |position| position - base)
+    }
+}
+
+fn parse_parcel(value: &str) -> Option<IVec2> {
+    let (x, y) = value.split_once(',')?;
+    Some(IVec2::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct IpfsPath {
     key_values: BTreeMap<IpfsKey, String>,
@@ -289,6 +339,7 @@ impl IpfsPath {
     }
 
     pub fn new_from_urn<T: IpfsAsset>(urn: &str) -> Result<Self, anyhow::Error> {
+        let (urn, query) = UrnQuery::parse(urn);
         let urn = Urn::from_str(urn)?;
         anyhow::ensure!(
             urn.nid() == "decentraland",
@@ -306,16 +357,8 @@ impl IpfsPath {
             _ => anyhow::bail!("unrecognised nss lhs: `{lhs}`"),
         };
 
-        let key_values = BTreeMap::from_iter(
-            urn.q_component()
-                .unwrap_or("")
-                .split('&')
-                .flat_map(|piece| piece.split_once('='))
-                .flat_map(|(key, value)| match key {
-                    "baseUrl" => Some((IpfsKey::BaseUrl, value.to_owned())),
-                    _ => None,
-                }),
-        );
+        let key_values =
+            BTreeMap::from_iter(query.base_url.map(|base_url| (IpfsKey::BaseUrl, base_url)));
 
         Ok(Self {
             ipfs_type: IpfsType::Entity {
@@ -464,3 +507,70 @@ impl From<&IpfsPath> for PathBuf {
 pub fn normalize_path(path: &str) -> String {
     path.replace('\\', "/")
 }
+
+#[cfg(test)]
+mod test {
+    use crate::EntityDefinition;
+
+    use super::*;
+
+    const HASH: &str = "bafkreiabcdef";
+    const BASE_URL: &str = "https://content.example.org/contents/";
+
+    fn entity_path(urn: &str) -> IpfsPath {
+        IpfsPath::new_from_urn::<EntityDefinition>(urn).unwrap()
+    }
+
+    #[test]
+    fn test_urn_without_query() {
+        let urn = format!("urn:decentraland:entity:{HASH}");
+        assert_eq!(UrnQuery::parse(&urn), (urn.as_str(), UrnQuery::default()));
+
+        let path = entity_path(&urn);
+        assert_eq!(path.context_free_hash().unwrap(), Some(HASH.to_owned()));
+        assert!(path.key_values.is_empty());
+    }
+
+    #[test]
+    fn test_urn_with_query() {
+        // realm form and rfc 8141 form parse the same
+        for separator in ["?", "?=", "?=&"] {
+            let urn = format!("urn:decentraland:entity:{HASH}{separator}baseUrl={BASE_URL}");
+            let (base, query) = UrnQuery::parse(&urn);
+            assert_eq!(base, format!("urn:decentraland:entity:{HASH}"));
+            assert_eq!(query.base_url.as_deref(), Some(BASE_URL));
+            assert_eq!(query.position, None);
+
+            let path = entity_path(&urn);
+            assert_eq!(path.context_free_hash().unwrap(), Some(HASH.to_owned()));
+            assert_eq!(
+                path.key_values.get(&IpfsKey::BaseUrl).map(String::as_str),
+                Some(BASE_URL)
+            );
+        }
+    }
+
+    #[test]
+    fn test_urn_with_multiple_params() {
+        let urn = format!(
+            "urn:decentraland:entity:{HASH}?position=-10,25&other=1&baseUrl={}",
+            urlencoding::encode(BASE_URL)
+        );
+        let (_, query) = UrnQuery::parse(&urn);
+        assert_eq!(query.base_url.as_deref(), Some(BASE_URL));
+        assert_eq!(query.position, Some(IVec2::new(-10, 25)));
+
+        // the scene's base parcel moves to the requested position, and the other parcels with it
+        let base = IVec2::new(3, 4);
+        let offset = query.parcel_offset(base);
+        assert_eq!(base + offset, IVec2::new(-10, 25));
+        assert_eq!(IVec2::new(4, 4) + offset, IVec2::new(-9, 25));
+
+        // no position, no offset
+        assert_eq!(UrnQuery::default().parcel_offset(base), IVec2::ZERO);
+
+        // an invalid position is ignored
+        let (_, query) = UrnQuery::parse("urn:decentraland:entity:x?position=nowhere");
+        assert_eq!(query.position, None);
+    }
+}
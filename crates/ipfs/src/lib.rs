@@ -5,7 +5,7 @@ use std::{
     marker::PhantomData,
     path::{Path, PathBuf},
     sync::{
-        atomic::{self, AtomicU16},
+        atomic::{self, AtomicU16, AtomicU64},
         Arc,
     },
     time::{Duration, Instant},
@@ -29,7 +29,7 @@ use bevy::{
     utils::{ConditionalSendFuture, HashMap},
 };
 use bevy_console::{ConsoleCommand, PrintConsoleLine};
-use common::util::project_directories;
+use common::{structs::RealmSettingOverrides, util::project_directories};
 use ipfs_path::IpfsAsset;
 use isahc::{http::StatusCode, prelude::Configurable, AsyncReadResponseExt, RequestExt};
 use serde::{Deserialize, Serialize};
@@ -370,6 +370,16 @@ pub struct ServerConfiguration {
     pub realm_name: Option<String>,
     pub network_id: Option<u32>,
     pub city_loader_content_server: Option<String>,
+    pub skybox: Option<RealmSkybox>,
+    // suggested settings, applied while in the realm unless the user has their own
+    pub settings: Option<RealmSettingOverrides>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RealmSkybox {
+    // url of a cubemap image, with the six faces stacked vertically
+    pub cubemap: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -585,6 +595,22 @@ pub struct IpfsContext {
     num_slots: usize,
 }
 
+// content request totals since startup
+#[derive(Default)]
+pub struct IpfsStats {
+    // requests served from the local cache
+    pub cache_hits: AtomicU64,
+    // successful remote requests
+    pub remote_ok: AtomicU64,
+    // failed remote requests, including repeats of recently failed requests
+    pub remote_failed: AtomicU64,
+    // bytes downloaded by successful remote requests
+    pub bytes_downloaded: AtomicU64,
+    // bytes of content written to the local cache. only counts files already in the cache
+    // once something measures them
+    pub cache_bytes: AtomicU64,
+}
+
 pub struct IpfsIo {
     is_preview: bool, // determines whether we always retry failed assets immediately
     default_io: Box<dyn ErasedAssetReader>,
@@ -595,6 +621,7 @@ pub struct IpfsIo {
     request_slots: tokio::sync::Semaphore,
     reqno: AtomicU16,
     static_files: HashMap<&'static str, &'static str>,
+    stats: IpfsStats,
 }
 
 impl IpfsIo {
@@ -620,6 +647,7 @@ impl IpfsIo {
             request_slots: tokio::sync::Semaphore::new(num_slots),
             reqno: default(),
             static_files: static_paths,
+            stats: default(),
         }
     }
 
@@ -737,6 +765,10 @@ impl IpfsIo {
         self.default_fs_path.as_path()
     }
 
+    pub fn stats(&self) -> &IpfsStats {
+        &self.stats
+    }
+
     // load entities from pointers and cache urls
     pub fn active_entities(
         self: &Arc<Self>,
@@ -860,6 +892,17 @@ impl IpfsIo {
         ))
     }
 
+    // whether the file is in the entity's content map, or is a url. note - blocking
+    pub fn has_content_file(&self, file_path: &str, content_hash: &str) -> bool {
+        let file_path = normalize_path(file_path);
+        self.context
+            .blocking_read()
+            .entities
+            .get(content_hash)
+            .is_some_and(|entity| entity.collection.hash(&file_path).is_some())
+            || url::Url::try_from(file_path.as_str()).is_ok()
+    }
+
     // note - blocking. use from a blockable thread
     pub fn content_url(&self, file_path: &str, content_hash: &str) -> Option<String> {
         let ipfs_path = IpfsPath::new(IpfsType::new_content_file(
@@ -950,6 +993,9 @@ impl AssetReader for IpfsIo {
                     if let Ok(mut res) = self.default_io.read(&self.cache_path().join(hash)).await {
                         let mut daft_buffer = Vec::default();
                         res.read_to_end(&mut daft_buffer).await?;
+                        self.stats
+                            .cache_hits
+                            .fetch_add(1, atomic::Ordering::Relaxed);
                         let reader: Box<Reader> = Box::new(Cursor::new(daft_buffer));
                         return Ok(reader);
                     }
@@ -990,6 +1036,9 @@ impl AssetReader for IpfsIo {
                 {
                     self.context.write().await.failed_remotes.remove(&remote);
                 } else {
+                    self.stats
+                        .remote_failed
+                        .fetch_add(1, atomic::Ordering::Relaxed);
                     return Err(AssetReaderError::Io(Arc::new(std::io::Error::new(
                         ErrorKind::Other,
                         format!("(repeat request for failed `{remote}`)"),
@@ -1034,6 +1083,9 @@ impl AssetReader for IpfsIo {
                             .await
                             .failed_remotes
                             .insert(remote.clone(), Instant::now());
+                        self.stats
+                            .remote_failed
+                            .fetch_add(1, atomic::Ordering::Relaxed);
                         return Err(AssetReaderError::Io(Arc::new(std::io::Error::new(
                             ErrorKind::Other,
                             format!("[{token:?}]: server responded `{e}` requesting `{remote}`"),
@@ -1045,6 +1097,9 @@ impl AssetReader for IpfsIo {
                             .await
                             .failed_remotes
                             .insert(remote.clone(), Instant::now());
+                        self.stats
+                            .remote_failed
+                            .fetch_add(1, atomic::Ordering::Relaxed);
                         return Err(AssetReaderError::Io(Arc::new(std::io::Error::new(
                             ErrorKind::Other,
                             format!(
@@ -1071,6 +1126,9 @@ impl AssetReader for IpfsIo {
                             .await
                             .failed_remotes
                             .insert(remote.clone(), Instant::now());
+                        self.stats
+                            .remote_failed
+                            .fetch_add(1, atomic::Ordering::Relaxed);
                         return Err(AssetReaderError::Io(Arc::new(std::io::Error::new(
                             ErrorKind::Other,
                             format!("[{token:?}] failed to convert to bytes: `{remote}`: {e}"),
@@ -1079,6 +1137,11 @@ impl AssetReader for IpfsIo {
                 }
             };
 
+            self.stats.remote_ok.fetch_add(1, atomic::Ordering::Relaxed);
+            self.stats
+                .bytes_downloaded
+                .fetch_add(data.len() as u64, atomic::Ordering::Relaxed);
+
             if let Some(hash) = hash {
                 if ipfs_path.should_cache(&hash) {
                     let mut cache_path = PathBuf::from(self.cache_path());
@@ -1094,6 +1157,9 @@ impl AssetReader for IpfsIo {
                         if let Err(e) = std::fs::rename(cache_path, &final_path) {
                             warn!("failed to rename cache item `{cache_path_str}`: {e}");
                         } else {
+                            self.stats
+                                .cache_bytes
+                                .fetch_add(data.len() as u64, atomic::Ordering::Relaxed);
                             debug!("cached ok `{}`", final_path.to_string_lossy());
                         }
                     }
@@ -1,4 +1,7 @@
+pub mod local_bus;
+pub mod portal;
 pub mod teleport;
+pub mod toast;
 
 use std::{
     path::{Path, PathBuf},
@@ -12,6 +15,7 @@ use bevy::{
     prelude::*,
     tasks::{IoTaskPool, Task},
     utils::{HashMap, HashSet},
+    window::PrimaryWindow,
 };
 use bevy_console::{ConsoleCommand, PrintConsoleLine};
 use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
@@ -49,7 +53,10 @@ use scene_runner::{
 };
 use serde_json::{json, Value};
 use teleport::{handle_out_of_world, teleport_player};
-use ui_core::button::DuiButton;
+use ui_core::{
+    button::DuiButton,
+    dialog::{ConfirmDialog, ConfirmDialogExt, ConfirmResponse},
+};
 use wallet::{browser_auth::remote_send_async, Wallet};
 
 pub struct RestrictedActionsPlugin;
@@ -86,11 +93,16 @@ impl Plugin for RestrictedActionsPlugin {
                     handle_texture_size,
                     handle_generic_perm,
                     handle_spawned_command,
+                    portal::portal_interaction,
+                    local_bus::handle_local_messages,
+                    toast::scene_toasts,
                 ),
             )
                 .in_set(SceneSets::RestrictedActions),
         );
         app.init_resource::<PendingPortableCommands>();
+        app.init_resource::<local_bus::LocalMessageRouter>();
+        app.init_resource::<toast::SceneToastLimiter>();
         app.add_console_command::<SpawnPortableCommand, _>(spawn_portable_command);
         app.add_console_command::<KillPortableCommand, _>(kill_portable_command);
     }
@@ -227,9 +239,30 @@ fn change_realm(
     }
 }
 
+// schemes a scene may ask to open. anything else (file, javascript, data, custom app
+// handlers) could run or expose local content, so is rejected outright
+const EXTERNAL_URL_SCHEMES: [&str; 2] = ["http", "https"];
+
+fn check_external_url(url: &str) -> Result<(), String> {
+    let Some((scheme, rest)) = url.trim().split_once(':') else {
+        return Err("invalid url".to_owned());
+    };
+    if !EXTERNAL_URL_SCHEMES.contains(&scheme.to_lowercase().as_str()) {
+        return Err(format!("url scheme `{scheme}` is not allowed"));
+    }
+    if !rest.starts_with("//") || rest.len() <= 2 {
+        return Err("invalid url".to_owned());
+    }
+    Ok(())
+}
+
 fn external_url(
+    mut commands: Commands,
     mut events: EventReader<RpcCall>,
     mut perms: Permission<(RpcResultSender<Result<(), String>>, String)>,
+    window: Query<(), With<PrimaryWindow>>,
+    dui: Res<DuiRegistry>,
+    mut confirming: Local<Vec<(RpcResultSender<Result<(), String>>, String, ConfirmResponse)>>,
 ) {
     for (scene, url, response) in events.read().filter_map(|ev| match ev {
         RpcCall::ExternalUrl {
@@ -239,6 +272,17 @@ fn external_url(
         } => Some((scene, url, response)),
         _ => None,
     }) {
+        if window.is_empty() {
+            response.send(Err("no browser available".to_owned()));
+            continue;
+        }
+
+        if let Err(e) = check_external_url(url) {
+            warn!("scene {scene:?} requested blocked url `{url}`: {e}");
+            response.send(Err(e));
+            continue;
+        }
+
         perms.check(
             PermissionType::OpenUrl,
             *scene,
@@ -248,13 +292,37 @@ fn external_url(
         );
     }
 
+    // always confirm the exact url, even if the scene is allowed to open urls
     for (response, url) in perms.drain_success(PermissionType::OpenUrl) {
-        let result = opener::open(Path::new(&url)).map_err(|e| e.to_string());
-        response.send(result);
+        let confirm = commands.spawn_confirm(
+            &dui,
+            ConfirmDialog::new(
+                "Open External Link",
+                format!(
+                    "The scene wants to open this link in your browser:\n\n{url}\n\n\
+                    Only continue if you trust the site."
+                ),
+            )
+            .with_labels("Open", "Cancel"),
+        );
+        confirming.push((response, url, confirm));
     }
 
+    confirming.retain_mut(|(response, url, confirm)| {
+        let Some(result) = confirm.try_result() else {
+            return true;
+        };
+        if result.confirmed {
+            info!("opening external url `{url}`");
+            response.send(opener::open(Path::new(url.as_str())).map_err(|e| e.to_string()));
+        } else {
+            response.send(Err("cancelled by user".to_owned()));
+        }
+        false
+    });
+
     for (response, _) in perms.drain_fail(PermissionType::OpenUrl) {
-        response.send(Err(String::default()));
+        response.send(Err("permission denied".to_owned()));
     }
 }
 
@@ -330,9 +398,7 @@ pub async fn lookup_portable(
     let Some(urn) = first_scene else {
         return Err("Empty scenesUrn on server/about/configurations".to_owned());
     };
-    let hacked_urn = urn.replace('?', "?=&").replace("?=&=&", "?=&");
-
-    let Ok(path) = IpfsPath::new_from_urn::<EntityDefinition>(&hacked_urn) else {
+    let Ok(path) = IpfsPath::new_from_urn::<EntityDefinition>(&urn) else {
         return Err("failed to parse urn".to_owned());
     };
 
@@ -343,7 +409,7 @@ pub async fn lookup_portable(
     Ok((
         hash,
         PortableSource {
-            pid: hacked_urn,
+            pid: urn,
             parent_scene,
             ens: None,
             super_user,
@@ -403,9 +469,7 @@ fn spawn_portable(
 
         match location {
             PortableLocation::Urn(urn) => {
-                let hacked_urn = urn.replace('?', "?=&");
-
-                let Ok(path) = IpfsPath::new_from_urn::<EntityDefinition>(&hacked_urn) else {
+                let Ok(path) = IpfsPath::new_from_urn::<EntityDefinition>(&urn) else {
                     response.send(Err("failed to parse urn".to_owned()));
                     continue;
                 };
@@ -418,7 +482,7 @@ fn spawn_portable(
                 new_portables.insert(
                     hash.clone(),
                     PortableSource {
-                        pid: hacked_urn,
+                        pid: urn,
                         parent_scene: Some(parent_hash),
                         ens: None,
                         super_user: false,
@@ -548,9 +612,7 @@ fn kill_portable(
     for (location, response) in perms.drain_success(PermissionType::KillPortables) {
         match location {
             PortableLocation::Urn(urn) => {
-                let hacked_urn = urn.replace('?', "?=&");
-
-                let Ok(path) = IpfsPath::new_from_urn::<EntityDefinition>(&hacked_urn) else {
+                let Ok(path) = IpfsPath::new_from_urn::<EntityDefinition>(&urn) else {
                     response.send(false);
                     continue;
                 };
@@ -1173,8 +1235,15 @@ pub fn handle_texture_size(
             response.send(Err("Scene not found".to_owned()));
             continue;
         };
-        let h_image = ipfas.load_content_file::<Image>(src, scene_hash).unwrap();
-        pending.push((h_image, response.clone()));
+        if !ipfas.ipfs().has_content_file(src, scene_hash) {
+            warn!("[{scene_hash}] texture size requested for missing file `{src}`");
+            response.send(Err(format!("file not found: {src}")));
+            continue;
+        }
+        match ipfas.load_content_file::<Image>(src, scene_hash) {
+            Ok(h_image) => pending.push((h_image, response.clone())),
+            Err(e) => response.send(Err(format!("{e}"))),
+        }
     }
 
     pending.retain_mut(|(h_image, response)| {
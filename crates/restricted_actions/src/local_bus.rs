@@ -0,0 +1,345 @@
+// routes `~system/LocalMessageBus` messages between scenes running in this client. scenes
+// join the bus when they first use it, once the scene messages permission is granted.
+// messages are only delivered to other joined scenes from the same realm (portables never
+// join), are size and rate limited per scene, and are never sent over the network.
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use common::{rpc::RpcCall, structs::PermissionType};
+use ipfs::CurrentRealm;
+use scene_runner::{permissions::Permission, renderer_context::RendererSceneContext};
+use tokio::sync::mpsc::UnboundedSender;
+
+pub const MAX_LOCAL_MESSAGE_BYTES: usize = 8 * 1024;
+pub const MAX_LOCAL_MESSAGES_PER_SECOND: usize = 20;
+
+type LocalMessageSender = UnboundedSender<(String, String)>;
+
+struct Endpoint {
+    hash: String,
+    realm: String,
+    sender: LocalMessageSender,
+    // start of the current rate limit window, and messages sent in it
+    window_start: f32,
+    window_count: usize,
+}
+
+#[derive(Resource, Default)]
+pub struct LocalMessageRouter {
+    endpoints: HashMap<Entity, Endpoint>,
+}
+
+impl LocalMessageRouter {
+    pub fn register(
+        &mut self,
+        scene: Entity,
+        hash: String,
+        realm: String,
+        sender: LocalMessageSender,
+    ) {
+        self.endpoints.insert(
+            scene,
+            Endpoint {
+                hash,
+                realm,
+                sender,
+                window_start: f32::MIN,
+                window_count: 0,
+            },
+        );
+    }
+
+    pub fn is_registered(&self, scene: Entity) -> bool {
+        self.endpoints.contains_key(&scene)
+    }
+
+    pub fn retain(&mut self, f: impl Fn(Entity) -> bool) {
+        self.endpoints
+            .retain(|scene, endpoint| f(*scene) && !endpoint.sender.is_closed());
+    }
+
+    // returns the number of scenes the message was delivered to
+    pub fn send(&mut self, from: Entity, message: &str, now: f32) -> Result<usize, String> {
+        if message.len() > MAX_LOCAL_MESSAGE_BYTES {
+            return Err(format!(
+                "message too large ({} bytes, max {MAX_LOCAL_MESSAGE_BYTES})",
+                message.len()
+            ));
+        }
+
+        let Some(endpoint) = self.endpoints.get_mut(&from) else {
+            return Err("scene is not connected to the local message bus".to_owned());
+        };
+
+        if now - endpoint.window_start >= 1.0 {
+            endpoint.window_start = now;
+            endpoint.window_count = 0;
+        }
+        if endpoint.window_count >= MAX_LOCAL_MESSAGES_PER_SECOND {
+            return Err("rate limit exceeded".to_owned());
+        }
+        endpoint.window_count += 1;
+
+        let hash = endpoint.hash.clone();
+        let realm = endpoint.realm.clone();
+        let mut delivered = 0;
+        for (scene, endpoint) in self.endpoints.iter() {
+            if *scene == from || endpoint.realm != realm {
+                continue;
+            }
+            if endpoint
+                .sender
+                .send((hash.clone(), message.to_owned()))
+                .is_ok()
+            {
+                delivered += 1;
+            }
+        }
+
+        Ok(delivered)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_local_messages(
+    mut events: EventReader<RpcCall>,
+    scenes: Query<&RendererSceneContext>,
+    current_realm: Res<CurrentRealm>,
+    time: Res<Time>,
+    mut router: ResMut<LocalMessageRouter>,
+    mut perms: Permission<(Entity, LocalMessageSender)>,
+    mut requested: Local<HashSet<Entity>>,
+    // messages sent while the permission request is pending
+    mut queued: Local<HashMap<Entity, Vec<String>>>,
+) {
+    router.retain(|scene| scenes.contains(scene));
+
+    let now = time.elapsed_seconds();
+    let send = |router: &mut LocalMessageRouter, scene: Entity, message: &str| {
+        if let Err(e) = router.send(scene, message, now) {
+            warn!("[{scene:?}] local message dropped: {e}");
+        }
+    };
+
+    for ev in events.read() {
+        match ev {
+            RpcCall::SubscribeLocalMessages { scene, sender } => {
+                let Ok(context) = scenes.get(*scene) else {
+                    continue;
+                };
+                if context.is_portable {
+                    warn!("[{scene:?}] portable experiences can't use the local message bus");
+                    continue;
+                }
+                requested.insert(*scene);
+                perms.check(
+                    PermissionType::SceneMessages,
+                    *scene,
+                    (*scene, sender.clone()),
+                    None,
+                    false,
+                );
+            }
+            RpcCall::SendLocalMessage { scene, message } => {
+                if router.is_registered(*scene) {
+                    send(&mut router, *scene, message);
+                } else if requested.contains(scene) {
+                    let queue = queued.entry(*scene).or_default();
+                    if queue.len() < MAX_LOCAL_MESSAGES_PER_SECOND {
+                        queue.push(message.clone());
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    for (scene, sender) in perms.drain_success(PermissionType::SceneMessages) {
+        requested.remove(&scene);
+        let Ok(context) = scenes.get(scene) else {
+            continue;
+        };
+        router.register(
+            scene,
+            context.hash.clone(),
+            current_realm.address.clone(),
+            sender,
+        );
+        for message in queued.remove(&scene).unwrap_or_default() {
+            send(&mut router, scene, &message);
+        }
+    }
+
+    for (scene, _) in perms.drain_fail(PermissionType::SceneMessages) {
+        requested.remove(&scene);
+        queued.remove(&scene);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::structs::{AppConfig, PrimaryPlayerRes};
+    use dcl::SceneId;
+    use scene_runner::{
+        initialize_scene::{LiveScenes, PortableScenes, ScenePointers},
+        permissions::PermissionManager,
+        Toasts,
+    };
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn spawn_scene(app: &mut App, hash: &str) -> Entity {
+        let root = app.world_mut().spawn_empty().id();
+        app.world_mut()
+            .entity_mut(root)
+            .insert(RendererSceneContext::new(
+                SceneId(root),
+                hash.to_owned(),
+                false,
+                hash.to_owned(),
+                IVec2::ZERO,
+                HashSet::default(),
+                Vec::default(),
+                Vec::default(),
+                root,
+                UVec2::ONE,
+                0.0,
+                false,
+                "7",
+                false,
+            ));
+        root
+    }
+
+    #[test]
+    fn test_local_bus_two_scenes() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<AppConfig>()
+            .init_resource::<CurrentRealm>()
+            .init_resource::<ScenePointers>()
+            .init_resource::<LiveScenes>()
+            .init_resource::<PortableScenes>()
+            .init_resource::<PermissionManager>()
+            .init_resource::<Toasts>()
+            .init_resource::<LocalMessageRouter>()
+            .insert_resource(PrimaryPlayerRes(Entity::PLACEHOLDER))
+            .add_event::<RpcCall>()
+            .add_systems(Update, handle_local_messages);
+
+        let scene_a = spawn_scene(&mut app, "hash-a");
+        let scene_b = spawn_scene(&mut app, "hash-b");
+        let scene_c = spawn_scene(&mut app, "hash-c");
+        let (sx_a, mut rx_a) = unbounded_channel();
+        let (sx_b, mut rx_b) = unbounded_channel();
+        let (sx_c, mut rx_c) = unbounded_channel();
+        for (scene, sender) in [(scene_a, sx_a), (scene_b, sx_b), (scene_c, sx_c)] {
+            app.world_mut()
+                .send_event(RpcCall::SubscribeLocalMessages { scene, sender });
+        }
+        app.update();
+
+        // the player isn't in any of the scenes, so each asks for permission. a and b are
+        // allowed, c is denied
+        let requests =
+            std::mem::take(&mut app.world_mut().resource_mut::<PermissionManager>().pending);
+        assert_eq!(requests.len(), 3);
+        for request in requests {
+            request.sender.send(request.scene != scene_c);
+        }
+        app.update();
+
+        let router = app.world().resource::<LocalMessageRouter>();
+        assert!(router.is_registered(scene_a) && router.is_registered(scene_b));
+        assert!(!router.is_registered(scene_c));
+
+        for (scene, message) in [
+            (scene_a, "from a"),
+            (scene_b, "from b"),
+            (scene_c, "from c"),
+        ] {
+            app.world_mut().send_event(RpcCall::SendLocalMessage {
+                scene,
+                message: message.to_owned(),
+            });
+        }
+        app.update();
+
+        assert_eq!(
+            rx_b.try_recv(),
+            Ok(("hash-a".to_owned(), "from a".to_owned()))
+        );
+        assert_eq!(
+            rx_a.try_recv(),
+            Ok(("hash-b".to_owned(), "from b".to_owned()))
+        );
+        // nothing else is delivered, and the denied scene neither sends nor receives
+        assert!(rx_a.try_recv().is_err());
+        assert!(rx_b.try_recv().is_err());
+        assert!(rx_c.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_local_messages_between_scenes() {
+        let mut router = LocalMessageRouter::default();
+        let (scene_a, scene_b, scene_c) = (
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+            Entity::from_raw(3),
+        );
+        let (sx_a, mut rx_a) = unbounded_channel();
+        let (sx_b, mut rx_b) = unbounded_channel();
+        let (sx_c, mut rx_c) = unbounded_channel();
+        router.register(scene_a, "hash-a".to_owned(), "realm".to_owned(), sx_a);
+        router.register(scene_b, "hash-b".to_owned(), "realm".to_owned(), sx_b);
+        router.register(scene_c, "hash-c".to_owned(), "other".to_owned(), sx_c);
+
+        assert_eq!(router.send(scene_a, "hello", 0.0), Ok(1));
+        assert_eq!(
+            rx_b.try_recv(),
+            Ok(("hash-a".to_owned(), "hello".to_owned()))
+        );
+        // not echoed to the sender, and not delivered to other realms
+        assert!(rx_a.try_recv().is_err());
+        assert!(rx_c.try_recv().is_err());
+
+        assert_eq!(router.send(scene_b, "hi", 0.0), Ok(1));
+        assert_eq!(rx_a.try_recv(), Ok(("hash-b".to_owned(), "hi".to_owned())));
+
+        // unregistered scenes can't send
+        assert!(router.send(Entity::from_raw(4), "hello", 0.0).is_err());
+    }
+
+    #[test]
+    fn test_local_message_limits() {
+        let mut router = LocalMessageRouter::default();
+        let (scene_a, scene_b) = (Entity::from_raw(1), Entity::from_raw(2));
+        let (sx_a, _rx_a) = unbounded_channel();
+        let (sx_b, mut rx_b) = unbounded_channel();
+        router.register(scene_a, "hash-a".to_owned(), "realm".to_owned(), sx_a);
+        router.register(scene_b, "hash-b".to_owned(), "realm".to_owned(), sx_b);
+
+        let large = "x".repeat(MAX_LOCAL_MESSAGE_BYTES + 1);
+        assert!(router.send(scene_a, &large, 0.0).is_err());
+
+        for _ in 0..MAX_LOCAL_MESSAGES_PER_SECOND {
+            assert_eq!(router.send(scene_a, "spam", 0.5), Ok(1));
+        }
+        assert!(router.send(scene_a, "spam", 0.9).is_err());
+        // next window
+        assert_eq!(router.send(scene_a, "spam", 1.5), Ok(1));
+
+        let mut received = 0;
+        while rx_b.try_recv().is_ok() {
+            received += 1;
+        }
+        assert_eq!(received, MAX_LOCAL_MESSAGES_PER_SECOND + 1);
+
+        // closed endpoints are dropped
+        drop(rx_b);
+        router.retain(|_| true);
+        assert!(!router.is_registered(scene_b));
+    }
+}
@@ -0,0 +1,165 @@
+// scene portals. an entity whose pointer-down event has hover text of the form
+// `Portal: <destination>` acts as a portal: clicking it asks the user to confirm and then
+// teleports them. the destination is `x,y` for a parcel in the current realm, or
+// `<realm>` / `<realm>@x,y` for another realm.
+
+use bevy::prelude::*;
+use bevy_dui::DuiRegistry;
+use common::{rpc::RpcCall, util::FireEventEx};
+use dcl_component::proto_components::sdk::components::common::{InputAction, PointerEventType};
+use input_manager::InputManager;
+use ipfs::{ChangeRealmEvent, CurrentRealm};
+use scene_runner::{
+    update_scene::pointer_results::PointerTarget, update_world::pointer_events::PointerEvents,
+};
+use ui_core::dialog::{ConfirmDialog, ConfirmDialogExt, ConfirmResponse};
+
+const PORTAL_PREFIX: &str = "portal:";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortalDestination {
+    // none for the current realm
+    pub realm: Option<String>,
+    pub parcel: Option<IVec2>,
+}
+
+impl PortalDestination {
+    pub fn parse(hover_text: &str) -> Option<Self> {
+        let text = hover_text.trim();
+        if !text
+            .get(..PORTAL_PREFIX.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(PORTAL_PREFIX))
+        {
+            return None;
+        }
+        let destination = text[PORTAL_PREFIX.len()..].trim();
+        if destination.is_empty() {
+            return None;
+        }
+
+        if let Some(parcel) = parse_parcel(destination) {
+            return Some(Self {
+                realm: None,
+                parcel: Some(parcel),
+            });
+        }
+
+        if let Some((realm, parcel)) = destination.rsplit_once('@') {
+            if let Some(parcel) = parse_parcel(parcel) {
+                return Some(Self {
+                    realm: Some(realm.trim().to_owned()).filter(|realm| !realm.is_empty()),
+                    parcel: Some(parcel),
+                });
+            }
+        }
+
+        Some(Self {
+            realm: Some(destination.to_owned()),
+            parcel: None,
+        })
+    }
+
+    fn description(&self) -> String {
+        match (&self.realm, self.parcel) {
+            (None, Some(parcel)) => format!("parcel {},{}", parcel.x, parcel.y),
+            (Some(realm), Some(parcel)) => {
+                format!("parcel {},{} in realm {realm}", parcel.x, parcel.y)
+            }
+            (Some(realm), None) => format!("realm {realm}"),
+            (None, None) => "nowhere".to_owned(),
+        }
+    }
+}
+
+fn parse_parcel(text: &str) -> Option<IVec2> {
+    let (x, y) = text.split_once(',')?;
+    Some(IVec2::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+fn same_realm(current: &str, other: &str) -> bool {
+    current.trim_end_matches('/') == other.trim_end_matches('/')
+}
+
+pub fn portal_interaction(
+    mut commands: Commands,
+    target: Res<PointerTarget>,
+    pointer_events: Query<&PointerEvents>,
+    input_mgr: InputManager,
+    current_realm: Res<CurrentRealm>,
+    dui: Res<DuiRegistry>,
+    mut confirming: Local<Vec<(PortalDestination, ConfirmResponse)>>,
+) {
+    confirming.retain_mut(|(destination, confirm)| {
+        let Some(result) = confirm.try_result() else {
+            return true;
+        };
+        if result.confirmed {
+            info!("travelling through portal to {}", destination.description());
+            if let Some(new_realm) = destination.realm.take() {
+                commands.fire_event(ChangeRealmEvent { new_realm });
+            }
+            if let Some(to) = destination.parcel {
+                commands.fire_event(RpcCall::TeleportPlayer {
+                    scene: None,
+                    to,
+                    response: Default::default(),
+                });
+            }
+        }
+        false
+    });
+
+    let Some(info) = target.0.as_ref() else {
+        return;
+    };
+    let Ok(pes) = pointer_events.get(info.container) else {
+        return;
+    };
+
+    let pressed = input_mgr.iter_just_down().copied().collect::<Vec<_>>();
+    if pressed.is_empty() {
+        return;
+    }
+
+    let Some(mut destination) = pes.msg.pointer_events.iter().find_map(|pe| {
+        if pe.event_type != PointerEventType::PetDown as i32 {
+            return None;
+        }
+        let pe_info = pe.event_info.as_ref()?;
+        let button = pe_info.button.unwrap_or(InputAction::IaAny as i32);
+        if button != InputAction::IaAny as i32 && !pressed.iter().any(|p| *p as i32 == button) {
+            return None;
+        }
+        if info.distance.0 > pe_info.max_distance.unwrap_or(10.0) {
+            return None;
+        }
+        PortalDestination::parse(pe_info.hover_text.as_ref()?)
+    }) else {
+        return;
+    };
+
+    if destination
+        .realm
+        .as_ref()
+        .is_some_and(|realm| same_realm(&current_realm.address, realm))
+    {
+        destination.realm = None;
+    }
+    if destination.realm.is_none() && destination.parcel.is_none() {
+        return;
+    }
+
+    let body = match destination.realm {
+        Some(_) => format!(
+            "This portal leads to {}.\n\nYou will leave the current realm.",
+            destination.description()
+        ),
+        None => format!("This portal leads to {}.", destination.description()),
+    };
+
+    let confirm = commands.spawn_confirm(
+        &dui,
+        ConfirmDialog::new("Travel Through Portal", body).with_labels("Travel", "Cancel"),
+    );
+    confirming.push((destination, confirm));
+}
@@ -2,7 +2,7 @@ use avatar::AvatarDynamicState;
 use bevy::{math::Vec3Swizzles, prelude::*};
 use common::{
     rpc::{RpcCall, RpcResultSender},
-    structs::{PermissionType, PrimaryUser},
+    structs::{PermissionType, PrimaryCamera, PrimaryUser},
 };
 use comms::global_crdt::ForeignPlayer;
 use ethers_core::rand::{seq::SliceRandom, thread_rng, Rng};
@@ -22,6 +22,7 @@ pub fn teleport_player(
     mut events: EventReader<RpcCall>,
     mut player: Query<(Entity, &mut Transform, &mut AvatarDynamicState), With<PrimaryUser>>,
     mut perms: Permission<(IVec2, RpcResultSender<Result<(), String>>)>,
+    mut camera: Query<&mut PrimaryCamera>,
 ) {
     let mut do_teleport = |to: IVec2, response: RpcResultSender<Result<(), String>>| {
         let Ok((ent, mut transform, mut dynamic_state)) = player.get_single_mut() else {
@@ -36,6 +37,9 @@ pub fn teleport_player(
         if let Some(mut commands) = commands.get_entity(ent) {
             commands.try_insert(OutOfWorld);
         }
+        if let Ok(mut camera) = camera.get_single_mut() {
+            camera.request_resync();
+        }
 
         response.send(Ok(()));
         info!("teleported to {to}");
@@ -71,7 +75,7 @@ pub fn teleport_player(
     }
 }
 
-#[allow(clippy::type_complexity)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn handle_out_of_world(
     mut commands: Commands,
     mut scenes: Query<
@@ -87,6 +91,7 @@ pub fn handle_out_of_world(
     live_scenes: Res<LiveScenes>,
     foreign_players: Query<&GlobalTransform, With<ForeignPlayer>>,
     wallet: Res<Wallet>,
+    mut camera: Query<&mut PrimaryCamera>,
 ) {
     let Ok((player, mut t)) = player.get_single_mut() else {
         return;
@@ -182,6 +187,9 @@ pub fn handle_out_of_world(
 
             debug!("chose {best_position}");
             t.translation = best_position;
+            if let Ok(mut camera) = camera.get_single_mut() {
+                camera.request_resync();
+            }
             commands.entity(player).remove::<OutOfWorld>();
         }
         return;
@@ -0,0 +1,161 @@
+// `~system/RestrictedActions.showToast`: scenes can show a short message via the toaster.
+// requires the notifications permission, messages are cleaned and truncated, labelled with
+// the scene title, and each scene is limited to a few toasts in any window of time.
+
+use bevy::{prelude::*, utils::HashMap};
+use common::{
+    rpc::{RpcCall, RpcResultSender},
+    structs::PermissionType,
+};
+use scene_runner::{permissions::Permission, renderer_context::RendererSceneContext, Toaster};
+
+pub const MAX_SCENE_TOAST_CHARS: usize = 200;
+pub const MAX_SCENE_TOASTS: usize = 3;
+pub const SCENE_TOAST_WINDOW_SECONDS: f32 = 10.0;
+
+// control characters are dropped, line breaks and tabs become spaces
+pub fn sanitize_toast(message: &str) -> String {
+    let cleaned = message
+        .chars()
+        .filter_map(|c| match c {
+            '\n' | '\r' | '\t' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect::<String>();
+    let cleaned = cleaned.trim();
+
+    if cleaned.chars().count() > MAX_SCENE_TOAST_CHARS {
+        let mut truncated = cleaned
+            .chars()
+            .take(MAX_SCENE_TOAST_CHARS - 1)
+            .collect::<String>();
+        truncated.push('…');
+        truncated
+    } else {
+        cleaned.to_owned()
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SceneToastLimiter {
+    // times of recent toasts per scene
+    recent: HashMap<Entity, Vec<f32>>,
+}
+
+impl SceneToastLimiter {
+    // records the toast and returns true if the scene is under its limit
+    pub fn try_add(&mut self, scene: Entity, now: f32) -> bool {
+        let recent = self.recent.entry(scene).or_default();
+        recent.retain(|time| now - *time < SCENE_TOAST_WINDOW_SECONDS);
+        if recent.len() >= MAX_SCENE_TOASTS {
+            return false;
+        }
+        recent.push(now);
+        true
+    }
+
+    pub fn retain(&mut self, f: impl Fn(Entity) -> bool) {
+        self.recent.retain(|scene, _| f(*scene));
+    }
+}
+
+pub fn scene_toasts(
+    mut events: EventReader<RpcCall>,
+    scenes: Query<&RendererSceneContext>,
+    time: Res<Time>,
+    mut limiter: ResMut<SceneToastLimiter>,
+    mut perms: Permission<(RpcResultSender<Result<(), String>>, Entity, String)>,
+    mut toaster: Toaster,
+    mut toast_id: Local<u64>,
+) {
+    limiter.retain(|scene| scenes.contains(scene));
+
+    for (scene, message, response) in events.read().filter_map(|ev| match ev {
+        RpcCall::ShowToast {
+            scene,
+            message,
+            response,
+        } => Some((scene, message, response)),
+        _ => None,
+    }) {
+        let message = sanitize_toast(message);
+        if message.is_empty() {
+            response.send(Err("empty message".to_owned()));
+            continue;
+        }
+
+        // counted before asking, so a denied scene can't queue up requests either
+        if !limiter.try_add(*scene, time.elapsed_seconds()) {
+            warn!("[{scene:?}] toast dropped: rate limit exceeded");
+            response.send(Err("rate limit exceeded".to_owned()));
+            continue;
+        }
+
+        perms.check(
+            PermissionType::Notifications,
+            *scene,
+            (response.clone(), *scene, message.clone()),
+            Some(message),
+            false,
+        );
+    }
+
+    for (response, scene, message) in perms.drain_success(PermissionType::Notifications) {
+        let Ok(context) = scenes.get(scene) else {
+            response.send(Err("scene unloaded".to_owned()));
+            continue;
+        };
+        let title = if context.title.is_empty() {
+            "Unnamed scene"
+        } else {
+            context.title.as_str()
+        };
+        // each toast gets its own key so repeated messages are shown again
+        *toast_id += 1;
+        toaster.add_toast(
+            format!("scene-toast-{scene:?}-{}", *toast_id),
+            format!("{title}: {message}"),
+        );
+        response.send(Ok(()));
+    }
+
+    for (response, ..) in perms.drain_fail(PermissionType::Notifications) {
+        response.send(Err("permission denied".to_owned()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_toast() {
+        assert_eq!(
+            sanitize_toast("  hello\nworld\u{7}\u{1b}[31m "),
+            "hello world[31m"
+        );
+        assert_eq!(sanitize_toast("\u{0}\t"), "");
+
+        let long = sanitize_toast(&"é".repeat(MAX_SCENE_TOAST_CHARS + 10));
+        assert_eq!(long.chars().count(), MAX_SCENE_TOAST_CHARS);
+        assert!(long.ends_with('…'));
+        let exact = "x".repeat(MAX_SCENE_TOAST_CHARS);
+        assert_eq!(sanitize_toast(&exact), exact);
+    }
+
+    #[test]
+    fn test_scene_toast_limit() {
+        let mut limiter = SceneToastLimiter::default();
+        let (scene_a, scene_b) = (Entity::from_raw(1), Entity::from_raw(2));
+
+        for _ in 0..MAX_SCENE_TOASTS {
+            assert!(limiter.try_add(scene_a, 0.0));
+        }
+        assert!(!limiter.try_add(scene_a, 1.0));
+        // other scenes have their own limit
+        assert!(limiter.try_add(scene_b, 1.0));
+        // allowed again once the window has passed
+        assert!(limiter.try_add(scene_a, SCENE_TOAST_WINDOW_SECONDS));
+    }
+}
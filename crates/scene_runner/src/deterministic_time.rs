@@ -0,0 +1,134 @@
+// deterministic time for reproducible scene runs (golden output tests, demo recording).
+// when the `DeterministicTime` resource exists, every frame advances bevy time and scene
+// time by exactly `dt` regardless of wall time, and the scene loop waits for all eligible
+// scenes each frame instead of giving up at the frame deadline. while paused, time is
+// frozen and scenes are blocked until frames are requested with `/step`.
+
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    time::{TimeSystem, TimeUpdateStrategy},
+};
+use bevy_console::ConsoleCommand;
+use console::DoAddConsoleCommand;
+
+use crate::renderer_context::RendererSceneContext;
+
+const TIME_PAUSED: &str = "time_paused";
+
+#[derive(Resource, Debug)]
+pub struct DeterministicTime {
+    pub dt: Duration,
+    pub paused: bool,
+    // frames remaining to run while paused
+    pub steps: u32,
+    // whether time advances this frame
+    advancing: bool,
+}
+
+impl DeterministicTime {
+    pub fn new(dt: Duration, paused: bool) -> Self {
+        Self {
+            dt,
+            paused,
+            steps: 0,
+            advancing: !paused,
+        }
+    }
+
+    pub fn advancing(&self) -> bool {
+        self.advancing
+    }
+}
+
+pub struct DeterministicTimePlugin;
+
+impl Plugin for DeterministicTimePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command::<StepCommand, _>(step_command);
+        app.add_systems(
+            First,
+            update_deterministic_time
+                .before(TimeSystem)
+                .run_if(resource_exists::<DeterministicTime>),
+        );
+    }
+}
+
+fn update_deterministic_time(
+    mut time: ResMut<DeterministicTime>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if time.is_added() {
+        dcl::set_fixed_scene_dt(Some(time.dt));
+        // allow large fixed steps through unclamped
+        let max_delta = virtual_time.max_delta().max(time.dt);
+        virtual_time.set_max_delta(max_delta);
+    }
+
+    let time = &mut *time;
+    time.advancing = if !time.paused {
+        true
+    } else if time.steps > 0 {
+        time.steps -= 1;
+        true
+    } else {
+        false
+    };
+
+    let step = if time.advancing {
+        time.dt
+    } else {
+        Duration::ZERO
+    };
+    *strategy = TimeUpdateStrategy::ManualDuration(step);
+}
+
+// block scenes on frames where time is frozen
+pub(crate) fn update_deterministic_pause(
+    time: Option<Res<DeterministicTime>>,
+    mut scenes: Query<&mut RendererSceneContext>,
+) {
+    let frozen = time.is_some_and(|time| !time.advancing);
+    for mut context in scenes.iter_mut() {
+        if context.blocked.contains(TIME_PAUSED) != frozen {
+            if frozen {
+                context.blocked.insert(TIME_PAUSED);
+            } else {
+                context.blocked.remove(TIME_PAUSED);
+            }
+        }
+    }
+}
+
+/// pause deterministic time and run the given number of frames (default 1), or resume
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/step")]
+struct StepCommand {
+    frames: Option<u32>,
+    #[arg(long)]
+    resume: bool,
+}
+
+fn step_command(mut input: ConsoleCommand<StepCommand>, time: Option<ResMut<DeterministicTime>>) {
+    if let Some(Ok(command)) = input.take() {
+        let Some(mut time) = time else {
+            input.reply_failed("deterministic time is not enabled, start with `--fixed_dt`");
+            return;
+        };
+
+        if command.resume {
+            time.paused = false;
+            time.steps = 0;
+            input.reply_ok("resumed");
+            return;
+        }
+
+        let frames = command.frames.unwrap_or(1);
+        time.paused = true;
+        time.steps += frames;
+        input.reply_ok(format!("stepping {} frames", time.steps));
+    }
+}
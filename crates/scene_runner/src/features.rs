@@ -0,0 +1,120 @@
+// sdk features the user can switch off for all scenes in `FeatureSettings`. systems check
+// their feature through `SceneFeatures` and treat a disabled feature as a no-op. the first
+// use by each scene is logged, so it's clear why a scene's video or audio doesn't play.
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashSet};
+use common::structs::{AppConfig, FeatureSettings};
+
+use crate::renderer_context::RendererSceneContext;
+
+pub struct SceneFeaturesPlugin;
+
+impl Plugin for SceneFeaturesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DisabledFeatureLog>();
+        app.add_systems(PostUpdate, forget_unloaded_scenes);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SceneFeature {
+    Video,
+    SceneAudio,
+    AvatarAttach,
+}
+
+impl SceneFeature {
+    pub fn enabled(&self, settings: &FeatureSettings) -> bool {
+        match self {
+            SceneFeature::Video => settings.video,
+            SceneFeature::SceneAudio => settings.scene_audio,
+            SceneFeature::AvatarAttach => settings.avatar_attach,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            SceneFeature::Video => "video",
+            SceneFeature::SceneAudio => "scene audio",
+            SceneFeature::AvatarAttach => "avatar attach",
+        }
+    }
+}
+
+// scenes that have been told a feature is disabled
+#[derive(Resource, Default)]
+pub struct DisabledFeatureLog(HashSet<(Entity, SceneFeature)>);
+
+fn forget_unloaded_scenes(
+    mut log: ResMut<DisabledFeatureLog>,
+    mut removed: RemovedComponents<RendererSceneContext>,
+) {
+    for scene in removed.read() {
+        log.0.retain(|(logged, _)| *logged != scene);
+    }
+}
+
+#[derive(SystemParam)]
+pub struct SceneFeatures<'w, 's> {
+    config: Res<'w, AppConfig>,
+    log: ResMut<'w, DisabledFeatureLog>,
+    scenes: Query<'w, 's, &'static RendererSceneContext>,
+}
+
+impl SceneFeatures<'_, '_> {
+    pub fn enabled(&self, feature: SceneFeature) -> bool {
+        feature.enabled(&self.config.features)
+    }
+
+    // true if the scene may use the feature, logging the scene's first use of a disabled one
+    pub fn allowed(&mut self, scene: Entity, feature: SceneFeature) -> bool {
+        if self.enabled(feature) {
+            return true;
+        }
+
+        if self.log.0.insert((scene, feature)) {
+            let hash = self
+                .scenes
+                .get(scene)
+                .map(|context| context.hash.as_str())
+                .unwrap_or("unknown scene");
+            info!(
+                "[{hash}] {} is disabled in the settings, ignoring",
+                feature.name()
+            );
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn test_disabled_feature_logged_once_per_scene() {
+        let mut world = World::new();
+        let mut config = AppConfig::default();
+        config.features.video = false;
+        world.insert_resource(config);
+        world.init_resource::<DisabledFeatureLog>();
+        let (scene_a, scene_b) = (Entity::from_raw(1), Entity::from_raw(2));
+
+        let allowed = world.run_system_once(move |mut features: SceneFeatures| {
+            [
+                features.allowed(scene_a, SceneFeature::Video),
+                features.allowed(scene_a, SceneFeature::Video),
+                features.allowed(scene_b, SceneFeature::Video),
+                features.allowed(scene_a, SceneFeature::SceneAudio),
+            ]
+        });
+        assert_eq!(allowed, [false, false, false, true]);
+
+        let log = &world.resource::<DisabledFeatureLog>().0;
+        assert_eq!(log.len(), 2);
+        assert!(log.contains(&(scene_a, SceneFeature::Video)));
+        assert!(log.contains(&(scene_b, SceneFeature::Video)));
+    }
+}
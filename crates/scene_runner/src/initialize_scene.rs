@@ -13,10 +13,14 @@ use bevy::{
 use futures_lite::AsyncReadExt;
 
 use common::{
-    structs::{AppConfig, IVec2Arg, SceneLoadDistance, SceneMeta},
+    structs::{
+        AppConfig, IVec2Arg, LoadingSkeletonSetting, RealmSettings, SceneLoadDistance, SceneMeta,
+    },
     util::{TaskExt, TryPushChildrenEx},
 };
-use comms::{global_crdt::GlobalCrdtState, preview::PreviewMode};
+use comms::{
+    global_crdt::GlobalCrdtState, position_compression::PositionGrid, preview::PreviewMode,
+};
 use dcl::{
     interface::{crdt_context::CrdtContext, CrdtComponentInterfaces, CrdtType},
     spawn_scene, SceneElapsedTime, SceneId, SceneResponse,
@@ -26,18 +30,19 @@ use dcl_component::{
     SceneEntityId,
 };
 use ipfs::{
-    ipfs_path::IpfsPath, ActiveEntityTask, CurrentRealm, EntityDefinition, IpfsAssetServer,
-    IpfsResource, SceneIpfsLocation, SceneJsFile,
+    ipfs_path::{IpfsPath, UrnQuery},
+    ActiveEntityTask, CurrentRealm, EntityDefinition, IpfsAssetServer, IpfsResource,
+    SceneIpfsLocation, SceneJsFile,
 };
 use scene_material::BoundRegion;
-use system_bridge::SystemBridge;
+use system_bridge::{settings::clamp_realm_overrides, SystemBridge};
 use wallet::Wallet;
 
 use super::{update_world::CrdtExtractors, LoadSceneEvent, PrimaryUser, SceneSets, SceneUpdates};
 use crate::{
     bounds_calc::scene_regions, renderer_context::RendererSceneContext,
-    update_world::ComponentTracker, ContainerEntity, DeletedSceneEntities, SceneEntity,
-    SceneThreadHandle,
+    update_world::ComponentTracker, util::ConsoleRelay, ContainerEntity, DeletedSceneEntities,
+    SceneEntity, SceneThreadHandle,
 };
 
 #[derive(Default)]
@@ -74,6 +79,10 @@ impl Plugin for SceneLifecyclePlugin {
         app.init_resource::<LiveScenes>();
         app.init_resource::<ScenePointers>();
         app.init_resource::<PortableScenes>();
+        app.init_resource::<ManualScenes>();
+        app.init_resource::<RealmSettings>();
+        app.init_resource::<PositionGrid>();
+        app.add_event::<RefreshScenePointers>();
         app.init_asset::<SerializedCrdtStore>();
         app.init_asset_loader::<CrdtLoader>();
         app.add_plugins(MaterialPlugin::<LoadingMaterial>::default());
@@ -116,12 +125,20 @@ pub enum SceneLoading {
     Failed,
 }
 
+// base parcel requested by the scene's urn, replacing the base in its scene.json
+#[derive(Component, Debug)]
+pub struct ScenePositionOverride(pub IVec2);
+
 pub(crate) fn load_scene_entity(
     mut commands: Commands,
     mut load_scene_events: EventReader<LoadSceneEvent>,
     ipfas: IpfsAssetServer,
+    overridden: Query<(), With<ScenePositionOverride>>,
 ) {
     for event in load_scene_events.read() {
+        let has_override = event
+            .entity
+            .is_some_and(|entity| overridden.contains(entity));
         let mut commands = match event.entity {
             Some(entity) => {
                 let Some(commands) = commands.get_entity(entity) else {
@@ -135,7 +152,15 @@ pub(crate) fn load_scene_entity(
         let h_scene = match &event.location {
             SceneIpfsLocation::Hash(hash) => ipfas.load_hash::<EntityDefinition>(hash),
             SceneIpfsLocation::Urn(urn) => match ipfas.load_urn::<EntityDefinition>(urn) {
-                Ok(h_scene) => h_scene,
+                Ok(h_scene) => {
+                    // a position given when spawning takes precedence over the urn's
+                    if let Some(position) = UrnQuery::parse(urn).1.position {
+                        if !has_override {
+                            commands.try_insert(ScenePositionOverride(position));
+                        }
+                    }
+                    h_scene
+                }
                 Err(e) => {
                     warn!("failed to parse urn: {e}");
                     commands.try_insert(SceneLoading::Failed);
@@ -215,7 +240,12 @@ pub struct SerializedCrdtStore(pub Vec<u8>);
 pub(crate) fn load_scene_javascript(
     mut commands: Commands,
     config: Res<AppConfig>,
-    loading_scenes: Query<(Entity, &SceneLoading, &Handle<EntityDefinition>)>,
+    loading_scenes: Query<(
+        Entity,
+        &SceneLoading,
+        &Handle<EntityDefinition>,
+        Option<&ScenePositionOverride>,
+    )>,
     scene_definitions: Res<Assets<EntityDefinition>>,
     main_crdts: Res<Assets<SerializedCrdtStore>>,
     ipfas: IpfsAssetServer,
@@ -224,9 +254,9 @@ pub(crate) fn load_scene_javascript(
     global_scene: Res<GlobalCrdtState>,
     portable_scenes: Res<PortableScenes>,
 ) {
-    for (root, state, h_scene) in loading_scenes
+    for (root, state, h_scene, position_override) in loading_scenes
         .iter()
-        .filter(|(_, state, _)| matches!(**state, SceneLoading::MainCrdt { .. }))
+        .filter(|(_, state, ..)| matches!(**state, SceneLoading::MainCrdt { .. }))
     {
         let mut fail = |msg: &str| {
             warn!("{root:?} failed to initialize scene: {msg}");
@@ -266,6 +296,10 @@ pub(crate) fn load_scene_javascript(
         let base_x = base_x.parse::<i32>().unwrap();
         let base_y = base_y.parse::<i32>().unwrap();
         let base = IVec2::new(base_x, base_y);
+        let (base, offset) = match position_override {
+            Some(ScenePositionOverride(position)) => (*position, *position - base),
+            None => (base, IVec2::ZERO),
+        };
 
         // populate pointers
         let mut extent_min = IVec2::MAX;
@@ -278,7 +312,7 @@ pub(crate) fn load_scene_javascript(
                 let (x, y) = pointer.split_once(',').unwrap();
                 let x = x.parse::<i32>().unwrap();
                 let y = y.parse::<i32>().unwrap();
-                let parcel = IVec2::new(x, y);
+                let parcel = IVec2::new(x, y) + offset;
 
                 extent_min = extent_min.min(parcel);
                 extent_max = extent_max.max(parcel);
@@ -324,18 +358,24 @@ pub(crate) fn load_scene_javascript(
             None => false,
         };
 
-        let h_code = if is_sdk7 {
+        // a scene with no main script only shows its main.crdt content. a script that is named
+        // but can't be loaded is still a failure
+        let is_static = is_sdk7 && meta.main.trim().is_empty();
+
+        let h_code = if is_static {
+            None
+        } else if is_sdk7 {
             match ipfas.load_content_file::<SceneJsFile>(&meta.main, &definition.id) {
-                Ok(h_code) => h_code,
+                Ok(h_code) => Some(h_code),
                 Err(e) => {
                     fail(&format!("couldn't load javascript: {}", e));
                     continue;
                 }
             }
         } else {
-            ipfas.load_url(
+            Some(ipfas.load_url(
                 "https://renderer-artifacts.decentraland.org/sdk6-adaption-layer/main/index.min.js",
-            )
+            ))
         };
 
         let crdt_component_interfaces = CrdtComponentInterfaces(HashMap::from_iter(
@@ -451,9 +491,20 @@ pub(crate) fn load_scene_javascript(
             },
         ));
 
-        commands
-            .entity(root)
-            .try_insert((h_code, SceneLoading::Javascript(Some(global_updates))));
+        match h_code {
+            Some(h_code) => {
+                commands
+                    .entity(root)
+                    .try_insert((h_code, SceneLoading::Javascript(Some(global_updates))));
+            }
+            None => {
+                info!("{root:?}: no main script, showing static content only");
+                commands
+                    .entity(root)
+                    .try_insert(StaticScene)
+                    .remove::<SceneLoading>();
+            }
+        }
     }
 }
 
@@ -504,6 +555,10 @@ pub struct TestingData {
 #[derive(Component)]
 pub struct SuperUserScene;
 
+// a scene with no script. it has no scene thread, its tick is advanced by `tick_static_scenes`
+#[derive(Component)]
+pub struct StaticScene;
+
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub(crate) fn initialize_scene(
     mut commands: Commands,
@@ -612,6 +667,15 @@ pub struct PortableSource {
 #[derive(Resource, Default)]
 pub struct PortableScenes(pub HashMap<String, PortableSource>);
 
+// a scene loaded by hand at a given parcel, replacing the realm's scene there
+pub struct ManualScene {
+    pub urn: Option<String>,
+    pub parcel: IVec2,
+}
+
+#[derive(Resource, Default)]
+pub struct ManualScenes(pub HashMap<String, ManualScene>);
+
 pub const PARCEL_SIZE: f32 = 16.0;
 
 #[derive(Resource, Debug)]
@@ -619,6 +683,9 @@ pub struct ScenePointers {
     pointers: HashMap<IVec2, PointerResult>,
     realm_bounds: (IVec2, IVec2),
     crcs: Vec<Vec<Option<u32>>>,
+    // incremented on each realm change, so responses to requests made for a previous realm
+    // can be recognised and dropped
+    generation: u64,
 }
 
 impl Default for ScenePointers {
@@ -627,6 +694,7 @@ impl Default for ScenePointers {
             pointers: Default::default(),
             realm_bounds: (IVec2::MAX, IVec2::MIN),
             crcs: Default::default(),
+            generation: 0,
         }
     }
 }
@@ -646,11 +714,76 @@ impl ScenePointers {
         self.pointers.retain(|_, r| r != &PointerResult::Nothing);
         // exists will be rechecked / replaced when active entities returns
         self.crcs.clear();
+        self.generation += 1;
     }
     pub fn insert(&mut self, parcel: IVec2, result: PointerResult) {
         self.pointers.insert(parcel, result);
     }
 
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // store the results of a pointer request made at the given generation. returns false
+    // and stores nothing if the realm has changed since the request was made
+    pub fn insert_response(
+        &mut self,
+        generation: u64,
+        results: impl IntoIterator<Item = (IVec2, PointerResult)>,
+    ) -> bool {
+        if generation != self.generation {
+            return false;
+        }
+        self.pointers.extend(results);
+        true
+    }
+
+    // store the results of a refresh request made at the given generation, returning the scenes
+    // that appeared or disappeared over the refreshed parcels. parcels whose scene is unchanged
+    // keep their results, so the scenes on them are not reloaded
+    pub fn refresh_response(
+        &mut self,
+        generation: u64,
+        results: impl IntoIterator<Item = (IVec2, PointerResult)>,
+    ) -> Option<SceneListChanges> {
+        if generation != self.generation {
+            return None;
+        }
+
+        let mut previous_hashes = HashSet::new();
+        let mut current_hashes = HashSet::new();
+        let mut changed = false;
+        for (parcel, result) in results {
+            if let Some(PointerResult::Exists { hash, .. }) = &result {
+                current_hashes.insert(hash.clone());
+            }
+            let previous = self.pointers.insert(parcel, result);
+            if let Some(PointerResult::Exists { hash, .. }) = &previous {
+                previous_hashes.insert(hash.clone());
+            }
+            changed |= previous.as_ref() != self.pointers.get(&parcel);
+        }
+
+        if changed {
+            // cached checksums cover the old hashes
+            self.crcs.clear();
+        }
+
+        let mut changes = SceneListChanges {
+            added: current_hashes
+                .difference(&previous_hashes)
+                .cloned()
+                .collect(),
+            removed: previous_hashes
+                .difference(&current_hashes)
+                .cloned()
+                .collect(),
+        };
+        changes.added.sort();
+        changes.removed.sort();
+        Some(changes)
+    }
+
     pub fn min(&self) -> IVec2 {
         self.realm_bounds.0
     }
@@ -737,6 +870,17 @@ pub enum PointerResult {
     },
 }
 
+// scene hashes that appeared or disappeared when the scene list was refreshed
+#[derive(Debug, Default, PartialEq)]
+pub struct SceneListChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+// re-request the scene list around the player, reloading only scenes whose hash has changed
+#[derive(Event)]
+pub struct RefreshScenePointers;
+
 impl PointerResult {
     const NOTHING: Self = Self::Nothing;
 }
@@ -794,9 +938,28 @@ pub fn process_realm_change(
     current_realm: Res<CurrentRealm>,
     mut live_scenes: ResMut<LiveScenes>,
     mut segment_config: Option<ResMut<SegmentConfig>>,
+    config: Res<AppConfig>,
+    mut realm_settings: ResMut<RealmSettings>,
+    mut manual_scenes: ResMut<ManualScenes>,
 ) {
     if current_realm.is_changed() {
         info!("realm change `{}`! purging scenes", current_realm.address);
+
+        // scenes loaded by hand replaced the previous realm's parcels
+        for (hash, _) in manual_scenes.0.drain() {
+            live_scenes.0.remove(&hash);
+        }
+
+        // replaces the previous realm's overrides, restoring base settings for any it had
+        realm_settings.set_realm(
+            &current_realm.address,
+            &config,
+            current_realm.config.settings.clone().unwrap_or_default(),
+        );
+        clamp_realm_overrides(&mut realm_settings.overrides);
+        if !realm_settings.overrides.is_empty() {
+            info!("realm settings: {:?}", realm_settings.overrides);
+        }
         let mut realm_scene_urns = HashSet::default();
         for urn in current_realm
             .config
@@ -804,8 +967,7 @@ pub fn process_realm_change(
             .as_ref()
             .unwrap_or(&Vec::default())
         {
-            let hacked_urn = urn.replace('?', "?=&");
-            let path = match IpfsPath::new_from_urn::<EntityDefinition>(&hacked_urn) {
+            let path = match IpfsPath::new_from_urn::<EntityDefinition>(urn) {
                 Ok(path) => path,
                 Err(e) => {
                     warn!("failed to parse urn: `{}`: {}", urn, e);
@@ -813,7 +975,7 @@ pub fn process_realm_change(
                 }
             };
 
-            realm_scene_urns.insert((hacked_urn, path));
+            realm_scene_urns.insert((urn.clone(), path));
         }
 
         let realm_scene_ids = realm_scene_urns
@@ -844,29 +1006,60 @@ pub fn process_realm_change(
     }
 }
 
-#[allow(clippy::type_complexity)]
+// an active entities request, with the pointer generation and realm it was made for
+struct PointerRequest {
+    parcels: HashSet<IVec2>,
+    urns: HashMap<String, String>,
+    generation: u64,
+    realm: String,
+    refresh: bool,
+    task: ActiveEntityTask,
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn load_active_entities(
     current_realm: Res<CurrentRealm>,
     focus: Query<&GlobalTransform, With<PrimaryUser>>,
     range: Res<SceneLoadDistance>,
     mut pointers: ResMut<ScenePointers>,
-    mut pointer_request: Local<Option<(HashSet<IVec2>, HashMap<String, String>, ActiveEntityTask)>>,
+    mut position_grid: ResMut<PositionGrid>,
+    mut pointer_request: Local<Option<PointerRequest>>,
     ipfas: IpfsAssetServer,
+    mut refresh_events: EventReader<RefreshScenePointers>,
+    mut refresh_pending: Local<bool>,
+    console: Option<Res<ConsoleRelay>>,
 ) {
+    let report = |line: String| {
+        info!("{line}");
+        if let Some(console) = console.as_ref() {
+            let _ = console.send.send(line.into());
+        }
+    };
+
+    if refresh_events.read().count() > 0 {
+        // the refresh request replaces any request in flight
+        *pointer_request = None;
+        *refresh_pending = true;
+    }
+
     if current_realm.is_changed() {
         // drop current request
         *pointer_request = None;
+        // a realm change reloads everything anyway
+        *refresh_pending = false;
         // set current realm and clear
         // TODO base this on the actual bounds
         // pointers.set_realm(IVec2::new(-15, -33), IVec2::new(-1,-12));
         // pointers.set_realm(IVec2::new(100, -94), IVec2::new(123,-85));
         pointers.set_realm(IVec2::new(-152, -152), IVec2::new(152, 152));
+        *position_grid = PositionGrid::for_realm(pointers.min(), pointers.max());
     }
 
     if pointer_request.is_none()
         && !current_realm.address.is_empty()
         && ipfas.active_endpoint().is_some()
     {
+        let generation = pointers.generation();
         let has_scene_urns = !current_realm
             .config
             .scenes_urn
@@ -876,6 +1069,7 @@ fn load_active_entities(
         let Ok(focus) = focus.get_single() else {
             return;
         };
+        let refresh = std::mem::take(&mut *refresh_pending);
 
         let required_parcels: HashSet<_> = parcels_in_range(
             focus,
@@ -885,6 +1079,7 @@ fn load_active_entities(
         )
         .into_iter()
         .filter_map(|(parcel, _)| match pointers.get(parcel) {
+            _ if refresh => Some(parcel),
             Some(PointerResult::Exists { realm, .. }) => {
                 (realm != &current_realm.address).then_some(parcel)
             }
@@ -903,14 +1098,17 @@ fn load_active_entities(
             if !required_parcels.is_empty() {
                 info!("requesting {} parcels", pointers.len());
 
-                *pointer_request = Some((
-                    required_parcels,
-                    HashMap::default(),
-                    ipfas.ipfs().active_entities(
+                *pointer_request = Some(PointerRequest {
+                    parcels: required_parcels,
+                    urns: HashMap::default(),
+                    generation,
+                    realm: current_realm.address.clone(),
+                    refresh,
+                    task: ipfas.ipfs().active_entities(
                         ipfs::ActiveEntitiesRequest::Pointers(pointers),
                         current_realm.config.city_loader_content_server.as_deref(),
                     ),
-                ));
+                });
             }
         } else {
             // TODO perf might be worth caching available and required
@@ -944,7 +1142,7 @@ fn load_active_entities(
                                 .map(|hash| (hash, path, urn))
                         })
                 })
-                .filter(|(hash, ..)| !available_hashes.contains(hash))
+                .filter(|(hash, ..)| refresh || !available_hashes.contains(hash))
                 .collect::<Vec<_>>();
 
             let required_paths = required_hashes_and_urns
@@ -960,22 +1158,39 @@ fn load_active_entities(
             // issue request if either parcels or urns are non-empty, so that we populate `PointerResult::Nothing`s
             if !required_paths.is_empty() || !required_parcels.is_empty() {
                 debug!("requesting {} urns", required_paths.len());
-                *pointer_request = Some((
-                    required_parcels,
-                    lookup,
-                    ipfas.ipfs().active_entities(
+                *pointer_request = Some(PointerRequest {
+                    parcels: required_parcels,
+                    urns: lookup,
+                    generation,
+                    realm: current_realm.address.clone(),
+                    refresh,
+                    task: ipfas.ipfs().active_entities(
                         ipfs::ActiveEntitiesRequest::Urns(required_paths),
                         current_realm.config.city_loader_content_server.as_deref(),
                     ),
-                ));
+                });
             }
         }
-    } else if let Some(task_result) = pointer_request.as_mut().and_then(|req| req.2.complete()) {
+
+        if refresh && pointer_request.is_none() {
+            report("[ok] no parcels in range to refresh".to_owned());
+        }
+    } else if let Some(task_result) = pointer_request.as_mut().and_then(|req| req.task.complete()) {
         // process active scenes in the requested set
-        let (mut requested_parcels, mut urn_lookup, _) = pointer_request.take().unwrap();
+        let PointerRequest {
+            parcels: mut requested_parcels,
+            urns: mut urn_lookup,
+            generation,
+            realm,
+            refresh,
+            ..
+        } = pointer_request.take().unwrap();
 
         let Ok(retrieved_parcels) = task_result else {
             warn!("failed to retrieve active scenes, will retry");
+            if refresh {
+                report("[failed] couldn't retrieve the scene list".to_owned());
+            }
             return;
         };
 
@@ -985,6 +1200,7 @@ fn load_active_entities(
             requested_parcels
         );
 
+        let mut results = Vec::default();
         for active_entity in retrieved_parcels {
             // TODO check for portables
 
@@ -996,6 +1212,25 @@ fn load_active_entities(
                 continue;
             };
 
+            let parse_parcel = |pointer: &str| {
+                let (x, y) = pointer.split_once(',')?;
+                Some(IVec2::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+            };
+            let (Some(base), Some(parcels)) = (
+                parse_parcel(&meta.scene.base),
+                meta.scene
+                    .parcels
+                    .iter()
+                    .map(|pointer| parse_parcel(pointer))
+                    .collect::<Option<Vec<_>>>(),
+            ) else {
+                warn!(
+                    "active entity {} has malformed parcels, skipping",
+                    active_entity.id
+                );
+                continue;
+            };
+
             let mut urn = urn_lookup.remove(&active_entity.id);
 
             if urn.is_none() {
@@ -1009,29 +1244,58 @@ fn load_active_entities(
                 }
             }
 
-            for pointer in meta.scene.parcels {
-                let (x, y) = pointer.split_once(',').unwrap();
-                let x = x.parse::<i32>().unwrap();
-                let y = y.parse::<i32>().unwrap();
-                let parcel = IVec2::new(x, y);
+            // the urn may relocate the scene
+            let offset = urn.as_deref().map_or(IVec2::ZERO, |urn| {
+                UrnQuery::parse(urn).1.parcel_offset(base)
+            });
+
+            for parcel in parcels {
+                let parcel = parcel + offset;
 
                 requested_parcels.remove(&parcel);
-                pointers.pointers.insert(
+                results.push((
                     parcel,
                     PointerResult::Exists {
-                        realm: current_realm.address.clone(),
+                        realm: realm.clone(),
                         hash: active_entity.id.clone(),
                         urn: urn.clone(),
                     },
-                );
+                ));
             }
         }
 
         // any remaining requested parcels are empty
-        for empty_parcel in requested_parcels {
-            pointers
-                .pointers
-                .insert(empty_parcel, PointerResult::Nothing);
+        results.extend(
+            requested_parcels
+                .into_iter()
+                .map(|parcel| (parcel, PointerResult::Nothing)),
+        );
+
+        if !refresh {
+            if !pointers.insert_response(generation, results) {
+                debug!("discarding pointers requested for previous realm `{realm}`");
+            }
+            return;
+        }
+
+        match pointers.refresh_response(generation, results) {
+            None => report("[failed] realm changed during scene list refresh".to_owned()),
+            Some(changes) if changes == SceneListChanges::default() => {
+                report("[ok] scene list refreshed, no changes".to_owned())
+            }
+            Some(SceneListChanges { added, removed }) => {
+                report(format!(
+                    "[ok] scene list refreshed, {} added, {} removed",
+                    added.len(),
+                    removed.len()
+                ));
+                for hash in added {
+                    report(format!("+ {hash}"));
+                }
+                for hash in removed {
+                    report(format!("- {hash}"));
+                }
+            }
         }
     }
 }
@@ -1055,6 +1319,7 @@ pub fn process_scene_lifecycle(
     pointers: Res<ScenePointers>,
     config: Res<AppConfig>,
     imposter_scene: Res<CurrentImposterScene>,
+    manual_scenes: Res<ManualScenes>,
 ) {
     let mut required_scene_ids: HashMap<(String, Option<String>), bool> = HashMap::default();
 
@@ -1104,6 +1369,24 @@ pub fn process_scene_lifecycle(
             .map(|(h, u)| ((h, u), false)),
     );
 
+    // add manually loaded scenes, in place of the scenes at their parcels
+    let replaced_hashes = manual_scenes
+        .0
+        .values()
+        .filter_map(|scene| pointers.get(scene.parcel))
+        .filter_map(PointerResult::hash_and_urn)
+        .map(|(hash, _)| hash)
+        .collect::<HashSet<_>>();
+    required_scene_ids.retain(|(hash, _), _| {
+        !replaced_hashes.contains(hash) && !manual_scenes.0.contains_key(hash)
+    });
+    required_scene_ids.extend(
+        manual_scenes
+            .0
+            .iter()
+            .map(|(hash, scene)| ((hash.clone(), scene.urn.clone()), false)),
+    );
+
     // record additional optional scenes
     let mut keep_scene_ids = required_scene_ids.keys().cloned().collect::<HashSet<_>>();
     keep_scene_ids.extend(pir.iter().flat_map(|(parcel, dist)| {
@@ -1114,6 +1397,7 @@ pub fn process_scene_lifecycle(
                 // we don't check them until they are in range, so better to just nuke them
                 .filter(|pr| pr.realm() == Some(&current_realm.address))
                 .and_then(PointerResult::hash_and_urn)
+                .filter(|(hash, _)| !replaced_hashes.contains(hash))
         } else {
             None
         }
@@ -1184,12 +1468,14 @@ pub fn process_scene_lifecycle(
         .iter()
         .filter(|((hash, _), _)| !existing_ids.contains(hash))
     {
-        let entity = commands
-            .spawn((
-                SceneHash(required_scene_hash.clone()),
-                SceneLoading::SceneSpawned,
-            ))
-            .id();
+        let mut entity = commands.spawn((
+            SceneHash(required_scene_hash.clone()),
+            SceneLoading::SceneSpawned,
+        ));
+        if let Some(manual) = manual_scenes.0.get(required_scene_hash) {
+            entity.insert(ScenePositionOverride(manual.parcel));
+        }
+        let entity = entity.id();
         info!("spawning scene {:?} @ ??: {entity:?}", required_scene_hash);
         live_scenes.0.insert(required_scene_hash.clone(), entity);
         spawn.send(LoadSceneEvent {
@@ -1210,6 +1496,17 @@ pub struct SceneHash(pub String);
 #[derive(Component)]
 pub struct LoadingQuad(bool);
 
+// placeholder over the footprint of a scene that is still initializing
+#[derive(Component)]
+pub struct LoadingSkeleton;
+
+struct LoadingHandles {
+    quad: Handle<Mesh>,
+    grid: Handle<StandardMaterial>,
+    skeleton_mesh: Handle<Mesh>,
+    skeleton_material: Handle<StandardMaterial>,
+}
+
 #[allow(clippy::too_many_arguments)]
 fn animate_ready_scene(
     mut q: Query<(
@@ -1222,26 +1519,35 @@ fn animate_ready_scene(
     mut meshes: ResMut<Assets<Mesh>>,
     mut loading_materials: ResMut<Assets<LoadingMaterial>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    loading_quads: Query<(), With<LoadingQuad>>,
+    loading_quads: Query<(), Or<(With<LoadingQuad>, With<LoadingSkeleton>)>>,
     preview: Res<PreviewMode>,
-    mut handles: Local<Option<(Handle<Mesh>, Handle<StandardMaterial>)>>,
+    mut handles: Local<Option<LoadingHandles>>,
     asset_server: Res<AssetServer>,
     current_imposter_scene: Res<CurrentImposterScene>,
+    config: Res<AppConfig>,
 ) {
     if handles.is_none() {
-        *handles = Some((
-            meshes.add(
+        *handles = Some(LoadingHandles {
+            quad: meshes.add(
                 Rectangle::default()
                     .mesh()
                     .build()
                     .scaled_by(Vec3::splat(PARCEL_SIZE)),
             ),
-            materials.add(StandardMaterial {
+            grid: materials.add(StandardMaterial {
                 base_color_texture: Some(asset_server.load("images/grid.png")),
                 ..Default::default()
             }),
-        ));
+            skeleton_mesh: meshes.add(Plane3d::default().mesh().size(PARCEL_SIZE, PARCEL_SIZE)),
+            skeleton_material: materials.add(StandardMaterial {
+                base_color: Color::srgba(0.7, 0.7, 0.8, 0.25),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..Default::default()
+            }),
+        });
     }
+    let handles = handles.as_ref().unwrap();
 
     for (root, mut transform, ctx, children) in q.iter_mut() {
         // skip animating imposters
@@ -1290,7 +1596,7 @@ fn animate_ready_scene(
                             commands
                                 .spawn((
                                     MaterialMeshBundle {
-                                        mesh: handles.as_ref().unwrap().0.clone(),
+                                        mesh: handles.quad.clone(),
                                         material: loading_materials.add(LoadingMaterial::default()),
                                         transform: Transform::from_translation(
                                             position
@@ -1308,12 +1614,32 @@ fn animate_ready_scene(
                     }
                 }
 
+                // the scene root sits at -1000 until the scene is ready, so this appears at ground level
+                if config.graphics.loading_skeleton == LoadingSkeletonSetting::On {
+                    children.push(
+                        commands
+                            .spawn((
+                                PbrBundle {
+                                    mesh: handles.skeleton_mesh.clone(),
+                                    material: handles.skeleton_material.clone(),
+                                    transform: Transform::from_translation(
+                                        position + middle + Vec3::Y * 1000.05,
+                                    ),
+                                    ..Default::default()
+                                },
+                                LoadingSkeleton,
+                                NotShadowCaster,
+                            ))
+                            .id(),
+                    );
+                }
+
                 if preview.is_preview {
                     children.push(
                         commands
                             .spawn(PbrBundle {
-                                mesh: handles.as_ref().unwrap().0.clone(),
-                                material: handles.as_ref().unwrap().1.clone(),
+                                mesh: handles.quad.clone(),
+                                material: handles.grid.clone(),
                                 transform: Transform::from_translation(
                                     position
                                         + Vec3::new(PARCEL_SIZE * 0.5, -0.01, PARCEL_SIZE * -0.5),
@@ -1399,3 +1725,177 @@ impl Material for LoadingMaterial {
         AlphaMode::Blend
     }
 }
+
+#[cfg(test)]
+mod test {
+    use common::structs::RealmSettingOverrides;
+
+    use super::*;
+
+    #[test]
+    fn test_stale_pointer_response_ignored() {
+        let mut pointers = ScenePointers::default();
+        pointers.set_realm(IVec2::splat(-10), IVec2::splat(10));
+        let parcel = IVec2::new(1, 2);
+        let exists = |realm: &str| PointerResult::Exists {
+            realm: realm.to_owned(),
+            hash: format!("{realm}-hash"),
+            urn: None,
+        };
+
+        // a request is made, then the realm changes before it returns
+        let old_generation = pointers.generation();
+        pointers.set_realm(IVec2::splat(-10), IVec2::splat(10));
+        let new_generation = pointers.generation();
+        assert_ne!(old_generation, new_generation);
+
+        assert!(!pointers.insert_response(
+            old_generation,
+            [
+                (parcel, exists("old")),
+                (IVec2::ZERO, PointerResult::Nothing)
+            ]
+        ));
+        assert_eq!(pointers.get(parcel), None);
+        assert_eq!(pointers.get(IVec2::ZERO), None);
+
+        // responses for the current realm are stored
+        assert!(pointers.insert_response(new_generation, [(parcel, exists("new"))]));
+        assert_eq!(pointers.get(parcel), Some(&exists("new")));
+    }
+
+    #[test]
+    fn test_refresh_reports_changed_scenes() {
+        let mut pointers = ScenePointers::default();
+        pointers.set_realm(IVec2::splat(-10), IVec2::splat(10));
+        let exists = |hash: &str| PointerResult::Exists {
+            realm: "realm".to_owned(),
+            hash: hash.to_owned(),
+            urn: None,
+        };
+
+        let generation = pointers.generation();
+        assert!(pointers.insert_response(
+            generation,
+            [
+                (IVec2::new(0, 0), exists("kept")),
+                (IVec2::new(1, 0), exists("kept")),
+                (IVec2::new(2, 0), exists("old")),
+                (IVec2::new(3, 0), exists("deleted")),
+                (IVec2::new(4, 0), PointerResult::Nothing),
+            ]
+        ));
+        let crc = pointers.crc(IVec2::new(2, 0), 0);
+
+        let changes = pointers
+            .refresh_response(
+                generation,
+                [
+                    (IVec2::new(0, 0), exists("kept")),
+                    (IVec2::new(1, 0), exists("kept")),
+                    (IVec2::new(2, 0), exists("new")),
+                    (IVec2::new(3, 0), PointerResult::Nothing),
+                    (IVec2::new(4, 0), exists("deployed")),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            changes,
+            SceneListChanges {
+                added: vec!["deployed".to_owned(), "new".to_owned()],
+                removed: vec!["deleted".to_owned(), "old".to_owned()],
+            }
+        );
+        assert_eq!(pointers.get(IVec2::new(0, 0)), Some(&exists("kept")));
+        assert_eq!(pointers.get(IVec2::new(2, 0)), Some(&exists("new")));
+        assert_ne!(pointers.crc(IVec2::new(2, 0), 0), crc);
+
+        // an identical list reports nothing
+        let changes = pointers
+            .refresh_response(generation, [(IVec2::new(2, 0), exists("new"))])
+            .unwrap();
+        assert_eq!(changes, SceneListChanges::default());
+
+        // and a stale generation is ignored
+        pointers.set_realm(IVec2::splat(-10), IVec2::splat(10));
+        assert!(pointers
+            .refresh_response(generation, [(IVec2::new(2, 0), exists("newer"))])
+            .is_none());
+    }
+
+    #[test]
+    fn test_leaving_realm_restores_base_settings() {
+        let mut app = App::new();
+        let mut config = AppConfig::default();
+        let base = RealmSettingOverrides::from_config(&config);
+        config.realm_settings.insert(
+            "local".to_owned(),
+            RealmSettingOverrides {
+                max_avatars: Some(10),
+                ..Default::default()
+            },
+        );
+        app.insert_resource(config)
+            .init_resource::<CurrentRealm>()
+            .init_resource::<LiveScenes>()
+            .init_resource::<RealmSettings>()
+            .init_resource::<ManualScenes>()
+            .add_systems(Update, process_realm_change);
+
+        let set_realm = |app: &mut App, address: &str, settings: Option<RealmSettingOverrides>| {
+            let mut realm = app.world_mut().resource_mut::<CurrentRealm>();
+            realm.address = address.to_owned();
+            realm.config.settings = settings;
+            app.update();
+            let config = app.world().resource::<AppConfig>();
+            let effective = app.world().resource::<RealmSettings>().effective(config);
+            RealmSettingOverrides::from_config(&effective)
+        };
+
+        // the realm suggestion applies, the local per-realm setting wins over it
+        let suggested = RealmSettingOverrides {
+            scene_load_distance: Some(20.0),
+            scene_unload_extra_distance: Some(500.0),
+            max_avatars: Some(30),
+            ..Default::default()
+        };
+        let active = set_realm(&mut app, "local", Some(suggested));
+        assert_eq!(active.scene_load_distance, Some(20.0));
+        assert_eq!(active.max_avatars, Some(10));
+        // limited to the range the settings ui allows
+        assert_eq!(active.scene_unload_extra_distance, Some(100.0));
+        assert_eq!(active.shadow_settings, base.shadow_settings);
+
+        // an explicit change by the user drops that override
+        let mut changed = base.clone();
+        changed.scene_load_distance = Some(80.0);
+        let mut realm_settings = app.world_mut().resource_mut::<RealmSettings>();
+        assert!(realm_settings.user_changed(&base, &changed));
+        assert_eq!(realm_settings.overrides.scene_load_distance, None);
+        assert_eq!(realm_settings.overrides.max_avatars, Some(10));
+
+        // leaving restores the base settings
+        assert_eq!(set_realm(&mut app, "other", None), base);
+        // and the user's config was never modified
+        assert_eq!(
+            RealmSettingOverrides::from_config(app.world().resource::<AppConfig>()),
+            base
+        );
+
+        // scenes loaded with `/loadscene` don't carry over to the next realm
+        app.world_mut().resource_mut::<ManualScenes>().0.insert(
+            "manual".to_owned(),
+            ManualScene {
+                urn: None,
+                parcel: IVec2::ZERO,
+            },
+        );
+        app.world_mut()
+            .resource_mut::<LiveScenes>()
+            .0
+            .insert("manual".to_owned(), Entity::PLACEHOLDER);
+        set_realm(&mut app, "local", None);
+        assert!(app.world().resource::<ManualScenes>().0.is_empty());
+        assert!(app.world().resource::<LiveScenes>().0.is_empty());
+    }
+}
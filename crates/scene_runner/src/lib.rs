@@ -6,6 +6,7 @@ use std::{
 };
 
 use bevy::{
+    app::AppExit,
     core::FrameCount,
     ecs::{query::Has, schedule::ScheduleLabel, system::SystemParam},
     math::{FloatOrd, Vec3A, Vec3Swizzles},
@@ -17,21 +18,27 @@ use bevy::{
 };
 
 use common::{
+    config::autosave_config,
     rpc::RpcCall,
     sets::{SceneLoopSets, SceneSets},
-    structs::{AppConfig, PrimaryCamera, PrimaryUser},
+    structs::{
+        AfkState, AppConfig, BackgroundPause, FocusLossSetting, PerformanceSettings, PrimaryCamera,
+        PrimaryUser,
+    },
     util::{dcl_assert, TryPushChildrenEx},
 };
 use dcl::{
     interface::CrdtType, RendererResponse, SceneId, SceneLogLevel, SceneLogMessage, SceneResponse,
 };
 use dcl_component::{
-    proto_components::{common::BorderRect, sdk::components::PbUiCanvasInformation},
+    proto_components::{common::BorderRect, sdk::components::PbUiCanvasInformation, ColorSpace},
     transform_and_parent::DclTransformAndParent,
     DclReader, DclWriter, FromDclReader, SceneComponentId, SceneEntityId,
 };
+use deterministic_time::{update_deterministic_pause, DeterministicTime, DeterministicTimePlugin};
+use features::SceneFeaturesPlugin;
 use initialize_scene::{PortableScenes, TestingData};
-use ipfs::SceneIpfsLocation;
+use ipfs::{CurrentRealm, SceneIpfsLocation};
 use primary_entities::PrimaryEntities;
 use spin_sleep::SpinSleeper;
 use ui_core::ui_actions::{Click, On};
@@ -40,15 +47,19 @@ use util::SceneUtilPlugin;
 
 use self::{
     initialize_scene::{
-        LiveScenes, PointerResult, SceneLifecyclePlugin, SceneLoading, ScenePointers, PARCEL_SIZE,
+        LiveScenes, PointerResult, SceneLifecyclePlugin, SceneLoading, ScenePointers, StaticScene,
+        PARCEL_SIZE,
     },
     renderer_context::RendererSceneContext,
+    update_budget::UpdateBudget,
     update_scene::SceneInputPlugin,
     update_world::{CrdtExtractors, SceneOutputPlugin},
 };
 
 pub mod automatic_testing;
 pub mod bounds_calc;
+pub mod deterministic_time;
+pub mod features;
 pub mod gltf_resolver;
 pub mod initialize_scene;
 pub mod permissions;
@@ -56,6 +67,7 @@ pub mod primary_entities;
 pub mod renderer_context;
 #[cfg(test)]
 pub mod test;
+pub mod update_budget;
 pub mod update_scene;
 pub mod update_world;
 pub mod util;
@@ -71,6 +83,7 @@ pub struct SceneUpdates {
     pub eligible_jobs: usize,
     pub loop_end_time: Instant,
     pub scene_queue: VecDeque<(Entity, FloatOrd)>,
+    pub update_budget: UpdateBudget<SceneResponse>,
 }
 
 // safety: struct is sync except for the receiver.
@@ -206,6 +219,8 @@ impl Plugin for SceneRunnerPlugin {
         app.init_resource::<DebugInfo>();
         app.init_resource::<Toasts>();
         app.init_resource::<TestingData>();
+        app.init_resource::<BackgroundPause>();
+        app.init_resource::<ColorSpace>();
 
         let (sender, receiver) = sync_channel(1000);
         app.insert_resource(SceneUpdates {
@@ -217,6 +232,7 @@ impl Plugin for SceneRunnerPlugin {
             eligible_jobs: 0,
             scene_queue: Default::default(),
             loop_end_time: Instant::now(),
+            update_budget: Default::default(),
         });
 
         app.add_event::<LoadSceneEvent>();
@@ -272,10 +288,17 @@ impl Plugin for SceneRunnerPlugin {
         );
 
         app.add_plugins(SceneLifecyclePlugin);
+        app.add_plugins(SceneFeaturesPlugin);
 
         app.add_systems(
             Update,
-            (update_scene_priority, run_scene_loop)
+            (
+                update_background_pause,
+                update_deterministic_pause,
+                update_scene_priority,
+                tick_static_scenes,
+                run_scene_loop,
+            )
                 .chain()
                 .in_set(SceneSets::RunLoop),
         );
@@ -311,10 +334,58 @@ impl Plugin for SceneRunnerPlugin {
             sleeper: SpinSleeper::default(),
         });
 
+        // before the autosave, which writes the updated location on exit
+        app.add_systems(Last, shutdown_scenes.before(autosave_config));
+
         app.add_plugins(SceneInputPlugin);
         app.add_plugins(SceneOutputPlugin);
         app.add_plugins(SceneUtilPlugin);
         app.add_plugins(LightsPlugin);
+        app.add_plugins(DeterministicTimePlugin);
+    }
+}
+
+// max time to wait for scene threads to exit on shutdown
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+// on exit, stop all scene threads and save the current realm and location
+fn shutdown_scenes(world: &mut World, mut done: Local<bool>) {
+    if *done || world.resource::<Events<AppExit>>().is_empty() {
+        return;
+    }
+    *done = true;
+
+    // dropping the thread handles closes the renderer channels, scene threads exit on their next op
+    let scenes = world
+        .query_filtered::<Entity, With<SceneThreadHandle>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    for scene in scenes {
+        world.entity_mut(scene).remove::<SceneThreadHandle>();
+    }
+
+    let location = world
+        .query_filtered::<&GlobalTransform, With<PrimaryUser>>()
+        .get_single(world)
+        .ok()
+        .map(|gt| vec3_to_parcel(gt.translation()));
+    let realm = world
+        .get_resource::<CurrentRealm>()
+        .map(|realm| realm.address.clone())
+        .filter(|address| !address.is_empty());
+    let mut config = world.resource_mut::<AppConfig>();
+    if let Some(location) = location {
+        config.location = location;
+    }
+    if let Some(realm) = realm {
+        config.server = realm;
+    }
+
+    let remaining = dcl::wait_for_scene_threads(SHUTDOWN_TIMEOUT);
+    if remaining > 0 {
+        warn!("{remaining} scene threads did not exit within {SHUTDOWN_TIMEOUT:?}");
+    } else {
+        info!("all scenes stopped");
     }
 }
 
@@ -328,7 +399,7 @@ fn run_scene_loop(world: &mut World) {
         .and_then(|window| window.current_monitor())
         .and_then(|monitor| monitor.refresh_rate_millihertz());
     let config = world.resource::<AppConfig>();
-    let fps = if config.graphics.vsync {
+    let mut fps = if config.graphics.vsync {
         // TODO this should use video mode if we add fullscreen video modes
         refresh_rate
             .map(|rr| (((rr as f64 + 999.0) / 1000.0).ceil()))
@@ -336,6 +407,14 @@ fn run_scene_loop(world: &mut World) {
     } else {
         config.graphics.fps_target as f64
     };
+    // throttle the whole app while paused in the background or away
+    let afk_throttle = config.afk.throttle
+        && world
+            .get_resource::<AfkState>()
+            .is_some_and(|state| state.afk);
+    if (world.resource::<BackgroundPause>().scenes || afk_throttle) && fps != 0.0 {
+        fps = fps.min(BACKGROUND_FPS);
+    }
     let mut loop_schedule = world.resource_mut::<SceneLoopSchedule>();
     let mut schedule = std::mem::take(&mut loop_schedule.schedule);
 
@@ -361,14 +440,24 @@ fn run_scene_loop(world: &mut World) {
     let target_end_time = start_loop_time + Duration::from_secs_f64(loop_schedule.run_time);
     loop_schedule.prev_time = start_loop_time;
 
-    world.resource_mut::<SceneUpdates>().loop_end_time = target_end_time;
+    let update_budget = world
+        .resource::<AppConfig>()
+        .performance
+        .scene_update_budget;
+    let mut updates = world.resource_mut::<SceneUpdates>();
+    updates.loop_end_time = target_end_time;
+    updates.update_budget.new_frame(update_budget);
 
     // run at least once to collect updates even if no scenes are eligible
     let mut run_once = false;
+    // with deterministic time, wait for all scenes each frame for reproducibility
+    let deterministic = world.contains_resource::<DeterministicTime>();
 
     // run until time elapsed or all scenes are updated
+    // stop early if the update budget is used, no point sending more work to scenes
     while !run_once
-        || (Instant::now() < target_end_time
+        || ((deterministic || Instant::now() < target_end_time)
+            && !world.resource::<SceneUpdates>().update_budget.exhausted()
             && (world.resource::<SceneUpdates>().eligible_jobs > 0
                 || !world.resource::<SceneUpdates>().jobs_in_flight.is_empty()))
     {
@@ -386,12 +475,112 @@ fn run_scene_loop(world: &mut World) {
     }
 }
 
+const FOCUS_LOST: &str = "focus_lost";
+const BACKGROUND_FPS: f64 = 10.0;
+
+// pause scenes (and optionally comms) while the window is not focused
+fn update_background_pause(
+    window: Query<&Window, With<PrimaryWindow>>,
+    config: Res<AppConfig>,
+    mut pause: ResMut<BackgroundPause>,
+    mut scenes: Query<&mut RendererSceneContext>,
+) {
+    let focused = window.get_single().map_or(true, |w| w.focused);
+    let new_pause = if focused {
+        BackgroundPause::default()
+    } else {
+        match config.pause_on_focus_loss {
+            FocusLossSetting::Run => BackgroundPause::default(),
+            FocusLossSetting::PauseScenes => BackgroundPause {
+                scenes: true,
+                comms: false,
+            },
+            FocusLossSetting::PauseScenesAndComms => BackgroundPause {
+                scenes: true,
+                comms: true,
+            },
+        }
+    };
+    pause.set_if_neq(new_pause);
+
+    // also applies to scenes spawned while paused
+    for mut context in scenes.iter_mut() {
+        if context.blocked.contains(FOCUS_LOST) != new_pause.scenes {
+            if new_pause.scenes {
+                context.blocked.insert(FOCUS_LOST);
+            } else {
+                context.blocked.remove(FOCUS_LOST);
+            }
+        }
+    }
+}
+
+// distance from a world position to the nearest of the given parcels
+pub fn distance_to_parcels<'a>(
+    parcels: impl IntoIterator<Item = &'a IVec2>,
+    position: Vec3,
+) -> f32 {
+    let point = position.xz() * Vec2::new(1.0, -1.0);
+    parcels
+        .into_iter()
+        .map(|parcel| {
+            let min = parcel.as_vec2() * PARCEL_SIZE;
+            point.distance(point.clamp(min, min + PARCEL_SIZE))
+        })
+        .min_by(f32::total_cmp)
+        .unwrap_or(f32::MAX)
+}
+
+// scheduling priority for a scene, lower values are updated more often.
+// scenes containing the player always have the highest priority (0), other scenes fall off
+// with distance according to `exponent`
+pub fn scene_priority(containing: bool, distance: f32, exponent: f32) -> f32 {
+    if containing {
+        0.0
+    } else {
+        (1.0 + distance).powf(exponent).min(f32::MAX)
+    }
+}
+
+// minimum time between ticks for a scene. the scene containing the player always runs
+// at full rate, distant scenes are throttled. scenes measure real elapsed time between
+// ticks so throttling doesn't affect their delta time or runtime.
+pub fn min_tick_interval(containing: bool, distance: f32, settings: &PerformanceSettings) -> f32 {
+    if containing
+        || settings.scene_throttle_distance <= 0.0
+        || settings.scene_throttle_rate <= 0.0
+        || distance <= settings.scene_throttle_distance
+    {
+        0.0
+    } else {
+        1.0 / settings.scene_throttle_rate
+    }
+}
+
+// static scenes have no thread to run, but their tick still advances once any main.crdt has been
+// applied, so systems waiting for the first ticks don't wait forever
+fn tick_static_scenes(
+    mut scenes: Query<&mut RendererSceneContext, (With<StaticScene>, Without<SceneLoading>)>,
+) {
+    for mut context in scenes.iter_mut() {
+        if context.tick_number >= 1 && !context.broken && context.blocked.is_empty() {
+            context.tick_number = context.tick_number.wrapping_add(1);
+        }
+        // updates for the scene are never sent, so don't let them build up
+        context.crdt_store.take_updates();
+    }
+}
+
 fn update_scene_priority(
-    mut scenes: Query<(Entity, &GlobalTransform, &mut RendererSceneContext), Without<SceneLoading>>,
+    mut scenes: Query<
+        (Entity, &mut RendererSceneContext),
+        (Without<SceneLoading>, Without<StaticScene>),
+    >,
     player: Query<(Entity, &GlobalTransform), With<PrimaryUser>>,
     mut updates: ResMut<SceneUpdates>,
     time: Res<Time>,
     containing_scene: ContainingScene,
+    config: Res<AppConfig>,
 ) {
     updates.eligible_jobs = 0;
 
@@ -406,23 +595,21 @@ fn update_scene_priority(
     // sort eligible scenes
     updates.scene_queue = scenes
         .iter_mut()
-        .filter(|(ent, _, context)| {
+        .filter(|(ent, context)| {
             missing_in_flight.remove(ent);
             !context.in_flight && !context.broken && context.blocked.is_empty()
         })
-        .filter_map(|(ent, transform, mut context)| {
-            // clamp to scene bounds instead of using distance to scene origin
-            let nearest_point = player_translation.xz().clamp(
-                transform.translation().xz() - Vec2::Y * PARCEL_SIZE,
-                transform.translation().xz() + Vec2::X * PARCEL_SIZE,
+        .filter_map(|(ent, mut context)| {
+            let containing = active_scenes.contains(&ent);
+            let distance = distance_to_parcels(&context.parcels, player_translation);
+            context.priority = scene_priority(
+                containing,
+                distance,
+                config.performance.scene_priority_exponent,
             );
-            let distance = (nearest_point - player_translation.xz()).length_squared();
-            context.priority = if active_scenes.contains(&ent) {
-                0.0
-            } else {
-                distance
-            };
-            let not_yet_run = context.last_sent < time.elapsed_seconds();
+            let interval = min_tick_interval(containing, distance, &config.performance);
+            let not_yet_run = context.last_sent < time.elapsed_seconds()
+                && time.elapsed_seconds() - context.last_sent >= interval;
 
             (!context.in_flight && not_yet_run).then(|| {
                 updates.eligible_jobs += 1;
@@ -666,6 +853,9 @@ fn send_scene_updates(
     };
 
     let (_, mut context, handle, scene_transform) = scenes.get_mut(ent).unwrap();
+    // profiling spans are debug level so they cost only a callsite check unless enabled.
+    // they are named `area::phase`, with a `scene` field where they apply to a single scene
+    let _span = debug_span!("scene::send_updates", scene = %context.hash).entered();
 
     // collect components
 
@@ -769,6 +959,8 @@ fn send_scene_updates(
         Some(&mut DclReader::new(&buf)),
     );
 
+    // the scene waits for this response before sending its next batch, and we only respond once
+    // the previous batch has been applied, so a scene can never run ahead of the renderer
     if let Err(e) = handle
         .sender
         .blocking_send(RendererResponse::Ok(crdt_store.take_updates()))
@@ -790,15 +982,18 @@ fn send_scene_updates(
 }
 
 // system to run the current active script
+#[allow(clippy::too_many_arguments)]
 fn receive_scene_updates(
     mut commands: Commands,
     mut updates: ResMut<SceneUpdates>,
     mut scenes: Query<&mut RendererSceneContext>,
     crdt_interfaces: Res<CrdtExtractors>,
     frame: Res<FrameCount>,
+    time: Res<Time>,
     mut rpc_call_events: EventWriter<RpcCall>,
     mut toaster: Toaster,
 ) {
+    // collect available responses, deferring scene output to the update budget
     loop {
         let maybe_completed_job = match updates.receiver().try_recv() {
             Ok(response) => match response {
@@ -830,46 +1025,19 @@ fn receive_scene_updates(
                         None
                     }
                 }
-                SceneResponse::Ok(scene_id, census, mut crdt, runtime, messages, rpc_calls) => {
+                SceneResponse::Ok(scene_id, ref census, ref crdt, ..) => {
                     let root = updates.scene_ids.get(&scene_id).unwrap();
-                    debug!(
-                        "scene {:?}/{:?} received updates! [+{}, -{}]",
-                        census.scene_id,
-                        root,
-                        census.born.len(),
-                        census.died.len()
-                    );
-                    if let Ok(mut context) = scenes.get_mut(*root) {
-                        context.tick_number = context.tick_number.wrapping_add(1);
-                        context.last_update_dt = runtime.0 - context.total_runtime;
-                        context.total_runtime = runtime.0;
-                        context.last_update_frame = frame.0;
-                        context.in_flight = false;
-                        context.nascent = census.born;
-                        context.death_row = census.died;
-                        for message in messages.into_iter() {
-                            context.log(message);
-                        }
-                        let mut commands = commands.entity(*root);
-                        for (component_id, interface) in crdt_interfaces.0.iter() {
-                            interface.updates_to_entity(*component_id, &mut crdt, &mut commands);
-                        }
-                        dcl_assert!(
-                            updates.jobs_in_flight.contains(root) || context.tick_number <= 2
-                        );
-
-                        for rpc_call in rpc_calls {
-                            rpc_call_events.send(rpc_call);
-                        }
-                    } else {
-                        debug!(
-                            "no scene entity, probably got dropped before we processed the result"
-                        );
-                    }
-                    Some(*root)
+                    // scenes that no longer exist are cheap to discard, so process them first
+                    let priority = scenes.get_mut(*root).map_or(0.0, |mut context| {
+                        context.last_tick_time = time.elapsed_seconds() - context.last_sent;
+                        context.priority
+                    });
+                    let cost = crdt.update_count() + census.born.len() + census.died.len();
+                    updates.update_budget.push(response, priority, cost);
+                    None
                 }
             },
-            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Empty) => break,
             Err(TryRecvError::Disconnected) => {
                 panic!("render thread receiver exploded");
             }
@@ -878,6 +1046,50 @@ fn receive_scene_updates(
         if let Some(completed_job) = maybe_completed_job {
             updates.jobs_in_flight.remove(&completed_job);
         }
+    }
+
+    // apply scene output, highest priority first, until the frame budget is used
+    while let Some(response) = updates.update_budget.take_next() {
+        // only scene output is deferred
+        let SceneResponse::Ok(scene_id, census, mut crdt, runtime, messages, rpc_calls) = response
+        else {
+            continue;
+        };
+
+        let root = *updates.scene_ids.get(&scene_id).unwrap();
+        debug!(
+            "scene {:?}/{:?} received updates! [+{}, -{}]",
+            census.scene_id,
+            root,
+            census.born.len(),
+            census.died.len()
+        );
+        if let Ok(mut context) = scenes.get_mut(root) {
+            let _span = debug_span!("scene::apply_updates", scene = %context.hash).entered();
+            context.tick_number = context.tick_number.wrapping_add(1);
+            context.last_update_dt = runtime.0 - context.total_runtime;
+            context.total_runtime = runtime.0;
+            context.last_update_frame = frame.0;
+            context.in_flight = false;
+            context.nascent = census.born;
+            context.death_row = census.died;
+            for message in messages.into_iter() {
+                context.log(message);
+            }
+            let mut commands = commands.entity(root);
+            for (component_id, interface) in crdt_interfaces.0.iter() {
+                interface.updates_to_entity(*component_id, &mut crdt, &mut commands);
+            }
+            dcl_assert!(updates.jobs_in_flight.contains(&root) || context.tick_number <= 2);
+
+            for rpc_call in rpc_calls {
+                rpc_call_events.send(rpc_call);
+            }
+        } else {
+            debug!("no scene entity, probably got dropped before we processed the result");
+        }
+
+        updates.jobs_in_flight.remove(&root);
 
         if Instant::now() > updates.loop_end_time {
             return;
@@ -901,6 +1113,7 @@ fn process_scene_entity_lifecycle(
     primaries: PrimaryEntities,
 ) {
     for (root, mut context, mut deleted_entities) in scenes.iter_mut() {
+        let _span = debug_span!("scene::lifecycle", scene = %context.hash).entered();
         if !context.nascent.is_empty() {
             debug!("{:?}: nascent: {:?}", root, context.nascent);
         }
@@ -908,18 +1121,15 @@ fn process_scene_entity_lifecycle(
             debug!("{:?}: death row: {:?}", root, context.death_row);
         }
 
-        for scene_entity_id in std::mem::take(&mut context.nascent) {
-            if context.bevy_entity(scene_entity_id).is_some() {
-                continue;
-            }
-
-            context.spawn_bevy_entity(&mut commands, root, scene_entity_id, &primaries);
-        }
+        // process deaths before births, so that an id killed and recreated in the same batch
+        // releases the old bevy entity before the new generation is associated.
+        // sorted so older generations of the same id are handled first
+        let mut deaths = std::mem::take(&mut context.death_row)
+            .into_iter()
+            .collect::<Vec<_>>();
+        deaths.sort();
 
-        // update deleted entities list, used by crdt processors to filter results
-        deleted_entities.0 = std::mem::take(&mut context.death_row);
-
-        for deleted_scene_entity in &deleted_entities.0 {
+        for deleted_scene_entity in &deaths {
             if let Some(deleted_bevy_entity) = context.bevy_entity(*deleted_scene_entity) {
                 // reparent scene-entity children to the root entity
                 if let Ok(children) = children.get(deleted_bevy_entity) {
@@ -941,5 +1151,21 @@ fn process_scene_entity_lifecycle(
             }
             context.set_dead(*deleted_scene_entity);
         }
+
+        // update deleted entities list, used by crdt processors to filter results
+        deleted_entities.0 = deaths.into_iter().collect();
+
+        let mut births = std::mem::take(&mut context.nascent)
+            .into_iter()
+            .collect::<Vec<_>>();
+        births.sort();
+
+        for scene_entity_id in births {
+            if context.bevy_entity(scene_entity_id).is_some() || context.is_dead(scene_entity_id) {
+                continue;
+            }
+
+            context.spawn_bevy_entity(&mut commands, root, scene_entity_id, &primaries);
+        }
     }
 }
@@ -281,6 +281,9 @@ impl PermissionStrings for PermissionType {
             PermissionType::Fetch => "Fetch Data",
             PermissionType::Websocket => "Open Websocket",
             PermissionType::OpenUrl => "Open Url",
+            PermissionType::Microphone => "Use Microphone",
+            PermissionType::SceneMessages => "Message Other Scenes",
+            PermissionType::Notifications => "Show Notifications",
         }
     }
 
@@ -332,6 +335,9 @@ impl PermissionStrings for PermissionType {
             PermissionType::Fetch => "fetch data from a remote server",
             PermissionType::Websocket => "open a web socket to communicate with a remote server",
             PermissionType::OpenUrl => "open a url in your browser",
+            PermissionType::Microphone => "use your microphone",
+            PermissionType::SceneMessages => "exchange messages with other scenes in this realm",
+            PermissionType::Notifications => "show you notifications",
         }
     }
 
@@ -351,6 +357,9 @@ impl PermissionStrings for PermissionType {
             PermissionType::Fetch => "fetching remote data",
             PermissionType::Websocket => "opening a websocket",
             PermissionType::OpenUrl => "opening a url in your browser",
+            PermissionType::Microphone => "using your microphone",
+            PermissionType::SceneMessages => "exchanging messages with other scenes",
+            PermissionType::Notifications => "showing notifications",
         }
     }
 }
@@ -78,6 +78,8 @@ pub struct RendererSceneContext {
     pub tick_number: u32,
     // last tick delta
     pub last_update_dt: f32,
+    // real time between sending the last update and receiving the scene's response
+    pub last_tick_time: f32,
 
     // message buffer
     pub logs: RingBuffer<SceneLogMessage>,
@@ -137,6 +139,7 @@ impl RendererSceneContext {
             total_runtime: 0.0,
             tick_number: 0,
             last_update_dt: 0.0,
+            last_tick_time: 0.0,
             logs: RingBuffer::new(1000, 100),
             log_to_stdout,
             last_action_event: None,
@@ -178,8 +181,10 @@ impl RendererSceneContext {
 
     pub fn set_dead(&mut self, entity: SceneEntityId) {
         let entry = self.entity_entry_mut(entity.id);
-        if entry.0 == entity.generation {
-            entry.0 += 1;
+        if entry.0 <= entity.generation {
+            // a live older generation must be killed explicitly so its bevy entity is cleaned up
+            dcl_assert!(entry.0 == entity.generation || entry.1.is_none());
+            entry.0 = entity.generation + 1;
             entry.1 = None;
         }
     }
@@ -286,3 +291,56 @@ impl RendererSceneContext {
         self.logs.send(log);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_entity_generation_reuse() {
+        let mut context = RendererSceneContext::new(
+            SceneId(Entity::PLACEHOLDER),
+            String::default(),
+            false,
+            String::default(),
+            IVec2::ZERO,
+            HashSet::default(),
+            Vec::default(),
+            Vec::default(),
+            Entity::PLACEHOLDER,
+            UVec2::ONE,
+            0.0,
+            false,
+            "7",
+            false,
+        );
+        let id = |generation| SceneEntityId::new(512, generation);
+        let bevy_ids = (1..=4).map(Entity::from_raw).collect::<Vec<_>>();
+
+        context.associate_bevy_entity(id(0), bevy_ids[0]);
+        assert_eq!(context.bevy_entity(id(0)), Some(bevy_ids[0]));
+
+        // killed and recreated several times within one census: only the oldest live
+        // generation is reported dead, and only the newest is born
+        context.set_dead(id(0));
+        context.associate_bevy_entity(id(3), bevy_ids[3]);
+
+        for generation in 0..3 {
+            assert!(context.is_dead(id(generation)));
+            assert_eq!(context.bevy_entity(id(generation)), None);
+        }
+        assert!(!context.is_dead(id(3)));
+        assert_eq!(context.bevy_entity(id(3)), Some(bevy_ids[3]));
+
+        // killing a generation that was never live still advances the table
+        context.set_dead(id(3));
+        context.set_dead(id(5));
+        assert!(context.is_dead(id(5)));
+        context.associate_bevy_entity(id(6), bevy_ids[1]);
+        assert_eq!(context.bevy_entity(id(6)), Some(bevy_ids[1]));
+
+        // stale deaths are ignored
+        context.set_dead(id(2));
+        assert_eq!(context.bevy_entity(id(6)), Some(bevy_ids[1]));
+    }
+}
@@ -5,12 +5,17 @@ use std::{collections::BTreeMap, fs::File, io::Write};
 use bevy::{
     app::{PluginGroupBuilder, ScheduleRunnerPlugin},
     diagnostic::DiagnosticsPlugin,
+    ecs::system::RunSystemOnce,
     gizmos::GizmoPlugin,
     gltf::GltfPlugin,
     input::InputPlugin,
     log::LogPlugin,
     prelude::*,
-    render::mesh::MeshPlugin,
+    render::{
+        mesh::MeshPlugin,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
     scene::ScenePlugin,
     time::TimePlugin,
     utils::{HashMap, Instant},
@@ -18,27 +23,33 @@ use bevy::{
 use bevy_dui::DuiPlugin;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
-use scene_material::SceneBoundPlugin;
+use scene_material::{SceneBoundPlugin, SceneMaterial};
 use spin_sleep::SpinSleeper;
 use system_bridge::SystemBridgePlugin;
 use ui_core::{scrollable::ScrollTargetEvent, stretch_uvs_image::StretchUvMaterial};
 use visuals::SceneGlobalLight;
+use world_ui::WorldUi;
 
 use crate::{
-    initialize_scene::{PointerResult, ScenePointers},
+    initialize_scene::{PointerResult, ScenePointers, StaticScene},
     permissions::PermissionManager,
     process_scene_entity_lifecycle,
     update_world::{
-        transform_and_parent::process_transform_and_parent_updates, CrdtStateComponent,
+        material::{MissingTexture, TextureResolver},
+        mesh_renderer::MeshDefinition,
+        text_shape::TextShape,
+        texture_budget::SceneTextureMemory,
+        transform_and_parent::process_transform_and_parent_updates,
+        CrdtStateComponent,
     },
     RendererSceneContext, SceneEntity, SceneLoopLabel, SceneLoopSchedule, SceneRunnerPlugin,
-    SceneUpdates,
+    SceneThreadHandle, SceneUpdates,
 };
 use common::{
     rpc::RpcCall,
     structs::{
-        AppConfig, CursorLocks, GraphicsSettings, PrimaryCamera, PrimaryPlayerRes,
-        SceneLoadDistance, ToolTips,
+        AppConfig, CursorLocks, GraphicsSettings, PerformanceSettings, PrimaryCamera,
+        PrimaryPlayerRes, SceneLoadDistance, ToolTips,
     },
 };
 use comms::{preview::PreviewMode, CommsPlugin};
@@ -48,8 +59,12 @@ use dcl::{
     interface::{CrdtStore, CrdtType},
 };
 use dcl_component::{
-    transform_and_parent::DclTransformAndParent, DclReader, DclWriter, SceneComponentId,
-    SceneCrdtTimestamp, SceneEntityId,
+    proto_components::{
+        common::{texture_union, Texture},
+        sdk::components::{pb_mesh_renderer, PbMeshRenderer, PbTextShape},
+    },
+    transform_and_parent::{DclTransformAndParent, DclTranslation},
+    DclReader, DclWriter, SceneComponentId, SceneCrdtTimestamp, SceneEntityId,
 };
 use input_manager::{AcceptInput, InputMap};
 use ipfs::{IpfsIoPlugin, IpfsResource, ServerAbout, ServerConfiguration};
@@ -312,6 +327,58 @@ fn make_reparent_buffer(parent: u16) -> Vec<u8> {
     buf
 }
 
+// apply a transform crdt message and run the transform systems
+fn update_transform(
+    app: &mut App,
+    crdt_store: &mut CrdtStore,
+    dcl_entity: SceneEntityId,
+    timestamp: SceneCrdtTimestamp,
+    data: &[u8],
+) {
+    let (mut scene_context, mut crdt_state) = app
+        .world_mut()
+        .query::<(
+            &mut RendererSceneContext,
+            &mut CrdtStateComponent<CrdtLWWState, DclTransformAndParent>,
+        )>()
+        .single_mut(app.world_mut());
+
+    // initialize the scene entity
+    if scene_context.bevy_entity(dcl_entity).is_none() {
+        scene_context.nascent.insert(dcl_entity);
+    }
+
+    // add next message
+    crdt_store.try_update(
+        SceneComponentId::TRANSFORM,
+        CrdtType::LWW_ENT,
+        dcl_entity,
+        timestamp,
+        Some(&mut DclReader::new(data)),
+    );
+    // pull updates
+    *crdt_state = CrdtStateComponent::new(
+        crdt_store
+            .take_updates()
+            .lww
+            .get(&SceneComponentId::TRANSFORM)
+            .cloned()
+            .unwrap_or_default(),
+    );
+
+    // run systems
+    Schedule::new(SceneLoopLabel)
+        .add_systems(
+            (
+                process_scene_entity_lifecycle,
+                apply_deferred,
+                process_transform_and_parent_updates,
+            )
+                .chain(),
+        )
+        .run(app.world_mut());
+}
+
 // fn run_single_update(app: &mut App) {
 //     // run once
 //     while app
@@ -456,55 +523,306 @@ fn cyclic_recovery() {
         let mut crdt_store = CrdtStore::default();
 
         for (dcl_entity, timestamp, data) in messages.iter().take(4) {
-            let (mut scene_context, mut crdt_state) = app
-                .world_mut()
-                .query::<(
-                    &mut RendererSceneContext,
-                    &mut CrdtStateComponent<CrdtLWWState, DclTransformAndParent>,
-                )>()
-                .single_mut(app.world_mut());
-
-            // initialize the scene entity
-            if scene_context.bevy_entity(*dcl_entity).is_none() {
-                scene_context.nascent.insert(*dcl_entity);
-            }
-
-            // add next message
-            let reader = &mut DclReader::new(data);
-            crdt_store.try_update(
-                SceneComponentId::TRANSFORM,
-                CrdtType::LWW_ENT,
-                *dcl_entity,
-                *timestamp,
-                Some(reader),
-            );
-            // pull updates
-            *crdt_state = CrdtStateComponent::new(
-                crdt_store
-                    .take_updates()
-                    .lww
-                    .get(&SceneComponentId::TRANSFORM)
-                    .cloned()
-                    .unwrap_or_default(),
-            );
-
-            // run systems
-            Schedule::new(SceneLoopLabel)
-                .add_systems(
-                    (
-                        process_scene_entity_lifecycle,
-                        apply_deferred,
-                        process_transform_and_parent_updates,
-                    )
-                        .chain(),
-                )
-                .run(app.world_mut());
+            update_transform(&mut app, &mut crdt_store, *dcl_entity, *timestamp, data);
         }
         let graph = make_graph(&mut app);
         check_or_write!(graph, "expected/cyclic_recovery.dot");
     }
 }
 
+#[test]
+fn cycle_parents_one_entity_to_root() {
+    let mut app = init_test_app("empty_scene.entity_definition");
+    let root = app
+        .world_mut()
+        .query_filtered::<Entity, With<RendererSceneContext>>()
+        .single(app.world_mut());
+    app.world_mut()
+        .entity_mut(root)
+        .insert(CrdtStateComponent::<CrdtLWWState, DclTransformAndParent>::default());
+    let mut crdt_store = CrdtStore::default();
+
+    let id = |id: u16| SceneEntityId { id, generation: 0 };
+    let targets = [(601, 602), (602, 603), (603, 601)];
+    for (timestamp, (ent, par)) in targets.into_iter().enumerate() {
+        update_transform(
+            &mut app,
+            &mut crdt_store,
+            id(ent),
+            SceneCrdtTimestamp(timestamp as u32),
+            &make_reparent_buffer(par),
+        );
+    }
+    // the scene keeps running
+    app.update();
+    app.update();
+
+    // exactly one entity is moved to the root, the others keep their parents
+    let context = app.world().get::<RendererSceneContext>(root).unwrap();
+    let entity = |ent: u16| context.bevy_entity(id(ent)).unwrap();
+    let parent = |ent: u16| app.world().get::<Parent>(entity(ent)).unwrap().get();
+    assert_eq!(
+        targets
+            .iter()
+            .filter(|(ent, _)| parent(*ent) == root)
+            .count(),
+        1
+    );
+    for (ent, par) in targets {
+        assert!(parent(ent) == root || parent(ent) == entity(par));
+    }
+}
+
+#[test]
+fn invalid_transform_ignored() {
+    let mut app = init_test_app("empty_scene.entity_definition");
+    let scene_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<RendererSceneContext>>()
+        .single(app.world_mut());
+    app.world_mut()
+        .entity_mut(scene_entity)
+        .insert(CrdtStateComponent::<CrdtLWWState, DclTransformAndParent>::default());
+    let mut crdt_store = CrdtStore::default();
+
+    let dcl_entity = SceneEntityId {
+        id: 600,
+        generation: 0,
+    };
+    for (timestamp, translation) in [[1.0, 2.0, 3.0], [f32::NAN, 0.0, f32::INFINITY]]
+        .into_iter()
+        .enumerate()
+    {
+        let mut buf = Vec::new();
+        DclWriter::new(&mut buf).write(&DclTransformAndParent {
+            translation: DclTranslation(translation),
+            scale: Vec3::ONE,
+            ..Default::default()
+        });
+        update_transform(
+            &mut app,
+            &mut crdt_store,
+            dcl_entity,
+            SceneCrdtTimestamp(timestamp as u32),
+            &buf,
+        );
+    }
+    app.update();
+
+    // the entity keeps its last valid transform
+    let entity = app
+        .world()
+        .get::<RendererSceneContext>(scene_entity)
+        .unwrap()
+        .bevy_entity(dcl_entity)
+        .unwrap();
+    let transform = app.world().get::<Transform>(entity).unwrap();
+    assert_eq!(transform.translation, Vec3::new(1.0, 2.0, -3.0));
+    let global = app.world().get::<GlobalTransform>(entity).unwrap();
+    assert!(global.translation().is_finite());
+
+    // and nothing propagates to the camera
+    let camera = app
+        .world_mut()
+        .query_filtered::<&GlobalTransform, With<PrimaryCamera>>()
+        .single(app.world());
+    assert!(camera.compute_matrix().is_finite());
+}
+
+#[test]
+fn static_scene_without_main() {
+    let mut app = init_test_app("static_scene.entity_definition");
+    let scene = app
+        .world_mut()
+        .query_filtered::<Entity, With<RendererSceneContext>>()
+        .single(app.world_mut());
+    assert!(app.world().get::<StaticScene>(scene).is_some());
+    assert!(app.world().get::<SceneThreadHandle>(scene).is_none());
+
+    // it keeps ticking without a scene thread
+    for _ in 0..10 {
+        app.update();
+    }
+    let context = app.world().get::<RendererSceneContext>(scene).unwrap();
+    assert!(!context.broken);
+    assert!(context.tick_number >= 5);
+}
+
+#[test]
+fn missing_texture_uses_fallback() {
+    let mut app = init_test_app("static_scene.entity_definition");
+    let scene = app
+        .world_mut()
+        .query_filtered::<Entity, With<RendererSceneContext>>()
+        .single(app.world_mut());
+
+    let mut resolve = |src: &str| {
+        let texture = texture_union::Tex::Texture(Texture {
+            src: src.to_owned(),
+            ..Default::default()
+        });
+        app.world_mut().run_system_once(
+            move |mut resolver: TextureResolver, scenes: Query<&RendererSceneContext>| {
+                resolver
+                    .resolve_texture(scenes.get(scene).unwrap(), &texture)
+                    .map(|resolved| resolved.image)
+            },
+        )
+    };
+
+    let missing_file = resolve("images/missing.png").unwrap();
+    let present_file = resolve("scene.json").unwrap();
+    let fallback = app.world().resource::<MissingTexture>().0.clone();
+    assert_eq!(missing_file, fallback);
+    assert_ne!(present_file, fallback);
+}
+
+#[test]
+fn oversized_texture_downscaled_over_budget() {
+    let mut app = init_test_app("static_scene.entity_definition");
+    app.world_mut()
+        .resource_mut::<AppConfig>()
+        .graphics
+        .texture_budget_mb = 1;
+
+    // 8mb, over the budget by itself
+    let image = Image::new_fill(
+        Extent3d {
+            width: 2048,
+            height: 1024,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 255, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    let handle = app.world_mut().resource_mut::<Assets<Image>>().add(image);
+    app.world_mut()
+        .resource_mut::<SceneTextureMemory>()
+        .track(handle.id(), "scene");
+    app.update();
+
+    let image = app
+        .world()
+        .resource::<Assets<Image>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(image.size(), UVec2::new(512, 256));
+    let memory = app.world().resource::<SceneTextureMemory>();
+    assert_eq!(memory.scene_bytes("scene"), 512 * 256 * 4);
+    assert!(memory.total_bytes() <= 1024 * 1024);
+}
+
+#[test]
+fn box_mesh_renderer() {
+    let mut app = init_test_app("empty_scene.entity_definition");
+    let (root, scene_id) = app
+        .world_mut()
+        .query::<(Entity, &RendererSceneContext)>()
+        .iter(app.world())
+        .map(|(root, context)| (root, context.scene_id))
+        .next()
+        .unwrap();
+
+    // default uvs, and custom uvs for each of the 24 vertices
+    let boxes = [Vec::default(), (0..48).map(|ix| ix as f32 / 48.0).collect()].map(|uvs| {
+        let definition = MeshDefinition::from(PbMeshRenderer {
+            mesh: Some(pb_mesh_renderer::Mesh::Box(pb_mesh_renderer::BoxMesh {
+                uvs,
+            })),
+        });
+        app.world_mut()
+            .spawn((
+                SceneEntity {
+                    root,
+                    scene_id,
+                    id: SceneEntityId::new(600, 0),
+                },
+                definition,
+            ))
+            .id()
+    });
+    app.update();
+
+    for ent in boxes {
+        let handle = app.world().get::<Handle<Mesh>>(ent).unwrap();
+        let mesh = app.world().resource::<Assets<Mesh>>().get(handle).unwrap();
+        assert_eq!(mesh.count_vertices(), 24);
+        let aabb = mesh.compute_aabb().unwrap();
+        assert_eq!(Vec3::from(aabb.center), Vec3::ZERO);
+        assert_eq!(Vec3::from(aabb.half_extents), Vec3::splat(0.5));
+        // composes with materials, a default is added when none is set
+        assert!(app.world().get::<Handle<SceneMaterial>>(ent).is_some());
+    }
+}
+
+#[test]
+fn text_shape_renders_content() {
+    // text needs the ui fonts, which are normally loaded by the ui plugin
+    {
+        use ui_core::{FontName::*, WeightName::*};
+        let _ = ui_core::FONTS.set(HashMap::from_iter(
+            [Mono, Sans, Serif].into_iter().flat_map(|name| {
+                [Regular, Bold, Italic, BoldItalic]
+                    .map(|weight| ((name, weight), Handle::default()))
+            }),
+        ));
+    }
+
+    let mut app = init_test_app("empty_scene.entity_definition");
+    let (root, scene_id) = app
+        .world_mut()
+        .query::<(Entity, &RendererSceneContext)>()
+        .iter(app.world())
+        .map(|(root, context)| (root, context.scene_id))
+        .next()
+        .unwrap();
+
+    let text_color = Color::srgb(1.0, 0.0, 0.0);
+    let ent = app
+        .world_mut()
+        .spawn((
+            SceneEntity {
+                root,
+                scene_id,
+                id: SceneEntityId::new(600, 0),
+            },
+            TextShape(PbTextShape {
+                text: "hello\\nworld".to_owned(),
+                text_color: Some(text_color.into()),
+                outline_width: Some(0.1),
+                outline_color: Some(Color::BLACK.into()),
+                ..Default::default()
+            }),
+        ))
+        .id();
+    app.update();
+
+    // the entity is drawn from a world ui node
+    let world_ui = app.world().get::<WorldUi>(ent).unwrap();
+    assert!(app.world().get::<Node>(world_ui.ui_node).is_some());
+
+    // the text and its outline copies have the content, split over two lines
+    let texts = app
+        .world_mut()
+        .query::<&Text>()
+        .iter(app.world())
+        .filter(|text| {
+            text.sections
+                .iter()
+                .map(|section| section.value.as_str())
+                .collect::<String>()
+                == "hello\nworld"
+        })
+        .map(|text| text.sections[0].style.color.to_srgba())
+        .collect::<Vec<_>>();
+    assert_eq!(texts.len(), 9);
+    let count = |color: Srgba| texts.iter().filter(|c| **c == color).count();
+    assert_eq!(count(text_color.to_srgba()), 1);
+    assert_eq!(count(Srgba::BLACK), 8);
+}
+
 #[test]
 fn test_scene_ray() {
     fn ray_code(mut position: Vec3, mut ray: Vec3) -> Vec<(IVec2, f32)> {
@@ -610,3 +928,109 @@ fn test_scene_ray() {
     assert_eq!(results[0].1, 0.0);
     assert!((results[1].1 - f32::sqrt(8.0 * 8.0 * 2.0)).abs() < 0.01);
 }
+
+#[test]
+fn test_scene_priority() {
+    let parcels = [IVec2::new(0, 0), IVec2::new(1, 0)];
+    let inside = Vec3::new(20.0, 0.0, -8.0);
+    let near = Vec3::new(40.0, 0.0, -8.0);
+    let far = Vec3::new(200.0, 0.0, -8.0);
+
+    assert_eq!(crate::distance_to_parcels(&parcels, inside), 0.0);
+    assert_eq!(crate::distance_to_parcels(&parcels, near), 8.0);
+
+    let priority = |position, containing| {
+        crate::scene_priority(
+            containing,
+            crate::distance_to_parcels(&parcels, position),
+            2.0,
+        )
+    };
+
+    // lower is higher priority
+    assert!(priority(inside, true) < priority(inside, false));
+    assert!(priority(inside, true) < priority(far, false));
+    assert!(priority(near, false) < priority(far, false));
+
+    // exponent 0 treats all non-containing scenes equally
+    assert_eq!(
+        crate::scene_priority(false, 8.0, 0.0),
+        crate::scene_priority(false, 200.0, 0.0)
+    );
+}
+
+#[test]
+fn test_scene_throttle() {
+    let mut world = World::new();
+    world.init_resource::<Time>();
+    world.init_resource::<AppConfig>();
+    world.init_resource::<ScenePointers>();
+    world.init_resource::<crate::initialize_scene::LiveScenes>();
+    world.init_resource::<crate::initialize_scene::PortableScenes>();
+    let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+    world.insert_resource(SceneUpdates {
+        sender,
+        receiver,
+        scene_ids: Default::default(),
+        jobs_in_flight: Default::default(),
+        update_deadline: std::time::SystemTime::now(),
+        eligible_jobs: 0,
+        scene_queue: Default::default(),
+        loop_end_time: Instant::now(),
+        update_budget: Default::default(),
+    });
+    world.spawn((PrimaryUser::default(), GlobalTransform::default()));
+
+    let mut spawn_scene = |parcel: IVec2| {
+        let root = world.spawn_empty().id();
+        world.entity_mut(root).insert(RendererSceneContext::new(
+            dcl::SceneId(root),
+            String::default(),
+            false,
+            String::default(),
+            parcel,
+            bevy::utils::HashSet::from_iter([parcel]),
+            Vec::default(),
+            Vec::default(),
+            root,
+            UVec2::ONE,
+            0.0,
+            false,
+            "7",
+            false,
+        ));
+        root
+    };
+    let near = spawn_scene(IVec2::ZERO);
+    let far = spawn_scene(IVec2::new(100, 0));
+
+    let interval = 1.0 / PerformanceSettings::default().scene_throttle_rate;
+    let mut ticks = HashMap::<Entity, Vec<f32>>::default();
+    for _ in 0..120 {
+        world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(1.0 / 60.0));
+        world.run_system_once(crate::update_scene_priority);
+
+        // send every eligible scene, as the budget would with time to spare
+        let now = world.resource::<Time>().elapsed_seconds();
+        let queue = std::mem::take(&mut world.resource_mut::<SceneUpdates>().scene_queue);
+        for (scene, _) in queue {
+            world
+                .get_mut::<RendererSceneContext>(scene)
+                .unwrap()
+                .last_sent = now;
+            ticks.entry(scene).or_default().push(now);
+        }
+    }
+
+    // the nearby scene runs every frame
+    assert_eq!(ticks[&near].len(), 120);
+
+    // the distant scene is skipped until its interval has passed
+    let far_ticks = &ticks[&far];
+    assert!((18..=20).contains(&far_ticks.len()), "{}", far_ticks.len());
+    for (prev, next) in far_ticks.iter().tuple_windows() {
+        assert!(next - prev >= interval - 1e-4, "{prev} -> {next}");
+    }
+}
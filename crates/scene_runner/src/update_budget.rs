@@ -0,0 +1,158 @@
+// limits how much scene output is applied to the world each frame, so that a burst of
+// responses (e.g. after unpausing several scenes) is spread across multiple frames
+// instead of causing a frame spike.
+
+struct PendingUpdate<T> {
+    item: T,
+    priority: f32,
+    cost: usize,
+    frames_waiting: u32,
+}
+
+pub struct UpdateBudget<T> {
+    pending: Vec<PendingUpdate<T>>,
+    // max cost per frame, 0 for unlimited
+    limit: usize,
+    used: usize,
+}
+
+impl<T> Default for UpdateBudget<T> {
+    fn default() -> Self {
+        Self {
+            pending: Vec::default(),
+            limit: 0,
+            used: 0,
+        }
+    }
+}
+
+impl<T> UpdateBudget<T> {
+    pub fn new_frame(&mut self, limit: usize) {
+        self.limit = limit;
+        self.used = 0;
+        for pending in self.pending.iter_mut() {
+            pending.frames_waiting += 1;
+        }
+    }
+
+    // lower priority values are processed first. priority 0 (the player's current scene) is never deferred
+    pub fn push(&mut self, item: T, priority: f32, cost: usize) {
+        self.pending.push(PendingUpdate {
+            item,
+            priority,
+            cost,
+            frames_waiting: 0,
+        });
+    }
+
+    pub fn exhausted(&self) -> bool {
+        self.limit != 0 && self.used >= self.limit
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    // take the next item to apply this frame, if the budget allows.
+    // at least one item is taken each frame, and deferred items gain priority
+    // the longer they wait, so distant scenes are delayed but never starved.
+    pub fn take_next(&mut self) -> Option<T> {
+        let index = if self.used == 0 || !self.exhausted() {
+            self.pending
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    let a = a.priority / (a.frames_waiting + 1) as f32;
+                    let b = b.priority / (b.frames_waiting + 1) as f32;
+                    a.total_cmp(&b)
+                })
+                .map(|(ix, _)| ix)
+        } else {
+            self.pending.iter().position(|p| p.priority == 0.0)
+        }?;
+
+        let pending = self.pending.swap_remove(index);
+        self.used += pending.cost;
+        Some(pending.item)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // simulate a burst of responses from many scenes arriving in the same frame
+    fn run_burst(limit: usize) -> Vec<usize> {
+        let mut budget = UpdateBudget::default();
+        for ix in 0..20 {
+            budget.push(ix, (ix * 100) as f32, 500);
+        }
+
+        let mut frame_costs = Vec::default();
+        while budget.pending() > 0 {
+            budget.new_frame(limit);
+            let mut cost = 0;
+            while budget.take_next().is_some() {
+                cost += 500;
+            }
+            frame_costs.push(cost);
+        }
+        frame_costs
+    }
+
+    #[test]
+    fn test_update_budget_burst() {
+        // unlimited applies everything at once
+        assert_eq!(run_burst(0), vec![10000]);
+
+        // budgeted spreads the work
+        let frame_costs = run_burst(1000);
+        assert_eq!(frame_costs.len(), 10);
+        assert!(frame_costs.iter().all(|cost| *cost == 1000));
+    }
+
+    #[test]
+    fn test_update_budget_priority() {
+        let mut budget = UpdateBudget::default();
+        budget.new_frame(100);
+        budget.push("far", 1000.0, 100);
+        budget.push("near", 10.0, 100);
+        budget.push("current", 0.0, 100);
+
+        // nearest first
+        assert_eq!(budget.take_next(), Some("current"));
+        assert!(budget.exhausted());
+        assert_eq!(budget.take_next(), None);
+
+        // current scene is never deferred
+        budget.push("current", 0.0, 100);
+        assert_eq!(budget.take_next(), Some("current"));
+        assert_eq!(budget.take_next(), None);
+
+        // at least one per frame, in priority order
+        budget.new_frame(100);
+        assert_eq!(budget.take_next(), Some("near"));
+        assert_eq!(budget.take_next(), None);
+        budget.new_frame(100);
+        assert_eq!(budget.take_next(), Some("far"));
+        assert_eq!(budget.pending(), 0);
+    }
+
+    #[test]
+    fn test_update_budget_no_starvation() {
+        let mut budget = UpdateBudget::default();
+        budget.push("far", 1000.0, 100);
+
+        // a nearby scene responding every frame doesn't block the far scene forever
+        let mut frames = 0;
+        loop {
+            budget.new_frame(100);
+            budget.push("near", 10.0, 100);
+            frames += 1;
+            if budget.take_next() == Some("far") {
+                break;
+            }
+            assert!(frames < 200);
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use common::structs::{CameraOverride, PrimaryCamera};
+use common::structs::{AppConfig, CameraOverride, PrimaryCamera, CAMERA_BOOM};
 
 use crate::{renderer_context::RendererSceneContext, SceneSets};
 use dcl::interface::CrdtType;
@@ -16,7 +16,11 @@ impl Plugin for CameraModePlugin {
     }
 }
 
-fn update_camera_mode(mut scenes: Query<&mut RendererSceneContext>, camera: Query<&PrimaryCamera>) {
+fn update_camera_mode(
+    mut scenes: Query<&mut RendererSceneContext>,
+    camera: Query<&PrimaryCamera>,
+    config: Res<AppConfig>,
+) {
     let Ok(camera) = camera.get_single() else {
         return;
     };
@@ -29,7 +33,7 @@ fn update_camera_mode(mut scenes: Query<&mut RendererSceneContext>, camera: Quer
     let mode = match camera.scene_override {
         Some(CameraOverride::Cinematic(_)) => CameraType::CtCinematic,
         _ => {
-            if distance <= 0.05 {
+            if distance <= 0.0 || distance * CAMERA_BOOM < config.camera.first_person_zoom {
                 CameraType::CtFirstPerson
             } else {
                 CameraType::CtThirdPerson
@@ -1,9 +1,5 @@
-use bevy::{
-    input::mouse::MouseMotion,
-    prelude::*,
-    window::{CursorGrabMode, PrimaryWindow},
-};
-use common::structs::PrimaryCamera;
+use bevy::{input::mouse::MouseMotion, prelude::*, window::PrimaryWindow};
+use common::structs::{CursorLocks, PrimaryCamera};
 
 use crate::{renderer_context::RendererSceneContext, SceneSets};
 use dcl::interface::CrdtType;
@@ -39,6 +35,7 @@ fn update_pointer_lock(
     window: Query<&Window, With<PrimaryWindow>>,
     mut mouse_events: EventReader<MouseMotion>,
     camera: Query<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
+    locks: Res<CursorLocks>,
 ) {
     let Ok(window) = window.get_single() else {
         return;
@@ -47,16 +44,18 @@ fn update_pointer_lock(
         return;
     };
 
-    let screen_coordinates = if window.cursor.grab_mode == bevy::window::CursorGrabMode::Locked {
+    // report the requested lock state rather than the window's, which lags a frame and is not
+    // applied while the window is unfocused
+    let is_pointer_locked = !locks.0.is_empty();
+
+    let screen_coordinates = if is_pointer_locked {
         // if pointer locked, just middle
         Some(Vec2::new(window.width(), window.height()) / 2.0)
     } else {
         window.cursor_position()
     };
 
-    let pointer_lock = PbPointerLock {
-        is_pointer_locked: window.cursor.grab_mode == CursorGrabMode::Locked,
-    };
+    let pointer_lock = PbPointerLock { is_pointer_locked };
 
     let mut frame_delta = Vec2::ZERO;
     for mouse_event in mouse_events.read() {
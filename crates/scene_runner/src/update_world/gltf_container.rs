@@ -36,10 +36,13 @@ use crate::{
 };
 use dcl::interface::{ComponentPosition, CrdtType};
 use dcl_component::{
-    proto_components::sdk::components::{
-        common::LoadingState, pb_material, pb_mesh_collider, pb_mesh_renderer, ColliderLayer,
-        GltfNodeStateValue, PbGltfContainer, PbGltfContainerLoadingState, PbGltfNode,
-        PbGltfNodeState, PbLight, PbMaterial, PbMeshCollider, PbMeshRenderer, PbSpotlight,
+    proto_components::{
+        sdk::components::{
+            common::LoadingState, pb_material, pb_mesh_collider, pb_mesh_renderer, ColliderLayer,
+            GltfNodeStateValue, PbGltfContainer, PbGltfContainerLoadingState, PbGltfNode,
+            PbGltfNodeState, PbLight, PbMaterial, PbMeshCollider, PbMeshRenderer, PbSpotlight,
+        },
+        ColorSpace,
     },
     transform_and_parent::DclTransformAndParent,
     SceneComponentId, SceneEntityId,
@@ -1112,6 +1115,7 @@ fn expose_gltfs(
     )>,
     mats: Res<Assets<SceneMaterial>>,
     images: Res<Assets<Image>>,
+    color_space: Res<ColorSpace>,
 ) {
     for e in removed.read() {
         if let Some(mut commands) = commands.get_entity(e) {
@@ -1344,7 +1348,9 @@ fn expose_gltfs(
                         scene_ent.id,
                         &PbMaterial {
                             material: Some(dcl_material_from_standard_material(
-                                &base.base, &images,
+                                &base.base,
+                                &images,
+                                *color_space,
                             )),
                             gltf: maybe_mat_name.map(|name| pb_material::GltfMaterial {
                                 gltf_src: src.to_owned(),
@@ -1361,12 +1367,14 @@ fn expose_gltfs(
                         .entity(gltf_entity)
                         .remove::<PointLight>()
                         .insert(HiddenPointLight(*point));
+                    // the light color is read back through the scene color space
+                    let color = color_space.to_sdk(point.color);
                     // copy
                     commands.entity(ent).insert(Light {
                         enabled: true,
                         illuminance: Some(point.intensity / (4.0 * PI)),
                         shadows: Some(true),
-                        color: Some(point.color),
+                        color: Some(color),
                     });
                     // write to scene
                     scene.update_crdt(
@@ -1377,7 +1385,7 @@ fn expose_gltfs(
                             enabled: None,
                             illuminance: Some(point.intensity / (4.0 * PI)),
                             shadows: Some(true),
-                            color: Some(point.color.into()),
+                            color: Some(color.into()),
                         },
                     );
                 }
@@ -1389,13 +1397,15 @@ fn expose_gltfs(
                         .entity(gltf_entity)
                         .remove::<SpotLight>()
                         .insert(HiddenSpotLight(*spot));
+                    // the light color is read back through the scene color space
+                    let color = color_space.to_sdk(spot.color);
                     // copy
                     commands.entity(ent).insert((
                         Light {
                             enabled: true,
                             illuminance: Some(spot.intensity / (4.0 * PI)),
                             shadows: Some(true),
-                            color: Some(spot.color),
+                            color: Some(color),
                         },
                         SpotlightAngles {
                             inner_angle: spot.inner_angle,
@@ -1411,7 +1421,7 @@ fn expose_gltfs(
                             enabled: None,
                             illuminance: Some(spot.intensity / (4.0 * PI)),
                             shadows: Some(true),
-                            color: Some(spot.color.into()),
+                            color: Some(color.into()),
                         },
                     );
                     scene.update_crdt(
@@ -4,7 +4,7 @@ use bevy::{math::FloatOrd, prelude::*, render::view::RenderLayers};
 use common::{
     dynamics::PLAYER_COLLIDER_RADIUS,
     sets::SceneSets,
-    structs::{AppConfig, PrimaryUser, PRIMARY_AVATAR_LIGHT_LAYER},
+    structs::{AppConfig, PrimaryUser, RealmSettings, PRIMARY_AVATAR_LIGHT_LAYER},
     util::TryPushChildrenEx,
 };
 use dcl::interface::ComponentPosition;
@@ -12,6 +12,7 @@ use dcl_component::{
     proto_components::{
         common::Vector3,
         sdk::components::{PbGlobalLight, PbLight, PbSpotlight},
+        ColorSpace,
     },
     SceneComponentId,
 };
@@ -106,6 +107,7 @@ pub fn update_directional_light(
     containing_scene: ContainingScene,
     player: Query<Entity, With<PrimaryUser>>,
     time: Res<Time>,
+    color_space: Res<ColorSpace>,
 ) {
     // reset to default
     let t = ((TAU * 0.15 + time.elapsed_seconds_wrapped() / 20.0) % TAU) * 0.6 - TAU * 0.05;
@@ -129,7 +131,7 @@ pub fn update_directional_light(
             global_light.source = Some(parcel);
             if let Some(light) = maybe_light {
                 if let Some(color) = light.color {
-                    global_light.dir_color = color;
+                    global_light.dir_color = color_space.from_sdk(color);
                 }
                 if let Some(ill) = if light.enabled {
                     light.illuminance
@@ -145,7 +147,7 @@ pub fn update_directional_light(
                     global_light.dir_direction = dir;
                 }
                 if let Some(color) = global.ambient_color {
-                    global_light.ambient_color = color;
+                    global_light.ambient_color = color_space.from_sdk(color);
                 }
                 if let Some(brightness) = global.ambient_brightness {
                     global_light.ambient_brightness = brightness;
@@ -213,6 +215,7 @@ fn update_point_lights(
     mut removed_points: RemovedComponents<Light>,
     children: Query<&Children>,
     child_lights: Query<&LightEntity>,
+    color_space: Res<ColorSpace>,
 ) {
     for (entity, container, light, angles, maybe_children) in q.iter() {
         // despawn any previous
@@ -230,10 +233,13 @@ fn update_point_lights(
             0.0
         };
         let range = light.illuminance.unwrap_or(10000.0).powf(0.25);
+        let color = light
+            .color
+            .map_or(Color::WHITE, |color| color_space.from_sdk(color));
         let mut light = match angles {
             Some(angles) => commands.spawn(SpotLightBundle {
                 spot_light: SpotLight {
-                    color: light.color.unwrap_or(Color::WHITE),
+                    color,
                     intensity: lumens,
                     range,
                     radius: 0.0,
@@ -246,7 +252,7 @@ fn update_point_lights(
             }),
             None => commands.spawn(PointLightBundle {
                 point_light: PointLight {
-                    color: light.color.unwrap_or(Color::WHITE),
+                    color,
                     intensity: lumens,
                     range,
                     radius: 0.0,
@@ -295,6 +301,7 @@ fn manage_shadow_casters(
     player: Query<(Entity, &GlobalTransform), With<PrimaryUser>>,
     containing_scene: ContainingScene,
     config: Res<AppConfig>,
+    realm_settings: Res<RealmSettings>,
     mut lights: Local<Vec<(Entity, bool, FloatOrd, bool)>>,
 ) {
     let Ok((player, player_gt)) = player.get_single() else {
@@ -318,7 +325,11 @@ fn manage_shadow_casters(
     // sort by scene-active and distance
     lights.sort_by_key(|(_, scene_active, distance, _)| (*scene_active, *distance));
     // enable up to limit
-    let max_casters = match config.graphics.shadow_settings {
+    let shadow_settings = realm_settings
+        .overrides
+        .shadow_settings
+        .unwrap_or(config.graphics.shadow_settings);
+    let max_casters = match shadow_settings {
         common::structs::ShadowSetting::Off => 0,
         _ => config.graphics.shadow_caster_count,
     };
@@ -1,12 +1,19 @@
 use std::sync::OnceLock;
 
 use bevy::{
+    core_pipeline::core_3d::Transparent3d,
     ecs::system::SystemParam,
     pbr::NotShadowCaster,
     prelude::*,
     render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
         primitives::Aabb,
+        render_asset::RenderAssetUsages,
+        render_phase::{sort_phase_system, ViewSortedRenderPhases},
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
         texture::{ImageAddressMode, ImageFilterMode, ImageSampler, ImageSamplerDescriptor},
+        view::ExtractedView,
+        Render, RenderApp, RenderSet,
     },
 };
 use common::{structs::AppConfig, util::AsH160};
@@ -22,12 +29,18 @@ use dcl_component::{
     proto_components::{
         common::{texture_union, TextureUnion},
         sdk::components::{pb_material, MaterialTransparencyMode, PbMaterial},
+        ColorSpace,
     },
     SceneComponentId, SceneEntityId,
 };
 use scene_material::{SceneBound, SceneMaterial};
 
-use super::{mesh_renderer::update_mesh, scene_ui::UiTextureOutput, AddCrdtInterfaceExt};
+use super::{
+    mesh_renderer::update_mesh,
+    scene_ui::UiTextureOutput,
+    texture_budget::{apply_texture_budget, SceneTextureMemory},
+    AddCrdtInterfaceExt,
+};
 
 pub struct MaterialDefinitionPlugin;
 
@@ -59,7 +72,11 @@ impl From<PbMaterial> for PbMaterialComponent {
 static DEFAULT_BASE: OnceLock<StandardMaterial> = OnceLock::new();
 
 impl MaterialDefinition {
-    pub fn from_base_and_material(base: Option<&BaseMaterial>, pb_material: &PbMaterial) -> Self {
+    pub fn from_base_and_material(
+        base: Option<&BaseMaterial>,
+        pb_material: &PbMaterial,
+        color_space: ColorSpace,
+    ) -> Self {
         let base = base
             .map(|b| &b.material)
             .unwrap_or(DEFAULT_BASE.get_or_init(|| StandardMaterial {
@@ -77,8 +94,7 @@ impl MaterialDefinition {
             Some(pb_material::Material::Unlit(unlit)) => {
                 let base_color = unlit
                     .diffuse_color
-                    .map(Color::from)
-                    .unwrap_or(base.base_color);
+                    .map_or(base.base_color, |color| color_space.from_sdk(color.into()));
 
                 let alpha_mode = if base_color.alpha() < 1.0 {
                     AlphaMode::Blend
@@ -108,7 +124,9 @@ impl MaterialDefinition {
                     warn!("separate alpha texture not supported");
                 }
 
-                let base_color = pbr.albedo_color.map(Color::from).unwrap_or(base.base_color);
+                let base_color = pbr
+                    .albedo_color
+                    .map_or(base.base_color, |color| color_space.from_sdk(color.into()));
 
                 let alpha_mode = match pbr
                     .transparency_mode
@@ -140,7 +158,7 @@ impl MaterialDefinition {
 
                 let emissive_intensity = pbr.emissive_intensity.unwrap_or(2.0);
                 let emissive = if let Some(color) = pbr.emissive_color {
-                    Color::from(color).to_linear() * emissive_intensity
+                    color_space.from_sdk(color.into()).to_linear() * emissive_intensity
                 } else if pbr.emissive_texture.is_some() {
                     Color::WHITE.to_linear() * emissive_intensity
                 } else {
@@ -193,14 +211,32 @@ impl Plugin for MaterialDefinitionPlugin {
             ComponentPosition::EntityOnly,
         );
 
+        app.init_resource::<MissingTexture>();
+        app.init_resource::<SceneTextureMemory>();
+
         app.add_systems(
             Update,
-            (update_materials, update_bias)
+            (
+                update_materials,
+                update_bias,
+                update_sort_offsets,
+                apply_texture_budget,
+            )
                 .chain()
                 .in_set(SceneSets::PostLoop)
                 // we must run after update_mesh as that inserts a default material if none is present
                 .after(update_mesh),
         );
+
+        app.add_plugins(ExtractComponentPlugin::<TransparentSortOffset>::default());
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.add_systems(
+                Render,
+                apply_sort_offsets
+                    .in_set(RenderSet::PhaseSort)
+                    .before(sort_phase_system::<Transparent3d>),
+            );
+        }
     }
 }
 
@@ -222,9 +258,43 @@ pub enum TextureResolveError {
     NotImplemented,
 }
 
+// shown in place of textures that are not in the scene's content
+#[derive(Resource)]
+pub struct MissingTexture(pub Handle<Image>);
+
+impl FromWorld for MissingTexture {
+    fn from_world(world: &mut World) -> Self {
+        // 8x8 magenta and black checkerboard
+        let data = (0..64)
+            .flat_map(|ix| {
+                if (ix % 8 + ix / 8) % 2 == 0 {
+                    [255, 0, 255, 255]
+                } else {
+                    [0, 0, 0, 255]
+                }
+            })
+            .collect();
+        let mut image = Image::new(
+            Extent3d {
+                width: 8,
+                height: 8,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.sampler = ImageSampler::nearest();
+        Self(world.resource_mut::<Assets<Image>>().add(image))
+    }
+}
+
 #[derive(SystemParam)]
 pub struct TextureResolver<'w, 's> {
     ipfas: IpfsAssetServer<'w, 's>,
+    missing: Res<'w, MissingTexture>,
+    memory: ResMut<'w, SceneTextureMemory>,
     videos: Query<'w, 's, &'static VideoTextureOutput>,
     uis: Query<'w, 's, &'static UiTextureOutput>,
     profiles: ProfileManager<'w, 's>,
@@ -246,11 +316,32 @@ impl TextureResolver<'_, '_> {
         match texture {
             texture_union::Tex::Texture(texture) => {
                 // TODO handle wrapmode and filtering once we have some asset processing pipeline in place (bevy 0.11-0.12)
-                Ok(ResolvedTexture {
-                    image: self
-                        .ipfas
+                let image = if self
+                    .ipfas
+                    .ipfs()
+                    .has_content_file(&texture.src, &scene.hash)
+                {
+                    self.ipfas
                         .load_content_file::<Image>(&texture.src, &scene.hash)
-                        .unwrap(),
+                } else {
+                    Err(anyhow::anyhow!("not in the scene content"))
+                };
+                let image = match image {
+                    Ok(image) => {
+                        self.memory.track(image.id(), &scene.hash);
+                        image
+                    }
+                    Err(e) => {
+                        warn!(
+                            "[{}] failed to load texture `{}`: {e}",
+                            scene.hash, texture.src
+                        );
+                        self.missing.0.clone()
+                    }
+                };
+
+                Ok(ResolvedTexture {
+                    image,
                     source_entity: None,
                     camera_target: None,
                 })
@@ -343,7 +434,9 @@ fn update_materials(
     config: Res<AppConfig>,
     mut gltf_resolver: GltfMaterialResolver,
     images: Res<Assets<Image>>,
+    color_space: Res<ColorSpace>,
 ) {
+    let _span = debug_span!("material::update").entered();
     gltf_resolver.begin_frame();
 
     for (ent, mat, container, scene_ent, base) in new_materials.iter_mut() {
@@ -381,7 +474,7 @@ fn update_materials(
             None
         };
 
-        let defn = MaterialDefinition::from_base_and_material(base, &mat.0);
+        let defn = MaterialDefinition::from_base_and_material(base, &mat.0, *color_space);
         let textures: Result<Vec<_>, _> = [
             &defn.base_color_texture,
             &defn.emmissive_texture,
@@ -473,6 +566,7 @@ fn update_materials(
                     material: Some(dcl_material_from_standard_material(
                         &base.as_ref().unwrap().material,
                         &images,
+                        *color_space,
                     )),
                     gltf: mat.0.gltf.clone(),
                 },
@@ -489,6 +583,66 @@ fn update_materials(
     }
 }
 
+// transparent meshes are drawn back to front by the view depth of their origin plus their
+// material's depth bias. scene meshes often have origins far from their geometry, so each mesh
+// carries the offset from its origin to the centre of its bounds, and the render world moves its
+// sort key by that offset's view depth. the key is kept apart from the material so the depth bias
+// passed to the rasterizer stays tiny and turning the camera doesn't re-specialize pipelines
+#[derive(Component, Clone, Copy, ExtractComponent)]
+pub struct TransparentSortOffset(pub Vec3);
+
+// correction to the view depth of a mesh's origin giving the view depth of its bounds' centre.
+// `view_back` is the camera's back vector, along which view depth increases
+pub fn transparent_sort_correction(view_back: Vec3, offset: Vec3) -> f32 {
+    view_back.dot(offset)
+}
+
+#[allow(clippy::type_complexity)]
+fn update_sort_offsets(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &Aabb, &GlobalTransform),
+        (
+            With<Handle<SceneMaterial>>,
+            Or<(
+                Added<Handle<SceneMaterial>>,
+                Changed<Aabb>,
+                Changed<GlobalTransform>,
+            )>,
+        ),
+    >,
+) {
+    for (ent, aabb, transform) in query.iter() {
+        let offset = transform.transform_point(aabb.center.into()) - transform.translation();
+        commands.entity(ent).insert(TransparentSortOffset(offset));
+    }
+}
+
+// runs in the render world before the transparent phase is sorted
+fn apply_sort_offsets(
+    mut phases: ResMut<ViewSortedRenderPhases<Transparent3d>>,
+    views: Query<&ExtractedView>,
+    offsets: Query<&TransparentSortOffset>,
+) {
+    for (view_entity, phase) in phases.iter_mut() {
+        let Ok(view) = views.get(*view_entity) else {
+            continue;
+        };
+        let view_back = *view.world_from_view.back();
+        for item in phase.items.iter_mut() {
+            if let Ok(offset) = offsets.get(item.entity) {
+                item.distance += transparent_sort_correction(view_back, offset.0);
+            }
+        }
+    }
+}
+
+// add a bias based on the aabb size, to force an explicit transparent order which is
+// hopefully correct, but should be better than nothing even if not always perfect
+fn size_bias(half_extents: Vec3) -> f32 {
+    half_extents.length() * 1e-5
+}
+
 #[allow(clippy::type_complexity)]
 fn update_bias(
     mut materials: ResMut<Assets<SceneMaterial>>,
@@ -500,9 +654,7 @@ fn update_bias(
     for (aabb, h_material) in query.iter() {
         if let Some(material) = materials.get_mut(h_material) {
             if material.base.alpha_mode == AlphaMode::Blend {
-                // add a bias based on the aabb size, to force an explicit transparent order which is
-                // hopefully correct, but should be better than nothing even if not always perfect
-                material.base.depth_bias = aabb.half_extents.length() * 1e-5;
+                material.base.depth_bias = size_bias(aabb.half_extents.into());
             }
         }
     }
@@ -511,6 +663,7 @@ fn update_bias(
 pub fn dcl_material_from_standard_material(
     base: &StandardMaterial,
     images: &Assets<Image>,
+    color_space: ColorSpace,
 ) -> pb_material::Material {
     let dcl_texture = |h: &Handle<Image>| -> TextureUnion {
         let path = h.path().unwrap().path();
@@ -553,7 +706,7 @@ pub fn dcl_material_from_standard_material(
             texture: base.base_color_texture.as_ref().map(dcl_texture),
             alpha_test,
             cast_shadows: Some(true),
-            diffuse_color: Some(base.base_color.into()),
+            diffuse_color: Some(color_space.to_sdk(base.base_color).into()),
         })
     } else {
         pb_material::Material::Pbr(pb_material::PbrMaterial {
@@ -563,8 +716,8 @@ pub fn dcl_material_from_standard_material(
             alpha_texture: base.base_color_texture.as_ref().map(dcl_texture),
             emissive_texture: base.emissive_texture.as_ref().map(dcl_texture),
             bump_texture: base.normal_map_texture.as_ref().map(dcl_texture),
-            albedo_color: Some(base.base_color.into()),
-            emissive_color: Some((base.emissive * 0.5).into()),
+            albedo_color: Some(color_space.to_sdk(base.base_color).into()),
+            emissive_color: Some(color_space.to_sdk((base.emissive * 0.5).into()).into()),
             reflectivity_color: None,
             transparency_mode: Some(match base.alpha_mode() {
                 AlphaMode::Opaque => MaterialTransparencyMode::MtmOpaque,
@@ -579,3 +732,42 @@ pub fn dcl_material_from_standard_material(
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_transparent_sort_correction() {
+        // camera at the origin looking down -z, so view depth is the z coordinate
+        let view_back = Vec3::Z;
+        let sort_key = |origin: Vec3, center: Vec3, half_extents: Vec3| {
+            view_back.dot(origin)
+                + transparent_sort_correction(view_back, center - origin)
+                + size_bias(half_extents)
+        };
+        let pane = Vec3::new(2.0, 2.0, 0.0);
+
+        // a pane whose origin is 10m away but whose geometry is 2m away sorts in front of
+        // (so draws after) a centred pane 5m away
+        let offset = sort_key(Vec3::new(0.0, 0.0, -10.0), Vec3::new(0.0, 0.0, -2.0), pane);
+        let centred = sort_key(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, -5.0), pane);
+        assert!(offset > centred);
+        assert!((offset + 2.0).abs() < 1e-3);
+
+        // offsets across the view don't affect depth
+        let sideways = sort_key(Vec3::new(0.0, 0.0, -5.0), Vec3::new(8.0, 3.0, -5.0), pane);
+        assert_eq!(sideways, centred);
+
+        // coincident panes still fall back to ordering by size
+        let large = sort_key(
+            Vec3::new(0.0, 0.0, -5.0),
+            Vec3::new(0.0, 0.0, -5.0),
+            pane * 2.0,
+        );
+        assert!(large > centred);
+
+        // and the bias that reaches the rasterizer stays below a unit
+        assert!(size_bias(Vec3::splat(1000.0)) < 1.0);
+    }
+}
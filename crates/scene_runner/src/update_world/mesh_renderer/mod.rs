@@ -90,49 +90,6 @@ impl Plugin for MeshDefinitionPlugin {
             mesh.generate_tangents().unwrap();
             mesh
         };
-        let cube_uvs = |mut mesh: Mesh| {
-            let Some(VertexAttributeValues::Float32x2(ref mut uvs)) =
-                mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
-            else {
-                panic!()
-            };
-            let mut uvs = uvs.iter_mut();
-            for uv in uvs.by_ref().take(4) {
-                *uv = [uv[0], 1.0 - uv[1]];
-            }
-            for uv in uvs.by_ref().take(4) {
-                *uv = [1.0 - uv[0], 1.0 - uv[1]];
-            }
-            for uv in uvs.by_ref().take(4) {
-                *uv = [uv[0], 1.0 - uv[1]];
-            }
-            for uv in uvs.by_ref().take(4) {
-                *uv = [1.0 - uv[0], 1.0 - uv[1]];
-            }
-            for uv in uvs.by_ref().take(4) {
-                *uv = [1.0 - uv[1], uv[0]];
-            }
-            for uv in uvs.by_ref().take(4) {
-                *uv = [uv[1], uv[0]];
-            }
-            mesh
-        };
-        let plane_uvs = |mut mesh: Mesh| {
-            let Some(VertexAttributeValues::Float32x2(ref mut uvs)) =
-                mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
-            else {
-                panic!()
-            };
-            uvs[0] = [0.0, 1.0];
-            uvs[1] = [1.0, 1.0];
-            uvs[2] = [1.0, 0.0];
-            uvs[3] = [0.0, 0.0];
-            uvs[4] = [0.0, 0.0];
-            uvs[5] = [1.0, 0.0];
-            uvs[6] = [1.0, 1.0];
-            uvs[7] = [0.0, 1.0];
-            mesh
-        };
         let yflip_uvs = |mut mesh: Mesh| {
             let Some(VertexAttributeValues::Float32x2(ref mut uvs)) =
                 mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
@@ -157,14 +114,9 @@ impl Plugin for MeshDefinitionPlugin {
             mesh
         };
         let mut assets = app.world_mut().resource_mut::<Assets<Mesh>>();
-        let boxx = assets.add(generate_tangents(cube_uvs(Cuboid::default().into())));
+        let boxx = assets.add(box_mesh(&[]));
         let cylinder = assets.add(generate_tangents(yflip_uvs(Cylinder::default().into())));
-        let plane = assets.add(generate_tangents(plane_uvs(
-            Cuboid::default()
-                .mesh()
-                .build()
-                .scaled_by(Vec3::new(1.0, 1.0, 0.0)),
-        )));
+        let plane = assets.add(plane_mesh(&[]));
         let sphere = assets.add(xyflip_uvs(generate_tangents(
             Sphere::new(0.5)
                 .mesh()
@@ -182,6 +134,115 @@ impl Plugin for MeshDefinitionPlugin {
     }
 }
 
+// sdk uv index and the bevy cuboid vertex it applies to. sdk uvs have v pointing up
+const BOX_UV_ORDER: [(usize, usize); 24] = [
+    (0, 17),
+    (1, 16),
+    (2, 19),
+    (3, 18),
+    (4, 22),
+    (5, 23),
+    (6, 20),
+    (7, 21),
+    (8, 14),
+    (9, 13),
+    (10, 12),
+    (11, 15),
+    (12, 9),
+    (13, 10),
+    (14, 11),
+    (15, 8),
+    (16, 4),
+    (17, 5),
+    (18, 6),
+    (19, 7),
+    (20, 3),
+    (21, 2),
+    (22, 1),
+    (23, 0),
+];
+
+// planes are flattened cuboids, only the front and back faces are visible
+const PLANE_UV_ORDER: [(usize, usize); 8] = [
+    (0, 0),
+    (1, 3),
+    (2, 2),
+    (3, 1),
+    (4, 6),
+    (5, 5),
+    (6, 4),
+    (7, 7),
+];
+
+fn mesh_uvs(mesh: &mut Mesh) -> &mut Vec<[f32; 2]> {
+    let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
+    else {
+        panic!("uvs are not f32x2")
+    };
+    uvs
+}
+
+fn apply_sdk_uvs(mesh: &mut Mesh, uvs: &[[f32; 2]], order: &[(usize, usize)]) {
+    let mesh_uvs = mesh_uvs(mesh);
+    for &(from, to) in order {
+        mesh_uvs[to] = [uvs[from][0], 1.0 - uvs[from][1]];
+    }
+}
+
+// a unit box with the given 24 sdk uvs, or the sdk default layout
+pub fn box_mesh(uvs: &[[f32; 2]]) -> Mesh {
+    let mut mesh = Mesh::from(Cuboid::default());
+    if uvs.len() == BOX_UV_ORDER.len() {
+        apply_sdk_uvs(&mut mesh, uvs, &BOX_UV_ORDER);
+    } else {
+        let mut mesh_uvs = mesh_uvs(&mut mesh).iter_mut();
+        for uv in mesh_uvs.by_ref().take(4) {
+            *uv = [uv[0], 1.0 - uv[1]];
+        }
+        for uv in mesh_uvs.by_ref().take(4) {
+            *uv = [1.0 - uv[0], 1.0 - uv[1]];
+        }
+        for uv in mesh_uvs.by_ref().take(4) {
+            *uv = [uv[0], 1.0 - uv[1]];
+        }
+        for uv in mesh_uvs.by_ref().take(4) {
+            *uv = [1.0 - uv[0], 1.0 - uv[1]];
+        }
+        for uv in mesh_uvs.by_ref().take(4) {
+            *uv = [1.0 - uv[1], uv[0]];
+        }
+        for uv in mesh_uvs.by_ref().take(4) {
+            *uv = [uv[1], uv[0]];
+        }
+    }
+    mesh.generate_tangents().unwrap();
+    mesh
+}
+
+// a unit plane with the given 8 sdk uvs (front then back), or the sdk default layout
+pub fn plane_mesh(uvs: &[[f32; 2]]) -> Mesh {
+    let mut mesh = Cuboid::default()
+        .mesh()
+        .build()
+        .scaled_by(Vec3::new(1.0, 1.0, 0.0));
+    if uvs.len() == PLANE_UV_ORDER.len() {
+        apply_sdk_uvs(&mut mesh, uvs, &PLANE_UV_ORDER);
+    } else {
+        mesh_uvs(&mut mesh)[..8].copy_from_slice(&[
+            [0.0, 1.0],
+            [1.0, 1.0],
+            [1.0, 0.0],
+            [0.0, 0.0],
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [1.0, 1.0],
+            [0.0, 1.0],
+        ]);
+    }
+    mesh.generate_tangents().unwrap();
+    mesh
+}
+
 #[derive(Component)]
 pub struct RetryMeshDefinition;
 
@@ -214,42 +275,7 @@ pub fn update_mesh(
                 if uvs.len() != 24 {
                     defaults.boxx.clone()
                 } else {
-                    let mut mesh = Mesh::from(bevy::math::primitives::Cuboid::default());
-                    let Some(VertexAttributeValues::Float32x2(mesh_uvs)) =
-                        mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
-                    else {
-                        panic!("uvs are not f32x2")
-                    };
-                    for (from, to) in [
-                        (0, 17),
-                        (1, 16),
-                        (2, 19),
-                        (3, 18),
-                        (4, 22),
-                        (5, 23),
-                        (6, 20),
-                        (7, 21),
-                        (8, 14),
-                        (9, 13),
-                        (10, 12),
-                        (11, 15),
-                        (12, 9),
-                        (13, 10),
-                        (14, 11),
-                        (15, 8),
-                        (16, 4),
-                        (17, 5),
-                        (18, 6),
-                        (19, 7),
-                        (20, 3),
-                        (21, 2),
-                        (22, 1),
-                        (23, 0),
-                    ] {
-                        mesh_uvs[to][0] = uvs[from][0];
-                        mesh_uvs[to][1] = 1.0 - uvs[from][1];
-                    }
-                    meshes.add(mesh)
+                    meshes.add(box_mesh(uvs))
                 }
             }
             MeshDefinition::Cylinder {
@@ -270,29 +296,7 @@ pub fn update_mesh(
                 if uvs.len() != 8 {
                     defaults.plane.clone()
                 } else {
-                    let mut mesh = Cuboid::default()
-                        .mesh()
-                        .build()
-                        .scaled_by(Vec3::new(1.0, 1.0, 0.0));
-                    let Some(VertexAttributeValues::Float32x2(mesh_uvs)) =
-                        mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
-                    else {
-                        panic!("uvs are not f32x2")
-                    };
-                    for (from, to) in [
-                        (0, 0),
-                        (1, 3),
-                        (2, 2),
-                        (3, 1),
-                        (4, 6),
-                        (5, 5),
-                        (6, 4),
-                        (7, 7),
-                    ] {
-                        mesh_uvs[to][0] = uvs[from][0];
-                        mesh_uvs[to][1] = 1.0 - uvs[from][1];
-                    }
-                    meshes.add(mesh)
+                    meshes.add(plane_mesh(uvs))
                 }
             }
             MeshDefinition::Sphere => defaults.sphere.clone(),
@@ -343,3 +347,93 @@ pub fn update_mesh(
 
     default_material.retain(|scene, _| scenes.get(*scene).is_ok());
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn uvs(mesh: &Mesh) -> Vec<[f32; 2]> {
+        let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("uvs are not f32x2")
+        };
+        uvs.clone()
+    }
+
+    // the sdk uvs that reproduce the given mesh
+    fn sdk_uvs(mesh: &Mesh, order: &[(usize, usize)]) -> Vec<[f32; 2]> {
+        let uvs = uvs(mesh);
+        let mut sdk_uvs = vec![[0.0; 2]; order.len()];
+        for &(from, to) in order {
+            sdk_uvs[from] = [uvs[to][0], 1.0 - uvs[to][1]];
+        }
+        sdk_uvs
+    }
+
+    #[test]
+    fn test_box_uvs() {
+        // each sdk uv is used for exactly one vertex
+        let mut targets = BOX_UV_ORDER.map(|(_, to)| to);
+        targets.sort_unstable();
+        assert_eq!(targets, std::array::from_fn(|ix| ix));
+
+        // the default layout, given as custom uvs, produces the default mesh
+        let default = box_mesh(&[]);
+        let custom = box_mesh(&sdk_uvs(&default, &BOX_UV_ORDER));
+        assert_eq!(uvs(&custom), uvs(&default));
+        assert!(custom.attribute(Mesh::ATTRIBUTE_TANGENT).is_some());
+
+        // custom uvs are flipped to bevy's v-down convention
+        let sdk = (0..24)
+            .map(|ix| [ix as f32 / 24.0, 0.25])
+            .collect::<Vec<_>>();
+        let custom = uvs(&box_mesh(&sdk));
+        for (from, to) in BOX_UV_ORDER {
+            assert_eq!(custom[to], [sdk[from][0], 0.75]);
+        }
+    }
+
+    #[test]
+    fn test_plane_uvs() {
+        // the sdk default plane layout
+        let sdk_default = [
+            [0.0, 0.0],
+            [0.0, 1.0],
+            [1.0, 1.0],
+            [1.0, 0.0],
+            [1.0, 0.0],
+            [1.0, 1.0],
+            [0.0, 1.0],
+            [0.0, 0.0],
+        ];
+        let default = plane_mesh(&[]);
+        assert_eq!(sdk_uvs(&default, &PLANE_UV_ORDER), sdk_default);
+        assert_eq!(uvs(&plane_mesh(&sdk_default)), uvs(&default));
+
+        // custom uvs are applied to both faces
+        let sdk = [
+            [0.0, 0.0],
+            [0.0, 0.5],
+            [0.5, 0.5],
+            [0.5, 0.0],
+            [0.5, 0.0],
+            [0.5, 0.5],
+            [0.0, 0.5],
+            [0.0, 0.0],
+        ];
+        let custom = uvs(&plane_mesh(&sdk));
+        assert_eq!(
+            custom[..8],
+            [
+                [0.0, 1.0],
+                [0.5, 1.0],
+                [0.5, 0.5],
+                [0.0, 0.5],
+                [0.0, 0.5],
+                [0.5, 0.5],
+                [0.5, 1.0],
+                [0.0, 1.0],
+            ]
+        );
+    }
+}
@@ -40,6 +40,7 @@ pub mod pointer_events;
 pub mod raycast;
 pub mod scene_ui;
 pub mod text_shape;
+pub mod texture_budget;
 pub mod transform_and_parent;
 pub mod visibility;
 
@@ -30,10 +30,13 @@ use common::{
 };
 use dcl::interface::{ComponentPosition, CrdtType};
 use dcl_component::{
-    proto_components::sdk::components::{
-        self, scroll_position_value, PbUiBackground, PbUiCanvas, PbUiDropdown, PbUiInput,
-        PbUiScrollResult, PbUiText, PbUiTransform, ScrollPositionValue, YgAlign, YgDisplay,
-        YgFlexDirection, YgJustify, YgOverflow, YgPositionType, YgUnit, YgWrap,
+    proto_components::{
+        sdk::components::{
+            self, scroll_position_value, PbUiBackground, PbUiCanvas, PbUiDropdown, PbUiInput,
+            PbUiScrollResult, PbUiText, PbUiTransform, ScrollPositionValue, YgAlign, YgDisplay,
+            YgFlexDirection, YgJustify, YgOverflow, YgPositionType, YgUnit, YgWrap,
+        },
+        ColorSpace,
     },
     SceneComponentId, SceneEntityId,
 };
@@ -60,71 +63,108 @@ struct MaybeSize {
     height: Option<Val>,
 }
 
-// macro helpers to convert proto format to bevy format for val, size, rect
+// limits for numeric ui values from scenes. nan and infinite values are replaced with
+// defaults, and finite values are clamped to these ranges so that a buggy or malicious
+// scene can't break layout
+const MAX_UI_PX: f32 = 100_000.0;
+const MAX_UI_PERCENT: f32 = 10_000.0;
+const MAX_UI_FLEX: f32 = 10_000.0;
+
+// clamp a value from a scene into range, recording the field if it had to be corrected.
+// returns None for nan or infinite values
+fn sanitize(
+    value: f32,
+    min: f32,
+    max: f32,
+    field: &'static str,
+    corrected: &mut Vec<&'static str>,
+) -> Option<f32> {
+    if !value.is_finite() {
+        corrected.push(field);
+        return None;
+    }
+    let clamped = value.clamp(min, max);
+    if clamped != value {
+        corrected.push(field);
+    }
+    Some(clamped)
+}
+
+fn sanitize_px(
+    value: f32,
+    signed: bool,
+    field: &'static str,
+    corrected: &mut Vec<&'static str>,
+) -> Option<Val> {
+    let min = if signed { -MAX_UI_PX } else { 0.0 };
+    sanitize(value, min, MAX_UI_PX, field, corrected).map(Val::Px)
+}
+
+fn sanitize_percent(
+    value: f32,
+    signed: bool,
+    field: &'static str,
+    corrected: &mut Vec<&'static str>,
+) -> Option<Val> {
+    let min = if signed { -MAX_UI_PERCENT } else { 0.0 };
+    sanitize(value, min, MAX_UI_PERCENT, field, corrected).map(Val::Percent)
+}
+
+// macro helpers to convert proto format to bevy format for val, size, rect.
+// `$s` is whether negative values are allowed, `$c` collects corrected field names
 macro_rules! val {
-    ($pb:ident, $u:ident, $v:ident, $d:expr) => {
+    ($pb:ident, $u:ident, $v:ident, $d:expr, $s:expr, $c:ident) => {
         match $pb.$u() {
             YgUnit::YguUndefined => $d,
             YgUnit::YguAuto => Val::Auto,
-            YgUnit::YguPoint => {
-                if $pb.$v.is_nan() {
-                    $d
-                } else {
-                    Val::Px($pb.$v)
-                }
-            }
+            YgUnit::YguPoint => sanitize_px($pb.$v, $s, stringify!($v), &mut $c).unwrap_or($d),
             YgUnit::YguPercent => {
-                if $pb.$v.is_nan() {
-                    $d
-                } else {
-                    Val::Percent($pb.$v)
-                }
+                sanitize_percent($pb.$v, $s, stringify!($v), &mut $c).unwrap_or($d)
             }
         }
     };
 }
 
 macro_rules! size {
-    ($pb:ident, $wu:ident, $w:ident, $hu:ident, $h:ident, $d:expr) => {{
+    ($pb:ident, $wu:ident, $w:ident, $hu:ident, $h:ident, $d:expr, $c:ident) => {{
         Size {
-            width: val!($pb, $wu, $w, $d),
-            height: val!($pb, $hu, $h, $d),
+            width: val!($pb, $wu, $w, $d, false, $c),
+            height: val!($pb, $hu, $h, $d, false, $c),
         }
     }};
 }
 
-// macro helpers to convert proto format to bevy format for val, size, rect
 macro_rules! maybe_val {
-    ($pb:ident, $u:ident, $v:ident, $d:expr) => {
+    ($pb:ident, $u:ident, $v:ident, $d:expr, $s:expr, $c:ident) => {
         match $pb.$u() {
             YgUnit::YguUndefined => None,
             YgUnit::YguAuto => Some(Val::Auto),
-            YgUnit::YguPoint => Some(if $pb.$v.is_nan() { $d } else { Val::Px($pb.$v) }),
-            YgUnit::YguPercent => Some(if $pb.$v.is_nan() {
-                $d
-            } else {
-                Val::Percent($pb.$v)
-            }),
+            YgUnit::YguPoint => {
+                Some(sanitize_px($pb.$v, $s, stringify!($v), &mut $c).unwrap_or($d))
+            }
+            YgUnit::YguPercent => {
+                Some(sanitize_percent($pb.$v, $s, stringify!($v), &mut $c).unwrap_or($d))
+            }
         }
     };
 }
 
 macro_rules! maybe_size {
-    ($pb:ident, $wu:ident, $w:ident, $hu:ident, $h:ident, $d:expr) => {{
+    ($pb:ident, $wu:ident, $w:ident, $hu:ident, $h:ident, $d:expr, $c:ident) => {{
         MaybeSize {
-            width: maybe_val!($pb, $wu, $w, $d),
-            height: maybe_val!($pb, $hu, $h, $d),
+            width: maybe_val!($pb, $wu, $w, $d, false, $c),
+            height: maybe_val!($pb, $hu, $h, $d, false, $c),
         }
     }};
 }
 
 macro_rules! rect {
-    ($pb:ident, $lu:ident, $l:ident, $ru:ident, $r:ident, $tu:ident, $t:ident, $bu:ident, $b:ident, $d:expr) => {
+    ($pb:ident, $lu:ident, $l:ident, $ru:ident, $r:ident, $tu:ident, $t:ident, $bu:ident, $b:ident, $d:expr, $s:expr, $c:ident) => {
         UiRect {
-            left: val!($pb, $lu, $l, $d),
-            right: val!($pb, $ru, $r, $d),
-            top: val!($pb, $tu, $t, $d),
-            bottom: val!($pb, $bu, $b, $d),
+            left: val!($pb, $lu, $l, $d, $s, $c),
+            right: val!($pb, $ru, $r, $d, $s, $c),
+            top: val!($pb, $tu, $t, $d, $s, $c),
+            bottom: val!($pb, $bu, $b, $d, $s, $c),
         }
     };
 }
@@ -143,7 +183,8 @@ pub struct UiTransform {
     flex_direction: FlexDirection,
     justify_content: JustifyContent,
     overflow: Overflow,
-    scroll: bool,
+    scroll_x: bool,
+    scroll_y: bool,
     scroll_h_visible: bool,
     scroll_v_visible: bool,
     scroll_position: Option<ScrollPositionValue>,
@@ -162,7 +203,8 @@ pub struct UiTransform {
 
 impl From<PbUiTransform> for UiTransform {
     fn from(value: PbUiTransform) -> Self {
-        Self {
+        let mut corrected = Vec::default();
+        let transform = Self {
             // debug: value.clone(),
             element_id: value.element_id.clone(),
             parent: SceneEntityId::from_proto_u32(value.parent as u32),
@@ -192,7 +234,12 @@ impl From<PbUiTransform> for UiTransform {
                 YgWrap::YgwWrap => FlexWrap::Wrap,
                 YgWrap::YgwWrapReverse => FlexWrap::WrapReverse,
             },
-            shrink: value.flex_shrink.unwrap_or(1.0),
+            shrink: value
+                .flex_shrink
+                .and_then(|shrink| {
+                    sanitize(shrink, 0.0, MAX_UI_FLEX, "flex_shrink", &mut corrected)
+                })
+                .unwrap_or(1.0),
             position_type: match value.position_type() {
                 YgPositionType::YgptRelative => PositionType::Relative,
                 YgPositionType::YgptAbsolute => PositionType::Absolute,
@@ -221,12 +268,13 @@ impl From<PbUiTransform> for UiTransform {
                 YgJustify::YgjSpaceAround => JustifyContent::SpaceAround,
                 YgJustify::YgjSpaceEvenly => JustifyContent::SpaceEvenly,
             },
-            overflow: match value.overflow() {
-                YgOverflow::YgoVisible => Overflow::DEFAULT,
-                YgOverflow::YgoHidden => Overflow::clip(),
-                YgOverflow::YgoScroll => Overflow::clip(),
+            // the sdk currently sends a single overflow value which applies to both axes
+            overflow: Overflow {
+                x: overflow_axis(value.overflow()),
+                y: overflow_axis(value.overflow()),
             },
-            scroll: value.overflow() == YgOverflow::YgoScroll,
+            scroll_x: value.overflow() == YgOverflow::YgoScroll,
+            scroll_y: value.overflow() == YgOverflow::YgoScroll,
             scroll_position: value.scroll_position.clone(),
             scroll_h_visible: [
                 components::ShowScrollBar::SsbBoth,
@@ -242,16 +290,39 @@ impl From<PbUiTransform> for UiTransform {
                 YgDisplay::YgdFlex => Display::Flex,
                 YgDisplay::YgdNone => Display::None,
             },
-            basis: val!(value, flex_basis_unit, flex_basis, Val::Auto),
-            grow: value.flex_grow,
-            size: maybe_size!(value, width_unit, width, height_unit, height, Val::Auto),
+            basis: val!(
+                value,
+                flex_basis_unit,
+                flex_basis,
+                Val::Auto,
+                false,
+                corrected
+            ),
+            grow: sanitize(
+                value.flex_grow,
+                0.0,
+                MAX_UI_FLEX,
+                "flex_grow",
+                &mut corrected,
+            )
+            .unwrap_or(0.0),
+            size: maybe_size!(
+                value,
+                width_unit,
+                width,
+                height_unit,
+                height,
+                Val::Auto,
+                corrected
+            ),
             min_size: size!(
                 value,
                 min_width_unit,
                 min_width,
                 min_height_unit,
                 min_height,
-                Val::Auto
+                Val::Auto,
+                corrected
             ),
             max_size: size!(
                 value,
@@ -259,7 +330,8 @@ impl From<PbUiTransform> for UiTransform {
                 max_width,
                 max_height_unit,
                 max_height,
-                Val::Auto
+                Val::Auto,
+                corrected
             ),
             position: rect!(
                 value,
@@ -271,7 +343,9 @@ impl From<PbUiTransform> for UiTransform {
                 position_top,
                 position_bottom_unit,
                 position_bottom,
-                Val::Auto
+                Val::Auto,
+                true,
+                corrected
             ),
             margin: rect!(
                 value,
@@ -283,7 +357,9 @@ impl From<PbUiTransform> for UiTransform {
                 margin_top,
                 margin_bottom_unit,
                 margin_bottom,
-                Val::Px(0.0)
+                Val::Px(0.0),
+                true,
+                corrected
             ),
             padding: rect!(
                 value,
@@ -295,10 +371,78 @@ impl From<PbUiTransform> for UiTransform {
                 padding_top,
                 padding_bottom_unit,
                 padding_bottom,
-                Val::Px(0.0)
+                Val::Px(0.0),
+                false,
+                corrected
             ),
-            opacity: value.opacity.unwrap_or(1.0),
-            zindex: value.z_index.map(|z| z as i16),
+            opacity: value
+                .opacity
+                .and_then(|opacity| sanitize(opacity, 0.0, 1.0, "opacity", &mut corrected))
+                .unwrap_or(1.0),
+            zindex: value
+                .z_index
+                .map(|z| z.clamp(i16::MIN as i32, i16::MAX as i32) as i16),
+        };
+
+        if !corrected.is_empty() {
+            warn!("ui transform had invalid values for {corrected:?}, corrected");
+        }
+
+        transform
+    }
+}
+
+fn overflow_axis(overflow: YgOverflow) -> OverflowAxis {
+    match overflow {
+        YgOverflow::YgoVisible => OverflowAxis::Visible,
+        YgOverflow::YgoHidden | YgOverflow::YgoScroll => OverflowAxis::Clip,
+    }
+}
+
+impl UiTransform {
+    fn scroll(&self) -> bool {
+        self.scroll_x || self.scroll_y
+    }
+
+    fn scroll_direction(&self, start: Vec2) -> ScrollDirection {
+        let (h, v) = (
+            StartPosition::Explicit(start.x),
+            StartPosition::Explicit(start.y),
+        );
+        match (self.scroll_x, self.scroll_y) {
+            (true, false) => ScrollDirection::Horizontal(h),
+            (false, true) => ScrollDirection::Vertical(v),
+            _ => ScrollDirection::Both(h, v),
+        }
+    }
+
+    fn style(&self) -> Style {
+        Style {
+            align_content: self.align_content,
+            align_items: self.align_items,
+            flex_wrap: self.wrap,
+            position_type: self.position_type,
+            flex_shrink: self.shrink,
+            align_self: self.align_self,
+            flex_direction: self.flex_direction,
+            justify_content: self.justify_content,
+            overflow: self.overflow,
+            display: self.display,
+            flex_basis: self.basis,
+            flex_grow: self.grow,
+            width: self.size.width.unwrap_or_default(),
+            height: self.size.height.unwrap_or_default(),
+            min_width: self.min_size.width,
+            min_height: self.min_size.height,
+            max_width: self.max_size.width,
+            max_height: self.max_size.height,
+            left: self.position.left,
+            right: self.position.right,
+            top: self.position.top,
+            bottom: self.position.bottom,
+            margin: self.margin,
+            padding: self.padding,
+            ..Default::default()
         }
     }
 }
@@ -363,9 +507,56 @@ impl Plugin for SceneUiPlugin {
 
 #[derive(Component, Default)]
 pub struct SceneUiData {
+    // live ui nodes
     nodes: BTreeSet<Entity>,
+    // nodes removed since the last layout
+    removed_nodes: BTreeSet<Entity>,
+    // nodes turned away by the node limit, admitted when slots are freed
+    dropped_nodes: BTreeSet<Entity>,
     relayout: bool,
     super_user: bool,
+    // the scene tried to create more ui nodes than allowed
+    ui_limit_exceeded: bool,
+}
+
+impl SceneUiData {
+    // track a ui node. returns false if the scene already has `limit` live nodes (0 for
+    // unlimited), in which case the node is held back until a slot is free
+    fn add_node(&mut self, node: Entity, limit: usize) -> bool {
+        self.removed_nodes.remove(&node);
+        if limit != 0 && self.nodes.len() >= limit && !self.nodes.contains(&node) {
+            self.ui_limit_exceeded = true;
+            self.dropped_nodes.insert(node);
+            return false;
+        }
+        self.dropped_nodes.remove(&node);
+        self.nodes.insert(node);
+        true
+    }
+
+    fn remove_node(&mut self, node: Entity) {
+        self.dropped_nodes.remove(&node);
+        if self.nodes.remove(&node) {
+            self.removed_nodes.insert(node);
+        }
+    }
+
+    // admit held back nodes into free slots. returns true if any were admitted
+    fn readmit_dropped_nodes(&mut self, limit: usize) -> bool {
+        let mut admitted = false;
+        while limit == 0 || self.nodes.len() < limit {
+            let Some(node) = self.dropped_nodes.pop_first() else {
+                break;
+            };
+            self.nodes.insert(node);
+            admitted = true;
+        }
+        admitted
+    }
+
+    pub fn ui_limit_exceeded(&self) -> bool {
+        self.ui_limit_exceeded
+    }
 }
 
 #[derive(Component)]
@@ -393,16 +584,44 @@ fn init_scene_ui_root(
 #[allow(clippy::type_complexity)]
 fn update_scene_ui_components(
     changed_entities: Query<(Entity, &SceneEntity), Changed<UiTransform>>,
+    live_entities: Query<(), With<UiTransform>>,
+    mut removed_transforms: RemovedComponents<UiTransform>,
     mut ui_roots: Query<&mut SceneUiData>,
+    config: Res<AppConfig>,
 ) {
+    let limit = config.performance.max_scene_ui_nodes;
+
+    // release removed nodes first, so their slots are available to held back nodes
+    let removed = removed_transforms
+        .read()
+        .filter(|ent| !live_entities.contains(*ent))
+        .collect::<Vec<_>>();
+    if !removed.is_empty() || config.is_changed() {
+        for mut ui_data in ui_roots.iter_mut() {
+            for ent in removed.iter() {
+                ui_data.remove_node(*ent);
+            }
+            if ui_data.readmit_dropped_nodes(limit) {
+                ui_data.relayout = true;
+            }
+        }
+    }
+
     for (ent, scene_id) in changed_entities.iter() {
         let Ok(mut ui_data) = ui_roots.get_mut(scene_id.root) else {
             warn!("scene root missing for {:?}", scene_id.root);
             continue;
         };
 
-        ui_data.nodes.insert(ent);
-        ui_data.relayout = true;
+        let already_exceeded = ui_data.ui_limit_exceeded;
+        if ui_data.add_node(ent, limit) {
+            ui_data.relayout = true;
+        } else if !already_exceeded {
+            warn!(
+                "scene {:?} exceeded the limit of {limit} ui nodes, further nodes will be hidden",
+                scene_id.root
+            );
+        }
     }
 }
 
@@ -422,6 +641,13 @@ pub struct UiLink {
     scroll_position: Option<ScrollPositionValue>,
 }
 
+impl UiLink {
+    // the bevy ui node for this scene entity
+    pub fn ui_entity(&self) -> Entity {
+        self.ui_entity
+    }
+}
+
 impl Default for UiLink {
     fn default() -> Self {
         Self {
@@ -435,12 +661,46 @@ impl Default for UiLink {
     }
 }
 
+// last scroll position of a scrollable node, kept on the scene entity so it is restored when
+// the ui is rebuilt (e.g. on leaving and re-entering the scene)
+#[derive(Component, Clone, Copy)]
+pub struct PersistentScrollPosition(pub Vec2);
+
 #[derive(Component)]
 pub struct SceneUiRoot {
     scene: Entity,
     canvas: Entity,
 }
 
+// style for the root of a scene's window ui. this rect is the coordinate space for nodes
+// with a `parent` of ROOT: it is the full window (or the constrained area inside the system
+// ui), origin at the top left and y increasing downwards. absolute nodes anchor to its
+// edges via left/right/top/bottom, and percentages resolve against its size, so e.g.
+// `right: 0, bottom: 0` pins to the bottom right corner and `left: 50%` is the centre line
+fn window_root_style(constrain: bool) -> Style {
+    if constrain {
+        Style {
+            position_type: PositionType::Absolute,
+            left: Val::VMin(27.0),
+            right: Val::VMin(12.0),
+            top: Val::VMin(6.0),
+            bottom: Val::VMin(6.0),
+            overflow: Overflow::clip(),
+            ..Default::default()
+        }
+    } else {
+        // explicit insets so the root covers the window regardless of its static position
+        Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            top: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..Default::default()
+        }
+    }
+}
+
 fn create_ui_roots(
     mut commands: Commands,
     mut scene_uis: Query<(Entity, Option<&UiLink>, &SceneUiData)>,
@@ -456,6 +716,7 @@ fn create_ui_roots(
         Option<&mut UiTextureOutput>,
     )>,
     images: ResMut<Assets<Image>>,
+    color_space: Res<ColorSpace>,
 ) {
     let images = images.into_inner();
 
@@ -478,25 +739,7 @@ fn create_ui_roots(
     // spawn window root ui nodes
     for (ent, maybe_link, ui_data) in scene_uis.iter_mut() {
         if current_scenes.contains(&ent) && (maybe_link.is_none() || config.is_changed()) {
-            let root_style = if config.constrain_scene_ui {
-                Style {
-                    position_type: PositionType::Absolute,
-                    left: Val::VMin(27.0),
-                    right: Val::VMin(12.0),
-                    top: Val::VMin(6.0),
-                    bottom: Val::VMin(6.0),
-                    overflow: Overflow::clip(),
-                    ..Default::default()
-                }
-            } else {
-                Style {
-                    position_type: PositionType::Absolute,
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
-                    ..Default::default()
-                }
-            };
-
+            let root_style = window_root_style(config.constrain_scene_ui);
             let z_index = ZIndex::Global(if ui_data.super_user { 1 << 17 } else { 0 });
 
             let window_root = commands
@@ -595,7 +838,9 @@ fn create_ui_roots(
             }
 
             // and background
-            let color = canvas_info.color.map(Into::into).unwrap_or(Color::NONE);
+            let color = canvas_info
+                .color
+                .map_or(Color::NONE, |color| color_space.from_sdk(color.into()));
             commands
                 .entity(ui_entity)
                 .modify_component(move |c: &mut Camera| {
@@ -605,6 +850,62 @@ fn create_ui_roots(
     }
 }
 
+// a ui node waiting to be placed: (scene id, (entity, transform, transform changed, root))
+type PendingUiNode = (SceneEntityId, (Entity, UiTransform, bool, Entity));
+
+// called when no more nodes can be placed. releases nodes waiting on a parent or right_of
+// that will never be placed (deleted, or part of a cycle), attaching them to the root
+// instead so they don't silently vanish. children of nodes held back by the node limit
+// are held back with them, and added to `dropped`
+fn release_orphaned_nodes(
+    blocked_elements: &mut HashMap<SceneEntityId, Vec<PendingUiNode>>,
+    dropped: &mut HashSet<SceneEntityId>,
+) -> Vec<PendingUiNode> {
+    let pending = blocked_elements
+        .values()
+        .flatten()
+        .map(|(scene_id, _)| *scene_id)
+        .collect::<HashSet<_>>();
+    let mut missing = blocked_elements
+        .keys()
+        .filter(|id| !pending.contains(*id))
+        .copied()
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        // only cycles remain, break at the lowest id
+        missing.extend(blocked_elements.keys().min().copied());
+    }
+
+    let mut released = Vec::default();
+    for missing_id in missing {
+        for (scene_id, (bevy_entity, mut ui_transform, transform_is_changed, root_node)) in
+            blocked_elements.remove(&missing_id).unwrap_or_default()
+        {
+            let is_dropped = dropped.contains(&missing_id);
+            if ui_transform.parent == missing_id {
+                if is_dropped {
+                    dropped.insert(scene_id);
+                    continue;
+                }
+                warn!("ui node {scene_id} has unplaceable parent {missing_id}, attaching to root");
+                ui_transform.parent = SceneEntityId::ROOT;
+            }
+            if ui_transform.right_of == missing_id {
+                if !is_dropped {
+                    warn!("ui node {scene_id} has unplaceable right_of {missing_id}, ignoring");
+                }
+                ui_transform.right_of = SceneEntityId::ROOT;
+            }
+            released.push((
+                scene_id,
+                (bevy_entity, ui_transform, transform_is_changed, root_node),
+            ));
+        }
+    }
+    released.sort_by_key(|(scene_id, _)| *scene_id);
+    released
+}
+
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn layout_scene_ui(
     mut commands: Commands,
@@ -613,9 +914,9 @@ fn layout_scene_ui(
     containing_scene: ContainingScene,
     ui_nodes: Query<(&SceneEntity, Ref<UiTransform>, &Parent)>,
     config: Res<AppConfig>,
-    mut removed_transforms: RemovedComponents<UiTransform>,
     ui_links: Query<&UiLink>,
     dui: Res<DuiRegistry>,
+    scroll_positions: Query<&PersistentScrollPosition>,
 ) {
     let current_scenes = player
         .get_single()
@@ -623,15 +924,13 @@ fn layout_scene_ui(
         .map(|p| containing_scene.get(p))
         .unwrap_or_default();
 
-    let removed_transforms = removed_transforms.read().collect::<Vec<_>>();
-
     for (scene_root, mut ui_data) in scene_uis.iter_mut() {
         if !current_scenes.contains(&scene_root) {
             ui_data.relayout = true;
             continue;
         }
 
-        let any_removed = removed_transforms.iter().any(|r| ui_data.nodes.contains(r));
+        let any_removed = !ui_data.removed_nodes.is_empty();
         if !(ui_data.relayout || config.is_changed() || any_removed) {
             continue;
         }
@@ -644,9 +943,12 @@ fn layout_scene_ui(
             config.is_changed()
         );
         ui_data.relayout = false;
+        let _span = debug_span!("scene_ui::layout", scene = ?scene_root).entered();
 
         // collect ui data
-        let mut deleted_nodes = HashSet::default();
+        let mut deleted_nodes: HashSet<_> = std::mem::take(&mut ui_data.removed_nodes)
+            .into_iter()
+            .collect();
         let mut unprocessed_uis = ui_data
             .nodes
             .iter()
@@ -677,14 +979,28 @@ fn layout_scene_ui(
         let mut named_nodes = HashMap::new();
         let mut pending_scroll_events = HashMap::new();
 
-        let mut blocked_elements: HashMap<
-            SceneEntityId,
-            Vec<(SceneEntityId, (Entity, UiTransform, bool, Entity))>,
-        > = HashMap::default();
+        let mut blocked_elements: HashMap<SceneEntityId, Vec<PendingUiNode>> = HashMap::default();
+        let mut dropped_ids = ui_data
+            .dropped_nodes
+            .iter()
+            .filter_map(|node| ui_nodes.get(*node).ok())
+            .map(|(scene_entity, ..)| scene_entity.id)
+            .collect::<HashSet<_>>();
+
+        loop {
+            let Some((scene_id, (bevy_entity, ui_transform, transform_is_changed, root_node))) =
+                unprocessed_uis.pop_front()
+            else {
+                if blocked_elements.is_empty() {
+                    break;
+                }
+                unprocessed_uis.extend(release_orphaned_nodes(
+                    &mut blocked_elements,
+                    &mut dropped_ids,
+                ));
+                continue;
+            };
 
-        while let Some((scene_id, (bevy_entity, ui_transform, transform_is_changed, root_node))) =
-            unprocessed_uis.pop_front()
-        {
             let Ok(bevy_ui_root) = ui_links.get(root_node).cloned() else {
                 warn!("no root for {:?}", root_node);
                 continue;
@@ -726,7 +1042,7 @@ fn layout_scene_ui(
             let existing_link = if let Ok(link) = ui_links.get(bevy_entity) {
                 if commands.get_entity(link.ui_entity).is_none() {
                     None
-                } else if link.scroll_entity.is_some() == ui_transform.scroll {
+                } else if link.scroll_entity.is_some() == ui_transform.scroll() {
                     debug!("{scene_id} reuse linked {:?}", link.ui_entity);
                     Some(link)
                 } else {
@@ -764,7 +1080,10 @@ fn layout_scene_ui(
                 let ui_entity = ent_cmds.id();
                 debug!("{scene_id} create linked {:?}", ui_entity);
 
-                let (scroll_entity, content_entity) = if ui_transform.scroll {
+                let (scroll_entity, content_entity) = if ui_transform.scroll() {
+                    let start = scroll_positions
+                        .get(bevy_entity)
+                        .map_or(Vec2::ZERO, |position| position.0);
                     ent_cmds.try_insert(FocusPolicy::Block);
                     let content = ent_cmds.commands().spawn(NodeBundle::default()).id();
                     let scrollable = ent_cmds
@@ -775,10 +1094,7 @@ fn layout_scene_ui(
                                 .with_prop(
                                     "scroll-settings",
                                     Scrollable::new()
-                                        .with_direction(ScrollDirection::Both(
-                                            StartPosition::Explicit(0.0),
-                                            StartPosition::Explicit(0.0),
-                                        ))
+                                        .with_direction(ui_transform.scroll_direction(start))
                                         .with_drag(true)
                                         .with_wheel(true)
                                         .with_bars_visible(
@@ -796,13 +1112,17 @@ fn layout_scene_ui(
                     .entity(scrollable)
                     .set_parent(ui_entity)
                     .try_insert(On::<DataChanged>::new(
-                        move |caller: Res<UiCaller>,
+                        move |mut commands: Commands,
+                            caller: Res<UiCaller>,
                             position: Query<&ScrollPosition>,
                             mut context: Query<&mut RendererSceneContext>| {
                             let Ok(pos) = position.get(caller.0) else {
                                 warn!("failed to get scroll pos on scrollable update");
                                 return;
                             };
+                            if let Some(mut commands) = commands.get_entity(bevy_entity) {
+                                commands.try_insert(PersistentScrollPosition(Vec2::new(pos.h, pos.v)));
+                            }
                             let Ok(mut context) = context.get_mut(scene_root) else {
                                 warn!("failed to get context on scrollable update");
                                 return;
@@ -830,7 +1150,12 @@ fn layout_scene_ui(
                     content_entity,
                     scroll_entity,
                     opacity: FloatOrd(parent_link.opacity.0 * ui_transform.opacity),
-                    scroll_position: None,
+                    // keep the scene's last scroll request so a rebuild doesn't replay it
+                    scroll_position: ui_links
+                        .get(bevy_entity)
+                        .ok()
+                        .filter(|link| link.scroll_entity.is_some())
+                        .and_then(|link| link.scroll_position.clone()),
                 };
                 commands.entity(bevy_entity).try_insert(new_link.clone());
                 valid_nodes.insert(scene_id, new_link);
@@ -841,33 +1166,7 @@ fn layout_scene_ui(
 
             // update style
             if !existing || transform_is_changed {
-                let style = Style {
-                    align_content: ui_transform.align_content,
-                    align_items: ui_transform.align_items,
-                    flex_wrap: ui_transform.wrap,
-                    position_type: ui_transform.position_type,
-                    flex_shrink: ui_transform.shrink,
-                    align_self: ui_transform.align_self,
-                    flex_direction: ui_transform.flex_direction,
-                    justify_content: ui_transform.justify_content,
-                    overflow: ui_transform.overflow,
-                    display: ui_transform.display,
-                    flex_basis: ui_transform.basis,
-                    flex_grow: ui_transform.grow,
-                    width: ui_transform.size.width.unwrap_or_default(),
-                    height: ui_transform.size.height.unwrap_or_default(),
-                    min_width: ui_transform.min_size.width,
-                    min_height: ui_transform.min_size.height,
-                    max_width: ui_transform.max_size.width,
-                    max_height: ui_transform.max_size.height,
-                    left: ui_transform.position.left,
-                    right: ui_transform.position.right,
-                    top: ui_transform.position.top,
-                    bottom: ui_transform.position.bottom,
-                    margin: ui_transform.margin,
-                    padding: ui_transform.padding,
-                    ..Default::default()
-                };
+                let style = ui_transform.style();
 
                 debug!("{scene_id} set style {ui_transform:?} -> {style:?}");
 
@@ -944,12 +1243,7 @@ fn layout_scene_ui(
             }
         }
 
-        debug!(
-            "made ui; placed: {}, unplaced: {} ({:?})",
-            valid_nodes.len(),
-            blocked_elements.len(),
-            blocked_elements
-        );
+        debug!("made ui; placed: {}", valid_nodes.len());
         ui_data.relayout = false;
 
         // remove any dead nodes
@@ -1049,3 +1343,506 @@ fn update_children_target_camera(
         );
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn valid(val: Val) -> bool {
+        match val {
+            Val::Px(v) => v.is_finite() && v.abs() <= MAX_UI_PX,
+            Val::Percent(v) => v.is_finite() && v.abs() <= MAX_UI_PERCENT,
+            Val::Auto => true,
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_ui_transform_sanitizes_values() {
+        let point = YgUnit::YguPoint as i32;
+        let percent = YgUnit::YguPercent as i32;
+        let transform = UiTransform::from(PbUiTransform {
+            width_unit: point,
+            width: f32::NAN,
+            height_unit: point,
+            height: -50.0,
+            min_width_unit: percent,
+            min_width: f32::INFINITY,
+            max_width_unit: percent,
+            max_width: 1e30,
+            margin_left_unit: point,
+            margin_left: -20.0,
+            position_top_unit: point,
+            position_top: f32::NEG_INFINITY,
+            padding_right_unit: point,
+            padding_right: -5.0,
+            flex_basis_unit: point,
+            flex_basis: f32::NAN,
+            flex_grow: -1.0,
+            flex_shrink: Some(f32::NAN),
+            opacity: Some(7.0),
+            z_index: Some(1 << 20),
+            ..Default::default()
+        });
+        let style = transform.style();
+
+        // nan and infinities fall back to defaults
+        assert_eq!(style.width, Val::Auto);
+        assert_eq!(style.min_width, Val::Auto);
+        assert_eq!(style.top, Val::Auto);
+        assert_eq!(style.flex_basis, Val::Auto);
+        assert_eq!(style.flex_shrink, 1.0);
+        // negative sizes clamp to zero, negative margins are kept
+        assert_eq!(style.height, Val::Px(0.0));
+        assert_eq!(style.padding.right, Val::Px(0.0));
+        assert_eq!(style.margin.left, Val::Px(-20.0));
+        assert_eq!(style.flex_grow, 0.0);
+        // extreme values are capped
+        assert_eq!(style.max_width, Val::Percent(MAX_UI_PERCENT));
+        assert_eq!(transform.opacity, 1.0);
+        assert_eq!(transform.zindex, Some(i16::MAX));
+
+        for val in [
+            style.width,
+            style.height,
+            style.min_width,
+            style.min_height,
+            style.max_width,
+            style.max_height,
+            style.flex_basis,
+            style.left,
+            style.right,
+            style.top,
+            style.bottom,
+            style.margin.left,
+            style.margin.right,
+            style.margin.top,
+            style.margin.bottom,
+            style.padding.left,
+            style.padding.right,
+            style.padding.top,
+            style.padding.bottom,
+        ] {
+            assert!(valid(val), "{val:?}");
+        }
+        assert!(style.flex_grow.is_finite() && style.flex_shrink.is_finite());
+    }
+
+    #[test]
+    fn test_ui_transform_keeps_valid_values() {
+        let transform = UiTransform::from(PbUiTransform {
+            width_unit: YgUnit::YguPoint as i32,
+            width: 250.0,
+            height_unit: YgUnit::YguPercent as i32,
+            height: 50.0,
+            position_left_unit: YgUnit::YguPoint as i32,
+            position_left: -30.0,
+            flex_grow: 2.0,
+            opacity: Some(0.5),
+            ..Default::default()
+        });
+        let style = transform.style();
+
+        assert_eq!(style.width, Val::Px(250.0));
+        assert_eq!(style.height, Val::Percent(50.0));
+        assert_eq!(style.left, Val::Px(-30.0));
+        assert_eq!(style.flex_grow, 2.0);
+        assert_eq!(transform.opacity, 0.5);
+    }
+
+    #[test]
+    fn test_ui_transform_overflow_axes() {
+        let mut transform = UiTransform::from(PbUiTransform {
+            overflow: YgOverflow::YgoScroll as i32,
+            ..Default::default()
+        });
+        assert_eq!(transform.style().overflow, Overflow::clip());
+        assert!(transform.scroll());
+        assert!(matches!(
+            transform.scroll_direction(),
+            ScrollDirection::Both(..)
+        ));
+
+        // mixed axes map independently
+        transform.overflow = Overflow {
+            x: overflow_axis(YgOverflow::YgoVisible),
+            y: overflow_axis(YgOverflow::YgoScroll),
+        };
+        transform.scroll_x = false;
+        assert_eq!(transform.style().overflow, Overflow::clip_y());
+        assert!(matches!(
+            transform.scroll_direction(),
+            ScrollDirection::Vertical(..)
+        ));
+
+        transform.overflow.x = overflow_axis(YgOverflow::YgoHidden);
+        transform.overflow.y = overflow_axis(YgOverflow::YgoVisible);
+        assert_eq!(transform.style().overflow, Overflow::clip_x());
+
+        let visible = UiTransform::from(PbUiTransform::default());
+        assert_eq!(visible.style().overflow, Overflow::visible());
+        assert!(!visible.scroll());
+    }
+
+    #[test]
+    fn test_scene_ui_node_limit() {
+        let mut ui_data = SceneUiData::default();
+        for i in 0..100_000 {
+            ui_data.add_node(Entity::from_raw(i), 10);
+        }
+        assert_eq!(ui_data.nodes.len(), 10);
+        assert!(ui_data.ui_limit_exceeded());
+
+        // existing nodes are still accepted
+        assert!(ui_data.add_node(Entity::from_raw(0), 10));
+
+        // removing a node frees a slot
+        ui_data.remove_node(Entity::from_raw(0));
+        assert!(ui_data.removed_nodes.contains(&Entity::from_raw(0)));
+        assert!(ui_data.add_node(Entity::from_raw(50_000), 10));
+        assert!(!ui_data.add_node(Entity::from_raw(50_001), 10));
+
+        // held back nodes are admitted in order as slots are freed, unless removed meanwhile
+        ui_data.remove_node(Entity::from_raw(10));
+        ui_data.remove_node(Entity::from_raw(1));
+        ui_data.remove_node(Entity::from_raw(2));
+        assert!(ui_data.readmit_dropped_nodes(10));
+        assert!(ui_data.nodes.contains(&Entity::from_raw(11)));
+        assert!(ui_data.nodes.contains(&Entity::from_raw(12)));
+        assert!(!ui_data.nodes.contains(&Entity::from_raw(10)));
+        assert!(!ui_data.readmit_dropped_nodes(10));
+        assert_eq!(ui_data.nodes.len(), 10);
+
+        // no limit
+        let mut ui_data = SceneUiData::default();
+        for i in 0..1000 {
+            assert!(ui_data.add_node(Entity::from_raw(i), 0));
+        }
+        assert!(!ui_data.ui_limit_exceeded());
+    }
+
+    #[test]
+    fn test_release_orphaned_ui_nodes() {
+        let id = |n| SceneEntityId::new(n, 0);
+        let pending = |scene_id, parent, right_of| -> PendingUiNode {
+            let mut transform = UiTransform::from(PbUiTransform::default());
+            transform.parent = parent;
+            transform.right_of = right_of;
+            (
+                scene_id,
+                (Entity::PLACEHOLDER, transform, false, Entity::PLACEHOLDER),
+            )
+        };
+
+        // 5 is right of deleted 3, 6 is right of 5 so waits for it,
+        // 7 and 8 are children of each other
+        let mut blocked = HashMap::<SceneEntityId, Vec<PendingUiNode>>::default();
+        blocked.insert(id(3), vec![pending(id(5), SceneEntityId::ROOT, id(3))]);
+        blocked.insert(id(5), vec![pending(id(6), SceneEntityId::ROOT, id(5))]);
+        blocked.insert(id(7), vec![pending(id(8), id(7), SceneEntityId::ROOT)]);
+        blocked.insert(id(8), vec![pending(id(7), id(8), SceneEntityId::ROOT)]);
+
+        // only the node with a missing reference is released, under the root
+        let mut dropped = HashSet::default();
+        let released = release_orphaned_nodes(&mut blocked, &mut dropped);
+        assert_eq!(released.len(), 1);
+        let (scene_id, (_, transform, ..)) = &released[0];
+        assert_eq!(*scene_id, id(5));
+        assert_eq!(transform.parent, SceneEntityId::ROOT);
+        assert_eq!(transform.right_of, SceneEntityId::ROOT);
+
+        // once 5 is placed 6 is unblocked
+        assert!(blocked.remove(&id(5)).is_some());
+
+        // the cycle is broken at the lowest id
+        let released = release_orphaned_nodes(&mut blocked, &mut dropped);
+        assert_eq!(released.len(), 1);
+        let (scene_id, (_, transform, ..)) = &released[0];
+        assert_eq!(*scene_id, id(8));
+        assert_eq!(transform.parent, SceneEntityId::ROOT);
+        assert_eq!(blocked.len(), 1);
+
+        // the subtree of a node held back by the limit is held back too, while nodes only
+        // right of it are placed
+        let mut blocked = HashMap::<SceneEntityId, Vec<PendingUiNode>>::default();
+        blocked.insert(
+            id(3),
+            vec![
+                pending(id(4), id(3), SceneEntityId::ROOT),
+                pending(id(6), SceneEntityId::ROOT, id(3)),
+            ],
+        );
+        blocked.insert(id(4), vec![pending(id(5), id(4), SceneEntityId::ROOT)]);
+        let mut dropped = HashSet::from_iter([id(3)]);
+        let released = release_orphaned_nodes(&mut blocked, &mut dropped);
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].0, id(6));
+        assert!(dropped.contains(&id(4)));
+        assert!(release_orphaned_nodes(&mut blocked, &mut dropped).is_empty());
+        assert!(blocked.is_empty());
+        assert!(dropped.contains(&id(5)));
+    }
+
+    // lay out nodes under a window root in an 800x600 window, returning their rects
+    fn layout_under_window_root(styles: Vec<Style>) -> Vec<Rect> {
+        layout_in_window(|world, root| {
+            styles
+                .into_iter()
+                .map(|style| spawn_node(world, root, style))
+                .collect()
+        })
+    }
+
+    fn spawn_node(world: &mut World, parent: Entity, style: Style) -> Entity {
+        world
+            .spawn(NodeBundle {
+                style,
+                ..Default::default()
+            })
+            .set_parent(parent)
+            .id()
+    }
+
+    // lay out the nodes `spawn` adds under a window root in an 800x600 window, returning the
+    // rects of the nodes it returns, clipped by their ancestors. this is the region bevy hit
+    // tests against, and the region in which their content is visible
+    fn layout_in_window(spawn: impl FnOnce(&mut World, Entity) -> Vec<Entity>) -> Vec<Rect> {
+        use bevy::{
+            asset::AssetEvent,
+            render::camera::{camera_system, ManualTextureViews},
+            text::TextPipeline,
+            transform::systems::{propagate_transforms, sync_simple_transforms},
+            ui::{
+                ui_layout_system, update::update_clipping_system, CalculatedClip, UiScale,
+                UiSurface,
+            },
+            window::{
+                PrimaryWindow, WindowCreated, WindowResized, WindowResolution,
+                WindowScaleFactorChanged,
+            },
+        };
+
+        let mut world = World::new();
+        world.init_resource::<UiScale>();
+        world.init_resource::<UiSurface>();
+        world.init_resource::<Events<WindowScaleFactorChanged>>();
+        world.init_resource::<Events<WindowResized>>();
+        world.init_resource::<Events<WindowCreated>>();
+        world.init_resource::<Events<AssetEvent<Image>>>();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<ManualTextureViews>();
+        world.init_resource::<TextPipeline>();
+        world.spawn((
+            Window {
+                resolution: WindowResolution::new(800.0, 600.0),
+                ..Default::default()
+            },
+            PrimaryWindow,
+        ));
+        world.spawn(Camera2dBundle::default());
+
+        let root = world
+            .spawn(NodeBundle {
+                style: window_root_style(false),
+                ..Default::default()
+            })
+            .id();
+        let nodes = spawn(&mut world, root);
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                camera_system::<OrthographicProjection>,
+                ui_layout_system,
+                sync_simple_transforms,
+                propagate_transforms,
+                update_clipping_system,
+            )
+                .chain(),
+        );
+        schedule.run(&mut world);
+
+        nodes
+            .into_iter()
+            .map(|node| {
+                let size = world.get::<Node>(node).unwrap().size();
+                let center = world.get::<GlobalTransform>(node).unwrap().translation();
+                let rect = Rect::from_center_size(center.truncate(), size);
+                world
+                    .get::<CalculatedClip>(node)
+                    .map_or(rect, |clip| rect.intersect(clip.clip))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_absolute_canvas_anchors() {
+        let percent = YgUnit::YguPercent as i32;
+        let anchored =
+            |left: Option<f32>, right: Option<f32>, top: Option<f32>, bottom: Option<f32>| {
+                let unit = |v: Option<f32>| if v.is_some() { percent } else { 0 };
+                UiTransform::from(PbUiTransform {
+                    position_type: YgPositionType::YgptAbsolute as i32,
+                    width_unit: percent,
+                    width: 10.0,
+                    height_unit: percent,
+                    height: 10.0,
+                    position_left_unit: unit(left),
+                    position_left: left.unwrap_or_default(),
+                    position_right_unit: unit(right),
+                    position_right: right.unwrap_or_default(),
+                    position_top_unit: unit(top),
+                    position_top: top.unwrap_or_default(),
+                    position_bottom_unit: unit(bottom),
+                    position_bottom: bottom.unwrap_or_default(),
+                    ..Default::default()
+                })
+                .style()
+            };
+
+        let rects = layout_under_window_root(vec![
+            anchored(Some(0.0), None, Some(0.0), None),
+            anchored(None, Some(0.0), Some(0.0), None),
+            anchored(Some(0.0), None, None, Some(0.0)),
+            anchored(None, Some(0.0), None, Some(0.0)),
+            // percentages of the root size
+            anchored(Some(90.0), None, Some(90.0), None),
+            anchored(Some(45.0), None, Some(45.0), None),
+        ]);
+
+        let expected = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(720.0, 0.0),
+            Vec2::new(0.0, 540.0),
+            Vec2::new(720.0, 540.0),
+            Vec2::new(720.0, 540.0),
+            Vec2::new(360.0, 270.0),
+        ];
+        for (rect, expected) in rects.into_iter().zip(expected) {
+            assert_eq!(rect.size(), Vec2::new(80.0, 60.0));
+            assert!(
+                rect.min.distance(expected) < 0.01,
+                "expected {expected}, got {rect:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_pointer_regions_match_visible_content() {
+        use super::{
+            ui_background::{center_column_style, center_row_style, fill_style},
+            ui_text::{any_axis_specified, text_styles, UiText, VAlign},
+        };
+
+        let same = |a: Rect, b: Rect| {
+            assert!(
+                a.min.distance(b.min) < 0.01 && a.max.distance(b.max) < 0.01,
+                "expected {b:?}, got {a:?}"
+            );
+        };
+        let px = |width: f32, height: f32| Style {
+            width: Val::Px(width),
+            height: Val::Px(height),
+            overflow: Overflow::clip(),
+            ..Default::default()
+        };
+        let spacer = || Style {
+            flex_grow: 1.0,
+            ..Default::default()
+        };
+
+        // a centered background image, as built by `set_ui_background`, under a 200x100 element
+        // which takes the pointer events
+        let centered_image = |image: Style| {
+            layout_in_window(|world, root| {
+                let element = spawn_node(world, root, px(200.0, 100.0));
+                let row = spawn_node(world, element, center_row_style());
+                spawn_node(world, row, spacer());
+                let column = spawn_node(world, row, center_column_style());
+                spawn_node(world, column, spacer());
+                let image = spawn_node(world, column, image);
+                spawn_node(world, column, spacer());
+                spawn_node(world, row, spacer());
+                vec![element, image]
+            })
+        };
+
+        // a small image is drawn in the middle of the element
+        let rects = centered_image(px(50.0, 40.0));
+        let (element, image) = (rects[0], rects[1]);
+        assert_eq!(element.size(), Vec2::new(200.0, 100.0));
+        same(
+            image,
+            Rect::from_center_size(element.center(), Vec2::new(50.0, 40.0)),
+        );
+
+        // a large image is clipped to the element, so is visible exactly where it can be clicked
+        let rects = centered_image(px(400.0, 300.0));
+        same(rects[1], rects[0]);
+
+        // a stretched or nine-slice background fills the element
+        let rects = layout_in_window(|world, root| {
+            let element = spawn_node(world, root, px(200.0, 100.0));
+            let background = spawn_node(world, element, fill_style());
+            vec![element, background]
+        });
+        same(rects[1], rects[0]);
+
+        // aligned text, as built by `set_ui_text`, with a fixed size node standing in for the
+        // text. returns the element, text, and the node marked as a pointer region if any
+        let aligned_text = |transform: PbUiTransform, h_align, v_align| {
+            let ui_transform = UiTransform::from(transform);
+            let ui_text = UiText {
+                text: "text".to_owned(),
+                color: Color::WHITE,
+                h_align,
+                v_align,
+                font: Default::default(),
+                font_size: 10.0,
+                wrapping: false,
+            };
+            let (outer_style, inner_style) = text_styles(&ui_text, &ui_transform);
+            let has_region = !any_axis_specified(&ui_transform);
+            let rects = layout_in_window(|world, root| {
+                let element = spawn_node(world, root, ui_transform.style());
+                let outer = spawn_node(world, element, outer_style);
+                let inner = spawn_node(world, outer, inner_style);
+                let text = spawn_node(world, inner, px(120.0, 20.0));
+                vec![element, text, inner]
+            });
+            (rects[0], rects[1], has_region.then_some(rects[2]))
+        };
+
+        // with no size the element is empty, and the text's own node takes the pointer events
+        for (h_align, v_align) in [
+            (JustifyText::Left, VAlign::Top),
+            (JustifyText::Center, VAlign::Middle),
+            (JustifyText::Right, VAlign::Bottom),
+        ] {
+            let (element, text, region) = aligned_text(PbUiTransform::default(), h_align, v_align);
+            assert_eq!(text.size(), Vec2::new(120.0, 20.0));
+            assert!(element.is_empty());
+            same(region.unwrap(), text);
+        }
+
+        // with a size the text is aligned inside the element, which takes the pointer events
+        let point = YgUnit::YguPoint as i32;
+        let sized = PbUiTransform {
+            width_unit: point,
+            width: 200.0,
+            height_unit: point,
+            height: 100.0,
+            ..Default::default()
+        };
+        for (h_align, v_align) in [
+            (JustifyText::Left, VAlign::Top),
+            (JustifyText::Center, VAlign::Middle),
+            (JustifyText::Right, VAlign::Bottom),
+        ] {
+            let (element, text, region) = aligned_text(sized.clone(), h_align, v_align);
+            assert!(region.is_none());
+            assert_eq!(element.size(), Vec2::new(200.0, 100.0));
+            same(element.intersect(text), text);
+        }
+    }
+}
@@ -3,6 +3,7 @@ use common::util::TryPushChildrenEx;
 use dcl_component::proto_components::{
     common::{texture_union, BorderRect, TextureUnion},
     sdk::components::{self, PbUiBackground},
+    ColorSpace,
 };
 use ui_core::{
     nine_slice::Ui9Slice, stretch_uvs_image::StretchUvMaterial, ui_builder::SpawnSpacer,
@@ -89,6 +90,40 @@ impl From<PbUiBackground> for UiBackground {
 #[derive(Component)]
 pub struct UiBackgroundMarker;
 
+// background node filling the element node and clipped to it, so the visible background
+// matches the element's pointer region
+pub(super) fn fill_style() -> Style {
+    Style {
+        position_type: PositionType::Absolute,
+        top: Val::Px(0.0),
+        right: Val::Px(0.0),
+        left: Val::Px(0.0),
+        bottom: Val::Px(0.0),
+        overflow: Overflow::clip(),
+        ..Default::default()
+    }
+}
+
+// a centered background is a row and column each centering the image between spacers,
+// both clipped to the element node
+pub(super) fn center_row_style() -> Style {
+    Style {
+        justify_content: JustifyContent::Center,
+        width: Val::Percent(100.0),
+        ..fill_style()
+    }
+}
+
+pub(super) fn center_column_style() -> Style {
+    Style {
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        overflow: Overflow::clip(),
+        height: Val::Percent(100.0),
+        ..Default::default()
+    }
+}
+
 #[derive(Component)]
 pub struct RetryBackground;
 
@@ -113,6 +148,7 @@ pub fn set_ui_background(
     mut resolver: TextureResolver,
     mut stretch_uvs: ResMut<Assets<StretchUvMaterial>>,
     mut images: ResMut<Assets<Image>>,
+    color_space: Res<ColorSpace>,
     sourced: Query<(
         Entity,
         Option<&Handle<StretchUvMaterial>>,
@@ -183,7 +219,9 @@ pub fn set_ui_background(
             };
 
             if let Some(image) = image {
-                let image_color = background.color.unwrap_or(Color::WHITE);
+                let image_color = background
+                    .color
+                    .map_or(Color::WHITE, |color| color_space.from_sdk(color));
                 let image_color = image_color.with_alpha(image_color.alpha() * link.opacity.0);
 
                 let background_entity = match texture_mode {
@@ -191,15 +229,7 @@ pub fn set_ui_background(
                         .commands()
                         .spawn((
                             NodeBundle {
-                                style: Style {
-                                    position_type: PositionType::Absolute,
-                                    top: Val::Px(0.0),
-                                    right: Val::Px(0.0),
-                                    left: Val::Px(0.0),
-                                    bottom: Val::Px(0.0),
-                                    overflow: Overflow::clip(),
-                                    ..Default::default()
-                                },
+                                style: fill_style(),
                                 ..Default::default()
                             },
                             Ui9Slice {
@@ -214,15 +244,7 @@ pub fn set_ui_background(
                         .commands()
                         .spawn((
                             NodeBundle {
-                                style: Style {
-                                    position_type: PositionType::Absolute,
-                                    top: Val::Px(0.0),
-                                    right: Val::Px(0.0),
-                                    left: Val::Px(0.0),
-                                    bottom: Val::Px(0.0),
-                                    overflow: Overflow::clip(),
-                                    ..Default::default()
-                                },
+                                style: fill_style(),
                                 ..Default::default()
                             },
                             UiBackgroundMarker,
@@ -253,17 +275,7 @@ pub fn set_ui_background(
                         .commands()
                         .spawn((
                             NodeBundle {
-                                style: Style {
-                                    position_type: PositionType::Absolute,
-                                    left: Val::Px(0.0),
-                                    right: Val::Px(0.0),
-                                    top: Val::Px(0.0),
-                                    bottom: Val::Px(0.0),
-                                    justify_content: JustifyContent::Center,
-                                    overflow: Overflow::clip(),
-                                    width: Val::Percent(100.0),
-                                    ..Default::default()
-                                },
+                                style: center_row_style(),
                                 ..Default::default()
                             },
                             UiBackgroundMarker,
@@ -271,13 +283,7 @@ pub fn set_ui_background(
                         .try_with_children(|c| {
                             c.spacer();
                             c.spawn(NodeBundle {
-                                style: Style {
-                                    flex_direction: FlexDirection::Column,
-                                    justify_content: JustifyContent::Center,
-                                    overflow: Overflow::clip(),
-                                    height: Val::Percent(100.0),
-                                    ..Default::default()
-                                },
+                                style: center_column_style(),
                                 ..Default::default()
                             })
                             .try_with_children(|c| {
@@ -310,7 +316,7 @@ pub fn set_ui_background(
                 warn!("failed to load ui image from content map: {:?}", texture);
             }
         } else if let Some(color) = background.color {
-            commands.insert(BackgroundColor(color));
+            commands.insert(BackgroundColor(color_space.from_sdk(color)));
         }
     }
 
@@ -2,7 +2,10 @@ use bevy::prelude::*;
 use common::util::ModifyComponentExt;
 use dcl::interface::CrdtType;
 use dcl_component::{
-    proto_components::sdk::components::{self, PbUiDropdown, PbUiDropdownResult},
+    proto_components::{
+        sdk::components::{self, PbUiDropdown, PbUiDropdownResult},
+        ColorSpace,
+    },
     SceneComponentId,
 };
 use ui_core::{
@@ -32,6 +35,7 @@ pub fn set_ui_dropdown(
     >,
     mut removed: RemovedComponents<UiDropdown>,
     links: Query<&UiLink>,
+    color_space: Res<ColorSpace>,
 ) {
     for ent in removed.read() {
         if let Ok(link) = links.get(ent) {
@@ -83,7 +87,10 @@ pub fn set_ui_dropdown(
                 Some(TextStyle {
                     font: user_font(font_name, ui_core::WeightName::Regular),
                     font_size,
-                    color: dropdown.0.color.map(Into::into).unwrap_or(Color::BLACK),
+                    color: dropdown
+                        .0
+                        .color
+                        .map_or(Color::BLACK, |color| color_space.from_sdk(color.into())),
                 }),
             ),
             On::<DataChanged>::new(
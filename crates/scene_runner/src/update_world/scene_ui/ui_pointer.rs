@@ -7,6 +7,34 @@ use crate::{
 
 use super::UiLink;
 
+// marks a node whose visible content extends outside its scene entity's ui node, so it
+// must receive pointer events itself (e.g. text on an element with no size)
+#[derive(Component)]
+pub struct UiPointerRegion {
+    pub scene_entity: Entity,
+}
+
+fn pointer_bundle(ent: Entity, is_primary: bool) -> impl Bundle {
+    (
+        FocusPolicy::Block,
+        Interaction::default(),
+        On::<HoverEnter>::new(move |mut ui_target: ResMut<UiPointerTarget>| {
+            if is_primary {
+                *ui_target = UiPointerTarget::Primary(ent);
+            } else {
+                *ui_target = UiPointerTarget::World(ent);
+            }
+        }),
+        On::<HoverExit>::new(move |mut ui_target: ResMut<UiPointerTarget>| {
+            if *ui_target == UiPointerTarget::Primary(ent)
+                || *ui_target == UiPointerTarget::World(ent)
+            {
+                *ui_target = UiPointerTarget::None;
+            };
+        }),
+    )
+}
+
 pub fn set_ui_pointer_events(
     mut commands: Commands,
     pes: Query<
@@ -17,6 +45,9 @@ pub fn set_ui_pointer_events(
         ),
     >,
     links: Query<&UiLink>,
+    has_pes: Query<(), With<PointerEvents>>,
+    regions: Query<(Entity, &UiPointerRegion)>,
+    new_regions: Query<(Entity, &UiPointerRegion), Added<UiPointerRegion>>,
     mut removed: RemovedComponents<PointerEvents>,
 ) {
     for ent in removed.read() {
@@ -24,32 +55,39 @@ pub fn set_ui_pointer_events(
             continue;
         };
 
-        if let Some(mut commands) = commands.get_entity(link.ui_entity) {
-            commands.remove::<(On<HoverEnter>, On<HoverExit>)>();
+        let region_entities = regions
+            .iter()
+            .filter(|(_, region)| region.scene_entity == ent)
+            .map(|(region_ent, _)| region_ent);
+        for target in std::iter::once(link.ui_entity).chain(region_entities) {
+            if let Some(mut commands) = commands.get_entity(target) {
+                commands.remove::<(On<HoverEnter>, On<HoverExit>)>();
+            }
         }
     }
 
     for (ent, link) in pes.iter() {
-        if let Some(mut commands) = commands.get_entity(link.ui_entity) {
-            let is_primary = link.is_window_ui;
-            commands.try_insert((
-                FocusPolicy::Block,
-                Interaction::default(),
-                On::<HoverEnter>::new(move |mut ui_target: ResMut<UiPointerTarget>| {
-                    if is_primary {
-                        *ui_target = UiPointerTarget::Primary(ent);
-                    } else {
-                        *ui_target = UiPointerTarget::World(ent);
-                    }
-                }),
-                On::<HoverExit>::new(move |mut ui_target: ResMut<UiPointerTarget>| {
-                    if *ui_target == UiPointerTarget::Primary(ent)
-                        || *ui_target == UiPointerTarget::World(ent)
-                    {
-                        *ui_target = UiPointerTarget::None;
-                    };
-                }),
-            ));
+        let region_entities = regions
+            .iter()
+            .filter(|(_, region)| region.scene_entity == ent)
+            .map(|(region_ent, _)| region_ent);
+        for target in std::iter::once(link.ui_entity).chain(region_entities) {
+            if let Some(mut commands) = commands.get_entity(target) {
+                commands.try_insert(pointer_bundle(ent, link.is_window_ui));
+            }
+        }
+    }
+
+    // regions created after the pointer events were set
+    for (region_ent, region) in new_regions.iter() {
+        if pes.contains(region.scene_entity) || !has_pes.contains(region.scene_entity) {
+            continue;
+        }
+        let Ok(link) = links.get(region.scene_entity) else {
+            continue;
+        };
+        if let Some(mut commands) = commands.get_entity(region_ent) {
+            commands.try_insert(pointer_bundle(region.scene_entity, link.is_window_ui));
         }
     }
 }
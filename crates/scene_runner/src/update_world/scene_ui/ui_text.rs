@@ -1,10 +1,13 @@
 use bevy::prelude::*;
-use dcl_component::proto_components::sdk::components::{self, PbUiText};
+use dcl_component::proto_components::{
+    sdk::components::{self, PbUiText},
+    ColorSpace,
+};
 
 use crate::{update_world::text_shape::make_text_section, SceneEntity};
 use common::util::TryPushChildrenEx;
 
-use super::{UiLink, UiTransform};
+use super::{ui_pointer::UiPointerRegion, UiLink, UiTransform};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum VAlign {
@@ -66,16 +69,106 @@ impl From<PbUiText> for UiText {
 #[derive(Component)]
 pub struct UiTextMarker;
 
+// whether the element has a size, so its node bounds the text. with no size the node is empty
+// and the text is positioned around it
+pub(super) fn any_axis_specified(ui_transform: &UiTransform) -> bool {
+    [ui_transform.size.width, ui_transform.size.height]
+        .iter()
+        .any(Option::is_some)
+}
+
+// styles for the text element added under the scene entity's node, and for the node wrapping the
+// text within it
+pub(super) fn text_styles(ui_text: &UiText, ui_transform: &UiTransform) -> (Style, Style) {
+    // with text nodes the axis sizes are unusual.
+    // a) if either size axis is NOT NONE, (explicit or auto), we want auto to size appropriately for the content.
+    // b) if both axes are NONE, we want to size to zero.
+    // a) - we tackle this by using a nested position-type: relative node which will size it's parent appropriately, and default the parent to Auto
+    //    - for alignment we use align-items and justify-content
+    // b) - we use a nested position-type: absolute node, and default the parent to auto
+    //    - for alignment we use align-items and justify-content as above, and we also set left/right/top/bottom to 50% if required
+
+    let inner_style = if any_axis_specified(ui_transform) {
+        Style {
+            position_type: PositionType::Relative,
+            margin: UiRect::all(Val::Px(ui_text.font_size * 0.5)),
+            ..Default::default()
+        }
+    } else {
+        Style {
+            position_type: PositionType::Absolute,
+            left: if ui_text.h_align == JustifyText::Left {
+                Val::Percent(50.0)
+            } else {
+                Val::Auto
+            },
+            right: if ui_text.h_align == JustifyText::Right {
+                Val::Percent(50.0)
+            } else {
+                Val::Auto
+            },
+            top: if ui_text.v_align == VAlign::Top {
+                Val::Percent(50.0)
+            } else {
+                Val::Auto
+            },
+            bottom: if ui_text.v_align == VAlign::Bottom {
+                Val::Percent(50.0)
+            } else {
+                Val::Auto
+            },
+            ..Default::default()
+        }
+    };
+
+    // we need to set size for the first inner element depending
+    // on how the outer was specified
+    let width = match ui_transform.size.width {
+        Some(Val::Px(px)) => Val::Px(px),
+        Some(Val::Percent(_)) => Val::Percent(100.0),
+        _ => Val::Auto,
+    };
+    let height = match ui_transform.size.height {
+        Some(Val::Px(px)) => Val::Px(px),
+        Some(Val::Percent(_)) => Val::Percent(100.0),
+        _ => Val::Auto,
+    };
+
+    let outer_style = Style {
+        flex_direction: FlexDirection::Row,
+        justify_content: match ui_text.h_align {
+            JustifyText::Left => JustifyContent::FlexStart,
+            JustifyText::Center => JustifyContent::Center,
+            JustifyText::Right => JustifyContent::FlexEnd,
+            JustifyText::Justified => unreachable!(),
+        },
+        align_items: match ui_text.v_align {
+            VAlign::Top => AlignItems::FlexStart,
+            VAlign::Middle => AlignItems::Center,
+            VAlign::Bottom => AlignItems::FlexEnd,
+        },
+        width,
+        height,
+        align_self: AlignSelf::FlexStart,
+        // elements are horizontally centered by default
+        margin: UiRect::horizontal(Val::Auto),
+        ..Default::default()
+    };
+
+    (outer_style, inner_style)
+}
+
 pub fn set_ui_text(
     mut commands: Commands,
     texts: Query<
-        (&SceneEntity, &UiText, &UiTransform, &UiLink),
+        (Entity, &SceneEntity, &UiText, &UiTransform, &UiLink),
         Or<(Changed<UiText>, Changed<UiLink>)>,
     >,
     mut removed: RemovedComponents<UiText>,
     links: Query<&UiLink>,
     children: Query<&Children>,
     prev_texts: Query<&UiTextMarker>,
+    color_space: Res<ColorSpace>,
 ) {
     for ent in removed.read() {
         let Ok(link) = links.get(ent) else {
@@ -91,7 +184,7 @@ pub fn set_ui_text(
         }
     }
 
-    for (scene_ent, ui_text, ui_transform, link) in texts.iter() {
+    for (ent, scene_ent, ui_text, ui_transform, link) in texts.iter() {
         debug!("{} added text {:?}", scene_ent.id, ui_text);
 
         // remove old text
@@ -111,109 +204,39 @@ pub fn set_ui_text(
             continue;
         };
 
+        let color = color_space.from_sdk(ui_text.color);
         let (text, extras) = make_text_section(
             ui_text.text.as_str(),
             ui_text.font_size,
-            ui_text
-                .color
-                .with_alpha(ui_text.color.alpha() * link.opacity.0),
+            color.with_alpha(color.alpha() * link.opacity.0),
             ui_text.font,
             ui_text.h_align,
             ui_text.wrapping,
         );
 
-        // with text nodes the axis sizes are unusual.
-        // a) if either size axis is NOT NONE, (explicit or auto), we want auto to size appropriately for the content.
-        // b) if both axes are NONE, we want to size to zero.
-        // a) - we tackle this by using a nested position-type: relative node which will size it's parent appropriately, and default the parent to Auto
-        //    - for alignment we use align-items and justify-content
-        // b) - we use a nested position-type: absolute node, and default the parent to auto
-        //    - for alignment we use align-items and justify-content as above, and we also set left/right/top/bottom to 50% if required
-
-        let any_axis_specified = [ui_transform.size.width, ui_transform.size.height]
-            .iter()
-            .any(Option::is_some);
-
-        let inner_style = if any_axis_specified {
-            Style {
-                position_type: PositionType::Relative,
-                margin: UiRect::all(Val::Px(ui_text.font_size * 0.5)),
-                ..Default::default()
-            }
-        } else {
-            Style {
-                position_type: PositionType::Absolute,
-                left: if ui_text.h_align == JustifyText::Left {
-                    Val::Percent(50.0)
-                } else {
-                    Val::Auto
-                },
-                right: if ui_text.h_align == JustifyText::Right {
-                    Val::Percent(50.0)
-                } else {
-                    Val::Auto
-                },
-                top: if ui_text.v_align == VAlign::Top {
-                    Val::Percent(50.0)
-                } else {
-                    Val::Auto
-                },
-                bottom: if ui_text.v_align == VAlign::Bottom {
-                    Val::Percent(50.0)
-                } else {
-                    Val::Auto
-                },
-                ..Default::default()
-            }
-        };
-
-        // we need to set size for the first inner element depending
-        // on how the outer was specified
-        let width = match ui_transform.size.width {
-            Some(Val::Px(px)) => Val::Px(px),
-            Some(Val::Percent(_)) => Val::Percent(100.0),
-            _ => Val::Auto,
-        };
-        let height = match ui_transform.size.height {
-            Some(Val::Px(px)) => Val::Px(px),
-            Some(Val::Percent(_)) => Val::Percent(100.0),
-            _ => Val::Auto,
-        };
+        let any_axis_specified = any_axis_specified(ui_transform);
+        let (outer_style, inner_style) = text_styles(ui_text, ui_transform);
 
         let text_element = ent_cmds
             .commands()
             .spawn((
                 NodeBundle {
-                    style: Style {
-                        flex_direction: FlexDirection::Row,
-                        justify_content: match ui_text.h_align {
-                            JustifyText::Left => JustifyContent::FlexStart,
-                            JustifyText::Center => JustifyContent::Center,
-                            JustifyText::Right => JustifyContent::FlexEnd,
-                            JustifyText::Justified => unreachable!(),
-                        },
-                        align_items: match ui_text.v_align {
-                            VAlign::Top => AlignItems::FlexStart,
-                            VAlign::Middle => AlignItems::Center,
-                            VAlign::Bottom => AlignItems::FlexEnd,
-                        },
-                        width,
-                        height,
-                        align_self: AlignSelf::FlexStart,
-                        // elements are horizontally centered by default
-                        margin: UiRect::horizontal(Val::Auto),
-                        ..Default::default()
-                    },
+                    style: outer_style,
                     ..Default::default()
                 },
                 UiTextMarker,
             ))
             .try_with_children(|c| {
-                c.spawn(NodeBundle {
+                let mut inner = c.spawn(NodeBundle {
                     style: inner_style,
                     ..Default::default()
-                })
-                .try_with_children(|c| {
+                });
+                // with no size the element's node is empty, so the text bounds the visible
+                // content and must take pointer events itself
+                if !any_axis_specified {
+                    inner.insert(UiPointerRegion { scene_entity: ent });
+                }
+                inner.try_with_children(|c| {
                     let mut cmds = c.spawn(TextBundle {
                         text,
                         z_index: ZIndex::Local(1),
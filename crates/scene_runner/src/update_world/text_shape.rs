@@ -63,26 +63,26 @@ bevy: not implemented
 
     shadowOffsetX?: number | undefined;
 foundation: when 0, disables shadows. otherwise affects shadowBlur in a non-linear way, bigger X -> smaller blur. doesn't change shadow offset at all.
-bevy: not implemented
+bevy: when non-zero, draws a standard (unblurred) drop shadow
 
     shadowOffsetY?: number | undefined;
 does nothing
 
     outlineWidth?: number | undefined;
 foundation: changes outline thickness. units unclear. 0.15 seems to make something around half the letter size. larger than 0.25 just obscures the whole text. probably just check for non-zero and apply some standard outline in that case.
-bevy: not implemented
+bevy: when non-zero, draws a standard outline
 
     shadowColor?: Color3 | undefined;
 foundation: works
-bevy: not implemented
+bevy: works
 
     outlineColor?: Color3 | undefined;
 foundation: works
-bevy: not implemented
+bevy: works
 
     textColor?: Color4 | undefined;
 foundation: works
-bevy: not implemented
+bevy: works
 
 
 */
@@ -100,7 +100,10 @@ use common::{
 };
 use dcl::interface::ComponentPosition;
 use dcl_component::{
-    proto_components::sdk::components::{common::TextAlignMode, PbTextShape},
+    proto_components::{
+        sdk::components::{common::TextAlignMode, PbTextShape},
+        ColorSpace,
+    },
     SceneComponentId,
 };
 use ui_core::{ui_builder::SpawnSpacer, user_font, FontName, WeightName};
@@ -146,6 +149,9 @@ impl From<PbTextShape> for TextShape {
 }
 
 const PIX_PER_M: f32 = 200.0;
+// offsets of the standard shadow and outline, in text pixels
+const SHADOW_PIX: f32 = 2.0;
+const OUTLINE_PIX: f32 = 1.5;
 
 #[derive(Component)]
 pub struct PriorTextShapeUi(Entity, PbTextShape);
@@ -163,6 +169,7 @@ fn update_text_shapes(
     mut removed: RemovedComponents<TextShape>,
     scenes: Query<(&RendererSceneContext, Option<&SceneWorldUi>)>,
     frame: Res<FrameCount>,
+    color_space: Res<ColorSpace>,
 ) {
     // remove deleted ui nodes
     for e in removed.read() {
@@ -286,8 +293,7 @@ fn update_text_shapes(
             text_shape
                 .0
                 .text_color
-                .map(Into::into)
-                .unwrap_or(Color::WHITE),
+                .map_or(Color::WHITE, |color| color_space.from_sdk(color.into())),
             text_shape.0.font(),
             halign,
             wrapping,
@@ -327,6 +333,28 @@ fn update_text_shapes(
                     ..Default::default()
                 })
                 .with_children(|c| {
+                    // copies of the text for the shadow and outline, drawn behind it
+                    for (offset, effect_color) in text_effects(&text_shape.0, *color_space) {
+                        let mut text = text.clone();
+                        for section in text.sections.iter_mut() {
+                            section.style.color = effect_color
+                                .with_alpha(effect_color.alpha() * section.style.color.alpha());
+                        }
+                        c.spawn(TextBundle {
+                            text,
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                left: Val::Px(offset.x),
+                                top: Val::Px(offset.y),
+                                // same size as the text, so it wraps the same way
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        });
+                    }
+
                     let mut cmds = c.spawn(TextBundle {
                         text,
                         style: Style {
@@ -385,6 +413,32 @@ fn update_text_shapes(
     }
 }
 
+// offsets and colors of the text copies drawn for the shadow and outline, back to front.
+// the sdk's widths and offsets don't translate to distances, so standard sizes are used.
+// as in foundation, only a non-zero shadow x offset enables the shadow
+pub fn text_effects(text_shape: &PbTextShape, color_space: ColorSpace) -> Vec<(Vec2, Color)> {
+    let mut effects = Vec::default();
+
+    if text_shape.shadow_offset_x() != 0.0 {
+        let color = text_shape
+            .shadow_color
+            .map_or(Color::WHITE, |color| color_space.from_sdk(color.into()));
+        effects.push((Vec2::splat(SHADOW_PIX), color));
+    }
+
+    if text_shape.outline_width() > 0.0 {
+        let color = text_shape
+            .outline_color
+            .map_or(Color::WHITE, |color| color_space.from_sdk(color.into()));
+        for ix in 0..8 {
+            let angle = ix as f32 * std::f32::consts::FRAC_PI_4;
+            effects.push((Vec2::from_angle(angle) * OUTLINE_PIX, color));
+        }
+    }
+
+    effects
+}
+
 #[derive(Component)]
 pub struct TextExtraMarker;
 
@@ -0,0 +1,177 @@
+// keeps the memory used by scene textures within `texture_budget_mb`. textures loaded through
+// the `TextureResolver` are tracked per scene, and once the budget is exceeded newly loaded
+// textures are downscaled to fit, keeping their aspect ratio and a minimum size.
+
+use bevy::{asset::LoadState, prelude::*, render::render_resource::TextureFormat, utils::HashMap};
+use common::structs::AppConfig;
+use image::{
+    imageops::{self, FilterType},
+    ImageBuffer, Rgba,
+};
+
+// textures are not downscaled so that their smaller side is below this
+pub const MIN_TEXTURE_SIZE: u32 = 64;
+
+#[derive(Resource, Default)]
+pub struct SceneTextureMemory {
+    // requested textures that are not loaded yet, with the requesting scene's hash
+    pending: HashMap<AssetId<Image>, String>,
+    // loaded textures, with the owning scene's hash and size in bytes
+    loaded: HashMap<AssetId<Image>, (String, usize)>,
+    per_scene: HashMap<String, usize>,
+    total: usize,
+}
+
+impl SceneTextureMemory {
+    // count the texture against the scene once loaded. textures shared between scenes are
+    // counted against the first scene to request them
+    pub fn track(&mut self, id: AssetId<Image>, scene_hash: &str) {
+        if !self.loaded.contains_key(&id) {
+            self.pending
+                .entry(id)
+                .or_insert_with(|| scene_hash.to_owned());
+        }
+    }
+
+    pub fn scene_bytes(&self, scene_hash: &str) -> usize {
+        self.per_scene.get(scene_hash).copied().unwrap_or(0)
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.total
+    }
+
+    fn add(&mut self, id: AssetId<Image>, scene_hash: String, bytes: usize) {
+        *self.per_scene.entry(scene_hash.clone()).or_default() += bytes;
+        self.total += bytes;
+        self.loaded.insert(id, (scene_hash, bytes));
+    }
+
+    fn remove(&mut self, id: AssetId<Image>) {
+        self.pending.remove(&id);
+        let Some((scene_hash, bytes)) = self.loaded.remove(&id) else {
+            return;
+        };
+        self.total -= bytes;
+        if let Some(scene_bytes) = self.per_scene.get_mut(&scene_hash) {
+            *scene_bytes -= bytes;
+            if *scene_bytes == 0 {
+                self.per_scene.remove(&scene_hash);
+            }
+        }
+    }
+}
+
+// halve the image until it fits in `max_bytes` or its smaller side would drop below
+// `MIN_TEXTURE_SIZE`. only uncompressed rgba8 images without mips can be resized.
+// returns true if the image was resized
+pub fn downscale_to_fit(image: &mut Image, max_bytes: usize) -> bool {
+    let descriptor = &image.texture_descriptor;
+    if !matches!(
+        descriptor.format,
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+    ) || descriptor.mip_level_count != 1
+        || descriptor.size.depth_or_array_layers != 1
+    {
+        return false;
+    }
+
+    let size = image.size();
+    let mut target = size;
+    while target.x as usize * target.y as usize * 4 > max_bytes
+        && target.min_element() / 2 >= MIN_TEXTURE_SIZE
+    {
+        target /= 2;
+    }
+    if target == size {
+        return false;
+    }
+
+    let Some(source) =
+        ImageBuffer::<Rgba<u8>, &[u8]>::from_raw(size.x, size.y, image.data.as_slice())
+    else {
+        return false;
+    };
+    let resized = imageops::resize(&source, target.x, target.y, FilterType::Triangle);
+    image.texture_descriptor.size.width = target.x;
+    image.texture_descriptor.size.height = target.y;
+    image.data = resized.into_raw();
+    true
+}
+
+pub fn apply_texture_budget(
+    mut memory: ResMut<SceneTextureMemory>,
+    mut images: ResMut<Assets<Image>>,
+    mut events: EventReader<AssetEvent<Image>>,
+    asset_server: Res<AssetServer>,
+    config: Res<AppConfig>,
+) {
+    for ev in events.read() {
+        if let AssetEvent::Removed { id } = ev {
+            memory.remove(*id);
+        }
+    }
+
+    let budget = config.graphics.texture_budget_mb * 1024 * 1024;
+    let pending = std::mem::take(&mut memory.pending);
+    for (id, scene_hash) in pending {
+        let Some(mut bytes) = images.get(id).map(|image| image.data.len()) else {
+            if let LoadState::Loading = asset_server.load_state(id) {
+                memory.pending.insert(id, scene_hash);
+            }
+            continue;
+        };
+
+        if budget != 0 && memory.total + bytes > budget {
+            let image = images.get_mut(id).unwrap();
+            let original = image.size();
+            if downscale_to_fit(image, budget.saturating_sub(memory.total)) {
+                debug!(
+                    "[{scene_hash}] texture downscaled from {original} to {} to fit the texture budget",
+                    image.size()
+                );
+            }
+            bytes = image.data.len();
+        }
+
+        memory.add(id, scene_hash, bytes);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_downscale_keeps_aspect_and_minimum() {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: 1024,
+                height: 512,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[255, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+
+        // already fits
+        assert!(!downscale_to_fit(&mut image, 1024 * 512 * 4));
+
+        assert!(downscale_to_fit(&mut image, 600 * 1024));
+        assert_eq!(image.size(), UVec2::new(512, 256));
+        assert_eq!(image.data.len(), 512 * 256 * 4);
+        assert_eq!(image.data[..4], [255, 0, 0, 255]);
+
+        // never below the minimum size
+        assert!(downscale_to_fit(&mut image, 0));
+        assert_eq!(image.size(), UVec2::new(128, MIN_TEXTURE_SIZE));
+        assert!(!downscale_to_fit(&mut image, 0));
+    }
+}
@@ -50,6 +50,13 @@ impl Plugin for TransformAndParentPlugin {
     }
 }
 
+// non-finite scene transforms are ignored, so they can't reach the hierarchy or the camera
+pub fn is_finite_transform(transform: &Transform) -> bool {
+    transform.translation.is_finite()
+        && transform.rotation.is_finite()
+        && transform.scale.is_finite()
+}
+
 #[allow(clippy::type_complexity)]
 pub(crate) fn process_transform_and_parent_updates(
     mut commands: Commands,
@@ -61,6 +68,8 @@ pub(crate) fn process_transform_and_parent_updates(
     )>,
     primaries: PrimaryEntities,
     mut scene_entities: Query<(&mut Transform, &mut TargetParent), With<SceneEntity>>,
+    // entities that have been warned about an invalid transform
+    mut warned_transforms: Local<HashSet<Entity>>,
     // mut restricted_actions: EventWriter<RpcCall>,
 ) {
     for (root, mut scene_context, mut updates, deleted_entities) in scenes.iter_mut() {
@@ -156,7 +165,15 @@ pub(crate) fn process_transform_and_parent_updates(
                         warn!("failed to find entity for transform update?!");
                         continue;
                     };
-                    *target_transform = transform;
+                    // keep the last valid transform, but still apply the parent
+                    if is_finite_transform(&transform) {
+                        *target_transform = transform;
+                        warned_transforms.remove(&entity);
+                    } else if warned_transforms.insert(entity) {
+                        warn!(
+                            "[{root:?}] ignoring invalid transform for {scene_entity}: {transform:?}"
+                        );
+                    }
                     if new_target_parent != target_parent.0 {
                         // update the target
                         target_parent.0 = new_target_parent;
@@ -179,19 +196,17 @@ pub(crate) fn process_transform_and_parent_updates(
 
             // entities that we know connect ultimately to the root
             let mut valid_entities = HashSet::from_iter(std::iter::once(root));
-            // entities that we know are part of a cycle (or lead to a cycle)
-            let mut invalid_entities = HashSet::default();
+            // entities parented to the root to break a cycle. they stay in the unparented list to
+            // recheck at the next hierarchy update, in case the cycle has been resolved
+            let mut cycle_breaks = HashSet::default();
 
             scene.unparented_entities.retain(|entity| {
                 // entities in the current chain
                 let mut checklist = HashSet::default();
 
-                // walk until we reach a known valid/invalid entity or our starting point
+                // walk until we reach a known valid entity or loop back into the current chain
                 let mut pointer = *entity;
-                while ![&valid_entities, &invalid_entities, &checklist]
-                    .iter()
-                    .any(|set| set.contains(&pointer))
-                {
+                while !valid_entities.contains(&pointer) && !checklist.contains(&pointer) {
                     checklist.insert(pointer);
                     let parent = match parents.entry(pointer) {
                         Entry::Occupied(o) => o.into_mut(),
@@ -205,29 +220,30 @@ pub(crate) fn process_transform_and_parent_updates(
                     pointer = *parent;
                 }
 
-                if valid_entities.contains(&pointer) {
-                    debug!(
-                        "{:?}: valid, setting parent to {:?}",
-                        entity, parents[entity]
-                    );
-                    // this entity (and all checked entities) link to the root
-                    // apply parenting
-                    commands.entity(*entity).set_parent(parents[entity]);
-                    //  record validity of the chain
-                    valid_entities.extend(checklist.into_iter());
-                    // remove from the unparented list
-                    false
-                } else {
-                    debug!("{:?}: not valid, setting parent to {:?}", entity, root);
-                    // this entity (and all checked entities) end in a cycle
-                    // parent to the root
-                    commands.entity(*entity).set_parent(root);
-                    // mark as invalid
-                    invalid_entities.extend(checklist.into_iter());
-                    // keep the entity in the unparented list to recheck at the next hierarchy update
-                    true
+                if !valid_entities.contains(&pointer) {
+                    // the chain ends in a cycle. parent the entity where it loops to the root,
+                    // the rest of the cycle and the chain then link to the root through it
+                    warn!("{:?}: parent cycle, setting parent to {:?}", pointer, root);
+                    commands.entity(pointer).set_parent(root);
+                    cycle_breaks.insert(pointer);
                 }
+
+                // this entity (and all checked entities) link to the root
+                valid_entities.extend(checklist);
+                if cycle_breaks.contains(entity) {
+                    return true;
+                }
+
+                debug!(
+                    "{:?}: valid, setting parent to {:?}",
+                    entity, parents[entity]
+                );
+                // apply parenting and remove from the unparented list
+                commands.entity(*entity).set_parent(parents[entity]);
+                false
             });
+
+            scene.unparented_entities.extend(cycle_breaks);
         }
     }
 }
@@ -273,6 +289,9 @@ pub fn parent_position_sync<T: ParentPositionSyncStage>(
         };
 
         let transform = gt.reparented_to(parent_transform);
+        if !is_finite_transform(&transform) {
+            continue;
+        }
 
         commands
             .entity(ent)
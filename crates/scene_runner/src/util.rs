@@ -1,16 +1,19 @@
 use std::{
     path::PathBuf,
+    str::FromStr,
     sync::{Arc, Mutex},
 };
 
 use bevy::{
     asset::io::AssetReader,
+    ecs::system::SystemParam,
     prelude::*,
     tasks::{IoTaskPool, Task},
+    utils::HashSet,
 };
 use bevy_console::{ConsoleCommand, PrintConsoleLine};
 use clap::builder::StyledStr;
-use common::structs::PrimaryUser;
+use common::structs::{IVec2Arg, PrimaryUser};
 use comms::preview::PreviewCommand;
 use console::DoAddConsoleCommand;
 use futures_lite::AsyncReadExt;
@@ -20,9 +23,12 @@ use ipfs::{
 };
 
 use crate::{
-    initialize_scene::{LiveScenes, PortableScenes},
+    distance_to_parcels,
+    initialize_scene::{
+        LiveScenes, ManualScene, ManualScenes, PortableScenes, RefreshScenePointers, SceneLoading,
+    },
     renderer_context::RendererSceneContext,
-    ContainingScene, Toaster,
+    vec3_to_parcel, ContainingScene, Toaster,
 };
 
 pub struct SceneUtilPlugin;
@@ -33,6 +39,9 @@ impl Plugin for SceneUtilPlugin {
         app.insert_resource(ConsoleRelay { send, recv });
         app.add_console_command::<DebugDumpScene, _>(debug_dump_scene);
         app.add_console_command::<ReloadCommand, _>(reload_command);
+        app.add_console_command::<SceneCommand, _>(scene_command);
+        app.add_console_command::<LoadSceneCommand, _>(load_scene_command);
+        app.add_console_command::<UnloadSceneCommand, _>(unload_scene_command);
         app.add_systems(Update, (console_relay, handle_preview_command));
     }
 }
@@ -177,26 +186,159 @@ fn debug_dump_scene(
 #[derive(clap::Parser, ConsoleCommand)]
 #[command(name = "/reload")]
 struct ReloadCommand {
+    /// force reload of a single scene, or all scenes if omitted
     hash: Option<String>,
+    /// re-request the scene list and reload only scenes whose hash has changed
+    #[arg(long, conflicts_with = "hash")]
+    refresh: bool,
 }
 
 fn reload_command(
     mut input: ConsoleCommand<ReloadCommand>,
     mut live_scenes: ResMut<LiveScenes>,
     mut portables: ResMut<PortableScenes>,
+    mut refresh_pointers: EventWriter<RefreshScenePointers>,
 ) {
-    if let Some(Ok(ReloadCommand { hash })) = input.take() {
+    if let Some(Ok(ReloadCommand { hash, refresh })) = input.take() {
         match hash {
             Some(hash) => {
                 live_scenes.0.remove(&hash);
                 portables.0.remove(&hash);
+                input.reply_ok(format!("reloading {hash}"));
+            }
+            None if refresh => {
+                refresh_pointers.send(RefreshScenePointers);
+                input.reply("refreshing scene list...");
             }
             None => {
                 live_scenes.0.clear();
                 portables.0.clear();
+                input.reply_ok("reloading all scenes");
+            }
+        }
+    }
+}
+
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/loadscene")]
+struct LoadSceneCommand {
+    /// scene entity hash or urn
+    scene: String,
+    /// parcel as `x,y`, defaults to the player's parcel
+    parcel: Option<String>,
+}
+
+// loads a scene in place of the realm's scene at a parcel, until `/unloadscene`
+fn load_scene_command(
+    mut input: ConsoleCommand<LoadSceneCommand>,
+    mut manual_scenes: ResMut<ManualScenes>,
+    mut live_scenes: ResMut<LiveScenes>,
+    player: Query<&GlobalTransform, With<PrimaryUser>>,
+) {
+    if let Some(Ok(LoadSceneCommand { scene, parcel })) = input.take() {
+        let (hash, urn) = match parse_scene_location(&scene) {
+            Ok(location) => location,
+            Err(e) => {
+                input.reply_failed(e);
+                return;
+            }
+        };
+
+        let parcel = match parcel {
+            Some(parcel) => match IVec2Arg::from_str(&parcel) {
+                Ok(IVec2Arg(parcel)) => parcel,
+                Err(_) => {
+                    input.reply_failed(format!("invalid parcel `{parcel}`, expected `x,y`"));
+                    return;
+                }
+            },
+            None => match player.get_single() {
+                Ok(player) => vec3_to_parcel(player.translation()),
+                Err(_) => {
+                    input.reply_failed("no player to load the scene at");
+                    return;
+                }
+            },
+        };
+
+        // drop any running instance so it respawns at the requested parcel
+        live_scenes.0.remove(&hash);
+        manual_scenes
+            .0
+            .insert(hash.clone(), ManualScene { urn, parcel });
+        input.reply_ok(format!("loading {hash} at {},{}", parcel.x, parcel.y));
+    }
+}
+
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/unloadscene")]
+struct UnloadSceneCommand {
+    /// hash or urn of a scene loaded with `/loadscene`, unloads all if omitted
+    scene: Option<String>,
+}
+
+// the scene lifecycle despawns the unloaded scenes and restores the realm's scenes
+fn unload_scene_command(
+    mut input: ConsoleCommand<UnloadSceneCommand>,
+    mut manual_scenes: ResMut<ManualScenes>,
+    mut live_scenes: ResMut<LiveScenes>,
+) {
+    if let Some(Ok(UnloadSceneCommand { scene })) = input.take() {
+        let hashes = match scene {
+            Some(scene) => {
+                let hash = parse_scene_location(&scene).map_or(scene, |(hash, _)| hash);
+                if !manual_scenes.0.contains_key(&hash) {
+                    input.reply_failed(format!("{hash} was not loaded with /loadscene"));
+                    return;
+                }
+                vec![hash]
             }
+            None => manual_scenes.0.keys().cloned().collect(),
+        };
+
+        for hash in &hashes {
+            manual_scenes.0.remove(hash);
+            live_scenes.0.remove(hash);
         }
+        input.reply_ok(format!("unloaded {} scenes", hashes.len()));
+    }
+}
+
+// resolve a scene entity hash or urn to the hash and optional urn to load it from
+fn parse_scene_location(scene: &str) -> Result<(String, Option<String>), String> {
+    if scene.starts_with("urn:") {
+        let path = IpfsPath::new_from_urn::<EntityDefinition>(scene)
+            .map_err(|e| format!("invalid urn `{scene}`: {e}"))?;
+        return match path.context_free_hash() {
+            Ok(Some(hash)) if is_entity_hash(&hash) => Ok((hash, Some(scene.to_owned()))),
+            _ => Err(format!("urn `{scene}` doesn't refer to a scene entity")),
+        };
+    }
+
+    if is_entity_hash(scene) {
+        Ok((scene.to_owned(), None))
+    } else {
+        Err(format!("invalid scene hash `{scene}`"))
+    }
+}
+
+// cidv1 (`bafy..`), cidv0 (`Qm..`), or a preview `b64-` hash
+fn is_entity_hash(hash: &str) -> bool {
+    if let Some(encoded) = hash.strip_prefix("b64-") {
+        return !encoded.is_empty()
+            && encoded
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+/=-_".contains(c));
+    }
+    if hash.starts_with("Qm") {
+        return hash.len() == 46
+            && hash
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() && !"0OIl".contains(c));
     }
+    hash.starts_with("baf")
+        && hash.len() >= 50
+        && hash.chars().all(|c| matches!(c, 'a'..='z' | '2'..='7'))
 }
 
 fn handle_preview_command(
@@ -221,3 +363,145 @@ fn handle_preview_command(
         }
     }
 }
+
+// summary of a loaded scene, borrowed from its context
+pub struct SceneInfo<'a> {
+    pub entity: Entity,
+    pub hash: &'a str,
+    pub title: &'a str,
+    pub base: IVec2,
+    pub parcels: &'a HashSet<IVec2>,
+    pub priority: f32,
+    pub is_portable: bool,
+    pub loading: bool,
+    pub broken: bool,
+    pub blocked: bool,
+    pub tick_number: u32,
+}
+
+impl SceneInfo<'_> {
+    // distance from a world position to the nearest parcel of the scene
+    pub fn distance_to(&self, position: Vec3) -> f32 {
+        distance_to_parcels(self.parcels, position)
+    }
+}
+
+// read-only view of all live scenes
+#[derive(SystemParam)]
+pub struct LiveSceneInfo<'w, 's> {
+    live_scenes: Res<'w, LiveScenes>,
+    scenes: Query<'w, 's, (&'static RendererSceneContext, Has<SceneLoading>)>,
+}
+
+impl LiveSceneInfo<'_, '_> {
+    // scenes that are still being fetched have no context yet and are not included
+    pub fn iter(&self) -> impl Iterator<Item = SceneInfo<'_>> {
+        self.live_scenes.0.values().filter_map(|entity| {
+            let (context, loading) = self.scenes.get(*entity).ok()?;
+            Some(SceneInfo {
+                entity: *entity,
+                hash: &context.hash,
+                title: &context.title,
+                base: context.base,
+                parcels: &context.parcels,
+                priority: context.priority,
+                is_portable: context.is_portable,
+                loading,
+                broken: context.broken,
+                blocked: !context.blocked.is_empty(),
+                tick_number: context.tick_number,
+            })
+        })
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<SceneInfo<'_>> {
+        self.iter().find(|info| info.entity == entity)
+    }
+}
+
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/scene")]
+struct SceneCommand {
+    #[command(subcommand)]
+    action: SceneAction,
+}
+
+#[derive(clap::Subcommand)]
+enum SceneAction {
+    /// list live scenes, nearest first
+    List,
+}
+
+fn scene_command(
+    mut input: ConsoleCommand<SceneCommand>,
+    scenes: LiveSceneInfo,
+    player: Query<&GlobalTransform, With<PrimaryUser>>,
+) {
+    if let Some(Ok(SceneCommand { action })) = input.take() {
+        match action {
+            SceneAction::List => {
+                let position = player
+                    .get_single()
+                    .map(GlobalTransform::translation)
+                    .unwrap_or_default();
+                let mut scenes = scenes
+                    .iter()
+                    .map(|info| (info.distance_to(position), info))
+                    .collect::<Vec<_>>();
+                scenes.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+                for (distance, info) in &scenes {
+                    let state = if info.broken {
+                        "broken"
+                    } else if info.loading {
+                        "loading"
+                    } else if info.blocked {
+                        "blocked"
+                    } else {
+                        "running"
+                    };
+                    input.reply(format!(
+                        "{} {:?} [{}] {} parcels, {distance:.0}m, priority {:.0}, tick {}, {state}{}",
+                        info.base,
+                        info.title,
+                        info.hash,
+                        info.parcels.len(),
+                        info.priority,
+                        info.tick_number,
+                        if info.is_portable { " (portable)" } else { "" },
+                    ));
+                }
+                input.reply_ok(format!("{} scenes", scenes.len()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_scene_location() {
+        let hash = "bafkreigs3awsrjtm3rdnrkrvx7vrqj6zrsbpzdzzpgfcrp3whcl5yg6xpe";
+        assert_eq!(parse_scene_location(hash), Ok((hash.to_owned(), None)));
+        let urn = format!("urn:decentraland:entity:{hash}?=&baseUrl=https://peer.org/contents/");
+        assert_eq!(
+            parse_scene_location(&urn),
+            Ok((hash.to_owned(), Some(urn.clone())))
+        );
+        assert!(parse_scene_location("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG").is_ok());
+        assert!(parse_scene_location("b64-L2hvbWUvc2NlbmU=").is_ok());
+
+        for invalid in [
+            "",
+            "not-a-hash",
+            "bafkrei",
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0",
+            "urn:decentraland:wearable:abc",
+            "urn:decentraland:entity:not-a-hash",
+        ] {
+            assert!(parse_scene_location(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, IntAppSetting};
+
+// in minutes
+#[derive(Debug, PartialEq, Eq)]
+pub struct AfkTimeoutSetting(i32);
+
+impl IntAppSetting for AfkTimeoutSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        30
+    }
+
+    fn display(&self) -> String {
+        if self.0 == 0 {
+            "Never".to_owned()
+        } else {
+            format!("{} min", self.0)
+        }
+    }
+}
+
+impl AppSetting for AfkTimeoutSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Away Timeout".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Away Timeout\n\nHow long without any keyboard or mouse input before you are marked as away. While away your avatar plays an idle emote and other players see (AFK) on your name tag. Any input brings you back.".to_string()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.afk.timeout_secs = self.0 as u32 * 60;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self((config.afk.timeout_secs.div_ceil(60) as i32).min(Self::max()))
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Gameplay
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in avatar
+    }
+}
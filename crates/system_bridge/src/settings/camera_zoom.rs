@@ -0,0 +1,100 @@
+use bevy::math::FloatOrd;
+use bevy::prelude::*;
+use common::structs::{AppConfig, CameraSettings};
+
+use super::{AppSetting, IntAppSetting};
+
+macro_rules! zoom_setting {
+    ($struct:ident, $name:expr, $description:expr, $set:expr, $get:expr, $min:expr, $max:expr, $scale: expr) => {
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        pub struct $struct(FloatOrd);
+
+        impl IntAppSetting for $struct {
+            fn from_int(value: i32) -> Self {
+                Self(FloatOrd(value as f32 * $scale))
+            }
+
+            fn value(&self) -> i32 {
+                (self.0 .0 / $scale) as i32
+            }
+
+            fn min() -> i32 {
+                ($min / $scale) as i32
+            }
+
+            fn max() -> i32 {
+                ($max / $scale) as i32
+            }
+
+            fn scale() -> f32 {
+                $scale
+            }
+
+            fn display(&self) -> String {
+                format!("{:.1}m", self.0 .0)
+            }
+        }
+
+        #[allow(clippy::redundant_closure_call)]
+        impl AppSetting for $struct {
+            type Param = ();
+
+            fn title() -> String {
+                format!("{}", $name)
+            }
+
+            fn description(&self) -> String {
+                format!("{}\n\n{}", $name, $description)
+            }
+
+            fn apply(&self, _: (), _: Commands) {
+                // handled in user_input
+            }
+
+            fn save(&self, config: &mut AppConfig) {
+                $set(&mut config.camera, self.0 .0)
+            }
+
+            fn load(config: &AppConfig) -> Self {
+                Self(FloatOrd($get(&config.camera)))
+            }
+
+            fn category() -> super::SettingCategory {
+                super::SettingCategory::Gameplay
+            }
+        }
+    };
+}
+
+zoom_setting!(
+    MinZoomSetting,
+    "Minimum Camera Distance",
+    "How close the mouse wheel can bring the camera to your avatar, in meters. Above the First Person Distance, first person is never used.",
+    |cfg: &mut CameraSettings, val: f32| cfg.min_zoom = val,
+    |cfg: &CameraSettings| cfg.min_zoom,
+    0.0,
+    10.0,
+    0.5
+);
+
+zoom_setting!(
+    MaxZoomSetting,
+    "Maximum Camera Distance",
+    "How far the mouse wheel can take the camera from your avatar, in meters.",
+    |cfg: &mut CameraSettings, val: f32| cfg.max_zoom = val,
+    |cfg: &CameraSettings| cfg.max_zoom,
+    5.0,
+    200.0,
+    5.0
+);
+
+zoom_setting!(
+    FirstPersonZoomSetting,
+    "First Person Distance",
+    "Zooming the camera closer than this switches to first person, and your avatar is hidden when the camera is nearer than this to it.",
+    |cfg: &mut CameraSettings, val: f32| cfg.first_person_zoom = val,
+    |cfg: &CameraSettings| cfg.first_person_zoom,
+    0.1,
+    5.0,
+    0.1
+);
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+use dcl_component::proto_components::ColorSpace;
+
+use super::{AppSetting, EnumAppSetting};
+
+impl EnumAppSetting for ColorSpace {
+    fn variants() -> Vec<Self> {
+        vec![Self::Srgb, Self::Linear]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            ColorSpace::Srgb => "sRGB",
+            ColorSpace::Linear => "Linear",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for ColorSpace {
+    type Param = ();
+
+    fn title() -> String {
+        "Scene Color Space".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Scene Color Space\n\nHow colors provided by scenes for materials, ui and text are interpreted. Decentraland scenes use sRGB. Applies to colors updated after the change.\n\n{}",
+            match self {
+                ColorSpace::Srgb => "sRGB: Colors are gamma encoded, matching the reference client.",
+                ColorSpace::Linear => "Linear: Colors are used as linear values, which makes most scene colors appear darker.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.color_space = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.graphics.color_space
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Graphics
+    }
+
+    fn apply(&self, (): (), mut commands: Commands) {
+        commands.insert_resource(*self);
+    }
+}
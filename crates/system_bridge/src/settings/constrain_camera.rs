@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, EnumAppSetting};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ConstrainCameraSetting {
+    Off,
+    On,
+}
+
+impl EnumAppSetting for ConstrainCameraSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::On]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            ConstrainCameraSetting::Off => "Don't Constrain",
+            ConstrainCameraSetting::On => "Constrain",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for ConstrainCameraSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Constrain Camera".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Whether to keep the third-person camera over loaded scenes.\n\nNear the edge of a scene the camera can swing out over parcels that haven't loaded or have nothing in them, showing the empty world behind you.\n\n{}",
+            match self {
+                ConstrainCameraSetting::Off => "Don't Constrain: The camera stays at its full distance, unless something is in the way.",
+                ConstrainCameraSetting::On => "Constrain: The camera moves closer to your avatar rather than leave the loaded scenes.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.constrain_camera = match self {
+            ConstrainCameraSetting::Off => false,
+            ConstrainCameraSetting::On => true,
+        };
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        if config.constrain_camera {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // handled in user_input
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Gameplay
+    }
+}
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, CursorToggleKey};
+
+use super::{AppSetting, EnumAppSetting};
+
+impl EnumAppSetting for CursorToggleKey {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::Tab, Self::CapsLock, Self::Backslash]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            CursorToggleKey::Off => "Off",
+            CursorToggleKey::Tab => "Tab",
+            CursorToggleKey::CapsLock => "Caps Lock",
+            CursorToggleKey::Backslash => "Backslash",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for CursorToggleKey {
+    type Param = ();
+
+    fn title() -> String {
+        "Toggle Cursor Key".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Toggle Cursor Key\n\nKey that switches between look mode, where the cursor is hidden and moving the mouse turns the camera, and ui mode, where the cursor is free to use menus and scene ui. Clicking the right mouse button also toggles, and Escape always returns to ui mode.\n\n{}",
+            match self {
+                CursorToggleKey::Off => "Off: Only the mouse toggles look mode.",
+                _ => "The key does nothing while typing into a text box or the console.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.cursor_toggle_key = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.cursor_toggle_key
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Gameplay
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in user_input::camera
+    }
+}
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, FocusLossSetting};
+
+use super::{AppSetting, EnumAppSetting};
+
+impl EnumAppSetting for FocusLossSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Run, Self::PauseScenes, Self::PauseScenesAndComms]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            FocusLossSetting::Run => "Keep Running",
+            FocusLossSetting::PauseScenes => "Pause Scenes",
+            FocusLossSetting::PauseScenesAndComms => "Pause Scenes and Comms",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for FocusLossSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Background Behaviour".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Background Behaviour\n\nWhat to do when the explorer window is not focused. Pausing reduces CPU, GPU and battery usage while the explorer is in the background.\n\n{}",
+            match self {
+                FocusLossSetting::Run => "Keep Running: Scenes and comms continue as normal.",
+                FocusLossSetting::PauseScenes => "Pause Scenes: Scenes are paused and the frame rate is reduced. Other players continue to see your position.",
+                FocusLossSetting::PauseScenesAndComms => "Pause Scenes and Comms: Scenes are paused, the frame rate is reduced, and your position is not sent to other players. You remain connected so everything resumes immediately.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.pause_on_focus_loss = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.pause_on_focus_loss
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Performance
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in scene_runner
+    }
+}
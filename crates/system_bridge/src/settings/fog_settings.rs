@@ -1,7 +1,7 @@
 use bevy::prelude::*;
-use common::structs::{AppConfig, FogSetting};
+use common::structs::{AppConfig, FogParams, FogSetting};
 
-use super::{AppSetting, EnumAppSetting};
+use super::{AppSetting, EnumAppSetting, IntAppSetting};
 
 impl EnumAppSetting for FogSetting {
     fn variants() -> Vec<Self> {
@@ -50,3 +50,56 @@ impl AppSetting for FogSetting {
         super::SettingCategory::Graphics
     }
 }
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FogDensitySetting(i32);
+
+impl IntAppSetting for FogDensitySetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        (FogParams::MIN_DENSITY * 100.0).round() as i32
+    }
+
+    fn max() -> i32 {
+        (FogParams::MAX_DENSITY * 100.0).round() as i32
+    }
+
+    fn display(&self) -> String {
+        format!("{}%", self.0)
+    }
+}
+
+impl AppSetting for FogDensitySetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Fog Density".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Fog Density\n\nHow thick the fog is, relative to the default which fades scenes in around the scene load distance. Has no effect when fog is off. Fog color and linear start/end distances can be set with the `/fog_params` console command.".to_owned()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.fog_params.density = self.0 as f32 / 100.0;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self((config.graphics.fog_params.density * 100.0).round() as i32)
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // handled in visuals
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Graphics
+    }
+}
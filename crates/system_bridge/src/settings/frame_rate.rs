@@ -1,4 +1,11 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::system::{
+        lifetimeless::{SQuery, Write},
+        SystemParamItem,
+    },
+    prelude::*,
+    window::{PresentMode, PrimaryWindow},
+};
 use common::structs::AppConfig;
 
 use super::{AppSetting, EnumAppSetting};
@@ -55,3 +62,67 @@ impl AppSetting for FpsTargetSetting {
         // handled in scene_runner
     }
 }
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum VsyncSetting {
+    Off,
+    On,
+}
+
+impl EnumAppSetting for VsyncSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::On]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            VsyncSetting::Off => "Off",
+            VsyncSetting::On => "On",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for VsyncSetting {
+    type Param = SQuery<Write<Window>, With<PrimaryWindow>>;
+
+    fn title() -> String {
+        "Vsync".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Vsync.\n\nWhether to synchronize frames with the display refresh rate.\n\n{}",
+            match self {
+                VsyncSetting::Off => "Off: Frames are presented immediately and limited by the Target Frame Rate. May cause tearing.",
+                VsyncSetting::On => "On: Frames are synchronized with the display, and the Target Frame Rate is ignored. Prevents tearing but may add latency.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.vsync = *self == VsyncSetting::On;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        if config.graphics.vsync {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Performance
+    }
+
+    fn apply(&self, mut window: SystemParamItem<Self::Param>, _: Commands) {
+        // frame limiting is handled in scene_runner
+        let Ok(mut window) = window.get_single_mut() else {
+            return;
+        };
+        window.present_mode = match self {
+            VsyncSetting::Off => PresentMode::AutoNoVsync,
+            VsyncSetting::On => PresentMode::AutoVsync,
+        };
+    }
+}
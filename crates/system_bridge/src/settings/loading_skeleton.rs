@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, LoadingSkeletonSetting};
+
+use super::{AppSetting, EnumAppSetting};
+
+impl EnumAppSetting for LoadingSkeletonSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::On]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            LoadingSkeletonSetting::Off => "Off",
+            LoadingSkeletonSetting::On => "On",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for LoadingSkeletonSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Scene Loading Placeholder".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Scene Loading Placeholder\n\nWhether to show a translucent placeholder over the parcels of scenes that are still loading, so they can be distinguished from empty parcels.\n\n{}",
+            match self {
+                LoadingSkeletonSetting::Off => "Off: Loading scenes show nothing until they are ready.",
+                LoadingSkeletonSetting::On => "On: Loading scenes are highlighted until their content appears.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.loading_skeleton = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.graphics.loading_skeleton
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Graphics
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in scene_runner::initialize_scene
+    }
+}
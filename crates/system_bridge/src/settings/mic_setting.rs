@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, MicrophoneSetting};
+
+use super::{AppSetting, EnumAppSetting};
+
+impl EnumAppSetting for MicrophoneSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Enabled, Self::Disabled]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            MicrophoneSetting::Enabled => "Enabled",
+            MicrophoneSetting::Disabled => "Disabled",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for MicrophoneSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Microphone".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Microphone\n\nWhether the microphone can be used for voice chat. The mic is always off at startup.\n\n{}",
+        match self {
+            MicrophoneSetting::Enabled => "Enabled: The mic records while the mic button or push-to-talk is on.",
+            MicrophoneSetting::Disabled => "Disabled: The mic is never opened, whatever the mic button state.",
+        })
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.audio.microphone = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.audio.microphone
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Audio
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in av::microphone
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     sync::{Arc, RwLock},
 };
 
+use afk::AfkTimeoutSetting;
 use ambient_brightness_setting::AmbientSetting;
 use anyhow::anyhow;
 use bevy::{
@@ -13,26 +14,35 @@ use bevy::{
     },
     prelude::*,
 };
-use common::{
-    structs::{
-        AaSetting, AppConfig, BloomSetting, FogSetting, ShadowSetting, SsaoSetting, WindowSetting,
-    },
-    util::config_file,
+use camera_zoom::{FirstPersonZoomSetting, MaxZoomSetting, MinZoomSetting};
+use common::structs::{
+    AaSetting, AppConfig, AvatarMotionMode, BloomSetting, CursorToggleKey, FocusLossSetting,
+    FogSetting, IslandChangeSetting, LoadingSkeletonSetting, MicrophoneSetting, NametagSetting,
+    RealmSettingOverrides, RealmSettings, SceneImposterSetting, ShadowSetting, SsaoSetting,
+    TonemappingSetting, VoiceChatSetting, WindowSetting,
 };
+use constrain_camera::ConstrainCameraSetting;
 use constrain_ui::ConstrainUiSetting;
+use dcl_component::proto_components::ColorSpace;
 use despawn_workaround::DespawnWorkaroundSetting;
-use frame_rate::FpsTargetSetting;
+use fog_settings::FogDensitySetting;
+use frame_rate::{FpsTargetSetting, VsyncSetting};
 use load_distance::{LoadDistanceSetting, UnloadDistanceSetting};
 use max_avatars::MaxAvatarsSetting;
 use max_downloads::MaxDownloadsSetting;
+use network_settings::JitterBufferSetting;
 use oob_setting::OobSetting;
 use player_settings::{
     FallSpeedSetting, FrictionSetting, GravitySetting, JumpSetting, RunSpeedSetting,
     WalkSpeedSetting,
 };
+use post_processing::{BrightnessSetting, ContrastSetting, GammaSetting};
+use scene_features::{AvatarAttachFeatureSetting, SceneAudioFeatureSetting, VideoFeatureSetting};
 use scene_threads::SceneThreadsSetting;
 use serde::{Deserialize, Serialize};
 use shadow_settings::{ShadowCasterCountSetting, ShadowDistanceSetting};
+use texture_budget::TextureBudgetSetting;
+use update_budget::SceneUpdateBudgetSetting;
 use video_threads::VideoThreadsSetting;
 use volume_settings::{
     AvatarVolumeSetting, MasterVolumeSetting, SceneVolumeSetting, SystemVolumeSetting,
@@ -42,20 +52,35 @@ use volume_settings::{
 use crate::SystemApi;
 
 pub mod aa_settings;
+pub mod afk;
 pub mod ambient_brightness_setting;
 pub mod bloom_settings;
+pub mod camera_zoom;
+pub mod color_space;
+pub mod constrain_camera;
 pub mod constrain_ui;
+pub mod cursor_toggle;
 pub mod despawn_workaround;
+pub mod focus_loss;
 pub mod fog_settings;
 pub mod frame_rate;
 pub mod load_distance;
+pub mod loading_skeleton;
 pub mod max_avatars;
 pub mod max_downloads;
+pub mod mic_setting;
+pub mod nametags;
+pub mod network_settings;
 pub mod oob_setting;
 pub mod player_settings;
+pub mod post_processing;
+pub mod scene_features;
+pub mod scene_imposters;
 pub mod scene_threads;
 pub mod shadow_settings;
 pub mod ssao_setting;
+pub mod texture_budget;
+pub mod update_budget;
 pub mod video_threads;
 pub mod volume_settings;
 pub mod window_settings;
@@ -70,12 +95,13 @@ impl Plugin for SettingBridgePlugin {
         fn apply_to_camera<S: AppSetting>(
             mut commands: Commands,
             config: Res<AppConfig>,
+            realm_settings: Res<RealmSettings>,
             mut new_camera_events: EventReader<NewCameraEvent>,
             param: StaticSystemParam<S::Param>,
         ) {
             let param = param.into_inner();
             for ev in new_camera_events.read() {
-                let setting = S::load(&config);
+                let setting = S::load(&realm_settings.effective(&config));
                 setting.apply_to_camera(&param, commands.reborrow(), ev.0);
             }
         }
@@ -123,24 +149,38 @@ impl Plugin for SettingBridgePlugin {
         );
 
         add_enum_setting::<FogSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<FogDensitySetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<BloomSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<SsaoSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<OobSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<LoadingSkeletonSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<ColorSpace>(app, &mut settings, &mut schedule);
+        add_int_setting::<BrightnessSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<ContrastSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<GammaSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<TonemappingSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<TextureBudgetSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<AaSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<AmbientSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<WindowSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<LoadDistanceSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<UnloadDistanceSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<SceneImposterSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<VsyncSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<FpsTargetSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<FocusLossSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<SceneThreadsSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<SceneUpdateBudgetSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<MaxAvatarsSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<MasterVolumeSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<SceneVolumeSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<VoiceVolumeSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<SystemVolumeSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<AvatarVolumeSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<MicrophoneSetting>(app, &mut settings, &mut schedule);
 
         add_enum_setting::<ConstrainUiSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<CursorToggleKey>(app, &mut settings, &mut schedule);
         add_int_setting::<RunSpeedSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<WalkSpeedSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<FrictionSetting>(app, &mut settings, &mut schedule);
@@ -150,12 +190,34 @@ impl Plugin for SettingBridgePlugin {
         add_int_setting::<VideoThreadsSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<MaxDownloadsSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<DespawnWorkaroundSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<VideoFeatureSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<SceneAudioFeatureSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<AvatarAttachFeatureSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<JitterBufferSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<AvatarMotionMode>(app, &mut settings, &mut schedule);
+        add_enum_setting::<VoiceChatSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<IslandChangeSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<NametagSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<AfkTimeoutSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<ConstrainCameraSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<MinZoomSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<MaxZoomSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<FirstPersonZoomSetting>(app, &mut settings, &mut schedule);
 
         app.insert_resource(settings);
         app.insert_resource(ApplyAppSettingsSchedule(schedule));
+        app.init_resource::<RealmSettings>();
         app.add_systems(
             Update,
-            apply_settings.run_if(|config: Res<AppConfig>| config.is_changed()),
+            (
+                drop_user_changed_overrides,
+                apply_settings.run_if(
+                    |config: Res<AppConfig>, realm_settings: Res<RealmSettings>| {
+                        config.is_changed() || realm_settings.is_changed()
+                    },
+                ),
+            )
+                .chain(),
         );
     }
 }
@@ -165,6 +227,7 @@ pub enum SettingCategory {
     Graphics,
     Audio,
     Performance,
+    Network,
 }
 
 impl Display for SettingCategory {
@@ -174,6 +237,7 @@ impl Display for SettingCategory {
             SettingCategory::Graphics => "Graphics",
             SettingCategory::Audio => "Audio",
             SettingCategory::Performance => "Performance",
+            SettingCategory::Network => "Network",
         })
     }
 }
@@ -373,22 +437,53 @@ fn apply_settings(world: &mut World) {
             schedule.0.run(world);
         },
     );
+}
+
+// settings the user changes while in a realm are no longer overridden by it
+fn drop_user_changed_overrides(
+    config: Res<AppConfig>,
+    mut realm_settings: ResMut<RealmSettings>,
+    mut previous: Local<Option<RealmSettingOverrides>>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+
+    let current = RealmSettingOverrides::from_config(&config);
+    if let Some(previous) = previous.as_ref() {
+        if realm_settings
+            .bypass_change_detection()
+            .user_changed(previous, &current)
+        {
+            debug!("dropped realm overrides for changed settings");
+        }
+    }
+    *previous = Some(current);
+}
 
-    let config_file = config_file();
-    if let Some(folder) = config_file.parent() {
-        std::fs::create_dir_all(folder).unwrap();
+// keep realm overrides within the ranges the settings ui allows
+pub fn clamp_realm_overrides(overrides: &mut RealmSettingOverrides) {
+    fn clamp<S: IntAppSetting>(value: &mut Option<f32>) {
+        if let Some(value) = value.as_mut() {
+            *value = value.clamp(S::min() as f32, S::max() as f32);
+        }
+    }
+
+    clamp::<LoadDistanceSetting>(&mut overrides.scene_load_distance);
+    clamp::<UnloadDistanceSetting>(&mut overrides.scene_unload_extra_distance);
+    if let Some(max_avatars) = overrides.max_avatars.as_mut() {
+        *max_avatars = (*max_avatars).clamp(
+            MaxAvatarsSetting::min() as usize,
+            MaxAvatarsSetting::max() as usize,
+        );
     }
-    std::fs::write(
-        config_file,
-        serde_json::to_string(world.resource::<AppConfig>()).unwrap(),
-    )
-    .unwrap();
 }
 
 fn apply_setting<S: AppSetting>(
     params: StaticSystemParam<S::Param>,
     config: Res<AppConfig>,
+    realm_settings: Res<RealmSettings>,
     commands: Commands,
 ) {
-    S::load(&config).apply(params.into_inner(), commands);
+    S::load(&realm_settings.effective(&config)).apply(params.into_inner(), commands);
 }
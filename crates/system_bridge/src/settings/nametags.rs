@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, NametagSetting};
+
+use super::{AppSetting, EnumAppSetting};
+
+impl EnumAppSetting for NametagSetting {
+    fn variants() -> Vec<Self> {
+        vec![
+            Self::All,
+            Self::FriendsAndSpeaking,
+            Self::Friends,
+            Self::Speaking,
+            Self::Off,
+        ]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            NametagSetting::All => "All",
+            NametagSetting::FriendsAndSpeaking => "Friends and Speaking",
+            NametagSetting::Friends => "Friends",
+            NametagSetting::Speaking => "Speaking",
+            NametagSetting::Off => "None",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for NametagSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Name Tags".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Name Tags\n\nWhich other players show a name tag above their avatar. Limiting name tags reduces clutter in busy areas.\n\n{}",
+            match self {
+                NametagSetting::All => "All: Show name tags for all players.",
+                NametagSetting::FriendsAndSpeaking => "Friends and Speaking: Show name tags for friends, and for anyone speaking on voice chat.",
+                NametagSetting::Friends => "Friends: Show name tags for friends only.",
+                NametagSetting::Speaking => "Speaking: Show name tags only for players speaking on voice chat.",
+                NametagSetting::Off => "None: Hide all player name tags.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.nametags = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.nametags
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Gameplay
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in avatar
+    }
+}
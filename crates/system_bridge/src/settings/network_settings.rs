@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, AvatarMotionMode, IslandChangeSetting, VoiceChatSetting};
+
+use super::{AppSetting, EnumAppSetting, IntAppSetting};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct JitterBufferSetting(i32);
+
+impl IntAppSetting for JitterBufferSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        50
+    }
+
+    fn scale() -> f32 {
+        10.0
+    }
+
+    fn display(&self) -> String {
+        format!("{}ms", self.0 * 10)
+    }
+}
+
+impl AppSetting for JitterBufferSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Avatar Smoothing Delay".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Avatar Smoothing Delay\n\nHow long to buffer other players' positions before displaying them. Higher values give smoother movement when the connection is unstable, but other players will appear further behind their real position.".to_string()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.network.jitter_buffer_ms = self.0 as u32 * 10;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.network.jitter_buffer_ms as i32 / 10)
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Network
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in avatar::foreign_dynamics
+    }
+}
+
+impl EnumAppSetting for AvatarMotionMode {
+    fn variants() -> Vec<Self> {
+        vec![Self::Interpolate, Self::Extrapolate]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            AvatarMotionMode::Interpolate => "Interpolate",
+            AvatarMotionMode::Extrapolate => "Extrapolate",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for AvatarMotionMode {
+    type Param = ();
+
+    fn title() -> String {
+        "Avatar Motion".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("How other players' movement is displayed between network updates.\n\n{}",
+        match self {
+            AvatarMotionMode::Interpolate => "Interpolate: Smooth movement, delayed by the Avatar Smoothing Delay.",
+            AvatarMotionMode::Extrapolate => "Extrapolate: Positions are predicted from the last known velocity. Lower latency, but avatars may overshoot when they stop or turn.",
+        })
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.network.avatar_motion = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.network.avatar_motion
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Network
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in avatar::foreign_dynamics
+    }
+}
+
+impl EnumAppSetting for VoiceChatSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Enabled, Self::Disabled]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            VoiceChatSetting::Enabled => "Enabled",
+            VoiceChatSetting::Disabled => "Disabled",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for VoiceChatSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Voice Chat".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Voice Chat\n\nWhether to send and receive voice. Chat and player positions are unaffected. Changing this reconnects to the current comms room.\n\n{}",
+        match self {
+            VoiceChatSetting::Enabled => "Enabled: Voice is sent while the mic is on, and other players' voices are played.",
+            VoiceChatSetting::Disabled => "Disabled: No voice is sent or received, saving bandwidth. The mic is not used.",
+        })
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.network.voice_chat = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.network.voice_chat
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Network
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in comms::livekit_room
+    }
+}
+
+impl EnumAppSetting for IslandChangeSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Immediate, Self::WhenStopped]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            IslandChangeSetting::Immediate => "Immediate",
+            IslandChangeSetting::WhenStopped => "When Stopped",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for IslandChangeSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Comms Island Changes".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Comms Island Changes\n\nIn realms using archipelago, you are grouped with nearby players into an island based on your position.\n\n{}",
+        match self {
+            IslandChangeSetting::Immediate => "Immediate: Your island follows your position as you move.",
+            IslandChangeSetting::WhenStopped => "When Stopped: Your island only changes once you stay in one area for a couple of seconds. Avoids repeatedly reconnecting while travelling quickly.",
+        })
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.network.island_change = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.network.island_change
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Network
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in comms::archipelago
+    }
+}
@@ -0,0 +1,182 @@
+use bevy::{
+    core_pipeline::tonemapping::Tonemapping,
+    ecs::system::{lifetimeless::SRes, SystemParamItem},
+    math::FloatOrd,
+    prelude::*,
+};
+use common::structs::{AppConfig, PostProcessingSettings, PrimaryCameraRes, TonemappingSetting};
+
+use super::{AppSetting, EnumAppSetting, IntAppSetting};
+
+fn apply_color_grading(config: &AppConfig, mut commands: Commands, camera_entity: Entity) {
+    if let Some(mut cmds) = commands.get_entity(camera_entity) {
+        cmds.insert(config.graphics.post_processing.color_grading());
+    }
+}
+
+macro_rules! post_processing_setting {
+    ($struct:ident, $name:expr, $description:expr, $field:ident, $min:expr, $max:expr, $scale: expr) => {
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        pub struct $struct(FloatOrd);
+
+        impl IntAppSetting for $struct {
+            fn from_int(value: i32) -> Self {
+                Self(FloatOrd(value as f32 * $scale))
+            }
+
+            fn value(&self) -> i32 {
+                (self.0 .0 / $scale).round() as i32
+            }
+
+            fn min() -> i32 {
+                ($min / $scale) as i32
+            }
+
+            fn max() -> i32 {
+                ($max / $scale) as i32
+            }
+
+            fn scale() -> f32 {
+                $scale
+            }
+
+            fn display(&self) -> String {
+                format!("{:.2}", self.0 .0)
+            }
+        }
+
+        impl AppSetting for $struct {
+            type Param = (SRes<AppConfig>, SRes<PrimaryCameraRes>);
+
+            fn title() -> String {
+                $name.to_owned()
+            }
+
+            fn description(&self) -> String {
+                format!(
+                    "{}\n\n{}\n\nDefault {}. Applies to the 3d view only, not the user interface. All post-processing settings can be reset with the `/reset_post_processing` console command.",
+                    $name,
+                    $description,
+                    PostProcessingSettings::default().$field
+                )
+            }
+
+            fn save(&self, config: &mut AppConfig) {
+                config.graphics.post_processing.$field = self.0 .0;
+            }
+
+            fn load(config: &AppConfig) -> Self {
+                Self(FloatOrd(config.graphics.post_processing.$field))
+            }
+
+            fn category() -> super::SettingCategory {
+                super::SettingCategory::Graphics
+            }
+
+            fn apply(&self, param: SystemParamItem<Self::Param>, commands: Commands) {
+                self.apply_to_camera(&param, commands, param.1 .0);
+            }
+
+            fn apply_to_camera(
+                &self,
+                (config, _): &SystemParamItem<Self::Param>,
+                commands: Commands,
+                camera_entity: Entity,
+            ) {
+                apply_color_grading(config, commands, camera_entity);
+            }
+        }
+    };
+}
+
+post_processing_setting!(
+    BrightnessSetting,
+    "Brightness",
+    "Exposure adjustment in stops. Positive values brighten the image.",
+    brightness,
+    -2.0,
+    2.0,
+    0.1
+);
+
+post_processing_setting!(
+    ContrastSetting,
+    "Contrast",
+    "Contrast multiplier. Higher values make dark areas darker and bright areas brighter.",
+    contrast,
+    0.5,
+    1.5,
+    0.05
+);
+
+post_processing_setting!(
+    GammaSetting,
+    "Gamma",
+    "Gamma multiplier. Higher values darken the midtones, lower values brighten them.",
+    gamma,
+    0.5,
+    2.0,
+    0.05
+);
+
+impl EnumAppSetting for TonemappingSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::Basic, Self::High]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            TonemappingSetting::Off => "Off",
+            TonemappingSetting::Basic => "Basic",
+            TonemappingSetting::High => "High",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for TonemappingSetting {
+    type Param = SRes<PrimaryCameraRes>;
+
+    fn title() -> String {
+        "Tonemapping".to_owned()
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Graphics
+    }
+
+    fn description(&self) -> String {
+        format!("Tonemapping\n\nHow the high dynamic range of the rendered scene is mapped to the display.\n\n{}",
+            match self {
+                TonemappingSetting::Off => "Off: No tonemapping. Cheapest, but bright areas are clipped.",
+                TonemappingSetting::Basic => "Basic: A simple curve that is cheap to compute.",
+                TonemappingSetting::High => "High: Natural colors for bright and saturated areas, using a lookup table.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.post_processing.tonemapping = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.graphics.post_processing.tonemapping
+    }
+
+    fn apply(&self, cam_res: SystemParamItem<Self::Param>, commands: Commands) {
+        self.apply_to_camera(&cam_res, commands, cam_res.0);
+    }
+
+    fn apply_to_camera(
+        &self,
+        _: &SystemParamItem<Self::Param>,
+        mut commands: Commands,
+        camera_entity: Entity,
+    ) {
+        let Some(mut cmds) = commands.get_entity(camera_entity) else {
+            return;
+        };
+
+        cmds.insert(Tonemapping::from(*self));
+    }
+}
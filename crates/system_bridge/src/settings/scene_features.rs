@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, FeatureSettings};
+
+use super::{AppSetting, EnumAppSetting};
+
+macro_rules! feature_setting {
+    ($struct:ident, $name:expr, $description:expr, $set:expr, $get:expr) => {
+        #[derive(Debug, PartialEq, Eq)]
+        pub enum $struct {
+            Off,
+            On,
+        }
+
+        impl EnumAppSetting for $struct {
+            fn variants() -> Vec<Self> {
+                vec![Self::Off, Self::On]
+            }
+
+            fn name(&self) -> String {
+                match self {
+                    Self::Off => "Disabled",
+                    Self::On => "Enabled",
+                }
+                .to_owned()
+            }
+        }
+
+        #[allow(clippy::redundant_closure_call)]
+        impl AppSetting for $struct {
+            type Param = ();
+
+            fn title() -> String {
+                format!("Scene {}", $name)
+            }
+
+            fn description(&self) -> String {
+                format!("Scene {}\n\n{}\n\nDisabling it doesn't stop scenes from running, requests to use it are ignored.", $name, $description)
+            }
+
+            fn save(&self, config: &mut AppConfig) {
+                $set(&mut config.features, matches!(self, Self::On))
+            }
+
+            fn load(config: &AppConfig) -> Self {
+                if $get(&config.features) {
+                    Self::On
+                } else {
+                    Self::Off
+                }
+            }
+
+            fn apply(&self, _: (), _: Commands) {
+                // checked by the feature's systems through scene_runner::features::SceneFeatures
+            }
+
+            fn category() -> super::SettingCategory {
+                super::SettingCategory::Performance
+            }
+        }
+    };
+}
+
+feature_setting!(
+    VideoFeatureSetting,
+    "Video",
+    "Whether scenes can play videos. Decoding video is expensive, when disabled video players show a placeholder instead.",
+    |cfg: &mut FeatureSettings, val: bool| cfg.video = val,
+    |cfg: &FeatureSettings| cfg.video
+);
+feature_setting!(
+    SceneAudioFeatureSetting,
+    "Audio",
+    "Whether scenes can play audio clips and audio streams.",
+    |cfg: &mut FeatureSettings, val: bool| cfg.scene_audio = val,
+    |cfg: &FeatureSettings| cfg.scene_audio
+);
+feature_setting!(
+    AvatarAttachFeatureSetting,
+    "Avatar Attachments",
+    "Whether scenes can attach objects to your avatar or to other players.",
+    |cfg: &mut FeatureSettings, val: bool| cfg.avatar_attach = val,
+    |cfg: &FeatureSettings| cfg.avatar_attach
+);
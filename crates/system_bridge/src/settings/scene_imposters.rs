@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, SceneImposterSetting};
+
+use super::{AppSetting, EnumAppSetting};
+
+impl EnumAppSetting for SceneImposterSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::Near, Self::Medium, Self::Far]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            SceneImposterSetting::Off => "Off",
+            SceneImposterSetting::Near => "Near",
+            SceneImposterSetting::Medium => "Medium",
+            SceneImposterSetting::Far => "Far",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for SceneImposterSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Distant Scenes".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Distant Scenes\n\nScenes beyond the load distance can be drawn as cheap static imposters, so the skyline looks populated without running the scenes. Imposters use some memory and download bandwidth, which increases with the distance they are drawn to.\n\n{}",
+            match self {
+                SceneImposterSetting::Off => "Off: Nothing is drawn beyond the load distance.",
+                SceneImposterSetting::Near => "Near: Imposters are drawn for nearby scenes only.",
+                SceneImposterSetting::Medium => "Medium: Imposters are drawn to a medium distance.",
+                SceneImposterSetting::Far => "Far: Imposters are drawn as far as they are available.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.scene_imposters = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.graphics.scene_imposters
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Performance
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in imposters::render
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, IntAppSetting};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TextureBudgetSetting(i32);
+
+impl IntAppSetting for TextureBudgetSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        32
+    }
+
+    fn scale() -> f32 {
+        256.0
+    }
+
+    fn display(&self) -> String {
+        if self.0 == 0 {
+            "Unlimited".to_owned()
+        } else {
+            format!("{} MB", self.0 * 256)
+        }
+    }
+}
+
+impl AppSetting for TextureBudgetSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Texture Memory".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Texture Memory\n\nMemory available for scene textures. Once it is used up, newly loaded scene textures are reduced in size to fit, so scenes with very large textures don't run out of GPU memory. Lower values save memory at the cost of blurrier textures. Textures that are already loaded are not affected.".to_string()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.texture_budget_mb = self.0 as usize * 256;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self((config.graphics.texture_budget_mb.div_ceil(256) as i32).min(Self::max()))
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Graphics
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in scene_runner
+    }
+}
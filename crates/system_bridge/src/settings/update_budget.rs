@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, IntAppSetting};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SceneUpdateBudgetSetting(i32);
+
+impl IntAppSetting for SceneUpdateBudgetSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        40
+    }
+
+    fn scale() -> f32 {
+        250.0
+    }
+
+    fn display(&self) -> String {
+        if self.0 == 0 {
+            "Unlimited".to_owned()
+        } else {
+            format!("{}", self.0 * 250)
+        }
+    }
+}
+
+impl AppSetting for SceneUpdateBudgetSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Scene Update Budget".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Scene Update Budget\n\nMaximum number of scene component updates to apply per frame. When many scenes update at once the excess is spread over the following frames, starting with the nearest scenes. Lower values give a more consistent framerate, but distant scenes may update less smoothly. The scene you are standing in is always updated.".to_string()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.performance.scene_update_budget = self.0 as usize * 250;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self((config.performance.scene_update_budget / 250) as i32)
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Performance
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in scene_runner
+    }
+}
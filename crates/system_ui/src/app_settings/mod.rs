@@ -1,30 +1,42 @@
 use bevy::{ecs::system::StaticSystemParam, prelude::*, ui::RelativeCursorPosition};
 use bevy_dui::{DuiCommandsExt, DuiEntities, DuiEntityCommandsExt, DuiProps, DuiRegistry};
 use common::structs::{
-    AaSetting, AppConfig, BloomSetting, FogSetting, SettingsTab, ShadowSetting, SsaoSetting,
-    WindowSetting,
+    AaSetting, AppConfig, AvatarMotionMode, BloomSetting, CursorToggleKey, FocusLossSetting,
+    FogSetting, IslandChangeSetting, LoadingSkeletonSetting, MicrophoneSetting, NametagSetting,
+    SceneImposterSetting, SettingsTab, ShadowSetting, SsaoSetting, TonemappingSetting,
+    VoiceChatSetting, WindowSetting,
 };
+use dcl_component::proto_components::ColorSpace;
 use system_bridge::settings::{EnumAppSetting, IntAppSetting};
 use ui_core::ui_actions::{Click, ClickRepeat, HoverEnter, On, UiCaller};
 
 use crate::profile::SettingsDialog;
 
 use system_bridge::settings::{
+    afk::AfkTimeoutSetting,
     ambient_brightness_setting::AmbientSetting,
+    camera_zoom::{FirstPersonZoomSetting, MaxZoomSetting, MinZoomSetting},
+    constrain_camera::ConstrainCameraSetting,
     constrain_ui::ConstrainUiSetting,
     despawn_workaround::DespawnWorkaroundSetting,
-    frame_rate::FpsTargetSetting,
+    fog_settings::FogDensitySetting,
+    frame_rate::{FpsTargetSetting, VsyncSetting},
     load_distance::{LoadDistanceSetting, UnloadDistanceSetting},
     max_avatars::MaxAvatarsSetting,
     max_downloads::MaxDownloadsSetting,
+    network_settings::JitterBufferSetting,
     oob_setting::OobSetting,
     player_settings::{
         FallSpeedSetting, FrictionSetting, GravitySetting, JumpSetting, RunSpeedSetting,
         WalkSpeedSetting,
     },
+    post_processing::{BrightnessSetting, ContrastSetting, GammaSetting},
+    scene_features::{AvatarAttachFeatureSetting, SceneAudioFeatureSetting, VideoFeatureSetting},
     scene_threads::SceneThreadsSetting,
     shadow_settings::ShadowCasterCountSetting,
     shadow_settings::ShadowDistanceSetting,
+    texture_budget::TextureBudgetSetting,
+    update_budget::SceneUpdateBudgetSetting,
     video_threads::VideoThreadsSetting,
     volume_settings::{
         AvatarVolumeSetting, MasterVolumeSetting, SceneVolumeSetting, SystemVolumeSetting,
@@ -105,10 +117,25 @@ fn set_app_settings_content(
             spawn_int_setting_template::<ShadowDistanceSetting>(&mut commands, &dui, &config),
             spawn_int_setting_template::<ShadowCasterCountSetting>(&mut commands, &dui, &config),
             spawn_enum_setting_template::<FogSetting>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<FogDensitySetting>(&mut commands, &dui, &config),
             spawn_enum_setting_template::<BloomSetting>(&mut commands, &dui, &config),
             spawn_enum_setting_template::<SsaoSetting>(&mut commands, &dui, &config),
             spawn_enum_setting_template::<OobSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<LoadingSkeletonSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<ColorSpace>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<BrightnessSetting>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<ContrastSetting>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<GammaSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<TonemappingSetting>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<TextureBudgetSetting>(&mut commands, &dui, &config),
             spawn_enum_setting_template::<ConstrainUiSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<ConstrainCameraSetting>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<MinZoomSetting>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<MaxZoomSetting>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<FirstPersonZoomSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<CursorToggleKey>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<NametagSetting>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<AfkTimeoutSetting>(&mut commands, &dui, &config),
             commands
                 .spawn_template(
                     &dui,
@@ -119,12 +146,19 @@ fn set_app_settings_content(
                 .root,
             spawn_int_setting_template::<LoadDistanceSetting>(&mut commands, &dui, &config),
             spawn_int_setting_template::<UnloadDistanceSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<SceneImposterSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<VsyncSetting>(&mut commands, &dui, &config),
             spawn_enum_setting_template::<FpsTargetSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<FocusLossSetting>(&mut commands, &dui, &config),
             spawn_int_setting_template::<SceneThreadsSetting>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<SceneUpdateBudgetSetting>(&mut commands, &dui, &config),
             spawn_int_setting_template::<VideoThreadsSetting>(&mut commands, &dui, &config),
             spawn_int_setting_template::<MaxAvatarsSetting>(&mut commands, &dui, &config),
             spawn_int_setting_template::<MaxDownloadsSetting>(&mut commands, &dui, &config),
             spawn_enum_setting_template::<DespawnWorkaroundSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<VideoFeatureSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<SceneAudioFeatureSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<AvatarAttachFeatureSetting>(&mut commands, &dui, &config),
             commands
                 .spawn_template(
                     &dui,
@@ -138,6 +172,19 @@ fn set_app_settings_content(
             spawn_int_setting_template::<VoiceVolumeSetting>(&mut commands, &dui, &config),
             spawn_int_setting_template::<SystemVolumeSetting>(&mut commands, &dui, &config),
             spawn_int_setting_template::<AvatarVolumeSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<MicrophoneSetting>(&mut commands, &dui, &config),
+            commands
+                .spawn_template(
+                    &dui,
+                    "settings-header",
+                    DuiProps::new().with_prop("label", "Network Settings".to_owned()),
+                )
+                .unwrap()
+                .root,
+            spawn_enum_setting_template::<AvatarMotionMode>(&mut commands, &dui, &config),
+            spawn_int_setting_template::<JitterBufferSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<VoiceChatSetting>(&mut commands, &dui, &config),
+            spawn_enum_setting_template::<IslandChangeSetting>(&mut commands, &dui, &config),
             commands
                 .spawn_template(
                     &dui,
@@ -0,0 +1,146 @@
+use av::voice_gain::VoiceGains;
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use common::{
+    rpc::RpcCall,
+    structs::{AppConfig, ShowAvatarMenuEvent, ShowProfileEvent},
+    util::FireEventEx,
+};
+use comms::global_crdt::{BlockedPeers, ForeignPlayer};
+use scene_runner::{initialize_scene::PARCEL_SIZE, Toaster};
+use social::{FriendshipEvent, FriendshipState, SocialClient};
+use ui_core::{
+    button::DuiButton,
+    ui_actions::{close_ui_silent, Click, On},
+};
+
+use crate::clipboard::copy_to_clipboard;
+
+pub struct AvatarMenuPlugin;
+
+impl Plugin for AvatarMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShowAvatarMenuEvent>();
+        app.add_systems(Update, show_avatar_menu);
+    }
+}
+
+#[derive(Component)]
+pub struct AvatarMenu;
+
+#[allow(clippy::too_many_arguments)]
+fn show_avatar_menu(
+    mut commands: Commands,
+    mut events: EventReader<ShowAvatarMenuEvent>,
+    existing: Query<Entity, With<AvatarMenu>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    social: Res<SocialClient>,
+    gains: Res<VoiceGains>,
+    blocked: Res<BlockedPeers>,
+    dui: Res<DuiRegistry>,
+) {
+    let Some(ev) = events.read().last() else {
+        return;
+    };
+
+    for ent in existing.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+
+    let address = ev.address;
+    let muted = gains.percent(&address) == 0;
+    let is_blocked = blocked.0.contains(&address);
+
+    let mut buttons = vec![DuiButton::new_enabled_and_close_happy(
+        "View Profile",
+        move |mut commands: Commands| {
+            commands.fire_event(ShowProfileEvent(address));
+        },
+    )];
+
+    if social.0.is_some() && social.get_state(address) == FriendshipState::NotFriends {
+        buttons.push(DuiButton::new_enabled_and_close_happy(
+            "Add Friend",
+            move |mut client: ResMut<SocialClient>, mut commands: Commands| {
+                let Some(client) = client.0.as_mut() else {
+                    warn!("not connected");
+                    return;
+                };
+
+                if let Err(e) = client.friend_request(address, None) {
+                    warn!("error: {e}");
+                } else {
+                    commands.fire_event(FriendshipEvent(None));
+                }
+            },
+        ));
+    }
+
+    buttons.extend([
+        DuiButton::new_enabled_and_close_happy(
+            if muted { "Unmute Voice" } else { "Mute Voice" },
+            move |mut gains: ResMut<VoiceGains>| {
+                if muted {
+                    gains.0.remove(&address);
+                } else {
+                    gains.0.insert(address, 0);
+                }
+            },
+        ),
+        DuiButton::new_enabled_and_close_happy(
+            if is_blocked { "Unblock" } else { "Block" },
+            move |mut blocked: ResMut<BlockedPeers>, mut config: ResMut<AppConfig>| {
+                blocked.set_blocked(address, !is_blocked, &mut config);
+            },
+        ),
+        DuiButton::new_enabled_and_close_happy("Copy Address", move |mut toaster: Toaster| {
+            copy_to_clipboard(format!("{address:#x}"), "Address", &mut toaster);
+        }),
+        DuiButton::new_enabled_and_close_happy(
+            "Teleport To",
+            move |mut commands: Commands, players: Query<(&ForeignPlayer, &GlobalTransform)>| {
+                let Some((_, transform)) =
+                    players.iter().find(|(player, _)| player.address == address)
+                else {
+                    warn!("player {address:#x} is no longer nearby");
+                    return;
+                };
+
+                // teleport to the parcel, the scene's spawn point logic places the player
+                let parcel = (transform.translation().xz() * Vec2::new(1.0, -1.0) / PARCEL_SIZE)
+                    .floor()
+                    .as_ivec2();
+                commands.fire_event(RpcCall::TeleportPlayer {
+                    scene: None,
+                    to: parcel,
+                    response: Default::default(),
+                });
+            },
+        ),
+    ]);
+
+    // keep the menu on screen
+    let mut position = ev.position;
+    if let Ok(window) = window.get_single() {
+        position = position.min(Vec2::new(window.width() * 0.8, window.height() * 0.6));
+    }
+
+    let components = commands
+        .spawn_template(
+            &dui,
+            "avatar-menu",
+            DuiProps::new()
+                .with_prop("name", ev.name.clone())
+                .with_prop("left", format!("{}px", position.x))
+                .with_prop("top", format!("{}px", position.y))
+                .with_prop("buttons", buttons),
+        )
+        .unwrap();
+
+    // close on click-away
+    commands.entity(components.root).insert((
+        AvatarMenu,
+        Interaction::default(),
+        On::<Click>::new(close_ui_silent),
+    ));
+}
@@ -0,0 +1,345 @@
+// console conveniences for the chat input: up/down walk previously entered commands (kept
+// across sessions), and tab completes command names and, for commands with a fixed set of
+// values, the first argument. the chat input takes the tab key while focused, so nothing
+// else reacts to it.
+
+use std::{collections::VecDeque, path::PathBuf};
+
+use bevy::{ecs::query::Has, prelude::*};
+use bevy_console::{ConsoleConfiguration, PrintConsoleLine};
+use bevy_simple_text_input::{TextInputSystem, TextInputValue};
+use common::{sets::SceneSets, util::project_directories};
+use ui_core::{focus::Focus, text_entry::TextEntrySubmit};
+
+use super::ChatInput;
+
+const MAX_HISTORY: usize = 100;
+
+pub struct CommandInputPlugin;
+
+impl Plugin for CommandInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CommandHistory::load());
+        app.add_systems(
+            Update,
+            (
+                record_commands.before(super::emit_user_chat),
+                command_keys
+                    .in_set(SceneSets::PostLoop)
+                    .before(TextInputSystem),
+            ),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CommandHistory {
+    entries: VecDeque<String>,
+    // position while browsing, and the text typed before browsing started
+    cursor: Option<usize>,
+    draft: String,
+    // text last written to the input, to notice user edits
+    shown: Option<String>,
+}
+
+fn history_file() -> PathBuf {
+    project_directories()
+        .data_local_dir()
+        .join("console_history.txt")
+}
+
+impl CommandHistory {
+    fn load() -> Self {
+        let entries = std::fs::read_to_string(history_file())
+            .map(|content| {
+                content
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect::<VecDeque<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut history = Self {
+            entries,
+            ..Default::default()
+        };
+        history.truncate();
+        history
+    }
+
+    fn save(&self) {
+        let path = history_file();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let content = self
+            .entries
+            .iter()
+            .fold(String::new(), |mut content, entry| {
+                content.push_str(entry);
+                content.push('\n');
+                content
+            });
+        if let Err(e) = std::fs::write(&path, content) {
+            warn!("failed to save console history to {path:?}: {e}");
+        }
+    }
+
+    fn truncate(&mut self) {
+        while self.entries.len() > MAX_HISTORY {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn push(&mut self, command: &str) {
+        self.reset();
+        let command = command.trim();
+        if command.is_empty() || self.entries.back().map(String::as_str) == Some(command) {
+            return;
+        }
+        self.entries.push_back(command.to_owned());
+        self.truncate();
+        self.save();
+    }
+
+    fn reset(&mut self) {
+        self.cursor = None;
+        self.draft.clear();
+        self.shown = None;
+    }
+
+    // the entry to show after moving back (older) or forward (newer) from the current text
+    fn step(&mut self, current: &str, back: bool) -> Option<String> {
+        if self.shown.as_deref() != Some(current) {
+            // typed something since the last step, browse from the end again
+            self.cursor = None;
+        }
+
+        let next = match (self.cursor, back) {
+            (None, true) => {
+                self.draft = current.to_owned();
+                self.entries.len().checked_sub(1)?
+            }
+            (None, false) => return None,
+            (Some(ix), true) => ix.checked_sub(1)?,
+            (Some(ix), false) if ix + 1 < self.entries.len() => ix + 1,
+            (Some(_), false) => {
+                // past the newest entry, back to what was typed
+                self.cursor = None;
+                self.shown = None;
+                return Some(std::mem::take(&mut self.draft));
+            }
+        };
+
+        self.cursor = Some(next);
+        let entry = self.entries[next].clone();
+        self.shown = Some(entry.clone());
+        Some(entry)
+    }
+}
+
+fn record_commands(
+    submits: Query<&TextEntrySubmit, (With<ChatInput>, Added<TextEntrySubmit>)>,
+    mut history: ResMut<CommandHistory>,
+) {
+    for TextEntrySubmit(message) in submits.iter() {
+        if message.starts_with('/') {
+            history.push(message);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Completion {
+    Replace(String),
+    Candidates(Vec<String>),
+}
+
+// complete the last word against the candidates
+fn complete<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<Completion> {
+    let mut matches = candidates
+        .filter(|candidate| candidate.starts_with(word))
+        .collect::<Vec<_>>();
+    matches.sort_unstable();
+    matches.dedup();
+
+    match matches.as_slice() {
+        [] => None,
+        [only] => Some(Completion::Replace(format!("{only} "))),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(*first, |prefix, other| {
+                let len = prefix
+                    .char_indices()
+                    .zip(other.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .map(|((ix, a), _)| ix + a.len_utf8())
+                    .last()
+                    .unwrap_or(0);
+                &prefix[..len]
+            });
+            if common.len() > word.len() {
+                Some(Completion::Replace(common.to_owned()))
+            } else {
+                Some(Completion::Candidates(
+                    matches.into_iter().map(ToOwned::to_owned).collect(),
+                ))
+            }
+        }
+    }
+}
+
+fn complete_command(text: &str, config: &ConsoleConfiguration) -> Option<Completion> {
+    if !text.starts_with('/') {
+        return None;
+    }
+
+    let words = text.split(' ').collect::<Vec<_>>();
+    match words.as_slice() {
+        [name] => complete(
+            name,
+            config
+                .commands
+                .keys()
+                .copied()
+                .filter(|name| name.starts_with('/')),
+        ),
+        [name, arg] => {
+            // values of the first positional argument, if it has a fixed set
+            let values = config
+                .commands
+                .get(*name)?
+                .get_positionals()
+                .next()?
+                .get_possible_values();
+            let completion = complete(arg, values.iter().map(|value| value.get_name()))?;
+            Some(match completion {
+                Completion::Replace(arg) => Completion::Replace(format!("{name} {arg}")),
+                candidates => candidates,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn command_keys(
+    mut key_input: ResMut<ButtonInput<KeyCode>>,
+    entry: Query<&Children, With<ChatInput>>,
+    mut inputs: Query<(&mut TextInputValue, Has<Focus>)>,
+    mut history: ResMut<CommandHistory>,
+    config: Res<ConsoleConfiguration>,
+    mut print: EventWriter<PrintConsoleLine>,
+) {
+    let Some((mut text, true)) = entry
+        .get_single()
+        .ok()
+        .and_then(|children| children.iter().find(|c| inputs.contains(**c)))
+        .and_then(|input| inputs.get_mut(*input).ok())
+    else {
+        return;
+    };
+
+    if key_input.just_pressed(KeyCode::Tab) {
+        // the chat input has precedence over other uses of tab while focused
+        key_input.clear_just_pressed(KeyCode::Tab);
+        match complete_command(&text.0, &config) {
+            Some(Completion::Replace(completed)) => text.0 = completed,
+            Some(Completion::Candidates(candidates)) => {
+                print.send(PrintConsoleLine::new(candidates.join("  ").into()));
+            }
+            None => (),
+        }
+        return;
+    }
+
+    let back = match (
+        key_input.just_pressed(KeyCode::ArrowUp),
+        key_input.just_pressed(KeyCode::ArrowDown),
+    ) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => return,
+    };
+
+    if let Some(entry) = history.step(&text.0, back) {
+        text.0 = entry;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_complete() {
+        let candidates = ["/reload", "/realm", "/rec", "/report", "/rec", "/voice"];
+        let complete = |word| complete(word, candidates.iter().copied());
+
+        // only candidates starting with the word match, and a single match completes fully
+        assert_eq!(
+            complete("/v"),
+            Some(Completion::Replace("/voice ".to_owned()))
+        );
+        assert_eq!(complete("/x"), None);
+
+        // several matches complete to their common prefix, duplicates ignored
+        assert_eq!(complete("/r"), Some(Completion::Replace("/re".to_owned())));
+        assert_eq!(
+            complete("/rea"),
+            Some(Completion::Replace("/realm ".to_owned()))
+        );
+        assert_eq!(
+            complete("/rec"),
+            Some(Completion::Replace("/rec ".to_owned()))
+        );
+
+        // when there's nothing more in common, the sorted matches are listed
+        assert_eq!(
+            complete("/re"),
+            Some(Completion::Candidates(vec![
+                "/realm".to_owned(),
+                "/rec".to_owned(),
+                "/reload".to_owned(),
+                "/report".to_owned(),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_command_history_step() {
+        let mut history = CommandHistory {
+            entries: ["/one", "/two", "/three"]
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect(),
+            ..Default::default()
+        };
+
+        // forward with nothing browsed does nothing
+        assert_eq!(history.step("/dr", false), None);
+
+        // back walks from the newest entry and stops at the oldest
+        assert_eq!(history.step("/dr", true).as_deref(), Some("/three"));
+        assert_eq!(history.step("/three", true).as_deref(), Some("/two"));
+        assert_eq!(history.step("/two", true).as_deref(), Some("/one"));
+        assert_eq!(history.step("/one", true), None);
+        assert_eq!(history.step("/one", true), None);
+
+        // forward walks back to the newest entry and then to what was typed
+        assert_eq!(history.step("/one", false).as_deref(), Some("/two"));
+        assert_eq!(history.step("/two", false).as_deref(), Some("/three"));
+        assert_eq!(history.step("/three", false).as_deref(), Some("/dr"));
+        assert_eq!(history.step("/dr", false), None);
+
+        // editing a shown entry starts browsing from the newest entry again
+        assert_eq!(history.step("/dr", true).as_deref(), Some("/three"));
+        assert_eq!(history.step("/three", true).as_deref(), Some("/two"));
+        assert_eq!(history.step("/two x", true).as_deref(), Some("/three"));
+        assert_eq!(history.step("/three", false).as_deref(), Some("/two x"));
+
+        // nothing to browse in an empty history
+        let mut history = CommandHistory::default();
+        assert_eq!(history.step("/dr", true), None);
+        assert_eq!(history.step("/dr", false), None);
+    }
+}
@@ -1,13 +1,11 @@
 use bevy::{core::FrameCount, ecs::system::SystemParam, prelude::*};
 use bevy_dui::{DuiCommandsExt, DuiEntities, DuiProps, DuiRegistry};
 use common::{structs::ShowProfileEvent, util::TryPushChildrenEx};
-use copypasta::{ClipboardContext, ClipboardProvider};
 use ethers_core::types::Address;
-use scene_runner::Toaster;
 use ui_core::ui_actions::{Click, EventCloneExt, On, UiCaller};
 use wallet::Wallet;
 
-use crate::chat::friends::PendingProfileUiImage;
+use crate::{chat::friends::PendingProfileUiImage, clipboard::copy_on_click};
 
 use super::friends::PrivateChat;
 
@@ -212,27 +210,7 @@ impl ConversationManager<'_, '_> {
                 },
                 DuiProps::new()
                     .with_prop("text", message_body.clone())
-                    .with_prop(
-                        "copy",
-                        On::<Click>::new(move |mut toaster: Toaster, frame: Res<FrameCount>| {
-                            let Ok(mut ctx) = ClipboardContext::new() else {
-                                warn!("failed to copy");
-                                return;
-                            };
-
-                            if ctx.set_contents(message_body.clone()).is_ok() {
-                                toaster.add_toast(
-                                    format!("chatcopy {}", frame.0),
-                                    "Message copied to clipboard",
-                                );
-                            } else {
-                                toaster.add_toast(
-                                    format!("chatcopy {}", frame.0),
-                                    "Failed to copy message",
-                                );
-                            }
-                        }),
-                    ),
+                    .with_prop("copy", copy_on_click(message_body, "Message")),
             )
             .unwrap()
             .root;
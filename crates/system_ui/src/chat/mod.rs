@@ -1,3 +1,4 @@
+pub mod command_input;
 pub mod conversation_manager;
 pub mod friends;
 pub mod history;
@@ -6,6 +7,7 @@ use bevy::{color::palettes::css, prelude::*};
 
 use bevy_console::{ConsoleCommand, ConsoleCommandEntered, ConsoleConfiguration, PrintConsoleLine};
 use bevy_dui::{DuiCommandsExt, DuiEntities, DuiProps, DuiRegistry};
+use command_input::CommandInputPlugin;
 use common::{
     dcl_assert,
     structs::{PrimaryUser, SystemAudio, ToolTips, TooltipSource},
@@ -53,7 +55,7 @@ impl Plugin for ChatPanelPlugin {
         app.add_systems(Update, keyboard_popup.run_if(should_accept_key));
         app.add_console_command::<Rechat, _>(debug_chat);
         app.add_event::<PrivateChatEntered>();
-        app.add_plugins((FriendsPlugin, ChatHistoryPlugin));
+        app.add_plugins((FriendsPlugin, ChatHistoryPlugin, CommandInputPlugin));
     }
 }
 
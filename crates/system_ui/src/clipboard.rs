@@ -0,0 +1,30 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use bevy::prelude::*;
+use copypasta::{ClipboardContext, ClipboardProvider};
+use scene_runner::Toaster;
+use ui_core::ui_actions::{Click, On};
+
+// copy text to the platform clipboard, confirming with a toast. `what` names the
+// copied value in the toast, e.g. "Address"
+pub fn copy_to_clipboard(text: impl Into<String>, what: &str, toaster: &mut Toaster) {
+    // each copy gets its own toast, so repeated copies are confirmed rather than merged
+    static COPIES: AtomicU32 = AtomicU32::new(0);
+    let key = format!("clipboard-{}", COPIES.fetch_add(1, Ordering::Relaxed));
+
+    let result = ClipboardContext::new().and_then(|mut ctx| ctx.set_contents(text.into()));
+    match result {
+        Ok(()) => toaster.add_toast(key, format!("{what} copied to clipboard")),
+        Err(e) => {
+            warn!("failed to copy to clipboard: {e}");
+            toaster.add_toast(key, format!("Failed to copy {}", what.to_lowercase()));
+        }
+    }
+}
+
+// click action copying the given text
+pub fn copy_on_click(text: String, what: &'static str) -> On<Click> {
+    On::<Click>::new(move |mut toaster: Toaster| {
+        copy_to_clipboard(text.clone(), what, &mut toaster);
+    })
+}
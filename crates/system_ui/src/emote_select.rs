@@ -1,4 +1,4 @@
-use avatar::animate::{EmoteBroadcast, EmoteList};
+use avatar::animate::PlayerEmote;
 use bevy::{
     color::palettes::css,
     prelude::*,
@@ -10,7 +10,7 @@ use bevy::{
 use bevy_dui::{DuiComponentFromClone, DuiEntityCommandsExt, DuiProps, DuiRegistry};
 use collectibles::{CollectibleError, CollectibleManager, Emote, EmoteUrn};
 use common::{
-    structs::{ActiveDialog, PrimaryUser, SystemAudio},
+    structs::{ActiveDialog, SystemAudio},
     util::{FireEventEx, ModifyComponentExt},
 };
 use comms::profile::CurrentUserProfile;
@@ -76,8 +76,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut dui: ResMut
 
 #[allow(clippy::too_many_arguments)]
 fn handle_emote_key(
-    mut commands: Commands,
-    player: Query<Entity, With<PrimaryUser>>,
+    mut player_emote: PlayerEmote,
     key_input: Res<ButtonInput<KeyCode>>,
     window: Query<&Window, With<PrimaryWindow>>,
     mut w: EventWriter<EmoteUiEvent>,
@@ -128,9 +127,7 @@ fn handle_emote_key(
         for (emote_key, slot) in EMOTE_KEYS {
             if key_input.just_pressed(emote_key) {
                 if let Some(button) = buttons.iter().find(|b| b.1 == slot) {
-                    commands
-                        .entity(player.single())
-                        .try_insert(EmoteList::new(button.0.clone(), EmoteBroadcast::All));
+                    player_emote.play(button.0.clone(), false);
                     w.send(EmoteUiEvent::Hide);
                 }
             }
@@ -237,7 +234,7 @@ fn show_emote_ui(
     mut emote_loader: CollectibleManager<Emote>,
     asset_server: Res<AssetServer>,
     buttons: Query<(&EmoteButton, &Interaction)>,
-    player: Query<Entity, With<PrimaryUser>>,
+    mut player_emote: PlayerEmote,
     mut retry: Local<Option<EmoteUiEvent>>,
     active_dialog: Res<ActiveDialog>,
 ) {
@@ -253,9 +250,7 @@ fn show_emote_ui(
 
             for (button, interact) in &buttons {
                 if interact == &Interaction::Hovered || interact == &Interaction::Pressed {
-                    commands
-                        .entity(player.single())
-                        .try_insert(EmoteList::new(button.0.clone(), EmoteBroadcast::All));
+                    player_emote.play(button.0.clone(), false);
                 }
             }
         }
@@ -6,10 +6,13 @@ use common::{
     structs::{ActiveDialog, ShowProfileEvent, PROFILE_UI_RENDERLAYER},
     util::FireEventEx,
 };
-use comms::profile::{ProfileManager, UserProfile};
+use comms::profile::{CurrentUserProfile, ProfileManager, UserProfile};
 use ethers_core::types::Address;
 use social::{FriendshipEvent, FriendshipState, SocialClient};
 use ui_core::button::DuiButton;
+use wallet::Wallet;
+
+use crate::clipboard::copy_on_click;
 
 pub struct ForeignProfilePlugin;
 
@@ -35,6 +38,8 @@ fn show_foreign_profiles(
     active_dialog: Res<ActiveDialog>,
     mut photo_booth: PhotoBooth,
     dui: Res<DuiRegistry>,
+    wallet: Res<Wallet>,
+    current_profile: Res<CurrentUserProfile>,
 ) {
     pending_events.extend(evs.read().map(|ev| ev.0));
 
@@ -44,14 +49,23 @@ fn show_foreign_profiles(
             let default_profile = UserProfile {
                 content: SerializedProfile {
                     name: "Profile could not be loaded ...".to_owned(),
+                    eth_address: format!("{address:#x}"),
                     ..Default::default()
                 },
                 ..Default::default()
             };
-            let profile = match cache.get_data(address) {
-                Ok(None) => return true,
-                Err(_) => &default_profile,
-                Ok(Some(profile)) => profile,
+            // our own profile may not be deployed (e.g. guests), so use the local copy
+            let own_profile = current_profile
+                .profile
+                .as_ref()
+                .filter(|_| wallet.address() == Some(address));
+            let profile = match own_profile {
+                Some(profile) => profile,
+                None => match cache.get_data(address) {
+                    Ok(None) => return true,
+                    Err(_) => &default_profile,
+                    Ok(Some(profile)) => profile,
+                },
             };
 
             let Some(permit) = active_dialog.try_acquire() else {
@@ -73,7 +87,17 @@ fn show_foreign_profiles(
                     DuiProps::new()
                         .with_prop("title", format!("{} profile", profile.content.name))
                         .with_prop("booth-instance", instance)
-                        .with_prop("eth-address", profile.content.eth_address.clone())
+                        .with_prop("name", profile_name(&profile.content))
+                        .with_prop("name-status", name_status(&profile.content).to_owned())
+                        .with_prop(
+                            "eth-address",
+                            truncate_address(&profile.content.eth_address),
+                        )
+                        .with_prop(
+                            "copy-address",
+                            copy_on_click(profile.content.eth_address.clone(), "Address"),
+                        )
+                        .with_prop("wearables", wearable_list(&profile.content))
                         .with_prop(
                             "buttons",
                             vec![
@@ -166,9 +190,56 @@ fn show_foreign_profiles(
         .collect();
 }
 
+fn is_guest(profile: &SerializedProfile) -> bool {
+    profile.has_connected_web3 == Some(false)
+}
+
+fn profile_name(profile: &SerializedProfile) -> String {
+    if profile.has_claimed_name || profile.eth_address.len() < 4 {
+        profile.name.clone()
+    } else {
+        // unclaimed names are shown with the address suffix, as on the name tag
+        let suffix = &profile.eth_address[profile.eth_address.len() - 4..];
+        format!("{}#{suffix}", profile.name)
+    }
+}
+
+fn name_status(profile: &SerializedProfile) -> &'static str {
+    if is_guest(profile) {
+        "Guest (no wallet connected)"
+    } else if profile.has_claimed_name {
+        "Claimed name"
+    } else {
+        "Unclaimed name"
+    }
+}
+
+fn truncate_address(address: &str) -> String {
+    if address.len() <= 12 {
+        return address.to_owned();
+    }
+    format!("{}...{}", &address[..6], &address[address.len() - 4..])
+}
+
+// equipped wearables by name, taken from the last part of the urn
+fn wearable_list(profile: &SerializedProfile) -> String {
+    let wearables = profile
+        .avatar
+        .wearables
+        .iter()
+        .map(|urn| urn.rsplit(':').next().unwrap_or(urn))
+        .collect::<Vec<_>>();
+    if wearables.is_empty() {
+        "none".to_owned()
+    } else {
+        wearables.join("\n")
+    }
+}
+
 fn update_profile_friend_buttons(
     q: Query<(Ref<ProfileDialog>, &DuiEntities)>,
     client: Res<SocialClient>,
+    wallet: Res<Wallet>,
     mut events: EventReader<FriendshipEvent>,
     children: Query<&Children>,
     mut style: Query<&mut Style>,
@@ -192,7 +263,12 @@ fn update_profile_friend_buttons(
         return;
     };
 
-    let state = client.get_state(profile.0);
+    // no friendship actions on our own profile
+    let state = if wallet.address() == Some(profile.0) {
+        FriendshipState::Error
+    } else {
+        client.get_state(profile.0)
+    };
     for (index, req_state) in [
         // add
         (0, FriendshipState::NotFriends),    //add
@@ -1,6 +1,8 @@
 pub mod app_settings;
+pub mod avatar_menu;
 pub mod change_realm;
 pub mod chat;
+pub mod clipboard;
 pub mod crash_report;
 pub mod discover;
 pub mod emote_select;
@@ -8,18 +10,22 @@ pub mod emotes;
 pub mod foreign_profile;
 pub mod login;
 pub mod map;
+pub mod metrics_export;
 pub mod mic;
 pub mod oow;
+pub mod output_log;
 pub mod permission_manager;
 pub mod permissions;
 pub mod profile;
 pub mod profile_detail;
+pub mod scene_report;
 pub mod sysinfo;
 pub mod toasts;
 pub mod tooltip;
 pub mod version_check;
 pub mod wearables;
 
+use avatar_menu::AvatarMenuPlugin;
 use bevy::prelude::*;
 
 use change_realm::ChangeRealmPlugin;
@@ -32,10 +38,13 @@ use foreign_profile::ForeignProfilePlugin;
 use input_manager::MouseInteractionComponent;
 use login::LoginPlugin;
 use map::MapPlugin;
+use metrics_export::MetricsExportPlugin;
 use mic::MicUiPlugin;
 use oow::OowUiPlugin;
+use output_log::OutputLogPlugin;
 use permission_manager::PermissionPlugin;
 use profile_detail::ProfileDetailPlugin;
+use scene_report::SceneReportPlugin;
 use toasts::ToastsPlugin;
 use tooltip::ToolTipPlugin;
 
@@ -68,6 +77,12 @@ impl Plugin for SystemUiPlugin {
             PermissionPlugin,
             ForeignProfilePlugin,
         ));
+        app.add_plugins((
+            MetricsExportPlugin,
+            AvatarMenuPlugin,
+            SceneReportPlugin,
+            OutputLogPlugin,
+        ));
     }
 }
 
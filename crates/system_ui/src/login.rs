@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
 use analytics::segment_system::SegmentConfig;
 use bevy::{
@@ -7,14 +7,16 @@ use bevy::{
     tasks::{IoTaskPool, Task},
     window::PrimaryWindow,
 };
+use bevy_console::ConsoleCommand;
 use bevy_dui::{DuiCommandsExt, DuiEntityCommandsExt, DuiProps, DuiRegistry};
 use common::{
     profile::SerializedProfile,
     rpc::RpcResultSender,
     structs::{ActiveDialog, AppConfig, ChainLink, DialogPermit, PreviousLogin, SystemAudio},
-    util::{config_file, FireEventEx, TaskExt},
+    util::{FireEventEx, TaskExt},
 };
 use comms::profile::{get_remote_profile, CurrentUserProfile, UserProfile};
+use console::DoAddConsoleCommand;
 use ethers_core::types::Address;
 use ethers_signers::LocalWallet;
 use ipfs::{CurrentRealm, IpfsAssetServer};
@@ -26,7 +28,9 @@ use ui_core::{
     ui_actions::{close_ui_happy, Click, EventCloneExt, On},
 };
 use wallet::{
-    browser_auth::{finish_remote_ephemeral_request, init_remote_ephemeral_request},
+    browser_auth::{
+        auth_chain_expired, finish_remote_ephemeral_request, init_remote_ephemeral_request,
+    },
     Wallet,
 };
 
@@ -43,6 +47,27 @@ impl Plugin for LoginPlugin {
                 process_system_bridge,
             ),
         );
+        app.add_console_command::<LogoutCommand, _>(logout_command);
+    }
+}
+
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/logout")]
+struct LogoutCommand;
+
+fn logout_command(
+    mut input: ConsoleCommand<LogoutCommand>,
+    wallet: Res<Wallet>,
+    mut bridge: EventWriter<SystemApi>,
+) {
+    if let Some(Ok(_)) = input.take() {
+        if wallet.address().is_none() {
+            input.reply_failed("not logged in");
+            return;
+        }
+
+        bridge.send(SystemApi::Logout);
+        input.reply_ok("logged out");
     }
 }
 
@@ -70,6 +95,7 @@ fn login(
     mut motd_shown: Local<bool>,
     mut bridge: EventWriter<SystemApi>,
     native_active: Res<NativeUi>,
+    config: Res<AppConfig>,
 ) {
     if !native_active.login {
         return;
@@ -137,15 +163,14 @@ fn login(
             return;
         };
 
-        let previous_login = get_previous_login();
-
+        // expired logins are still offered, and prompt for a new login when chosen
         let mut dlg = commands.spawn(permit);
         *dialog = Some(dlg.id());
         dlg.apply_template(
             &dui,
             "login",
             DuiProps::new()
-                .with_prop("allow-reuse", previous_login.is_some())
+                .with_prop("allow-reuse", config.previous_login.is_some())
                 .with_prop("reuse", LoginType::ExistingRemote.send_value_on::<Click>())
                 .with_prop("connect", LoginType::NewRemote.send_value_on::<Click>())
                 .with_prop("guest", LoginType::Guest.send_value_on::<Click>())
@@ -235,6 +260,17 @@ fn login(
             *dialog = None;
         }
 
+        let login = match login {
+            LoginType::ExistingRemote if previous_login_expired(&config) => {
+                toaster.add_toast(
+                    "login profile",
+                    "Your previous session has expired, please sign in again",
+                );
+                &LoginType::NewRemote
+            }
+            login => login,
+        };
+
         match login {
             LoginType::ExistingRemote => {
                 info!("existing remote");
@@ -328,42 +364,22 @@ fn update_profile_for_realm(
     }
 }
 
-fn get_previous_login() -> Option<PreviousLogin> {
-    let previous_login = std::fs::read(config_file())
-        .ok()
-        .and_then(|f| serde_json::from_slice::<AppConfig>(&f).ok())
-        .unwrap_or_default()
-        .previous_login;
-
-    let mut expired = false;
-    if let Some(prev) = previous_login.as_ref() {
-        for link in &prev.auth {
-            if link.ty == "ECDSA_EPHEMERAL" {
-                for line in link.payload.lines() {
-                    if line.starts_with("Expiration:") {
-                        let exp = line.split_once(':').unwrap().1;
-                        if let Ok(exp) = chrono::DateTime::<chrono::Utc>::from_str(exp.trim()) {
-                            let now: chrono::DateTime<chrono::Utc> =
-                                std::time::SystemTime::now().into();
-                            if now > exp {
-                                warn!("previous login expired, removing");
-                                expired = true;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+// previous login, if it hasn't expired
+fn get_previous_login(config: &AppConfig) -> Option<PreviousLogin> {
+    config
+        .previous_login
+        .clone()
+        .filter(|login| !auth_chain_expired(&login.auth, SystemTime::now()))
+}
 
-    if expired {
-        None
-    } else {
-        previous_login
-    }
+fn previous_login_expired(config: &AppConfig) -> bool {
+    config
+        .previous_login
+        .as_ref()
+        .is_some_and(|login| auth_chain_expired(&login.auth, SystemTime::now()))
 }
 
-#[allow(clippy::type_complexity)]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn process_system_bridge(
     mut e: EventReader<SystemApi>,
     ipfas: IpfsAssetServer,
@@ -387,6 +403,7 @@ fn process_system_bridge(
     mut segment_config: ResMut<SegmentConfig>,
     mut current_profile: ResMut<CurrentUserProfile>,
     mut window: Query<&mut Window, With<PrimaryWindow>>,
+    mut config: ResMut<AppConfig>,
 ) {
     for ev in e.read().cloned() {
         match ev {
@@ -400,13 +417,19 @@ fn process_system_bridge(
             }
             SystemApi::GetPreviousLogin(rpc_result_sender) => {
                 rpc_result_sender
-                    .send(get_previous_login().map(|pl| format!("{:#x}", pl.root_address)));
+                    .send(get_previous_login(&config).map(|pl| format!("{:#x}", pl.root_address)));
             }
             SystemApi::LoginPrevious(rpc_result_sender) => {
                 let ipfs = ipfas.ipfs().clone();
+                let previous_login = get_previous_login(&config);
+                let expired = previous_login_expired(&config);
                 *login_task = Some(IoTaskPool::get().spawn(async move {
-                    let Some(previous_login) = get_previous_login() else {
-                        rpc_result_sender.send(Err("No Previous Login Available".to_string()));
+                    let Some(previous_login) = previous_login else {
+                        rpc_result_sender.send(Err(if expired {
+                            "Previous Login Expired".to_string()
+                        } else {
+                            "No Previous Login Available".to_string()
+                        }));
                         return Err(());
                     };
 
@@ -431,8 +454,11 @@ fn process_system_bridge(
             }
             SystemApi::LoginNew(code_sender, result_sender) => {
                 let ipfs = ipfas.ipfs().clone();
+                // a lifetime of 0 would expire the login immediately
+                let lifetime_days = config.login_lifetime_days.max(1);
+                let lifetime = Duration::from_secs(lifetime_days as u64 * 24 * 3600);
                 *login_task = Some(IoTaskPool::get().spawn(async move {
-                    let req = init_remote_ephemeral_request().await;
+                    let req = init_remote_ephemeral_request(lifetime).await;
                     let req = match req {
                         Err(e) => {
                             code_sender.send(Err(e.to_string()));
@@ -478,9 +504,13 @@ fn process_system_bridge(
                 *login_task = None;
             }
             SystemApi::Logout => {
+                // comms drops its transports when the identity is cleared, and the login
+                // dialog is shown again while there is no address
                 *login_task = None;
                 wallet.disconnect();
                 current_profile.profile = None;
+                // an explicit logout shouldn't be offered for reuse
+                config.previous_login = None;
             }
             _ => (),
         }
@@ -496,23 +526,11 @@ fn process_system_bridge(
                 let ephemeral_key = local_wallet.signer().to_bytes().to_vec();
 
                 // store to app config
-                let mut config: AppConfig = std::fs::read(config_file())
-                    .ok()
-                    .and_then(|f| serde_json::from_slice(&f).ok())
-                    .unwrap_or_default();
                 config.previous_login = Some(PreviousLogin {
                     root_address,
                     ephemeral_key,
                     auth: auth.clone(),
                 });
-                let config_file = config_file();
-                if let Some(folder) = config_file.parent() {
-                    std::fs::create_dir_all(folder).unwrap();
-                }
-                if let Err(e) = std::fs::write(config_file, serde_json::to_string(&config).unwrap())
-                {
-                    warn!("failed to write to config: {e}");
-                }
 
                 wallet.finalize(root_address, local_wallet, auth);
                 segment_config.update_identity(format!("{:#x}", wallet.address().unwrap()), false);
@@ -0,0 +1,292 @@
+// optional metrics export for operators running unattended instances (kiosks, servers).
+// enabled via `metrics` in the config: a json snapshot is served on a local http port
+// and/or logged periodically. when disabled the only cost is a run condition check per frame.
+
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::TcpListener,
+    sync::{atomic::Ordering, Arc, Mutex, Weak},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use bevy::{
+    app::AppExit,
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+use common::structs::AppConfig;
+use comms::{global_crdt::ForeignPlayer, packet_log::PACKET_COUNTERS, Transport};
+use ipfs::{IpfsAssetServer, IpfsIo};
+use scene_runner::{initialize_scene::SceneLoading, renderer_context::RendererSceneContext};
+use serde::Serialize;
+
+// how often the snapshot is refreshed, in seconds
+const SAMPLE_INTERVAL: f32 = 1.0;
+// how often the http thread checks for connections and shutdown
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct MetricsExportPlugin;
+
+impl Plugin for MetricsExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MetricsServer>();
+        app.add_systems(
+            Last,
+            (
+                export_metrics.run_if(|config: Res<AppConfig>| config.metrics.enabled()),
+                stop_metrics_server.run_if(|server: Res<MetricsServer>| server.0.is_some()),
+            )
+                .chain(),
+        );
+    }
+}
+
+// metric names are the json keys. counters are totals since startup
+#[derive(Serialize, Default)]
+struct Metrics {
+    // seconds since startup
+    uptime_s: f32,
+    // smoothed frames per second
+    fps: f64,
+    // duration of the last frame, in milliseconds
+    frame_time_ms: f32,
+    // number of scenes in each state
+    scenes_loading: usize,
+    scenes_running: usize,
+    scenes_blocked: usize,
+    scenes_broken: usize,
+    // connected comms transports
+    comms_transports: usize,
+    // remote players currently known
+    comms_peers: usize,
+    // comms packets and bytes sent and received
+    comms_packets_sent: u64,
+    comms_bytes_sent: u64,
+    comms_packets_received: u64,
+    comms_bytes_received: u64,
+    // content requests served from the local cache, fetched, and failed
+    content_cache_hits: u64,
+    content_requests_ok: u64,
+    content_requests_failed: u64,
+    // bytes downloaded by content requests
+    content_bytes_downloaded: u64,
+    // bytes of content in the local cache
+    content_cache_bytes: u64,
+    // per-scene metrics
+    scenes: Vec<SceneMetrics>,
+}
+
+#[derive(Serialize)]
+struct SceneMetrics {
+    hash: String,
+    title: String,
+    // scene ticks completed
+    ticks: u32,
+    // real time from sending the scene its last update to receiving the response, in ms
+    tick_time_ms: f32,
+    // scene runtime, in seconds
+    runtime_s: f32,
+}
+
+// the http thread, with its port and the latest snapshot it serves
+struct RunningServer {
+    port: u16,
+    snapshot: Arc<Mutex<String>>,
+    thread: JoinHandle<()>,
+}
+
+#[derive(Resource, Default)]
+struct MetricsServer(Option<RunningServer>);
+
+impl MetricsServer {
+    // drop the snapshot so the thread exits, and wait for it
+    fn stop(&mut self) {
+        if let Some(RunningServer {
+            snapshot, thread, ..
+        }) = self.0.take()
+        {
+            drop(snapshot);
+            let _ = thread.join();
+        }
+    }
+}
+
+#[derive(Default)]
+struct ExportState {
+    last_sample: Option<f32>,
+    last_log: f32,
+    cache_measured: bool,
+}
+
+// serve the latest snapshot to any request. the thread exits once the snapshot is dropped
+fn serve_metrics(port: u16, snapshot: Weak<Mutex<String>>) {
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            warn!("failed to start metrics server on port {port}: {e}");
+            return;
+        }
+    };
+    if let Err(e) = listener.set_nonblocking(true) {
+        warn!("failed to start metrics server on port {port}: {e}");
+        return;
+    }
+    info!("serving metrics on http://127.0.0.1:{port}/");
+
+    loop {
+        let Some(snapshot) = snapshot.upgrade() else {
+            return;
+        };
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                if e.kind() != ErrorKind::WouldBlock {
+                    debug!("metrics connection failed: {e}");
+                }
+                drop(snapshot);
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+        let _ = stream.set_nonblocking(false);
+
+        // we serve the same data for any request, so just consume the request header
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+        let _ = stream.read(&mut [0; 1024]);
+
+        let body = snapshot.lock().unwrap().clone();
+        let _ = write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+    }
+}
+
+// total size of the files already in the cache folder when metrics are first enabled.
+// later additions are counted as they are written
+fn measure_cache(ipfs: Arc<IpfsIo>) {
+    std::thread::spawn(move || {
+        let Ok(entries) = std::fs::read_dir(ipfs.cache_path()) else {
+            return;
+        };
+        let total = entries
+            .flatten()
+            .filter_map(|entry| entry.metadata().ok())
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len())
+            .sum();
+        ipfs.stats().cache_bytes.fetch_add(total, Ordering::Relaxed);
+    });
+}
+
+fn stop_metrics_server(
+    config: Res<AppConfig>,
+    mut exit: EventReader<AppExit>,
+    mut server: ResMut<MetricsServer>,
+) {
+    let port_disabled = config.metrics.http_port == 0;
+    if exit.read().count() > 0 || port_disabled {
+        server.stop();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn export_metrics(
+    config: Res<AppConfig>,
+    time: Res<Time<Real>>,
+    diagnostics: Res<DiagnosticsStore>,
+    scenes: Query<(&RendererSceneContext, Has<SceneLoading>)>,
+    transports: Query<(), With<Transport>>,
+    players: Query<(), With<ForeignPlayer>>,
+    ipfas: IpfsAssetServer,
+    mut server: ResMut<MetricsServer>,
+    mut state: Local<ExportState>,
+) {
+    if !state.cache_measured {
+        state.cache_measured = true;
+        measure_cache(ipfas.ipfs().clone());
+    }
+
+    let now = time.elapsed_seconds();
+    if state
+        .last_sample
+        .is_some_and(|last| now - last < SAMPLE_INTERVAL)
+    {
+        return;
+    }
+    state.last_sample = Some(now);
+
+    let mut metrics = Metrics {
+        uptime_s: now,
+        fps: diagnostics
+            .get(&FrameTimeDiagnosticsPlugin::FPS)
+            .and_then(|fps| fps.smoothed())
+            .unwrap_or_else(|| 1.0 / time.delta_seconds_f64().max(f64::EPSILON)),
+        frame_time_ms: time.delta_seconds() * 1000.0,
+        comms_transports: transports.iter().count(),
+        comms_peers: players.iter().count(),
+        comms_packets_sent: PACKET_COUNTERS.packets_sent.load(Ordering::Relaxed),
+        comms_bytes_sent: PACKET_COUNTERS.bytes_sent.load(Ordering::Relaxed),
+        comms_packets_received: PACKET_COUNTERS.packets_received.load(Ordering::Relaxed),
+        comms_bytes_received: PACKET_COUNTERS.bytes_received.load(Ordering::Relaxed),
+        ..Default::default()
+    };
+
+    let stats = ipfas.ipfs().stats();
+    metrics.content_cache_hits = stats.cache_hits.load(Ordering::Relaxed);
+    metrics.content_requests_ok = stats.remote_ok.load(Ordering::Relaxed);
+    metrics.content_requests_failed = stats.remote_failed.load(Ordering::Relaxed);
+    metrics.content_bytes_downloaded = stats.bytes_downloaded.load(Ordering::Relaxed);
+    metrics.content_cache_bytes = stats.cache_bytes.load(Ordering::Relaxed);
+
+    for (context, loading) in scenes.iter() {
+        if loading {
+            metrics.scenes_loading += 1;
+        } else if context.broken {
+            metrics.scenes_broken += 1;
+        } else if !context.blocked.is_empty() {
+            metrics.scenes_blocked += 1;
+        } else {
+            metrics.scenes_running += 1;
+        }
+
+        metrics.scenes.push(SceneMetrics {
+            hash: context.hash.clone(),
+            title: context.title.clone(),
+            ticks: context.tick_number,
+            tick_time_ms: context.last_tick_time * 1000.0,
+            runtime_s: context.total_runtime,
+        });
+    }
+
+    let Ok(json) = serde_json::to_string(&metrics) else {
+        return;
+    };
+
+    let log_interval = config.metrics.log_interval;
+    if log_interval > 0.0 && now - state.last_log >= log_interval {
+        state.last_log = now;
+        info!("metrics: {json}");
+    }
+
+    let port = config.metrics.http_port;
+    if port == 0 {
+        return;
+    }
+    if server.0.as_ref().map(|running| running.port) != Some(port) {
+        server.stop();
+        let snapshot = Arc::new(Mutex::new(String::default()));
+        let weak = Arc::downgrade(&snapshot);
+        let thread = std::thread::spawn(move || serve_metrics(port, weak));
+        server.0 = Some(RunningServer {
+            port,
+            snapshot,
+            thread,
+        });
+    }
+    if let Some(running) = server.0.as_ref() {
+        *running.snapshot.lock().unwrap() = json;
+    }
+}
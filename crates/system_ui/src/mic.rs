@@ -1,10 +1,12 @@
 use av::microphone::MicState;
 use bevy::prelude::*;
 use common::{
-    structs::{SystemAudio, ToolTips, TooltipSource},
+    structs::{
+        AppConfig, MicrophoneSetting, SystemAudio, ToolTips, TooltipSource, VoiceChatSetting,
+    },
     util::FireEventEx,
 };
-use comms::{Transport, TransportType};
+use comms::{global_crdt::LocalAudioSource, Transport, TransportType};
 use ui_core::ui_actions::{Click, HoverEnter, HoverExit, On};
 
 use crate::chat::BUTTON_SCALE;
@@ -14,6 +16,10 @@ pub struct MicUiPlugin;
 #[derive(Component)]
 pub struct MicUiMarker;
 
+// shown whenever audio is actually captured or published, whatever the button state
+#[derive(Component)]
+pub struct MicInUseMarker;
+
 #[derive(Resource)]
 pub struct MicImages {
     inactive: Handle<Image>,
@@ -61,17 +67,24 @@ fn setup(mut commands: Commands, images: Res<MicImages>) {
             }
         }),
         On::<HoverEnter>::new(
-            |mut tooltip: ResMut<ToolTips>, transport: Query<&Transport>, state: Res<MicState>| {
-                let transport_available = transport
-                    .iter()
-                    .any(|t| t.transport_type == TransportType::Livekit);
-                tooltip.0.insert(
-                    TooltipSource::Label("mic"),
-                    vec![(
+            |mut tooltip: ResMut<ToolTips>,
+             transport: Query<&Transport>,
+             state: Res<MicState>,
+             config: Res<AppConfig>| {
+                let tip = if config.network.voice_chat == VoiceChatSetting::Disabled {
+                    ("Voice chat disabled".to_owned(), false)
+                } else if config.audio.microphone == MicrophoneSetting::Disabled {
+                    ("Microphone disabled in settings".to_owned(), false)
+                } else {
+                    let transport_available = transport
+                        .iter()
+                        .any(|t| t.transport_type == TransportType::Livekit);
+                    (
                         "LCtrl : Push to talk".to_owned(),
                         transport_available && state.available,
-                    )],
-                );
+                    )
+                };
+                tooltip.0.insert(TooltipSource::Label("mic"), vec![tip]);
             },
         ),
         On::<HoverExit>::new(|mut tooltip: ResMut<ToolTips>| {
@@ -79,6 +92,26 @@ fn setup(mut commands: Commands, images: Res<MicImages>) {
         }),
         MicUiMarker,
     ));
+
+    // in-use indicator over the top corner of the button
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::VMin(BUTTON_SCALE * 1.5),
+                right: Val::VMin(BUTTON_SCALE * 0.5),
+                width: Val::VMin(BUTTON_SCALE * 0.3),
+                height: Val::VMin(BUTTON_SCALE * 0.3),
+                ..Default::default()
+            },
+            background_color: Color::srgb(0.9, 0.1, 0.1).into(),
+            border_radius: BorderRadius::MAX,
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Local(1),
+            ..Default::default()
+        },
+        MicInUseMarker,
+    ));
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -91,11 +124,28 @@ fn update_mic_ui(
     input: Res<ButtonInput<KeyCode>>,
     mic_images: Res<MicImages>,
     mut prev_active: Local<bool>,
+    config: Res<AppConfig>,
+    mic: Res<LocalAudioSource>,
+    mut in_use: Query<&mut Visibility, With<MicInUseMarker>>,
 ) {
-    let mic_available = mic_state.available;
-    let transport_available = transport
-        .iter()
-        .any(|t| t.transport_type == TransportType::Livekit);
+    // reflects the device and the connection rather than the toggle
+    let capturing = mic_state.capturing || mic.is_publishing();
+    for mut visibility in in_use.iter_mut() {
+        visibility.set_if_neq(if capturing {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+
+    // hard-muted, the mic shows as unavailable
+    let mic_available =
+        mic_state.available && config.audio.microphone == MicrophoneSetting::Enabled;
+    // with voice chat disabled the mic shows as unavailable
+    let transport_available = config.network.voice_chat == VoiceChatSetting::Enabled
+        && transport
+            .iter()
+            .any(|t| t.transport_type == TransportType::Livekit);
 
     if mic_available && transport_available {
         if mic_state.enabled {
@@ -0,0 +1,252 @@
+// optional persistent log of chat and console output, and scene logs at or above a level, for
+// diagnosing intermittent issues after the app has exited. enabled via `output_log` in the
+// config. lines are timestamped and written to `logs/` in the local data directory, starting
+// a new file each day or when the current one reaches `max_file_mb`, and removing the oldest
+// files beyond `max_total_mb`. anything that looks like a private key is redacted.
+
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{LineWriter, Write},
+    path::PathBuf,
+};
+
+use bevy::{prelude::*, utils::HashMap};
+use chrono::{DateTime, Local, NaiveDate};
+use common::{
+    structs::{AppConfig, SceneLogFilter},
+    util::{project_directories, RingBufferReceiver},
+};
+use comms::{chat_marker_things, global_crdt::ChatEvent, profile::UserProfile};
+use dcl::{SceneLogLevel, SceneLogMessage};
+use scene_runner::renderer_context::RendererSceneContext;
+use tokio::sync::broadcast::error::TryRecvError;
+
+const FILE_PREFIX: &str = "output-";
+// hex digits in a private key
+const KEY_DIGITS: usize = 64;
+const MB: u64 = 1024 * 1024;
+
+pub struct OutputLogPlugin;
+
+impl Plugin for OutputLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Last,
+            write_output_log.run_if(|config: Res<AppConfig>| config.output_log.enabled),
+        );
+    }
+}
+
+// replaces runs of exactly `KEY_DIGITS` hex digits, with or without a 0x prefix
+fn scrub_keys(line: &str) -> Cow<'_, str> {
+    let bytes = line.as_bytes();
+    let mut scrubbed = String::new();
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_hexdigit() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < bytes.len() && bytes[i].is_ascii_hexdigit() {
+            i += 1;
+        }
+        if i - start == KEY_DIGITS {
+            scrubbed.push_str(&line[copied..start]);
+            scrubbed.push_str("[redacted]");
+            copied = i;
+        }
+    }
+
+    if copied == 0 {
+        Cow::Borrowed(line)
+    } else {
+        scrubbed.push_str(&line[copied..]);
+        Cow::Owned(scrubbed)
+    }
+}
+
+struct LogFile {
+    writer: LineWriter<File>,
+    date: NaiveDate,
+    size: u64,
+}
+
+struct RotatingLog {
+    dir: PathBuf,
+    max_file_bytes: u64,
+    max_total_bytes: u64,
+    current: Option<LogFile>,
+}
+
+impl RotatingLog {
+    fn write(&mut self, now: DateTime<Local>, line: &str) -> std::io::Result<()> {
+        let line = format!(
+            "{} {}\n",
+            now.format("%Y-%m-%d %H:%M:%S%.3f"),
+            scrub_keys(line)
+        );
+        let len = line.len() as u64;
+
+        let fits = self.current.as_ref().is_some_and(|file| {
+            file.date == now.date_naive()
+                && (file.size == 0 || file.size + len <= self.max_file_bytes)
+        });
+        if !fits {
+            self.open(now)?;
+        }
+
+        let Some(file) = self.current.as_mut() else {
+            return Ok(());
+        };
+        file.writer.write_all(line.as_bytes())?;
+        file.size += len;
+        Ok(())
+    }
+
+    fn open(&mut self, now: DateTime<Local>) -> std::io::Result<()> {
+        self.current = None;
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self
+            .dir
+            .join(format!("{FILE_PREFIX}{}", now.format("%Y%m%d-%H%M%S-%3f")))
+            .with_extension("log");
+        let file = File::options().create(true).append(true).open(&path)?;
+        debug!("output log writing to {}", path.display());
+        self.current = Some(LogFile {
+            writer: LineWriter::new(file),
+            date: now.date_naive(),
+            size: 0,
+        });
+        self.prune();
+        Ok(())
+    }
+
+    // remove the oldest files while they add up to more than the cap
+    fn prune(&self) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut files = entries
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(FILE_PREFIX))
+            .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?.len())))
+            .collect::<Vec<_>>();
+        // names sort by creation time
+        files.sort();
+
+        let mut total = files.iter().map(|(_, len)| len).sum::<u64>();
+        for (path, len) in files {
+            if total <= self.max_total_bytes {
+                break;
+            }
+            if let Err(e) = std::fs::remove_file(&path) {
+                warn!("failed to remove old output log {}: {e}", path.display());
+            }
+            total -= len;
+        }
+    }
+}
+
+fn min_scene_level(filter: SceneLogFilter) -> Option<SceneLogLevel> {
+    match filter {
+        SceneLogFilter::Off => None,
+        SceneLogFilter::SystemErrors => Some(SceneLogLevel::SystemError),
+        SceneLogFilter::Errors => Some(SceneLogLevel::SceneError),
+        SceneLogFilter::All => Some(SceneLogLevel::Log),
+    }
+}
+
+fn write_output_log(
+    config: Res<AppConfig>,
+    mut chats: EventReader<ChatEvent>,
+    users: Query<&UserProfile>,
+    scenes: Query<(Entity, &RendererSceneContext)>,
+    mut scene_logs: Local<HashMap<Entity, RingBufferReceiver<SceneLogMessage>>>,
+    mut log: Local<Option<RotatingLog>>,
+    mut failed: Local<bool>,
+) {
+    if *failed {
+        return;
+    }
+
+    let settings = &config.output_log;
+    let log = log.get_or_insert_with(|| RotatingLog {
+        dir: project_directories().data_local_dir().join("logs"),
+        max_file_bytes: 0,
+        max_total_bytes: 0,
+        current: None,
+    });
+    log.max_file_bytes = settings.max_file_mb.max(1) as u64 * MB;
+    log.max_total_bytes = settings.max_total_mb as u64 * MB;
+
+    let mut lines = Vec::default();
+
+    for ev in chats.read() {
+        if chat_marker_things::ALL
+            .iter()
+            .any(|marker| ev.message.starts_with(*marker))
+        {
+            continue;
+        }
+
+        if ev.sender == Entity::PLACEHOLDER {
+            lines.push(format!("[console] {}", ev.message));
+        } else {
+            let name = users
+                .get(ev.sender)
+                .map(|profile| profile.content.name.as_str())
+                .unwrap_or("unknown");
+            lines.push(format!("[{}] {name}: {}", ev.channel, ev.message));
+        }
+    }
+
+    match min_scene_level(settings.scene_logs) {
+        Some(min_level) => {
+            scene_logs.retain(|scene, _| scenes.contains(*scene));
+            for (scene, context) in scenes.iter() {
+                let receiver = scene_logs
+                    .entry(scene)
+                    .or_insert_with(|| context.logs.read().2);
+                loop {
+                    let message = match receiver.try_recv() {
+                        Ok(message) => message,
+                        Err(TryRecvError::Lagged(missed)) => {
+                            lines.push(format!(
+                                "[scene {} {}] {missed} messages missed",
+                                context.base, context.title
+                            ));
+                            continue;
+                        }
+                        Err(_) => break,
+                    };
+                    if message.level < min_level {
+                        continue;
+                    }
+                    let level = match message.level {
+                        SceneLogLevel::Log => "log",
+                        SceneLogLevel::SceneError => "scene_error",
+                        SceneLogLevel::SystemError => "system_error",
+                    };
+                    lines.push(format!(
+                        "[scene {} {}] {level}: {}",
+                        context.base, context.title, message.message
+                    ));
+                }
+            }
+        }
+        None => scene_logs.clear(),
+    }
+
+    let now = Local::now();
+    for line in lines {
+        if let Err(e) = log.write(now, &line) {
+            warn!("failed to write output log, disabling it: {e}");
+            *failed = true;
+            return;
+        }
+    }
+}
@@ -331,6 +331,9 @@ fn set_permission_settings_content(
             spawn_row(PermissionType::Fetch, &mut commands),
             spawn_row(PermissionType::Websocket, &mut commands),
             spawn_row(PermissionType::OpenUrl, &mut commands),
+            spawn_row(PermissionType::Microphone, &mut commands),
+            spawn_row(PermissionType::SceneMessages, &mut commands),
+            spawn_row(PermissionType::Notifications, &mut commands),
         ];
 
         commands
@@ -0,0 +1,204 @@
+// `/report` bundles diagnostics for the scene the player is standing in into a json file
+// that can be attached to a bug report. the app config is included with the login removed,
+// and the ephemeral key is scrubbed from anything else that ends up in the bundle.
+
+use std::path::PathBuf;
+
+use bevy::{prelude::*, render::view::screenshot::ScreenshotManager, window::PrimaryWindow};
+use bevy_console::ConsoleCommand;
+use common::{
+    structs::{AppConfig, PrimaryUser, Version},
+    util::project_directories,
+};
+use console::DoAddConsoleCommand;
+use dcl::SceneLogLevel;
+use ipfs::CurrentRealm;
+use scene_runner::{
+    initialize_scene::PARCEL_SIZE, renderer_context::RendererSceneContext, ContainingScene, Toaster,
+};
+use serde::Serialize;
+
+use crate::clipboard::copy_to_clipboard;
+
+pub struct SceneReportPlugin;
+
+impl Plugin for SceneReportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command::<ReportCommand, _>(report_command);
+    }
+}
+
+#[derive(Serialize)]
+struct SceneReport {
+    version: String,
+    // local time the report was created
+    created: String,
+    realm: String,
+    parcel: IVec2,
+    scene: Option<SceneInfo>,
+    config: AppConfig,
+}
+
+#[derive(Serialize)]
+struct SceneInfo {
+    hash: String,
+    title: String,
+    base: IVec2,
+    sdk_version: &'static str,
+    broken: bool,
+    blocked: Vec<&'static str>,
+    ticks: u32,
+    runtime_s: f32,
+    // log messages dropped from the buffer before the report
+    logs_missed: usize,
+    logs: Vec<LogLine>,
+}
+
+#[derive(Serialize)]
+struct LogLine {
+    timestamp: f64,
+    level: &'static str,
+    message: String,
+}
+
+fn reports_dir() -> PathBuf {
+    project_directories().data_local_dir().join("reports")
+}
+
+// remove any hex-encoded copy of the key from the text
+fn scrub(text: &str, key_hex: Option<&str>) -> String {
+    match key_hex {
+        Some(key) if !key.is_empty() => text.replace(key, "<scrubbed>"),
+        _ => text.to_owned(),
+    }
+}
+
+/// write a diagnostics report for the current scene to disk
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/report")]
+struct ReportCommand {
+    /// also copy the report to the clipboard
+    #[arg(long)]
+    copy: bool,
+    /// also save a screenshot next to the report
+    #[arg(long)]
+    screenshot: bool,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn report_command(
+    mut input: ConsoleCommand<ReportCommand>,
+    player: Query<(Entity, &GlobalTransform), With<PrimaryUser>>,
+    containing_scene: ContainingScene,
+    scenes: Query<&RendererSceneContext>,
+    realm: Res<CurrentRealm>,
+    version: Res<Version>,
+    config: Res<AppConfig>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    mut toaster: Toaster,
+) {
+    let Some(Ok(command)) = input.take() else {
+        return;
+    };
+
+    let Ok((player, transform)) = player.get_single() else {
+        input.reply_failed("no player");
+        return;
+    };
+
+    let key_hex = config.previous_login.as_ref().map(|login| {
+        login
+            .ephemeral_key
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>()
+    });
+    let key_hex = key_hex.as_deref();
+
+    let parcel = (transform.translation().xz() * Vec2::new(1.0, -1.0) / PARCEL_SIZE)
+        .floor()
+        .as_ivec2();
+
+    let scene = containing_scene
+        .get_parcel_oow(player)
+        .and_then(|scene| scenes.get(scene).ok())
+        .map(|context| {
+            let (logs_missed, logs, _) = context.logs.read();
+            let mut blocked = context.blocked.iter().copied().collect::<Vec<_>>();
+            blocked.sort();
+            SceneInfo {
+                hash: context.hash.clone(),
+                title: context.title.clone(),
+                base: context.base,
+                sdk_version: context.sdk_version,
+                broken: context.broken,
+                blocked,
+                ticks: context.tick_number,
+                runtime_s: context.total_runtime,
+                logs_missed,
+                logs: logs
+                    .into_iter()
+                    .map(|log| LogLine {
+                        timestamp: log.timestamp,
+                        level: match log.level {
+                            SceneLogLevel::Log => "log",
+                            SceneLogLevel::SceneError => "scene_error",
+                            SceneLogLevel::SystemError => "system_error",
+                        },
+                        message: scrub(&log.message, key_hex),
+                    })
+                    .collect(),
+            }
+        });
+
+    let now = chrono::Local::now();
+    let mut report_config = config.clone();
+    report_config.previous_login = None;
+    let report = SceneReport {
+        version: version.0.clone(),
+        created: now.to_rfc3339(),
+        realm: realm.address.clone(),
+        parcel,
+        scene,
+        config: report_config,
+    };
+
+    let json = match serde_json::to_string_pretty(&report) {
+        Ok(json) => scrub(&json, key_hex),
+        Err(e) => {
+            input.reply_failed(format!("failed to serialize report: {e}"));
+            return;
+        }
+    };
+
+    let dir = reports_dir();
+    let name = format!("report-{}", now.format("%Y%m%d-%H%M%S"));
+    let path = dir.join(&name).with_extension("json");
+    if let Err(e) = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, &json)) {
+        input.reply_failed(format!("failed to write report: {e}"));
+        return;
+    }
+
+    if command.screenshot {
+        let screenshot_path = dir.join(&name).with_extension("png");
+        let result = window
+            .get_single()
+            .map_err(|e| e.to_string())
+            .and_then(|window| {
+                screenshots
+                    .save_screenshot_to_disk(window, &screenshot_path)
+                    .map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(()) => input.reply(format!("screenshot saved to {}", screenshot_path.display())),
+            Err(e) => input.reply(format!("failed to take screenshot: {e}")),
+        }
+    }
+
+    if command.copy {
+        copy_to_clipboard(json, "Report", &mut toaster);
+    }
+
+    input.reply_ok(format!("report saved to {}", path.display()));
+}
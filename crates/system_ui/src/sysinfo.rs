@@ -43,7 +43,7 @@ use ui_core::{
 };
 use world_ui::TextShapeMaterial;
 
-use crate::map::MapTexture;
+use crate::{clipboard::copy_to_clipboard, map::MapTexture};
 
 use super::SystemUiRoot;
 
@@ -380,6 +380,27 @@ fn setup_minimap(
         ShowSettingsEvent(SettingsTab::Map).send_value_on::<Click>(),
     ));
 
+    // click the scene title to copy the scene hash
+    commands.entity(components.named("title")).insert((
+        Interaction::default(),
+        On::<Click>::new(
+            |containing_scene: ContainingScene,
+             scenes: Query<&RendererSceneContext>,
+             player: Query<Entity, With<PrimaryUser>>,
+             mut toaster: Toaster| {
+                let Some(scene) = player
+                    .get_single()
+                    .ok()
+                    .and_then(|player| containing_scene.get_parcel_oow(player))
+                    .and_then(|scene| scenes.get(scene).ok())
+                else {
+                    return;
+                };
+                copy_to_clipboard(scene.hash.clone(), "Scene hash", &mut toaster);
+            },
+        ),
+    ));
+
     if preview.server.is_some() {
         let tracker = commands
             .entity(components.root)
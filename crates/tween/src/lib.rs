@@ -1,19 +1,26 @@
-use bevy::prelude::*;
+pub mod property;
+
+use bevy::{prelude::*, utils::HashSet};
 use common::sets::SceneSets;
 use dcl::interface::{ComponentPosition, CrdtType};
 use dcl_component::{
-    proto_components::sdk::components::{
-        pb_tween::Mode, EasingFunction, PbTween, PbTweenState, TweenStateStatus,
+    proto_components::{
+        explorer::{pb_tween::Mode, PbTween, TweenPlaybackMode, TweenRepeat, TweenRepeatMode},
+        sdk::components::{EasingFunction, PbTweenCompleted, PbTweenState, TweenStateStatus},
     },
     transform_and_parent::DclTransformAndParent,
     SceneComponentId,
 };
 
 use scene_runner::{
-    renderer_context::RendererSceneContext, update_world::AddCrdtInterfaceExt, ContainerEntity,
-    SceneEntity,
+    renderer_context::RendererSceneContext,
+    update_world::{transform_and_parent::is_finite_transform, AddCrdtInterfaceExt},
+    ContainerEntity, SceneEntity,
 };
 
+// xz length of a normalized move direction below which it counts as vertical
+const VERTICAL_EPSILON: f32 = 1e-4;
+
 #[derive(Component, Debug)]
 pub struct Tween(PbTween);
 
@@ -23,83 +30,317 @@ impl From<PbTween> for Tween {
     }
 }
 
+// sample an easing function at normalized time
+pub fn ease(function: EasingFunction, time: f32) -> f32 {
+    use simple_easing::*;
+    use EasingFunction::*;
+    let f = match function {
+        EfLinear => linear,
+        EfEaseinquad => quad_in,
+        EfEaseoutquad => quad_out,
+        EfEasequad => quad_in_out,
+        EfEaseinsine => sine_in,
+        EfEaseoutsine => sine_out,
+        EfEasesine => sine_in_out,
+        EfEaseinexpo => expo_in,
+        EfEaseoutexpo => expo_out,
+        EfEaseexpo => expo_in_out,
+        EfEaseinelastic => elastic_in,
+        EfEaseoutelastic => elastic_out,
+        EfEaseelastic => elastic_in_out,
+        EfEaseinbounce => bounce_in,
+        EfEaseoutbounce => bounce_out,
+        EfEasebounce => bounce_in_out,
+        EfEaseincubic => cubic_in,
+        EfEaseoutcubic => cubic_out,
+        EfEasecubic => cubic_in_out,
+        EfEaseinquart => quart_in,
+        EfEaseoutquart => quart_out,
+        EfEasequart => quart_in_out,
+        EfEaseinquint => quint_in,
+        EfEaseoutquint => quint_out,
+        EfEasequint => quint_in_out,
+        EfEaseincirc => circ_in,
+        EfEaseoutcirc => circ_out,
+        EfEasecirc => circ_in_out,
+        EfEaseinback => back_in,
+        EfEaseoutback => back_out,
+        EfEaseback => back_in_out,
+    };
+    f(time)
+}
+
 impl Tween {
-    fn apply(&self, time: f32, transform: &mut Transform) {
-        use simple_easing::*;
-        use EasingFunction::*;
-        let f = match self.0.easing_function() {
-            EfLinear => linear,
-            EfEaseinquad => quad_in,
-            EfEaseoutquad => quad_out,
-            EfEasequad => quad_in_out,
-            EfEaseinsine => sine_in,
-            EfEaseoutsine => sine_out,
-            EfEasesine => sine_in_out,
-            EfEaseinexpo => expo_in,
-            EfEaseoutexpo => expo_out,
-            EfEaseexpo => expo_in_out,
-            EfEaseinelastic => elastic_in,
-            EfEaseoutelastic => elastic_out,
-            EfEaseelastic => elastic_in_out,
-            EfEaseinbounce => bounce_in,
-            EfEaseoutbounce => bounce_out,
-            EfEasebounce => bounce_in_out,
-            EfEaseincubic => cubic_in,
-            EfEaseoutcubic => cubic_out,
-            EfEasecubic => cubic_in_out,
-            EfEaseinquart => quart_in,
-            EfEaseoutquart => quart_out,
-            EfEasequart => quart_in_out,
-            EfEaseinquint => quint_in,
-            EfEaseoutquint => quint_out,
-            EfEasequint => quint_in_out,
-            EfEaseincirc => circ_in,
-            EfEaseoutcirc => circ_out,
-            EfEasecirc => circ_in_out,
-            EfEaseinback => back_in,
-            EfEaseoutback => back_out,
-            EfEaseback => back_in_out,
-        };
-
-        let ease_value = f(time);
+    pub fn playback(&self) -> TweenPlayback {
+        self.0.playback().into()
+    }
+
+    pub fn looping(&self) -> Option<TweenLoop> {
+        self.0.repeat.as_ref().map(TweenLoop::from)
+    }
+
+    // color and alpha modes tween a material or ui background, see `property`
+    pub fn is_property(&self) -> bool {
+        matches!(
+            self.0.mode,
+            Some(
+                Mode::MaterialColor(_)
+                    | Mode::MaterialAlpha(_)
+                    | Mode::UiBackgroundColor(_)
+                    | Mode::UiBackgroundAlpha(_)
+            )
+        )
+    }
+
+    // `face` is set when a `face_direction` move should turn towards its direction of travel,
+    // true when travelling back towards the start. returns false, leaving the transform
+    // unchanged, if the result isn't finite
+    fn apply(&self, time: f32, face: Option<bool>, transform: &mut Transform) -> bool {
+        let ease_value = ease(self.0.easing_function(), time);
+        let mut tweened = *transform;
 
         match &self.0.mode {
             Some(Mode::Move(data)) => {
                 let start = data.start.unwrap_or_default().world_vec_to_vec3();
                 let end = data.end.unwrap_or_default().world_vec_to_vec3();
 
-                if data.face_direction == Some(true) && time == 0.0 {
-                    let direction = end - start;
-                    if direction == Vec3::ZERO {
-                        // can't look nowhere
-                    } else if direction * Vec3::new(1.0, 0.0, 1.0) != Vec3::ZERO {
-                        // randomly assume +z is up for a vertical movement
-                        transform.look_at(end - start, Vec3::Z);
-                    } else {
-                        transform.look_at(end - start, Vec3::Y);
+                if let (Some(true), Some(backward)) = (data.face_direction, face) {
+                    let direction = if backward { start - end } else { end - start };
+                    // can't look nowhere
+                    if let Some(direction) = direction.try_normalize() {
+                        // y can't be up for a vertical movement, randomly assume +z instead
+                        let up = if direction.xz().length() < VERTICAL_EPSILON {
+                            Vec3::Z
+                        } else {
+                            Vec3::Y
+                        };
+                        tweened.look_to(direction, up);
                     }
                 }
 
-                transform.translation = start + (end - start) * ease_value;
+                tweened.translation = start + (end - start) * ease_value;
             }
             Some(Mode::Rotate(data)) => {
                 let start: Quat = data.start.unwrap_or_default().into();
                 let end = data.end.unwrap_or_default().into();
-                transform.rotation = start.slerp(end, ease_value);
+                tweened.rotation = start.slerp(end, ease_value);
             }
             Some(Mode::Scale(data)) => {
                 let start = data.start.unwrap_or_default().abs_vec_to_vec3();
                 let end = data.end.unwrap_or_default().abs_vec_to_vec3();
-                transform.scale = start + ((end - start) * ease_value);
+                tweened.scale = start + ((end - start) * ease_value);
             }
             _ => {}
         }
+
+        if !is_finite_transform(&tweened) {
+            return false;
+        }
+        *transform = tweened;
+        true
     }
 }
 
 #[derive(Component, Debug, PartialEq)]
 pub struct TweenState(PbTweenState);
 
+// direction of playback, from the explorer extension `playback` field of `PbTween`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TweenPlayback {
+    #[default]
+    Forward,
+    // from 1 back to 0, `current_time` counts down
+    Reverse,
+}
+
+impl From<TweenPlaybackMode> for TweenPlayback {
+    fn from(value: TweenPlaybackMode) -> Self {
+        match value {
+            TweenPlaybackMode::TpmForward => TweenPlayback::Forward,
+            TweenPlaybackMode::TpmReverse => TweenPlayback::Reverse,
+        }
+    }
+}
+
+impl TweenPlayback {
+    // elapsed ms to start from, given the requested normalized time
+    pub fn start_elapsed(&self, current_time: Option<f32>, duration_ms: f32) -> f64 {
+        let duration_ms = duration_ms.max(0.0) as f64;
+        match self {
+            TweenPlayback::Forward => {
+                current_time.unwrap_or(0.0).clamp(0.0, 1.0) as f64 * duration_ms
+            }
+            TweenPlayback::Reverse => {
+                (1.0 - current_time.unwrap_or(1.0).clamp(0.0, 1.0)) as f64 * duration_ms
+            }
+        }
+    }
+
+    // normalized time for the elapsed ms, and whether it is heading back towards 0
+    pub fn position(&self, elapsed_ms: f64, duration_ms: f32) -> TweenPosition {
+        let duration_ms = duration_ms as f64;
+        let (time, backward) = match self {
+            // zero length tweens complete immediately
+            TweenPlayback::Forward if duration_ms <= 0.0 => (1.0, false),
+            TweenPlayback::Reverse if duration_ms <= 0.0 => (0.0, true),
+            TweenPlayback::Forward => (elapsed_ms / duration_ms, false),
+            TweenPlayback::Reverse => (1.0 - elapsed_ms / duration_ms, true),
+        };
+
+        TweenPosition {
+            elapsed_ms,
+            time: time.clamp(0.0, 1.0) as f32,
+            backward,
+        }
+    }
+
+    pub fn is_complete(&self, time: f32) -> bool {
+        match self {
+            TweenPlayback::Forward => time == 1.0,
+            TweenPlayback::Reverse => time == 0.0,
+        }
+    }
+}
+
+// repeat a tween instead of completing, from the explorer extension `repeat` field of `PbTween`.
+// the direction still comes from `TweenPlayback`, a reverse loop counts down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TweenLoop {
+    pub mode: LoopMode,
+    // number of cycles before completing, forever if `None`
+    pub count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopMode {
+    // jump back to the start after each cycle
+    Loop,
+    // play to the end and back again. a cycle is the full round trip
+    PingPong,
+}
+
+impl From<&TweenRepeat> for TweenLoop {
+    fn from(value: &TweenRepeat) -> Self {
+        Self {
+            mode: match value.mode() {
+                TweenRepeatMode::TrmLoop => LoopMode::Loop,
+                TweenRepeatMode::TrmPingPong => LoopMode::PingPong,
+            },
+            count: value.count,
+        }
+    }
+}
+
+impl TweenLoop {
+    fn cycle_ms(&self, duration_ms: f64) -> f64 {
+        match self.mode {
+            LoopMode::Loop => duration_ms,
+            LoopMode::PingPong => duration_ms * 2.0,
+        }
+    }
+
+    // as `TweenPlayback::position`. endless loops wrap the elapsed time to a single cycle, counted
+    // loops stop at the end of the last cycle
+    pub fn position(
+        &self,
+        playback: TweenPlayback,
+        elapsed_ms: f64,
+        duration_ms: f32,
+    ) -> TweenPosition {
+        let duration_ms = duration_ms as f64;
+        let cycle_ms = self.cycle_ms(duration_ms);
+        if cycle_ms <= 0.0 {
+            return playback.position(elapsed_ms, 0.0);
+        }
+
+        let elapsed_ms = match self.count {
+            Some(count) => elapsed_ms.min(cycle_ms * count as f64),
+            None => elapsed_ms.rem_euclid(cycle_ms),
+        };
+        // the end of a cycle stays at the end rather than wrapping to the start
+        let mut phase = elapsed_ms.rem_euclid(cycle_ms);
+        if phase == 0.0 && elapsed_ms > 0.0 {
+            phase = cycle_ms;
+        }
+        let phase = phase / duration_ms;
+        let (time, backward) = match self.mode {
+            LoopMode::PingPong if phase > 1.0 => (2.0 - phase, true),
+            _ => (phase, false),
+        };
+        let (time, backward) = match playback {
+            TweenPlayback::Reverse => (1.0 - time, !backward),
+            TweenPlayback::Forward => (time, backward),
+        };
+
+        TweenPosition {
+            elapsed_ms,
+            time: time.clamp(0.0, 1.0) as f32,
+            backward,
+        }
+    }
+
+    pub fn is_complete(&self, elapsed_ms: f64, duration_ms: f32) -> bool {
+        self.count.is_some_and(|count| {
+            elapsed_ms >= self.cycle_ms(duration_ms.max(0.0) as f64) * count as f64
+        })
+    }
+}
+
+// position of a tween with optional looping, and whether it has completed
+pub fn tween_position(
+    playback: TweenPlayback,
+    looping: Option<&TweenLoop>,
+    elapsed_ms: f64,
+    duration_ms: f32,
+) -> (TweenPosition, bool) {
+    match looping {
+        Some(looping) => {
+            let position = looping.position(playback, elapsed_ms, duration_ms);
+            (
+                position,
+                looping.is_complete(position.elapsed_ms, duration_ms),
+            )
+        }
+        None => {
+            let position = playback.position(elapsed_ms, duration_ms);
+            (position, playback.is_complete(position.time))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TweenPosition {
+    pub elapsed_ms: f64,
+    // normalized 0-1
+    pub time: f32,
+    pub backward: bool,
+}
+
+// elapsed milliseconds of the tween. kept in f64 and converted to the 0-1 `current_time`
+// each frame, rather than accumulating small f32 steps of the normalized time
+#[derive(Component, Debug, Default)]
+pub struct TweenClock {
+    elapsed_ms: f64,
+    backward: bool,
+}
+
+// limit on a single frame's progress, so a stall doesn't skip most of a tween
+pub const MAX_TWEEN_FRAME_SECONDS: f32 = 0.25;
+
+// advance the clock by a frame
+pub fn advance_tween(
+    elapsed_ms: f64,
+    delta_seconds: f32,
+    duration_ms: f32,
+    playback: TweenPlayback,
+) -> TweenPosition {
+    playback.position(elapsed_ms + frame_ms(delta_seconds), duration_ms)
+}
+
+fn frame_ms(delta_seconds: f32) -> f64 {
+    delta_seconds.clamp(0.0, MAX_TWEEN_FRAME_SECONDS) as f64 * 1000.0
+}
+
 pub struct TweenPlugin;
 
 impl Plugin for TweenPlugin {
@@ -109,6 +350,15 @@ impl Plugin for TweenPlugin {
             ComponentPosition::EntityOnly,
         );
         app.add_systems(Update, update_tween.in_set(SceneSets::PostLoop));
+        app.add_systems(
+            Update,
+            (
+                property::update_scene_property_tweens,
+                property::update_property_tweens,
+            )
+                .chain()
+                .in_set(SceneSets::PostLoop),
+        );
         app.add_systems(Update, update_system_tween);
     }
 }
@@ -124,29 +374,60 @@ pub fn update_tween(
         Ref<Tween>,
         &mut Transform,
         Option<&mut TweenState>,
+        Option<&mut TweenClock>,
     )>,
     mut scenes: Query<&mut RendererSceneContext>,
     parents: Query<&SceneEntity>,
+    mut warned: Local<HashSet<Entity>>,
 ) {
-    for (ent, scene_ent, parent, tween, mut transform, state) in tweens.iter_mut() {
+    for (ent, scene_ent, parent, tween, mut transform, state, clock) in tweens.iter_mut() {
+        if tween.is_property() {
+            continue;
+        }
+
         let playing = tween.0.playing.unwrap_or(true);
-        let delta = if playing {
-            time.delta_seconds() * 1000.0 / tween.0.duration
+        let delta = if playing { time.delta_seconds() } else { 0.0 };
+        let mode = tween.playback();
+        let looping = tween.looping();
+        let duration = tween.0.duration;
+
+        let elapsed_ms = if tween.is_changed() || clock.is_none() {
+            mode.start_elapsed(tween.0.current_time, duration)
         } else {
-            0.0
+            clock
+                .as_ref()
+                .map(|clock| clock.elapsed_ms)
+                .unwrap_or_default()
+                + frame_ms(delta)
         };
+        let (position, complete) = tween_position(mode, looping.as_ref(), elapsed_ms, duration);
+        let updated_time = position.time;
 
-        let updated_time = if tween.is_changed() {
-            tween.0.current_time.unwrap_or(0.0)
+        // face the direction of travel at the start, and again whenever it turns around
+        let turned = clock
+            .as_ref()
+            .is_some_and(|clock| clock.backward != position.backward);
+        let at_start = if position.backward {
+            updated_time == 1.0
         } else {
-            state
-                .as_ref()
-                .map(|state| state.0.current_time + delta)
-                .unwrap_or(0.0)
-                .min(1.0)
+            updated_time == 0.0
         };
+        let face = (turned || at_start).then_some(position.backward);
+
+        match clock {
+            Some(mut clock) => {
+                clock.elapsed_ms = position.elapsed_ms;
+                clock.backward = position.backward;
+            }
+            None => {
+                commands.entity(ent).try_insert(TweenClock {
+                    elapsed_ms: position.elapsed_ms,
+                    backward: position.backward,
+                });
+            }
+        }
 
-        let updated_status = if playing && updated_time == 1.0 {
+        let updated_status = if playing && complete {
             TweenStateStatus::TsCompleted
         } else if playing {
             TweenStateStatus::TsActive
@@ -159,7 +440,8 @@ pub fn update_tween(
             current_time: updated_time,
         });
 
-        if state.as_deref() != Some(&updated_state) {
+        // a changed tween is authoritative, so a scrubber seeking to any time is always applied
+        if tween.is_changed() || state.as_deref() != Some(&updated_state) {
             let Ok(mut scene) = scenes.get_mut(scene_ent.root) else {
                 continue;
             };
@@ -171,13 +453,32 @@ pub fn update_tween(
                 &updated_state.0,
             );
 
+            // a one-shot signal on the frame the tween completes, so scenes don't need to poll
+            let was_complete = state
+                .as_ref()
+                .is_some_and(|state| state.0.state == TweenStateStatus::TsCompleted as i32);
+            if updated_status == TweenStateStatus::TsCompleted && !was_complete {
+                let tick_number = scene.tick_number;
+                scene.update_crdt(
+                    SceneComponentId::TWEEN_COMPLETED,
+                    CrdtType::GO_ENT,
+                    scene_ent.container_id,
+                    &PbTweenCompleted {
+                        tick_number,
+                        current_time: updated_time,
+                    },
+                );
+            }
+
             if let Some(mut state) = state {
                 state.0 = updated_state.0;
             } else {
                 commands.entity(ent).try_insert(updated_state);
             }
 
-            tween.apply(updated_time, &mut transform);
+            if !tween.apply(updated_time, face, &mut transform) && warned.insert(ent) {
+                warn!("[{ent:?}] ignoring invalid tween {:?}", tween.0);
+            }
 
             let Ok(parent) = parents.get(parent.get()) else {
                 warn!("no parent for tweened ent");
@@ -241,3 +542,487 @@ pub fn update_system_tween(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use dcl::SceneId;
+    use dcl_component::{
+        proto_components::{common::Vector3, sdk::components::Move},
+        SceneEntityId,
+    };
+
+    use super::*;
+
+    // simulated seconds until the tween completes
+    fn run_tween(duration_ms: f32, deltas: impl Iterator<Item = f32>) -> f32 {
+        let mut elapsed = 0.0;
+        let mut seconds = 0.0;
+        for delta in deltas {
+            let position = advance_tween(elapsed, delta, duration_ms, TweenPlayback::Forward);
+            elapsed = position.elapsed_ms;
+            seconds += delta;
+            if position.time == 1.0 {
+                return seconds;
+            }
+            assert!(position.time < 1.0);
+        }
+        panic!("tween didn't complete");
+    }
+
+    #[test]
+    fn test_tween_timing_independent_of_frame_rate() {
+        let duration_ms = 10_000.0;
+        let tolerance = 0.05;
+
+        for fps in [5.0, 30.0, 60.0, 144.0, 1000.0] {
+            let seconds = run_tween(duration_ms, std::iter::repeat(1.0 / fps));
+            assert!(
+                (seconds - 10.0).abs() <= 1.0 / fps + tolerance,
+                "{fps} fps took {seconds}s"
+            );
+        }
+
+        // varying frame times
+        let deltas = [0.001, 0.016, 0.1, 0.033, 0.007].into_iter().cycle();
+        let seconds = run_tween(duration_ms, deltas);
+        assert!((seconds - 10.0).abs() <= 0.1 + tolerance, "took {seconds}s");
+    }
+
+    #[test]
+    fn test_tween_stall_is_clamped() {
+        let position = advance_tween(0.0, 5.0, 1000.0, TweenPlayback::Forward);
+        assert_eq!(position.elapsed_ms, MAX_TWEEN_FRAME_SECONDS as f64 * 1000.0);
+        assert_eq!(position.time, MAX_TWEEN_FRAME_SECONDS);
+
+        // zero length tweens complete on the first frame
+        assert_eq!(
+            advance_tween(0.0, 0.016, 0.0, TweenPlayback::Forward).time,
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_reverse_tween_reaches_start() {
+        let mode = TweenPlayback::Reverse;
+        let mut position = mode.position(mode.start_elapsed(None, 1000.0), 1000.0);
+        assert_eq!(position.time, 1.0);
+        assert!(!mode.is_complete(position.time));
+
+        let mut last = position.time;
+        while !mode.is_complete(position.time) {
+            position = advance_tween(position.elapsed_ms, 0.016, 1000.0, mode);
+            assert!(position.backward);
+            assert!(position.time < last);
+            last = position.time;
+        }
+        assert_eq!(position.time, 0.0);
+
+        // starting part way
+        let start = mode.position(mode.start_elapsed(Some(0.25), 1000.0), 1000.0);
+        assert!((start.time - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_face_direction() {
+        use dcl_component::proto_components::{common::Vector3, sdk::components::Move};
+
+        let move_tween = |end: Vector3| {
+            Tween(PbTween {
+                duration: 1000.0,
+                mode: Some(Mode::Move(Move {
+                    start: Some(Vector3 {
+                        x: 1.0,
+                        y: 2.0,
+                        z: 3.0,
+                    }),
+                    end: Some(end),
+                    face_direction: Some(true),
+                })),
+                ..Default::default()
+            })
+        };
+
+        // horizontal, along +x
+        let tween = move_tween(Vector3 {
+            x: 5.0,
+            y: 2.0,
+            z: 3.0,
+        });
+        let mut transform = Transform::from_translation(Vec3::new(100.0, 0.0, 0.0));
+        assert!(tween.apply(0.5, Some(false), &mut transform));
+        assert!(transform.rotation.is_finite());
+        assert!(transform.forward().distance(Vec3::X) < 1e-5);
+        assert!(transform.up().distance(Vec3::Y) < 1e-5);
+        assert!(tween.apply(0.5, Some(true), &mut transform));
+        assert!(transform.forward().distance(Vec3::NEG_X) < 1e-5);
+
+        // vertical, upwards
+        let tween = move_tween(Vector3 {
+            x: 1.0,
+            y: 7.0,
+            z: 3.0,
+        });
+        let mut transform = Transform::default();
+        assert!(tween.apply(0.5, Some(false), &mut transform));
+        assert!(transform.rotation.is_finite());
+        assert!(transform.forward().distance(Vec3::Y) < 1e-5);
+        assert!(transform.up().distance(Vec3::Z) < 1e-5);
+
+        // no movement keeps the rotation
+        let tween = move_tween(Vector3 {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+        });
+        let mut transform = Transform::from_rotation(Quat::from_rotation_y(1.0));
+        assert!(tween.apply(0.5, Some(false), &mut transform));
+        assert_eq!(transform.rotation, Quat::from_rotation_y(1.0));
+    }
+
+    #[test]
+    fn test_ping_pong_turns_around() {
+        let mode = TweenPlayback::Forward;
+        let looping = TweenLoop {
+            mode: LoopMode::PingPong,
+            count: None,
+        };
+        let frame = 0.016;
+        // largest change in normalized time for one frame of a one second tween
+        let max_step = frame + 1e-5;
+
+        let (mut position, _) = tween_position(
+            mode,
+            Some(&looping),
+            mode.start_elapsed(None, 1000.0),
+            1000.0,
+        );
+        let mut turns = 0;
+        for _ in 0..990 {
+            let (next, complete) = tween_position(
+                mode,
+                Some(&looping),
+                position.elapsed_ms + frame_ms(frame),
+                1000.0,
+            );
+            // no jumps at the turnaround points
+            assert!((next.time - position.time).abs() <= max_step);
+            if next.backward != position.backward {
+                turns += 1;
+            }
+            assert!(!complete);
+            // the clock stays within a single cycle
+            assert!(next.elapsed_ms < 2000.0);
+            position = next;
+        }
+        // nearly 16 seconds of a 1 second tween
+        assert_eq!(turns, 15);
+    }
+
+    #[test]
+    fn test_loop_wraps_and_counts() {
+        let duration = 1000.0;
+        let forward = TweenPlayback::Forward;
+
+        // wraps back to the start instead of clamping
+        let forever = TweenLoop {
+            mode: LoopMode::Loop,
+            count: None,
+        };
+        let position = forever.position(forward, 2500.0, duration);
+        assert_eq!(position.time, 0.5);
+        assert!(position.elapsed_ms < 1000.0);
+        assert!(!forever.is_complete(position.elapsed_ms, duration));
+
+        // reverse loops count down
+        let position = forever.position(TweenPlayback::Reverse, 1250.0, duration);
+        assert_eq!(position.time, 0.75);
+        assert!(position.backward);
+
+        // ping-pong turns around at the end
+        let ping_pong = TweenLoop {
+            mode: LoopMode::PingPong,
+            count: None,
+        };
+        let position = ping_pong.position(forward, 1250.0, duration);
+        assert_eq!(position.time, 0.75);
+        assert!(position.backward);
+        let position = ping_pong.position(forward, 2250.0, duration);
+        assert_eq!(position.time, 0.25);
+        assert!(!position.backward);
+
+        // counted loops complete at the end of the last cycle
+        let twice = TweenLoop {
+            mode: LoopMode::Loop,
+            count: Some(2),
+        };
+        let position = twice.position(forward, 1500.0, duration);
+        assert_eq!(position.time, 0.5);
+        assert!(!twice.is_complete(position.elapsed_ms, duration));
+        let position = twice.position(forward, 5000.0, duration);
+        assert_eq!(position.time, 1.0);
+        assert!(twice.is_complete(position.elapsed_ms, duration));
+
+        // and a counted ping-pong finishes back at the start
+        let there_and_back = TweenLoop {
+            mode: LoopMode::PingPong,
+            count: Some(1),
+        };
+        let position = there_and_back.position(forward, 2100.0, duration);
+        assert_eq!(position.time, 0.0);
+        assert!(there_and_back.is_complete(position.elapsed_ms, duration));
+    }
+
+    const TWEEN_CONTAINER: SceneEntityId = SceneEntityId {
+        id: 512,
+        generation: 0,
+    };
+
+    // one meter along x
+    fn move_tween() -> PbTween {
+        PbTween {
+            duration: 1000.0,
+            mode: Some(Mode::Move(Move {
+                start: Some(Vector3::default()),
+                end: Some(Vector3 {
+                    x: 1.0,
+                    y: 0.0,
+                    z: 0.0,
+                }),
+                face_direction: None,
+            })),
+            ..Default::default()
+        }
+    }
+
+    // a scene with one tweened entity, returns the scene root and the tweened entity
+    fn spawn_tween(world: &mut World, tween: PbTween) -> (Entity, Entity) {
+        world.init_resource::<Time>();
+        let root = world.spawn_empty().id();
+        world.entity_mut(root).insert(RendererSceneContext::new(
+            SceneId(root),
+            String::default(),
+            false,
+            String::default(),
+            IVec2::ZERO,
+            HashSet::default(),
+            Vec::default(),
+            Vec::default(),
+            root,
+            UVec2::ONE,
+            0.0,
+            false,
+            "7",
+            false,
+        ));
+        let parent = world
+            .spawn(SceneEntity {
+                root,
+                scene_id: SceneId(root),
+                id: SceneEntityId::ROOT,
+            })
+            .id();
+        let tweened = world
+            .spawn((
+                Transform::default(),
+                ContainerEntity {
+                    container: Entity::PLACEHOLDER,
+                    root,
+                    container_id: TWEEN_CONTAINER,
+                },
+                Tween(tween),
+            ))
+            .set_parent(parent)
+            .id();
+        (root, tweened)
+    }
+
+    #[test]
+    fn test_completion_appended_once() {
+        let mut world = World::new();
+        let (root, tweened) = spawn_tween(&mut world, move_tween());
+
+        // a persistent system, so change detection works across frames
+        let mut system = IntoSystem::into_system(update_tween);
+        system.initialize(&mut world);
+        let mut step = |world: &mut World, seconds: f32| {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(seconds));
+            system.run((), world);
+            system.apply_deferred(world);
+        };
+        let completions = |world: &World| {
+            world
+                .get::<RendererSceneContext>(root)
+                .unwrap()
+                .crdt_store
+                .go
+                .get(&SceneComponentId::TWEEN_COMPLETED)
+                .and_then(|state| state.0.get(&TWEEN_CONTAINER))
+                .map_or(0, |queue| queue.len())
+        };
+
+        step(&mut world, 0.0);
+        for _ in 0..4 {
+            step(&mut world, 0.2);
+        }
+        assert_eq!(completions(&world), 0);
+
+        // past t=1.0, and staying there
+        for _ in 0..5 {
+            step(&mut world, 0.2);
+        }
+        assert_eq!(completions(&world), 1);
+
+        // restarting part way doesn't repeat the old completion, finishing again is a new one
+        world.get_mut::<Tween>(tweened).unwrap().0.current_time = Some(0.5);
+        step(&mut world, 0.0);
+        assert_eq!(completions(&world), 1);
+        for _ in 0..5 {
+            step(&mut world, 0.2);
+        }
+        assert_eq!(completions(&world), 2);
+    }
+
+    #[test]
+    fn test_seek_backward() {
+        let mut world = World::new();
+        let (_, tweened) = spawn_tween(
+            &mut world,
+            PbTween {
+                playing: Some(false),
+                current_time: Some(1.0),
+                ..move_tween()
+            },
+        );
+
+        let mut system = IntoSystem::into_system(update_tween);
+        system.initialize(&mut world);
+        let mut step = |world: &mut World| {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(0.1));
+            system.run((), world);
+            system.apply_deferred(world);
+            (
+                world.get::<Transform>(tweened).unwrap().translation.x,
+                world.get::<TweenState>(tweened).unwrap().0.clone(),
+            )
+        };
+        let seek = |world: &mut World, time: f32| {
+            world.get_mut::<Tween>(tweened).unwrap().0.current_time = Some(time);
+        };
+
+        let (x, _) = step(&mut world);
+        assert_eq!(x, 1.0);
+
+        seek(&mut world, 0.25);
+        let (x, state) = step(&mut world);
+        assert_eq!(x, 0.25);
+        assert_eq!(state.current_time, 0.25);
+        assert_eq!(state.state, TweenStateStatus::TsPaused as i32);
+
+        // re-sending the same time applies it again, even though the state doesn't change
+        world.get_mut::<Transform>(tweened).unwrap().translation.x = 5.0;
+        seek(&mut world, 0.25);
+        let (x, _) = step(&mut world);
+        assert_eq!(x, 0.25);
+
+        // seeking back from a completed tween makes it active again
+        world.get_mut::<Tween>(tweened).unwrap().0.playing = Some(true);
+        seek(&mut world, 1.0);
+        let (_, state) = step(&mut world);
+        assert_eq!(state.state, TweenStateStatus::TsCompleted as i32);
+        seek(&mut world, 0.25);
+        let (x, state) = step(&mut world);
+        assert!((x - 0.25).abs() < 1e-6);
+        assert_eq!(state.state, TweenStateStatus::TsActive as i32);
+    }
+
+    #[test]
+    fn test_scene_reverse_playback() {
+        let mut world = World::new();
+        let (_, tweened) = spawn_tween(
+            &mut world,
+            PbTween {
+                playback: Some(TweenPlaybackMode::TpmReverse as i32),
+                ..move_tween()
+            },
+        );
+
+        let mut system = IntoSystem::into_system(update_tween);
+        system.initialize(&mut world);
+        let mut step = |world: &mut World, seconds: f32| {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(seconds));
+            system.run((), world);
+            system.apply_deferred(world);
+            (
+                world.get::<Transform>(tweened).unwrap().translation.x,
+                world.get::<TweenState>(tweened).unwrap().0.clone(),
+            )
+        };
+
+        // starts at the end and counts down to the start
+        let (x, _) = step(&mut world, 0.0);
+        assert_eq!(x, 1.0);
+        let (x, state) = step(&mut world, 0.2);
+        assert!((x - 0.8).abs() < 1e-5);
+        assert!((state.current_time - 0.8).abs() < 1e-5);
+        for _ in 0..5 {
+            step(&mut world, 0.2);
+        }
+        let (x, state) = step(&mut world, 0.0);
+        assert_eq!(x, 0.0);
+        assert_eq!(state.state, TweenStateStatus::TsCompleted as i32);
+    }
+
+    #[test]
+    fn test_scene_repeat() {
+        let mut world = World::new();
+        let (_, tweened) = spawn_tween(
+            &mut world,
+            PbTween {
+                repeat: Some(TweenRepeat {
+                    mode: TweenRepeatMode::TrmPingPong as i32,
+                    count: Some(1),
+                }),
+                ..move_tween()
+            },
+        );
+
+        let mut system = IntoSystem::into_system(update_tween);
+        system.initialize(&mut world);
+        let mut step = |world: &mut World, seconds: f32| {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(seconds));
+            system.run((), world);
+            system.apply_deferred(world);
+            (
+                world.get::<Transform>(tweened).unwrap().translation.x,
+                world.get::<TweenState>(tweened).unwrap().0.clone(),
+            )
+        };
+
+        // out to the end and still active
+        step(&mut world, 0.0);
+        for _ in 0..5 {
+            step(&mut world, 0.2);
+        }
+        let (x, state) = step(&mut world, 0.2);
+        assert!((x - 0.8).abs() < 1e-5);
+        assert_eq!(state.state, TweenStateStatus::TsActive as i32);
+
+        // and back to the start, completing after the single round trip
+        for _ in 0..4 {
+            step(&mut world, 0.2);
+        }
+        let (x, state) = step(&mut world, 0.2);
+        assert_eq!(x, 0.0);
+        assert_eq!(state.state, TweenStateStatus::TsCompleted as i32);
+    }
+}
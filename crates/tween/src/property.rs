@@ -0,0 +1,536 @@
+// explorer extension: tween a material or ui background color instead of the transform.
+// scenes use the color and alpha modes of `PbTween`, which are converted to a `PropertyTween`
+// on the entity. explorer code can also add one directly. uses the same easing, playback and
+// looping as sdk tweens. on scene entities without a transform tween the progress is reported
+// as the entity's `PbTweenState`, as for sdk tweens.
+
+use bevy::{ecs::query::Has, prelude::*};
+use dcl::interface::CrdtType;
+use dcl_component::{
+    proto_components::{
+        common::Color4,
+        explorer::pb_tween::Mode,
+        sdk::components::{EasingFunction, PbTweenCompleted, PbTweenState, TweenStateStatus},
+        ColorSpace,
+    },
+    SceneComponentId,
+};
+use scene_material::SceneMaterial;
+use scene_runner::{
+    renderer_context::RendererSceneContext, update_world::scene_ui::UiLink, ContainerEntity,
+};
+
+use crate::{ease, frame_ms, tween_position, Tween, TweenLoop, TweenPlayback, TweenState};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PropertyTarget {
+    // the material base color, including alpha
+    MaterialColor { start: Color, end: Color },
+    // the material alpha only, keeping its color
+    MaterialAlpha { start: f32, end: f32 },
+    // the background color of a ui node, including alpha
+    UiColor { start: Color, end: Color },
+    // the background alpha of a ui node only
+    UiAlpha { start: f32, end: f32 },
+}
+
+impl PropertyTarget {
+    // the target of a scene tween's color or alpha mode, none for transform modes
+    pub fn from_mode(mode: &Mode, color_space: ColorSpace) -> Option<Self> {
+        let color = |color: Option<Color4>| color_space.from_sdk(color.unwrap_or_default().into());
+        Some(match mode {
+            Mode::MaterialColor(data) => PropertyTarget::MaterialColor {
+                start: color(data.start),
+                end: color(data.end),
+            },
+            Mode::MaterialAlpha(data) => PropertyTarget::MaterialAlpha {
+                start: data.start,
+                end: data.end,
+            },
+            Mode::UiBackgroundColor(data) => PropertyTarget::UiColor {
+                start: color(data.start),
+                end: color(data.end),
+            },
+            Mode::UiBackgroundAlpha(data) => PropertyTarget::UiAlpha {
+                start: data.start,
+                end: data.end,
+            },
+            Mode::Move(_) | Mode::Rotate(_) | Mode::Scale(_) => return None,
+        })
+    }
+
+    // the value at eased progress 0-1. alpha targets keep the rgb of `current`
+    pub fn color_at(&self, current: Color, progress: f32) -> Color {
+        match *self {
+            PropertyTarget::MaterialColor { start, end }
+            | PropertyTarget::UiColor { start, end } => {
+                let (start, end) = (start.to_linear(), end.to_linear());
+                let lerp = |a: f32, b: f32| a + (b - a) * progress;
+                LinearRgba::new(
+                    lerp(start.red, end.red),
+                    lerp(start.green, end.green),
+                    lerp(start.blue, end.blue),
+                    lerp(start.alpha, end.alpha).clamp(0.0, 1.0),
+                )
+                .into()
+            }
+            PropertyTarget::MaterialAlpha { start, end }
+            | PropertyTarget::UiAlpha { start, end } => {
+                current.with_alpha((start + (end - start) * progress).clamp(0.0, 1.0))
+            }
+        }
+    }
+
+    fn is_material(&self) -> bool {
+        matches!(
+            self,
+            PropertyTarget::MaterialColor { .. } | PropertyTarget::MaterialAlpha { .. }
+        )
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct PropertyTween {
+    pub target: PropertyTarget,
+    pub duration_ms: f32,
+    pub easing: EasingFunction,
+    pub playback: TweenPlayback,
+    pub looping: Option<TweenLoop>,
+    pub playing: bool,
+    // normalized time to start from
+    pub current_time: Option<f32>,
+}
+
+// marks a property tween converted from the scene's `PbTween`
+#[derive(Component)]
+pub struct ScenePropertyTween;
+
+pub fn update_scene_property_tweens(
+    mut commands: Commands,
+    tweens: Query<(Entity, &Tween, Has<ScenePropertyTween>), Changed<Tween>>,
+    mut removed: RemovedComponents<Tween>,
+    scene_tweens: Query<(), With<ScenePropertyTween>>,
+    color_space: Res<ColorSpace>,
+) {
+    for ent in removed.read() {
+        if scene_tweens.contains(ent) {
+            if let Some(mut commands) = commands.get_entity(ent) {
+                commands.remove::<(PropertyTween, PropertyTweenClock, ScenePropertyTween)>();
+            }
+        }
+    }
+
+    for (ent, tween, is_scene_tween) in tweens.iter() {
+        let target = tween
+            .0
+            .mode
+            .as_ref()
+            .and_then(|mode| PropertyTarget::from_mode(mode, *color_space));
+        match target {
+            Some(target) => {
+                commands.entity(ent).try_insert((
+                    PropertyTween {
+                        target,
+                        duration_ms: tween.0.duration,
+                        easing: tween.0.easing_function(),
+                        playback: tween.playback(),
+                        looping: tween.looping(),
+                        playing: tween.0.playing.unwrap_or(true),
+                        current_time: tween.0.current_time,
+                    },
+                    ScenePropertyTween,
+                ));
+            }
+            // changed to a transform tween
+            None if is_scene_tween => {
+                commands
+                    .entity(ent)
+                    .remove::<(PropertyTween, PropertyTweenClock, ScenePropertyTween)>();
+            }
+            None => (),
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct PropertyTweenClock {
+    elapsed_ms: f64,
+    progress: f32,
+    complete: bool,
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_property_tweens(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut tweens: Query<(
+        Entity,
+        Ref<PropertyTween>,
+        Option<&mut PropertyTweenClock>,
+        Option<&Handle<SceneMaterial>>,
+        Option<&UiLink>,
+        Option<&ContainerEntity>,
+        Option<&mut TweenState>,
+        Option<&Tween>,
+    )>,
+    mut materials: ResMut<Assets<SceneMaterial>>,
+    mut backgrounds: Query<&mut BackgroundColor>,
+    mut scenes: Query<&mut RendererSceneContext>,
+) {
+    for (ent, tween, clock, material, link, container, state, scene_tween) in tweens.iter_mut() {
+        let mode = tween.playback;
+        let delta = if tween.playing {
+            time.delta_seconds()
+        } else {
+            0.0
+        };
+        // normalized time and completion, when they were updated this frame
+        let mut reported = None;
+        let progress = match clock {
+            Some(clock) if clock.complete && !tween.is_changed() => clock.progress,
+            clock => {
+                let elapsed_ms = match clock.as_ref() {
+                    Some(clock) if !tween.is_changed() => clock.elapsed_ms + frame_ms(delta),
+                    _ => mode.start_elapsed(tween.current_time, tween.duration_ms),
+                };
+                let (position, complete) =
+                    tween_position(mode, tween.looping.as_ref(), elapsed_ms, tween.duration_ms);
+                let updated = PropertyTweenClock {
+                    elapsed_ms: position.elapsed_ms,
+                    progress: ease(tween.easing, position.time),
+                    complete,
+                };
+                let progress = updated.progress;
+                reported = Some((position.time, updated.complete));
+                match clock {
+                    Some(mut clock) => *clock = updated,
+                    None => {
+                        commands.entity(ent).try_insert(updated);
+                    }
+                }
+                progress
+            }
+        };
+
+        // a transform tween on the same entity reports its own state
+        let transform_tween = scene_tween.is_some_and(|scene_tween| !scene_tween.is_property());
+        if let (Some((current_time, complete)), Some(container), false) =
+            (reported, container, transform_tween)
+        {
+            let status = if !tween.playing {
+                TweenStateStatus::TsPaused
+            } else if complete {
+                TweenStateStatus::TsCompleted
+            } else {
+                TweenStateStatus::TsActive
+            };
+            let updated_state = PbTweenState {
+                state: status as i32,
+                current_time,
+            };
+            if tween.is_changed() || state.as_ref().map(|state| &state.0) != Some(&updated_state) {
+                if let Ok(mut scene) = scenes.get_mut(container.root) {
+                    scene.update_crdt(
+                        SceneComponentId::TWEEN_STATE,
+                        CrdtType::LWW_ENT,
+                        container.container_id,
+                        &updated_state,
+                    );
+
+                    // a one-shot signal on the frame the tween completes, as for transform tweens
+                    let was_complete = state
+                        .as_ref()
+                        .is_some_and(|state| state.0.state == TweenStateStatus::TsCompleted as i32);
+                    if status == TweenStateStatus::TsCompleted && !was_complete {
+                        let tick_number = scene.tick_number;
+                        scene.update_crdt(
+                            SceneComponentId::TWEEN_COMPLETED,
+                            CrdtType::GO_ENT,
+                            container.container_id,
+                            &PbTweenCompleted {
+                                tick_number,
+                                current_time,
+                            },
+                        );
+                    }
+                }
+                match state {
+                    Some(mut state) => state.0 = updated_state,
+                    None => {
+                        commands.entity(ent).try_insert(TweenState(updated_state));
+                    }
+                }
+            }
+        }
+
+        // written only when different, so a completed tween is reapplied if the material or
+        // background is replaced, without touching it every frame
+        if tween.target.is_material() {
+            let Some(handle) = material else {
+                continue;
+            };
+            let Some(current) = materials.get(handle).map(|mat| mat.base.base_color) else {
+                continue;
+            };
+            let color = tween.target.color_at(current, progress);
+            if color.to_linear() != current.to_linear() {
+                let mat = materials.get_mut(handle).unwrap();
+                mat.base.base_color = color;
+                if color.alpha() < 1.0 && mat.base.alpha_mode == AlphaMode::Opaque {
+                    mat.base.alpha_mode = AlphaMode::Blend;
+                }
+            }
+        } else {
+            let Some(mut background) =
+                link.and_then(|link| backgrounds.get_mut(link.ui_entity()).ok())
+            else {
+                continue;
+            };
+            let color = tween.target.color_at(background.0, progress);
+            if color.to_linear() != background.0.to_linear() {
+                background.0 = color;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_alpha_tween_interpolates_material_alpha() {
+        let target = PropertyTarget::MaterialAlpha {
+            start: 1.0,
+            end: 0.0,
+        };
+        let current = Color::srgba(0.2, 0.4, 0.6, 1.0);
+
+        for time in [0.0, 0.25, 0.5, 1.0] {
+            let progress = ease(EasingFunction::EfLinear, time);
+            let color = target.color_at(current, progress);
+            assert!((color.alpha() - (1.0 - time)).abs() < 1e-6);
+            // the color itself is untouched
+            assert_eq!(color.with_alpha(1.0), current);
+        }
+
+        // overshooting easings don't push alpha out of range
+        let eased = ease(EasingFunction::EfEaseinback, 0.2);
+        assert!(eased < 0.0);
+        assert_eq!(target.color_at(current, eased).alpha(), 1.0);
+    }
+
+    #[test]
+    fn test_material_color_tween_endpoints() {
+        let start = Color::srgba(1.0, 0.0, 0.0, 1.0);
+        let end = Color::srgba(0.0, 0.0, 1.0, 0.5);
+        let target = PropertyTarget::MaterialColor { start, end };
+        let current = Color::WHITE;
+
+        let at = |time: f32| {
+            target
+                .color_at(current, ease(EasingFunction::EfLinear, time))
+                .to_linear()
+        };
+        assert_eq!(at(0.0), start.to_linear());
+        assert_eq!(at(1.0), end.to_linear());
+        let (start, end) = (start.to_linear(), end.to_linear());
+        assert_eq!(
+            at(0.5),
+            LinearRgba::new(
+                (start.red + end.red) / 2.0,
+                (start.green + end.green) / 2.0,
+                (start.blue + end.blue) / 2.0,
+                0.75,
+            )
+        );
+    }
+
+    #[test]
+    fn test_color_tween_interpolates_all_channels() {
+        let target = PropertyTarget::MaterialColor {
+            start: LinearRgba::new(0.0, 0.0, 1.0, 1.0).into(),
+            end: LinearRgba::new(1.0, 0.0, 0.0, 0.0).into(),
+        };
+        let mid = target.color_at(Color::WHITE, 0.5).to_linear();
+        assert_eq!(mid, LinearRgba::new(0.5, 0.0, 0.5, 0.5));
+    }
+
+    #[test]
+    fn test_scene_color_tween_converted() {
+        use dcl_component::proto_components::{
+            explorer::{ColorTween, PbTween},
+            sdk::components::Scale,
+        };
+
+        let mut app = App::new();
+        app.init_resource::<ColorSpace>();
+        app.add_systems(Update, update_scene_property_tweens);
+
+        let start = Color4 {
+            r: 1.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        };
+        let end = Color4 {
+            r: 0.0,
+            g: 0.0,
+            b: 1.0,
+            a: 0.5,
+        };
+        let ent = app
+            .world_mut()
+            .spawn(Tween(PbTween {
+                duration: 1000.0,
+                mode: Some(Mode::MaterialColor(ColorTween {
+                    start: Some(start),
+                    end: Some(end),
+                })),
+                playing: Some(false),
+                current_time: Some(0.5),
+                ..Default::default()
+            }))
+            .id();
+        app.update();
+
+        let tween = app.world().get::<PropertyTween>(ent).unwrap();
+        assert_eq!(
+            tween.target,
+            PropertyTarget::MaterialColor {
+                start: start.into(),
+                end: end.into(),
+            }
+        );
+        assert!(!tween.playing);
+        assert_eq!(tween.current_time, Some(0.5));
+
+        // changing to a transform tween removes it
+        app.world_mut().get_mut::<Tween>(ent).unwrap().0.mode = Some(Mode::Scale(Scale::default()));
+        app.update();
+        assert!(app.world().get::<PropertyTween>(ent).is_none());
+    }
+
+    #[test]
+    fn test_scene_alpha_tween_reports_state() {
+        use std::time::Duration;
+
+        use bevy::utils::HashSet;
+        use dcl::SceneId;
+        use dcl_component::{
+            proto_components::explorer::{AlphaTween, PbTween},
+            SceneEntityId,
+        };
+        use scene_material::SceneBound;
+
+        const CONTAINER: SceneEntityId = SceneEntityId {
+            id: 512,
+            generation: 0,
+        };
+
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<ColorSpace>()
+            .init_resource::<Assets<SceneMaterial>>()
+            .add_systems(
+                Update,
+                (update_scene_property_tweens, update_property_tweens).chain(),
+            );
+
+        let root = app.world_mut().spawn_empty().id();
+        app.world_mut()
+            .entity_mut(root)
+            .insert(RendererSceneContext::new(
+                SceneId(root),
+                String::default(),
+                false,
+                String::default(),
+                IVec2::ZERO,
+                HashSet::default(),
+                Vec::default(),
+                Vec::default(),
+                root,
+                UVec2::ONE,
+                0.0,
+                false,
+                "7",
+                false,
+            ));
+        let material = app
+            .world_mut()
+            .resource_mut::<Assets<SceneMaterial>>()
+            .add(SceneMaterial {
+                base: StandardMaterial::default(),
+                extension: SceneBound::new(Vec::default(), 0.0),
+            });
+        let ent = app
+            .world_mut()
+            .spawn((
+                ContainerEntity {
+                    container: Entity::PLACEHOLDER,
+                    root,
+                    container_id: CONTAINER,
+                },
+                material.clone(),
+                Tween(PbTween {
+                    duration: 1000.0,
+                    mode: Some(Mode::MaterialAlpha(AlphaTween {
+                        start: 1.0,
+                        end: 0.0,
+                    })),
+                    ..Default::default()
+                }),
+            ))
+            .id();
+
+        let mut step = |app: &mut App, seconds: f32| {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(seconds));
+            app.update();
+            let state = app
+                .world()
+                .get::<TweenState>(ent)
+                .map(|state| state.0.clone());
+            let alpha = app
+                .world()
+                .resource::<Assets<SceneMaterial>>()
+                .get(&material)
+                .unwrap()
+                .base
+                .base_color
+                .alpha();
+            (state, alpha)
+        };
+        let completions = |app: &App| {
+            app.world()
+                .get::<RendererSceneContext>(root)
+                .unwrap()
+                .crdt_store
+                .go
+                .get(&SceneComponentId::TWEEN_COMPLETED)
+                .and_then(|state| state.0.get(&CONTAINER))
+                .map_or(0, |queue| queue.len())
+        };
+
+        // reported from the start, as for transform tweens
+        let (state, alpha) = step(&mut app, 0.0);
+        let state = state.unwrap();
+        assert_eq!(state.state, TweenStateStatus::TsActive as i32);
+        assert_eq!(state.current_time, 0.0);
+        assert_eq!(alpha, 1.0);
+
+        let (state, alpha) = step(&mut app, 0.25);
+        assert!((state.unwrap().current_time - 0.25).abs() < 1e-5);
+        assert!((alpha - 0.75).abs() < 1e-5);
+
+        for _ in 0..4 {
+            step(&mut app, 0.25);
+        }
+        // the completion is signalled once
+        let (state, alpha) = step(&mut app, 0.25);
+        assert_eq!(state.unwrap().state, TweenStateStatus::TsCompleted as i32);
+        assert_eq!(alpha, 0.0);
+        assert_eq!(completions(&app), 1);
+    }
+}
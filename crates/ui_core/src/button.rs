@@ -14,6 +14,7 @@ use common::{
 
 use crate::{
     bound_node::NodeBounds,
+    dialog::DialogButtons,
     dui_utils::PropsExt,
     interact_style::{Active, InteractStyles},
     text_size::FontSize,
@@ -280,8 +281,9 @@ impl DuiTemplate for DuiButtonSetTemplate {
         let buttons = props
             .take::<Vec<DuiButton>>("buttons")?
             .ok_or(anyhow!("no buttons in set"))?;
+        let dialog_keys = props.take_as::<bool>(ctx, "dialog-keys")?.unwrap_or(false);
 
-        let children = buttons
+        let nodes = buttons
             .into_iter()
             .map(|button| {
                 ctx.render_template(
@@ -289,11 +291,16 @@ impl DuiTemplate for DuiButtonSetTemplate {
                     "button",
                     DuiProps::new().with_prop("button-data", button),
                 )
-                .map(|nodes| nodes["root"])
             })
             .collect::<Result<Vec<_>, _>>()?;
+        let children = nodes.iter().map(|n| n["root"]).collect::<Vec<_>>();
 
         commands.try_push_children(&children);
+        if dialog_keys {
+            commands.insert(DialogButtons::new(
+                nodes.iter().map(|n| n["button-background"]).collect(),
+            ));
+        }
 
         Ok(NodeMap::from_iter(
             children
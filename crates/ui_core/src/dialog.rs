@@ -0,0 +1,250 @@
+// yes/no confirmation dialogs. `spawn_confirm` shows a modal that blocks interaction behind it
+// and returns a `ConfirmResponse`, which can be polled from a system with `try_result` or
+// awaited. closing the dialog without choosing counts as cancelled.
+//
+// dialogs also respond to the keyboard: the first enabled button is focused when the dialog
+// opens, enter clicks the focused button and escape the last. only the most recently opened
+// dialog responds, and not while typing into a text entry.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use bevy::prelude::*;
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use bevy_simple_text_input::TextInputSystem;
+use common::{rpc::RpcResultSender, sets::SceneSets};
+use tokio::sync::oneshot::{self, error::TryRecvError};
+
+use crate::{
+    button::DuiButton,
+    focus::{BlockKeyboard, Focus},
+    toggle::Toggled,
+    ui_actions::{ClickTrigger, DataChanged, Enabled, On, UiCaller},
+};
+
+pub struct DialogPlugin;
+
+impl Plugin for DialogPlugin {
+    fn build(&self, app: &mut App) {
+        // before text entries see the key, so enter/escape in a field don't also close the dialog
+        app.add_systems(
+            Update,
+            (focus_default_button, dialog_keys)
+                .chain()
+                .in_set(SceneSets::PostLoop)
+                .before(TextInputSystem),
+        );
+    }
+}
+
+static DIALOG_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+// the buttons of a dialog's button-set, added by `dialog-keys="true"`
+#[derive(Component)]
+pub struct DialogButtons {
+    buttons: Vec<Entity>,
+    sequence: u64,
+}
+
+impl DialogButtons {
+    pub fn new(buttons: Vec<Entity>) -> Self {
+        Self {
+            buttons,
+            sequence: DIALOG_SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+fn focus_default_button(
+    mut commands: Commands,
+    dialogs: Query<&DialogButtons, Added<DialogButtons>>,
+    enabled: Query<&Enabled>,
+) {
+    for dialog in dialogs.iter() {
+        if let Some(button) = dialog
+            .buttons
+            .iter()
+            .find(|button| enabled.get(**button).map_or(true, |e| e.0))
+        {
+            commands.entity(*button).try_insert(Focus);
+        }
+    }
+}
+
+fn dialog_keys(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    dialogs: Query<&DialogButtons>,
+    enabled: Query<&Enabled>,
+    focused: Query<(), With<Focus>>,
+    typing: Query<(), (With<Focus>, With<BlockKeyboard>)>,
+) {
+    let confirm = key_input.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter]);
+    let cancel = key_input.just_pressed(KeyCode::Escape);
+    if !(confirm || cancel) || !typing.is_empty() {
+        return;
+    }
+
+    let Some(dialog) = dialogs.iter().max_by_key(|dialog| dialog.sequence) else {
+        return;
+    };
+
+    let mut usable = dialog
+        .buttons
+        .iter()
+        .filter(|button| enabled.get(**button).map_or(true, |e| e.0));
+    let target = if confirm {
+        // the focused button, or the first if focus has moved off the dialog
+        usable
+            .clone()
+            .find(|button| focused.contains(**button))
+            .or_else(|| usable.next())
+    } else {
+        usable.last()
+    };
+
+    if let Some(button) = target {
+        commands.entity(*button).try_insert(ClickTrigger);
+    }
+}
+
+pub struct ConfirmDialog {
+    pub title: String,
+    pub body: String,
+    pub confirm_label: String,
+    pub cancel_label: String,
+    // show a "don't ask again" toggle. the choice is returned to the caller to remember
+    pub dont_ask_again: bool,
+}
+
+impl ConfirmDialog {
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            confirm_label: "Ok".to_owned(),
+            cancel_label: "Cancel".to_owned(),
+            dont_ask_again: false,
+        }
+    }
+
+    pub fn with_labels(mut self, confirm: impl Into<String>, cancel: impl Into<String>) -> Self {
+        self.confirm_label = confirm.into();
+        self.cancel_label = cancel.into();
+        self
+    }
+
+    pub fn with_dont_ask_again(mut self) -> Self {
+        self.dont_ask_again = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfirmResult {
+    pub confirmed: bool,
+    pub dont_ask_again: bool,
+}
+
+pub struct ConfirmResponse {
+    receiver: oneshot::Receiver<ConfirmResult>,
+    // the dialog root, for attaching components or closing it early
+    pub dialog: Option<Entity>,
+}
+
+impl ConfirmResponse {
+    // none while the dialog is still open
+    pub fn try_result(&mut self) -> Option<ConfirmResult> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Closed) => Some(ConfirmResult::default()),
+        }
+    }
+}
+
+impl Future for ConfirmResponse {
+    type Output = ConfirmResult;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver)
+            .poll(cx)
+            .map(|result| result.unwrap_or_default())
+    }
+}
+
+pub trait ConfirmDialogExt {
+    fn spawn_confirm(&mut self, dui: &DuiRegistry, dialog: ConfirmDialog) -> ConfirmResponse;
+}
+
+impl ConfirmDialogExt for Commands<'_, '_> {
+    fn spawn_confirm(&mut self, dui: &DuiRegistry, dialog: ConfirmDialog) -> ConfirmResponse {
+        let (sx, rx) = oneshot::channel();
+        let sender = RpcResultSender::new(sx);
+        let dont_ask = Arc::new(AtomicBool::new(false));
+
+        let buttons = vec![
+            DuiButton::new_enabled_and_close_happy(dialog.confirm_label, {
+                let sender = sender.clone();
+                let dont_ask = dont_ask.clone();
+                move || {
+                    sender.send(ConfirmResult {
+                        confirmed: true,
+                        dont_ask_again: dont_ask.load(Ordering::Relaxed),
+                    })
+                }
+            }),
+            DuiButton::new_enabled_and_close_sad(dialog.cancel_label, {
+                let dont_ask = dont_ask.clone();
+                move || {
+                    sender.send(ConfirmResult {
+                        confirmed: false,
+                        dont_ask_again: dont_ask.load(Ordering::Relaxed),
+                    })
+                }
+            }),
+        ];
+
+        let props = DuiProps::new()
+            .with_prop("title", dialog.title)
+            .with_prop("body", dialog.body)
+            .with_prop("buttons", buttons);
+
+        let result = if dialog.dont_ask_again {
+            self.spawn_template(
+                dui,
+                "confirm-dialog-dont-ask",
+                props.with_prop(
+                    "dont-ask",
+                    On::<DataChanged>::new(move |caller: Res<UiCaller>, q: Query<&Toggled>| {
+                        if let Ok(toggled) = q.get(caller.0) {
+                            dont_ask.store(toggled.0, Ordering::Relaxed);
+                        }
+                    }),
+                ),
+            )
+        } else {
+            self.spawn_template(dui, "text-dialog", props)
+        };
+
+        let dialog = match result {
+            Ok(components) => Some(components.root),
+            Err(e) => {
+                warn!("failed to show confirmation: {e}");
+                None
+            }
+        };
+
+        ConfirmResponse {
+            receiver: rx,
+            dialog,
+        }
+    }
+}
@@ -2,13 +2,17 @@ pub mod bound_node;
 pub mod button;
 pub mod color_picker;
 pub mod combo_box;
+pub mod dialog;
 pub mod dui_utils;
 pub mod focus;
 pub mod interact_style;
 pub mod nine_slice;
 pub mod scrollable;
+pub mod slider;
 pub mod spinner;
+pub mod stepper;
 pub mod stretch_uvs_image;
+pub mod tab_panel;
 pub mod text_size;
 // pub mod textentry;
 pub mod interact_sounds;
@@ -32,13 +36,17 @@ use bound_node::BoundedNodePlugin;
 use button::{DuiButtonSetTemplate, DuiButtonTemplate, DuiTabGroupTemplate};
 use color_picker::ColorPickerPlugin;
 use combo_box::ComboBoxPlugin;
+use dialog::DialogPlugin;
 use interact_sounds::InteractSoundsPlugin;
 use nine_slice::Ui9SlicePlugin;
 use once_cell::sync::OnceCell;
 
 use common::sets::SetupSets;
+use slider::SliderPlugin;
 use spinner::SpinnerPlugin;
+use stepper::StepperPlugin;
 use stretch_uvs_image::StretchUvsImagePlugin;
+use tab_panel::TabPanelPlugin;
 use text_entry::TextEntryPlugin;
 use text_size::TextSizePlugin;
 use toggle::TogglePlugin;
@@ -93,6 +101,10 @@ impl Plugin for UiCorePlugin {
         app.add_plugins(TextEntryPlugin);
         app.add_plugins(SpinnerPlugin);
         app.add_plugins(ColorPickerPlugin);
+        app.add_plugins(SliderPlugin);
+        app.add_plugins(StepperPlugin);
+        app.add_plugins(TabPanelPlugin);
+        app.add_plugins(DialogPlugin);
         app.init_state::<State>();
         app.init_resource::<StateTracker<State>>();
         app.add_systems(Startup, setup.in_set(SetupSets::Init));
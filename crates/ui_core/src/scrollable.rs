@@ -212,6 +212,64 @@ struct Slider {
 #[derive(Resource, Default)]
 pub struct UsedScrollWheel(pub bool);
 
+// offset of the content within its viewport for a scroll position from 0 (start) to 1 (end),
+// clamped so the content never moves past its edges
+pub fn scroll_offset(slide_amount: Vec2, position: f32) -> Vec2 {
+    slide_amount.max(Vec2::ZERO) * -position.clamp(0.0, 1.0)
+}
+
+#[derive(Copy, Clone, Debug)]
+enum UpdateSliderPosition {
+    Abs(f32),
+    Rel(f32),
+}
+
+impl UpdateSliderPosition {
+    fn apply(self, position: f32) -> f32 {
+        match self {
+            UpdateSliderPosition::Abs(p) => p.clamp(0.0, 1.0),
+            UpdateSliderPosition::Rel(r) => (position + r).clamp(0.0, 1.0),
+        }
+    }
+}
+
+// change in scroll position from wheel events, for content `slide_amount` larger than its viewport
+fn wheel_delta(events: &[&MouseWheel], slide_amount: Vec2) -> Vec2 {
+    events
+        .iter()
+        .map(|ev| {
+            let unit = match ev.unit {
+                bevy::input::mouse::MouseScrollUnit::Line => 20.0,
+                bevy::input::mouse::MouseScrollUnit::Pixel => 1.0,
+            };
+            Vec2::new(ev.x, ev.y) * unit / slide_amount
+        })
+        .sum()
+}
+
+// the candidate with no other candidate nested inside it in the ui hierarchy, so a region
+// within another region takes the wheel from it
+fn innermost_scrollable(
+    candidates: &[Entity],
+    parent: impl Fn(Entity) -> Option<Entity>,
+) -> Option<Entity> {
+    let contains = |ancestor: Entity, mut entity: Entity| {
+        while let Some(next) = parent(entity) {
+            if next == ancestor {
+                return true;
+            }
+            entity = next;
+        }
+        false
+    };
+
+    candidates.iter().copied().find(|&candidate| {
+        !candidates
+            .iter()
+            .any(|&other| other != candidate && contains(candidate, other))
+    })
+}
+
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn update_scrollables(
     mut commands: Commands,
@@ -221,6 +279,7 @@ fn update_scrollables(
         (Without<Scrollable>, Without<ScrollBar>, Without<Slider>),
     >,
     positions: Query<(&Node, &Transform, &Parent, &GlobalTransform)>,
+    parents: Query<&Parent>,
     mut scrollables: Query<(
         Entity,
         &mut Scrollable,
@@ -256,12 +315,6 @@ fn update_scrollables(
     cursors: Query<(Entity, &ManualCursorPosition)>,
     mut used_wheel: ResMut<UsedScrollWheel>,
 ) {
-    #[derive(Copy, Clone, Debug)]
-    enum UpdateSliderPosition {
-        Abs(f32),
-        Rel(f32),
-    }
-
     struct ScrollInfo {
         content: Entity,
         ratio: f32,
@@ -311,6 +364,44 @@ fn update_scrollables(
 
     let wheel_events = wheel.read().collect::<Vec<_>>();
 
+    // the wheel scrolls only the innermost scrollable under the cursor that has content to
+    // scroll, so nested regions don't move together
+    let mut wheel_candidates = Vec::default();
+    for (entity, scrollable, scroll_content, node, transform, .., maybe_target_camera) in
+        scrollables.iter()
+    {
+        let Some(cursor_position) = cursor_position(maybe_target_camera) else {
+            continue;
+        };
+        let Ok((child_node, ..)) = nodes.get(scroll_content.0) else {
+            continue;
+        };
+        let parent_size = node.size();
+        let ui_position = transform.translation().truncate() - parent_size * 0.5;
+        if !scrollable.wheel
+            || cursor_position.clamp(ui_position, ui_position + parent_size) != cursor_position
+        {
+            continue;
+        }
+
+        // we check only if the cursor is within our frame - this means scrollables can still be scrolled when
+        // blocking dialogs cover them. TODO: make this better, requires either
+        // - check all children for interaction (yuck)
+        // - add some context to FocusPolicy (e.g. FocusPolicy::Block(HashSet<Buttons>))
+        // - add another system to manage "container" focus based on child focus
+        used_wheel.0 = true;
+
+        let overflow = child_node.size() - parent_size;
+        let can_scroll = (overflow.x > 0.0 && scrollable.direction.horizontal().is_some())
+            || (overflow.y > 0.0 && scrollable.direction.vertical().is_some());
+        if can_scroll {
+            wheel_candidates.push(entity);
+        }
+    }
+    let wheel_target = innermost_scrollable(&wheel_candidates, |entity| {
+        parents.get(entity).ok().map(Parent::get)
+    });
+
     // gather scrollable components that need scrollbars
     for (
         entity,
@@ -386,24 +477,9 @@ fn update_scrollables(
                     *clicked_scrollable = Some((entity, cursor_position));
                 }
             }
-            if scrollable.wheel {
-                // we check only if the cursor is within our frame - this means scrollables can still be scrolled when
-                // blocking dialogs cover them. TODO: make this better, requires either
-                // - check all children for interaction (yuck)
-                // - add some context to FocusPolicy (e.g. FocusPolicy::Block(HashSet<Buttons>))
-                // - add another system to manage "container" focus based on child focus
-                if cursor_position.clamp(ui_position, ui_position + parent_size) == cursor_position
-                {
-                    used_wheel.0 = true;
-                    for ev in wheel_events.iter() {
-                        let unit = match ev.unit {
-                            bevy::input::mouse::MouseScrollUnit::Line => 20.0,
-                            bevy::input::mouse::MouseScrollUnit::Pixel => 1.0,
-                        };
-                        *new_slider_deltas.get_or_insert(Default::default()) +=
-                            Vec2::new(ev.x, ev.y) * unit / slide_amount;
-                    }
-                }
+            if wheel_target == Some(entity) && !wheel_events.is_empty() {
+                *new_slider_deltas.get_or_insert(Default::default()) +=
+                    wheel_delta(&wheel_events, slide_amount);
             }
         }
 
@@ -544,10 +620,7 @@ fn update_scrollables(
 
         if let Some(position) = info.update_slider {
             // the container or the bar have triggered a slider update
-            slider.position = match position {
-                UpdateSliderPosition::Abs(p) => p.clamp(0.0, 1.0),
-                UpdateSliderPosition::Rel(r) => (slider.position + r).clamp(0.0, 1.0),
-            };
+            slider.position = position.apply(slider.position);
             update_position = true;
         } else if info.redraw {
             // parent moved/resized or content moved/resized
@@ -575,7 +648,7 @@ fn update_scrollables(
 
             // re-paginate content
             let mut style = nodes.get_mut(info.content).unwrap().1;
-            let offset = info.slide_amount * -slider.position;
+            let offset = scroll_offset(info.slide_amount, slider.position);
             if slider.vertical {
                 style.top = Val::Px(offset.y.floor());
                 let position = slider.position;
@@ -932,3 +1005,84 @@ impl DuiTemplate for TwoWayScrollTemplate {
         ctx.render_template(commands, "scrollable-base", props)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scroll_offset_clamped() {
+        // a 100x400 child in a 100x100 viewport can move up by 300
+        let viewport = Vec2::new(100.0, 100.0);
+        let slide_amount = Vec2::new(100.0, 400.0) - viewport;
+
+        assert_eq!(scroll_offset(slide_amount, 0.0), Vec2::ZERO);
+        assert_eq!(scroll_offset(slide_amount, 0.5).y, -150.0);
+        assert_eq!(scroll_offset(slide_amount, 1.0).y, -300.0);
+        // past either end stays at the content bounds
+        assert_eq!(scroll_offset(slide_amount, 2.5).y, -300.0);
+        assert_eq!(scroll_offset(slide_amount, -1.0).y, 0.0);
+        // the axis that fits never moves
+        assert_eq!(scroll_offset(slide_amount, 1.0).x, 0.0);
+        assert_eq!(scroll_offset(Vec2::new(-20.0, -20.0), 1.0), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_wheel_clamped_to_oversized_child() {
+        use bevy::input::mouse::MouseScrollUnit;
+
+        // a 100x400 child in a 100x100 viewport
+        let viewport = Vec2::new(100.0, 100.0);
+        let slide_amount = Vec2::new(100.0, 400.0) - viewport;
+        let wheel = |y: f32| MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.0,
+            y,
+            window: Entity::PLACEHOLDER,
+        };
+        let scroll = |position: f32, y: f32| {
+            let delta = wheel_delta(&[&wheel(y)], slide_amount);
+            UpdateSliderPosition::Rel(-delta.y).apply(position)
+        };
+
+        // one line down moves the content up a line
+        let position = scroll(0.0, -1.0);
+        assert!((scroll_offset(slide_amount, position).y + 20.0).abs() < 1e-3);
+
+        // scrolling well past the end stops with the bottom of the child in view
+        let mut position = 0.0;
+        for _ in 0..50 {
+            position = scroll(position, -1.0);
+        }
+        assert_eq!(position, 1.0);
+        assert_eq!(scroll_offset(slide_amount, position).y, -300.0);
+
+        // and back past the start with the top in view
+        for _ in 0..50 {
+            position = scroll(position, 1.0);
+        }
+        assert_eq!(position, 0.0);
+        assert_eq!(scroll_offset(slide_amount, position).y, 0.0);
+    }
+
+    #[test]
+    fn test_innermost_scrollable_by_nesting() {
+        let mut world = World::new();
+        let inner = world.spawn_empty().id();
+        let middle = world.spawn_empty().add_child(inner).id();
+        let outer = world.spawn_empty().add_child(middle).id();
+        let unrelated = world.spawn_empty().id();
+        let parent = |entity| world.get::<Parent>(entity).map(Parent::get);
+
+        // the nested region wins regardless of order or size
+        assert_eq!(innermost_scrollable(&[outer, inner], parent), Some(inner));
+        assert_eq!(innermost_scrollable(&[inner, outer], parent), Some(inner));
+        assert_eq!(innermost_scrollable(&[middle, outer], parent), Some(middle));
+        // regions that aren't nested don't hide each other's inner regions
+        assert_ne!(
+            innermost_scrollable(&[unrelated, outer, inner], parent),
+            Some(outer)
+        );
+        assert_eq!(innermost_scrollable(&[], parent), None);
+    }
+}
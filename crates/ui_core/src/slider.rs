@@ -0,0 +1,228 @@
+use bevy::{
+    prelude::*,
+    ui::{FocusPolicy, RelativeCursorPosition},
+};
+use bevy_dui::{DuiRegistry, DuiTemplate};
+use common::sets::SceneSets;
+
+use crate::{
+    dui_utils::PropsExt,
+    focus::{Focus, Focusable},
+    ui_actions::{DataChanged, On},
+};
+
+// a horizontal slider. drag the track or handle with the mouse, or click it to focus and
+// use the arrow keys (page up/down for bigger steps, home/end for the limits).
+// `DataChanged` fires whenever the value changes while dragging or from the keyboard.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Slider {
+    pub min: f32,
+    pub max: f32,
+    // 0 for continuous
+    pub step: f32,
+    pub value: f32,
+    pub disabled: bool,
+}
+
+impl Slider {
+    pub fn new(min: f32, max: f32, step: f32, value: f32) -> Self {
+        let mut slider = Self {
+            min,
+            max: max.max(min),
+            step: step.max(0.0),
+            value: min,
+            disabled: false,
+        };
+        slider.set_value(value);
+        slider
+    }
+
+    // position of the value along the track, 0-1
+    pub fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    // snaps to the step and clamps to the range. returns true if the value changed
+    pub fn set_value(&mut self, value: f32) -> bool {
+        if !value.is_finite() {
+            return false;
+        }
+        let value = snap_value(value, self.min, self.max, self.step);
+        let changed = value != self.value;
+        self.value = value;
+        changed
+    }
+
+    pub fn set_fraction(&mut self, fraction: f32) -> bool {
+        self.set_value(self.min + (self.max - self.min) * fraction.clamp(0.0, 1.0))
+    }
+
+    // move by a number of steps, or hundredths of the range for continuous sliders
+    pub fn nudge(&mut self, steps: i32) -> bool {
+        let step = if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) / 100.0
+        };
+        self.set_value(self.value + step * steps as f32)
+    }
+}
+
+// clamp to the range and round to the nearest step from min, steps <= 0 are continuous
+pub fn snap_value(value: f32, min: f32, max: f32, step: f32) -> f32 {
+    let mut value = value.clamp(min, max);
+    if step > 0.0 {
+        value = (min + ((value - min) / step).round() * step).min(max);
+    }
+    value
+}
+
+#[derive(Component)]
+struct SliderHandle(Entity);
+
+pub struct SliderPlugin;
+
+impl Plugin for SliderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                setup_sliders,
+                (drag_sliders, key_sliders),
+                update_slider_handles,
+            )
+                .chain()
+                .in_set(SceneSets::PostLoop),
+        );
+    }
+}
+
+fn setup(mut dui: ResMut<DuiRegistry>) {
+    dui.register_template("slider", DuiSliderTemplate);
+}
+
+fn setup_sliders(mut commands: Commands, new_sliders: Query<Entity, Added<Slider>>) {
+    for ent in new_sliders.iter() {
+        let handle = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(12.0),
+                    height: Val::Percent(100.0),
+                    margin: UiRect::left(Val::Px(-6.0)),
+                    ..Default::default()
+                },
+                background_color: Color::srgb(0.9, 0.9, 0.9).into(),
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            })
+            .id();
+
+        commands
+            .entity(ent)
+            .try_insert((
+                Interaction::default(),
+                RelativeCursorPosition::default(),
+                Focusable,
+                SliderHandle(handle),
+            ))
+            .add_child(handle);
+    }
+}
+
+fn drag_sliders(
+    mut commands: Commands,
+    mut sliders: Query<(Entity, &mut Slider, &Interaction, &RelativeCursorPosition)>,
+) {
+    for (ent, mut slider, interaction, cursor) in sliders.iter_mut() {
+        if slider.disabled || *interaction != Interaction::Pressed {
+            continue;
+        }
+        // normalized is still reported outside the node while the button is held
+        let Some(pos) = cursor.normalized else {
+            continue;
+        };
+        if slider.bypass_change_detection().set_fraction(pos.x) {
+            slider.set_changed();
+            commands.entity(ent).try_insert(DataChanged);
+        }
+    }
+}
+
+fn key_sliders(
+    mut commands: Commands,
+    mut sliders: Query<(Entity, &mut Slider), With<Focus>>,
+    key_input: Res<ButtonInput<KeyCode>>,
+) {
+    for (ent, mut slider) in sliders.iter_mut() {
+        if slider.disabled {
+            continue;
+        }
+
+        let changed = if key_input.just_pressed(KeyCode::Home) {
+            let min = slider.min;
+            slider.set_value(min)
+        } else if key_input.just_pressed(KeyCode::End) {
+            let max = slider.max;
+            slider.set_value(max)
+        } else {
+            let steps = [
+                (KeyCode::ArrowLeft, -1),
+                (KeyCode::ArrowDown, -1),
+                (KeyCode::ArrowRight, 1),
+                (KeyCode::ArrowUp, 1),
+                (KeyCode::PageDown, -10),
+                (KeyCode::PageUp, 10),
+            ]
+            .into_iter()
+            .filter(|(key, _)| key_input.just_pressed(*key))
+            .map(|(_, steps)| steps)
+            .sum::<i32>();
+            steps != 0 && slider.nudge(steps)
+        };
+
+        if changed {
+            commands.entity(ent).try_insert(DataChanged);
+        }
+    }
+}
+
+fn update_slider_handles(
+    sliders: Query<(&Slider, &SliderHandle), Changed<Slider>>,
+    mut styles: Query<&mut Style>,
+) {
+    for (slider, handle) in sliders.iter() {
+        if let Ok(mut style) = styles.get_mut(handle.0) {
+            style.left = Val::Percent(slider.fraction() * 100.0);
+        }
+    }
+}
+
+pub struct DuiSliderTemplate;
+impl DuiTemplate for DuiSliderTemplate {
+    fn render(
+        &self,
+        commands: &mut bevy::ecs::system::EntityCommands,
+        mut props: bevy_dui::DuiProps,
+        ctx: &mut bevy_dui::DuiContext,
+    ) -> Result<bevy_dui::NodeMap, anyhow::Error> {
+        let mut slider = Slider::new(
+            props.take_as::<f32>(ctx, "min")?.unwrap_or(0.0),
+            props.take_as::<f32>(ctx, "max")?.unwrap_or(1.0),
+            props.take_as::<f32>(ctx, "step")?.unwrap_or(0.0),
+            props.take_as::<f32>(ctx, "value")?.unwrap_or(0.0),
+        );
+        slider.disabled = props.take_as::<bool>(ctx, "disabled")?.unwrap_or(false);
+        commands.insert(slider);
+
+        if let Some(onchanged) = props.take::<On<DataChanged>>("onchanged")? {
+            commands.insert(onchanged);
+        }
+
+        Ok(Default::default())
+    }
+}
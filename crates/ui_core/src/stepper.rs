@@ -0,0 +1,268 @@
+use bevy::{ecs::query::Has, prelude::*, ui::FocusPolicy};
+use bevy_dui::{DuiRegistry, DuiTemplate};
+use bevy_simple_text_input::TextInputValue;
+use common::sets::SceneSets;
+
+use crate::{
+    dui_utils::PropsExt,
+    focus::Focus,
+    slider::snap_value,
+    text_entry::TextEntry,
+    text_size::FontSize,
+    ui_actions::{Click, DataChanged, On},
+};
+
+// a numeric field with -/+ buttons. typed text is filtered to numeric characters and applied
+// when the field loses focus or is submitted, invalid text reverts to the last valid value.
+// up/down arrows step while the field is focused. `DataChanged` fires with the new `value`.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct NumberStepper {
+    pub min: f32,
+    pub max: f32,
+    // 0 for any value
+    pub step: f32,
+    pub value: f32,
+    // decimal places shown and accepted
+    pub decimals: usize,
+    pub disabled: bool,
+}
+
+impl NumberStepper {
+    pub fn new(min: f32, max: f32, step: f32, value: f32, decimals: usize) -> Self {
+        let mut stepper = Self {
+            min,
+            max: max.max(min),
+            step: step.max(0.0),
+            value: min,
+            decimals,
+            disabled: false,
+        };
+        stepper.set_value(value);
+        stepper
+    }
+
+    // snaps to the step and clamps to the range. returns true if the value changed
+    pub fn set_value(&mut self, value: f32) -> bool {
+        if !value.is_finite() {
+            return false;
+        }
+        let value = snap_value(value, self.min, self.max, self.step);
+        let changed = value != self.value;
+        self.value = value;
+        changed
+    }
+
+    pub fn nudge(&mut self, steps: i32) -> bool {
+        let step = if self.step > 0.0 {
+            self.step
+        } else {
+            10f32.powi(-(self.decimals as i32))
+        };
+        self.set_value(self.value + step * steps as f32)
+    }
+
+    pub fn format(&self) -> String {
+        format!("{:.*}", self.decimals, self.value)
+    }
+
+    // the typed text as a value, or none if it doesn't parse
+    pub fn parse(&self, text: &str) -> Option<f32> {
+        text.trim().parse::<f32>().ok().filter(|v| v.is_finite())
+    }
+
+    // drop characters that can't be part of a valid value
+    pub fn filter(&self, text: &str) -> String {
+        text.chars()
+            .enumerate()
+            .filter(|(ix, c)| {
+                c.is_ascii_digit()
+                    || (*c == '-' && *ix == 0 && self.min < 0.0)
+                    || (*c == '.' && self.decimals > 0)
+            })
+            .map(|(_, c)| c)
+            .collect()
+    }
+}
+
+#[derive(Component)]
+struct StepperNodes {
+    entry: Entity,
+    // text last written to the entry, to tell user edits from value updates
+    shown: String,
+}
+
+pub struct StepperPlugin;
+
+impl Plugin for StepperPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (setup_steppers, update_steppers)
+                .chain()
+                .in_set(SceneSets::PostLoop),
+        );
+    }
+}
+
+fn setup(mut dui: ResMut<DuiRegistry>) {
+    dui.register_template("number-stepper", DuiNumberStepperTemplate);
+}
+
+fn stepper_button(commands: &mut Commands, stepper: Entity, label: &str, steps: i32) -> Entity {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    min_width: Val::Px(24.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.2, 0.2, 0.2, 0.867).into(),
+                focus_policy: FocusPolicy::Block,
+                ..Default::default()
+            },
+            Interaction::default(),
+            On::<Click>::new(
+                move |mut commands: Commands, mut q: Query<&mut NumberStepper>| {
+                    let Ok(mut stepper_value) = q.get_mut(stepper) else {
+                        return;
+                    };
+                    if !stepper_value.disabled && stepper_value.nudge(steps) {
+                        commands.entity(stepper).try_insert(DataChanged);
+                    }
+                },
+            ),
+        ))
+        .with_children(|c| {
+            c.spawn((
+                TextBundle::from_section(label, Default::default()),
+                FontSize(0.03 / 1.3),
+            ));
+        })
+        .id()
+}
+
+fn setup_steppers(
+    mut commands: Commands,
+    new_steppers: Query<(Entity, &NumberStepper), Added<NumberStepper>>,
+) {
+    for (ent, stepper) in new_steppers.iter() {
+        let shown = stepper.format();
+        let minus = stepper_button(&mut commands, ent, "-", -1);
+        let entry = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_grow: 1.0,
+                        min_width: Val::Px(40.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                TextEntry {
+                    content: shown.clone(),
+                    enabled: !stepper.disabled,
+                    accept_line: false,
+                    ..Default::default()
+                },
+            ))
+            .id();
+        let plus = stepper_button(&mut commands, ent, "+", 1);
+
+        commands
+            .entity(ent)
+            .try_insert(StepperNodes { entry, shown })
+            .push_children(&[minus, entry, plus]);
+    }
+}
+
+fn update_steppers(
+    mut commands: Commands,
+    mut steppers: Query<(Entity, &mut NumberStepper, &mut StepperNodes)>,
+    children: Query<&Children>,
+    mut inputs: Query<(&mut TextInputValue, Has<Focus>)>,
+    key_input: Res<ButtonInput<KeyCode>>,
+) {
+    for (ent, mut stepper, mut nodes) in steppers.iter_mut() {
+        let Some((mut text, focused)) = children
+            .get(nodes.entry)
+            .ok()
+            .and_then(|children| children.iter().find(|c| inputs.contains(**c)))
+            .and_then(|input| inputs.get_mut(*input).ok())
+        else {
+            continue;
+        };
+
+        let mut changed = false;
+        if focused {
+            let filtered = stepper.filter(&text.0);
+            if filtered != text.0 {
+                text.0 = filtered;
+            }
+
+            let steps = match (
+                key_input.just_pressed(KeyCode::ArrowUp),
+                key_input.just_pressed(KeyCode::ArrowDown),
+            ) {
+                (true, false) => 1,
+                (false, true) => -1,
+                _ => 0,
+            };
+            if steps != 0 && !stepper.disabled {
+                // step from the typed value if it's valid
+                if let Some(typed) = stepper.parse(&text.0) {
+                    changed |= stepper.bypass_change_detection().set_value(typed);
+                }
+                changed |= stepper.bypass_change_detection().nudge(steps);
+                nodes.shown.clear();
+            }
+        } else if text.0 != nodes.shown {
+            // edited and then defocused or submitted
+            if let Some(typed) = stepper.parse(&text.0) {
+                if !stepper.disabled {
+                    changed |= stepper.bypass_change_detection().set_value(typed);
+                }
+            }
+            // show the applied value, or revert invalid text
+            nodes.shown.clear();
+        }
+
+        if changed {
+            stepper.set_changed();
+            commands.entity(ent).try_insert(DataChanged);
+        }
+
+        let formatted = stepper.format();
+        if nodes.shown != formatted {
+            text.0 = formatted.clone();
+            nodes.shown = formatted;
+        }
+    }
+}
+
+pub struct DuiNumberStepperTemplate;
+impl DuiTemplate for DuiNumberStepperTemplate {
+    fn render(
+        &self,
+        commands: &mut bevy::ecs::system::EntityCommands,
+        mut props: bevy_dui::DuiProps,
+        ctx: &mut bevy_dui::DuiContext,
+    ) -> Result<bevy_dui::NodeMap, anyhow::Error> {
+        let mut stepper = NumberStepper::new(
+            props.take_as::<f32>(ctx, "min")?.unwrap_or(0.0),
+            props.take_as::<f32>(ctx, "max")?.unwrap_or(f32::MAX),
+            props.take_as::<f32>(ctx, "step")?.unwrap_or(1.0),
+            props.take_as::<f32>(ctx, "value")?.unwrap_or(0.0),
+            props.take_as::<usize>(ctx, "decimals")?.unwrap_or(0),
+        );
+        stepper.disabled = props.take_as::<bool>(ctx, "disabled")?.unwrap_or(false);
+        commands.insert(stepper);
+
+        if let Some(onchanged) = props.take::<On<DataChanged>>("onchanged")? {
+            commands.insert(onchanged);
+        }
+
+        Ok(Default::default())
+    }
+}
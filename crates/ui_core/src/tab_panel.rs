@@ -0,0 +1,206 @@
+use anyhow::anyhow;
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry, DuiTemplate};
+use common::sets::SceneSets;
+
+use crate::{
+    button::{DuiButton, TabManager, TabSelection},
+    ui_actions::{DataChanged, On, UiCaller},
+};
+
+// a tab-group with a content area showing the selected tab's page. switch by clicking a tab,
+// or with ctrl+pageup / ctrl+pagedown while the pointer is over the panel. disabled tabs
+// can't be selected. `DataChanged` fires on the panel when `selected` changes.
+#[derive(Component)]
+pub struct TabPanel {
+    pub selected: Option<usize>,
+    tabs: Entity,
+    pages: Vec<Entity>,
+    enabled: Vec<bool>,
+}
+
+impl TabPanel {
+    pub fn page(&self, ix: usize) -> Option<Entity> {
+        self.pages.get(ix).copied()
+    }
+
+    // the next enabled tab in the given direction, wrapping around
+    fn next_enabled(&self, forward: bool) -> Option<usize> {
+        let count = self.enabled.len();
+        let start = self.selected.unwrap_or(if forward { count - 1 } else { 0 });
+        (1..=count)
+            .map(|offset| {
+                if forward {
+                    (start + offset) % count
+                } else {
+                    (start + count - offset) % count
+                }
+            })
+            .find(|ix| self.enabled[*ix])
+    }
+}
+
+pub struct TabPanelPlugin;
+
+impl Plugin for TabPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (key_tab_panels, update_tab_panels)
+                .chain()
+                .in_set(SceneSets::PostLoop),
+        );
+    }
+}
+
+fn setup(mut dui: ResMut<DuiRegistry>) {
+    dui.register_template("tab-panel", DuiTabPanelTemplate);
+}
+
+fn key_tab_panels(
+    panels: Query<(&TabPanel, &RelativeCursorPosition)>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut tab_manager: TabManager,
+) {
+    if !key_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let forward = match (
+        key_input.just_pressed(KeyCode::PageDown),
+        key_input.just_pressed(KeyCode::PageUp),
+    ) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => return,
+    };
+
+    for (panel, cursor) in panels.iter() {
+        if !cursor.mouse_over() {
+            continue;
+        }
+        if let Some(next) = panel.next_enabled(forward) {
+            if Some(next) != panel.selected {
+                // updates the tab buttons, and the panel via the tab-group's onchanged
+                tab_manager.set_selected(panel.tabs, Some(next));
+            }
+        }
+    }
+}
+
+fn update_tab_panels(panels: Query<&TabPanel, Changed<TabPanel>>, mut styles: Query<&mut Style>) {
+    for panel in panels.iter() {
+        for (ix, page) in panel.pages.iter().enumerate() {
+            if let Ok(mut style) = styles.get_mut(*page) {
+                style.display = if Some(ix) == panel.selected {
+                    Display::Flex
+                } else {
+                    Display::None
+                };
+            }
+        }
+    }
+}
+
+pub struct DuiTabPanelTemplate;
+impl DuiTemplate for DuiTabPanelTemplate {
+    fn render(
+        &self,
+        commands: &mut bevy::ecs::system::EntityCommands,
+        mut props: bevy_dui::DuiProps,
+        ctx: &mut bevy_dui::DuiContext,
+    ) -> Result<bevy_dui::NodeMap, anyhow::Error> {
+        let id = commands.id();
+
+        let buttons = props
+            .take::<Vec<DuiButton>>("tabs")?
+            .ok_or(anyhow!("no tabs in panel"))?;
+        let pages = props
+            .take::<Vec<Entity>>("pages")?
+            .ok_or(anyhow!("no pages in panel"))?;
+        if buttons.len() != pages.len() || buttons.is_empty() {
+            anyhow::bail!("tab panel needs one page per tab");
+        }
+        let enabled = buttons.iter().map(|b| b.enabled).collect::<Vec<_>>();
+        let selected = props
+            .take::<Option<usize>>("initial")?
+            .unwrap_or_default()
+            .or_else(|| enabled.iter().position(|e| *e));
+
+        let tabs = commands
+            .commands()
+            .spawn_template(
+                ctx.registry(),
+                "tab-group",
+                DuiProps::new()
+                    .with_prop("tabs", buttons)
+                    .with_prop("initial", selected)
+                    .with_prop("edge-scale", "1px 1px -0px 1px".to_owned())
+                    .with_prop(
+                        "onchanged",
+                        On::<DataChanged>::new(
+                            move |mut commands: Commands,
+                                  caller: Res<UiCaller>,
+                                  tabs: Query<&TabSelection>,
+                                  mut panels: Query<&mut TabPanel>| {
+                                let (Ok(tabs), Ok(mut panel)) =
+                                    (tabs.get(caller.0), panels.get_mut(id))
+                                else {
+                                    return;
+                                };
+                                if panel.selected != tabs.selected {
+                                    panel.selected = tabs.selected;
+                                    commands.entity(id).try_insert(DataChanged);
+                                }
+                            },
+                        ),
+                    ),
+            )?
+            .root;
+
+        let content = commands
+            .commands()
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_grow: 1.0,
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .push_children(&pages)
+            .id();
+
+        let container = commands
+            .commands()
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .push_children(&[tabs, content])
+            .id();
+
+        commands.insert((
+            TabPanel {
+                selected,
+                tabs,
+                pages,
+                enabled,
+            },
+            RelativeCursorPosition::default(),
+        ));
+        commands.add_child(container);
+        if let Some(onchanged) = props.take::<On<DataChanged>>("onchanged")? {
+            commands.insert(onchanged);
+        }
+
+        Ok(bevy_dui::NodeMap::from_iter([
+            ("tabs".to_owned(), tabs),
+            ("content".to_owned(), content),
+        ]))
+    }
+}
@@ -141,14 +141,23 @@ pub trait ActionMarker: Send + Sync + 'static {
 
 pub struct Click;
 impl ActionMarker for Click {
-    type Component = (&'static Interaction, Option<&'static Enabled>);
+    type Component = (
+        &'static Interaction,
+        Option<&'static Enabled>,
+        Option<Ref<'static, ClickTrigger>>,
+    );
     fn activate(
-        (interact, enabled): <<Self::Component as QueryData>::ReadOnly as WorldQuery>::Item<'_>,
+        (interact, enabled, trigger): <<Self::Component as QueryData>::ReadOnly as WorldQuery>::Item<'_>,
     ) -> bool {
-        matches!(interact, Interaction::Pressed) && enabled.map_or(true, |a| a.0)
+        (matches!(interact, Interaction::Pressed) || trigger.is_some_and(|t| t.is_changed()))
+            && enabled.map_or(true, |a| a.0)
     }
 }
 
+// insert to activate an entity's click actions without the mouse, e.g. from a shortcut key
+#[derive(Component)]
+pub struct ClickTrigger;
+
 pub struct ClickRepeat;
 impl ActionMarker for ClickRepeat {
     type Component = <Click as ActionMarker>::Component;
@@ -0,0 +1,395 @@
+// auto-walk drives the player along a path of waypoints, for demos and recordings. waypoints
+// are parcels (walking to the parcel's center) or world positions, written `x,y` and `x,y,z`.
+// the player walks at the path's speed facing the direction of travel, waits `pause_secs` at
+// each waypoint, and either stops or starts over at the end. any movement input hands control
+// back. paths are built and run with `/path`, and can be saved to and loaded from json files.
+// when the scene ahead isn't ready yet the player waits for it, up to `MAX_SCENE_WAIT`.
+
+use std::{fmt::Display, str::FromStr};
+
+use bevy::{ecs::query::Has, math::Vec3Swizzles, prelude::*};
+use bevy_console::ConsoleCommand;
+use common::{sets::SceneSets, structs::PrimaryUser};
+use console::DoAddConsoleCommand;
+use dcl_component::proto_components::sdk::components::common::InputAction;
+use input_manager::InputManager;
+use scene_runner::{
+    initialize_scene::{LiveScenes, PointerResult, ScenePointers, PARCEL_SIZE},
+    renderer_context::RendererSceneContext,
+    vec3_to_parcel, OutOfWorld,
+};
+use serde::{Deserialize, Serialize};
+
+use avatar::AvatarDynamicState;
+
+use crate::player_input::update_user_velocity;
+
+// horizontal distance that counts as reaching a waypoint
+const ARRIVAL_DISTANCE: f32 = 0.5;
+// how far ahead to check that the scene is ready
+const LOOKAHEAD: f32 = 4.0;
+// seconds to wait for a scene before walking on regardless
+const MAX_SCENE_WAIT: f32 = 10.0;
+
+// inputs that take control back
+pub(crate) const MANUAL_ACTIONS: [InputAction; 5] = [
+    InputAction::IaForward,
+    InputAction::IaBackward,
+    InputAction::IaLeft,
+    InputAction::IaRight,
+    InputAction::IaJump,
+];
+
+pub struct AutoWalkPlugin;
+
+impl Plugin for AutoWalkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AutoWalk>();
+        app.add_systems(
+            Update,
+            auto_walk
+                .after(update_user_velocity)
+                .in_set(SceneSets::Input),
+        );
+        app.add_console_command::<PathCommand, _>(path_command);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Waypoint {
+    Parcel(IVec2),
+    Position(Vec3),
+}
+
+impl Waypoint {
+    pub fn target(&self) -> Vec3 {
+        match self {
+            Waypoint::Parcel(parcel) => Vec3::new(
+                (parcel.x as f32 + 0.5) * PARCEL_SIZE,
+                0.0,
+                -(parcel.y as f32 + 0.5) * PARCEL_SIZE,
+            ),
+            Waypoint::Position(position) => *position,
+        }
+    }
+}
+
+impl FromStr for Waypoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split(',').map(str::trim).collect::<Vec<_>>();
+        match parts.as_slice() {
+            [x, y] => match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => Ok(Waypoint::Parcel(IVec2::new(x, y))),
+                _ => Err(format!("invalid parcel `{s}`, expected `x,y`")),
+            },
+            [x, y, z] => match (x.parse(), y.parse(), z.parse()) {
+                (Ok(x), Ok(y), Ok(z)) => Ok(Waypoint::Position(Vec3::new(x, y, z))),
+                _ => Err(format!("invalid position `{s}`, expected `x,y,z`")),
+            },
+            _ => Err(format!(
+                "invalid waypoint `{s}`, expected a parcel `x,y` or a position `x,y,z`"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Waypoint {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for Waypoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Waypoint::Parcel(parcel) => write!(f, "{},{}", parcel.x, parcel.y),
+            Waypoint::Position(position) => {
+                write!(f, "{},{},{}", position.x, position.y, position.z)
+            }
+        }
+    }
+}
+
+impl From<Waypoint> for String {
+    fn from(value: Waypoint) -> Self {
+        value.to_string()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalkPath {
+    pub waypoints: Vec<Waypoint>,
+    // meters per second
+    pub speed: f32,
+    pub pause_secs: f32,
+    pub looping: bool,
+}
+
+impl Default for WalkPath {
+    fn default() -> Self {
+        Self {
+            waypoints: Vec::default(),
+            speed: 4.0,
+            pause_secs: 0.0,
+            looping: false,
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+struct WalkState {
+    next: usize,
+    // seconds left to wait at the last waypoint
+    pause: f32,
+    // seconds spent waiting for scenes to load
+    scene_wait: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct AutoWalk {
+    pub path: WalkPath,
+    state: Option<WalkState>,
+}
+
+impl AutoWalk {
+    pub fn walking(&self) -> bool {
+        self.state.is_some()
+    }
+
+    pub fn start(&mut self) {
+        self.state = Some(WalkState::default());
+    }
+
+    pub fn stop(&mut self) {
+        self.state = None;
+    }
+}
+
+/// build and run an auto-walk path
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/path")]
+struct PathCommand {
+    #[command(subcommand)]
+    action: PathAction,
+}
+
+#[derive(clap::Subcommand)]
+enum PathAction {
+    /// add a waypoint, a parcel `x,y` or a position `x,y,z`
+    Add {
+        #[arg(allow_hyphen_values = true)]
+        waypoint: String,
+    },
+    /// remove all waypoints
+    Clear,
+    /// show the path
+    List,
+    /// start walking from the first waypoint
+    Start {
+        #[arg(long)]
+        looping: Option<bool>,
+        #[arg(long)]
+        speed: Option<f32>,
+        #[arg(long)]
+        pause: Option<f32>,
+    },
+    /// hand control back
+    Stop,
+    /// replace the path with one from a json file
+    Load {
+        file: String,
+    },
+    /// write the path to a json file
+    Save {
+        file: String,
+    },
+}
+
+fn path_command(mut input: ConsoleCommand<PathCommand>, mut auto_walk: ResMut<AutoWalk>) {
+    let Some(Ok(PathCommand { action })) = input.take() else {
+        return;
+    };
+
+    match action {
+        PathAction::Add { waypoint } => match waypoint.parse::<Waypoint>() {
+            Ok(waypoint) => {
+                auto_walk.path.waypoints.push(waypoint);
+                input.reply_ok(format!(
+                    "added waypoint {} ({waypoint})",
+                    auto_walk.path.waypoints.len()
+                ));
+            }
+            Err(e) => input.reply_failed(e),
+        },
+        PathAction::Clear => {
+            auto_walk.stop();
+            auto_walk.path.waypoints.clear();
+            input.reply_ok("path cleared");
+        }
+        PathAction::List => {
+            for (i, waypoint) in auto_walk.path.waypoints.iter().enumerate() {
+                input.reply(format!("{}: {waypoint}", i + 1));
+            }
+            let path = &auto_walk.path;
+            input.reply_ok(format!(
+                "{} waypoints, {}m/s, {}s pause{}",
+                path.waypoints.len(),
+                path.speed,
+                path.pause_secs,
+                if path.looping { ", looping" } else { "" }
+            ));
+        }
+        PathAction::Start {
+            looping,
+            speed,
+            pause,
+        } => {
+            let path = &mut auto_walk.path;
+            path.looping = looping.unwrap_or(path.looping);
+            path.speed = speed.unwrap_or(path.speed).max(0.1);
+            path.pause_secs = pause.unwrap_or(path.pause_secs).max(0.0);
+            if path.waypoints.is_empty() {
+                input.reply_failed("the path is empty, add waypoints with `/path add`");
+                return;
+            }
+            auto_walk.start();
+            input.reply_ok("walking");
+        }
+        PathAction::Stop => {
+            auto_walk.stop();
+            input.reply_ok("stopped");
+        }
+        PathAction::Load { file } => {
+            let path = std::fs::read_to_string(&file)
+                .map_err(|e| e.to_string())
+                .and_then(|json| {
+                    serde_json::from_str::<WalkPath>(&json).map_err(|e| e.to_string())
+                });
+            match path {
+                Ok(path) => {
+                    auto_walk.stop();
+                    input.reply_ok(format!("loaded {} waypoints", path.waypoints.len()));
+                    auto_walk.path = path;
+                }
+                Err(e) => input.reply_failed(format!("failed to load {file}: {e}")),
+            }
+        }
+        PathAction::Save { file } => {
+            let result = serde_json::to_string_pretty(&auto_walk.path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| std::fs::write(&file, json).map_err(|e| e.to_string()));
+            match result {
+                Ok(()) => input.reply_ok(format!("saved to {file}")),
+                Err(e) => input.reply_failed(format!("failed to save {file}: {e}")),
+            }
+        }
+    }
+}
+
+// true if the scene at `position` has loaded, or there is no scene there
+fn scene_ready(
+    position: Vec3,
+    pointers: &ScenePointers,
+    live_scenes: &LiveScenes,
+    scenes: &Query<&RendererSceneContext>,
+) -> bool {
+    let hash = match pointers.get(vec3_to_parcel(position)) {
+        Some(PointerResult::Exists { hash, .. }) => hash,
+        Some(PointerResult::Nothing) => return true,
+        None => return false,
+    };
+
+    live_scenes
+        .0
+        .get(hash)
+        .and_then(|scene| scenes.get(*scene).ok())
+        .is_some_and(|context| {
+            context.broken || (context.tick_number > 5 && context.blocked.is_empty())
+        })
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn auto_walk(
+    mut auto_walk: ResMut<AutoWalk>,
+    mut player: Query<(&Transform, &mut AvatarDynamicState, Has<OutOfWorld>), With<PrimaryUser>>,
+    input: InputManager,
+    pointers: Res<ScenePointers>,
+    live_scenes: Res<LiveScenes>,
+    scenes: Query<&RendererSceneContext>,
+    time: Res<Time>,
+    mut was_walking: Local<bool>,
+) {
+    let Ok((transform, mut dynamic_state, out_of_world)) = player.get_single_mut() else {
+        return;
+    };
+
+    if auto_walk.walking() && MANUAL_ACTIONS.iter().any(|action| input.is_down(*action)) {
+        info!("auto-walk stopped by movement input");
+        auto_walk.stop();
+    }
+
+    let AutoWalk { path, state } = &mut *auto_walk;
+    let Some(walk) = state.as_mut() else {
+        // the player input system may not be running to clear our force
+        if std::mem::take(&mut *was_walking) {
+            dynamic_state.force = Vec2::ZERO;
+        }
+        return;
+    };
+
+    *was_walking = true;
+    dynamic_state.force = Vec2::ZERO;
+    dynamic_state.rotate = 0.0;
+    dynamic_state.tank = false;
+
+    if walk.pause > 0.0 {
+        walk.pause -= time.delta_seconds();
+        return;
+    }
+
+    let Some(waypoint) = path.waypoints.get(walk.next) else {
+        *state = None;
+        return;
+    };
+
+    let offset = (waypoint.target() - transform.translation).xz();
+    if offset.length() < ARRIVAL_DISTANCE {
+        walk.next += 1;
+        walk.pause = path.pause_secs;
+        walk.scene_wait = 0.0;
+        if walk.next == path.waypoints.len() {
+            if path.looping {
+                walk.next = 0;
+            } else {
+                info!("auto-walk finished");
+                *state = None;
+            }
+        }
+        return;
+    }
+
+    let direction = offset.normalize();
+    let ahead = transform.translation
+        + Vec3::new(direction.x, 0.0, direction.y) * LOOKAHEAD.min(offset.length());
+    let ready = !out_of_world
+        && scene_ready(transform.translation, &pointers, &live_scenes, &scenes)
+        && scene_ready(ahead, &pointers, &live_scenes, &scenes);
+    if ready {
+        walk.scene_wait = 0.0;
+    } else if walk.scene_wait < MAX_SCENE_WAIT {
+        walk.scene_wait += time.delta_seconds();
+        if walk.scene_wait >= MAX_SCENE_WAIT {
+            warn!("auto-walk: scene at {ahead} not ready, walking on");
+        }
+        return;
+    }
+
+    // the avatar turns to face the force
+    dynamic_state.force = direction * path.speed;
+}
@@ -12,9 +12,11 @@ use bevy::{
     window::{CursorGrabMode, PrimaryWindow},
 };
 
+use bevy_console::ConsoleOpen;
 use common::{
     structs::{
-        ActiveDialog, CameraOverride, CursorLocked, CursorLocks, PrimaryCamera, PrimaryUser,
+        ActiveDialog, AppConfig, CameraOverride, CameraSettings, CursorLocked, CursorLocks,
+        CursorMode, PhotoMode, PrimaryCamera, PrimaryUser, CAMERA_BOOM,
     },
     util::ModifyComponentExt,
 };
@@ -26,7 +28,15 @@ use scene_runner::{
 use tween::SystemTween;
 use ui_core::scrollable::UsedScrollWheel;
 
-use crate::TRANSITION_TIME;
+use crate::{photo_mode::PhotoCamera, TRANSITION_TIME};
+
+// how far the camera keeps from unloaded parcels when `constrain_camera` is set
+const BOUNDS_MARGIN: f32 = 0.5;
+// spacing of the checks along the camera's boom, wider for a distant camera
+const BOUNDS_STEP: f32 = 0.25;
+const MAX_BOUNDS_CHECKS: f32 = 64.0;
+// meters added to each wheel step, so zooming in reaches first person
+const ZOOM_STEP: f32 = 0.25;
 
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub struct CinematicInitialData {
@@ -83,31 +93,55 @@ impl MouseInteractionState<'_, '_> {
     }
 }
 
+// one wheel step of zoom in meters, within the configured range. closer than
+// `first_person_zoom` goes to first person, and zooming out from there goes back to it
+fn step_zoom(zoom: f32, zoom_in: bool, settings: &CameraSettings) -> f32 {
+    let zoom = if zoom_in {
+        (zoom - ZOOM_STEP) * 0.9
+    } else if zoom < settings.first_person_zoom {
+        settings.first_person_zoom
+    } else {
+        zoom / 0.9 + ZOOM_STEP
+    };
+    let zoom = zoom.clamp(settings.min_zoom, settings.max_zoom.max(settings.min_zoom));
+    if zoom < settings.first_person_zoom {
+        0.0
+    } else {
+        zoom
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn update_camera(
     time: Res<Time>,
     mut mouse_events: EventReader<MouseMotion>,
     mut wheel_events: EventReader<MouseWheel>,
     key_input: Res<ButtonInput<KeyCode>>,
-    mut move_toggled: Local<bool>,
-    mut camera: Query<(&Transform, &mut PrimaryCamera)>,
+    mut cursor_mode: ResMut<CursorMode>,
+    mut config: ResMut<AppConfig>,
+    console: Res<ConsoleOpen>,
+    mut camera: Query<(&Transform, &mut PrimaryCamera, Option<&SystemTween>)>,
     accept_input: Res<AcceptInput>,
     used_wheel: Res<UsedScrollWheel>,
     mut cursor_locked: ResMut<CursorLocked>,
     mut locks: ResMut<CursorLocks>,
-    active_dialog: Res<ActiveDialog>,
+    (active_dialog, photo_mode): (Res<ActiveDialog>, Res<PhotoMode>),
     mut cinematic_data: Local<Option<CinematicInitialData>>,
     mut mb_state: MouseInteractionState,
     gt_helper: TransformHelper,
 ) {
     let dt = time.delta_seconds();
 
-    let Ok((camera_transform, mut options)) = camera.get_single_mut() else {
+    let Ok((camera_transform, mut options, tween)) = camera.get_single_mut() else {
         return;
     };
 
-    if !options.initialized {
-        let (yaw, pitch, roll) = camera_transform.rotation.to_euler(EulerRot::YXZ);
+    // a cinematic camera's rotation is relative to its origin, so wait until it ends
+    let cinematic = matches!(options.scene_override, Some(CameraOverride::Cinematic(_)));
+    if !options.initialized && !cinematic {
+        // while easing, sync to where the camera is heading
+        let settled = tween.map_or(camera_transform, |tween| &tween.target);
+        let (yaw, pitch, roll) = settled.rotation.to_euler(EulerRot::YXZ);
         options.yaw = yaw;
         options.pitch = pitch;
         options.roll = roll;
@@ -174,20 +208,31 @@ pub fn update_camera(
 
     // Handle mouse input
     let mut state = mb_state.update(options.mouse_key_enable_mouse);
-    if key_input.just_pressed(KeyCode::Escape) && *move_toggled {
+    if key_input.just_pressed(KeyCode::Escape) && *cursor_mode == CursorMode::Look {
         // override
         state = ClickState::Released;
-        *move_toggled = false;
+        *cursor_mode = CursorMode::Ui;
+    }
+
+    // clicking the look button or pressing the toggle key switches mode
+    let toggle_pressed = config
+        .cursor_toggle_key
+        .key()
+        .is_some_and(|key| accept_input.key && key_input.just_pressed(key));
+    if toggle_pressed || (allow_cam_move && state == ClickState::Clicked) {
+        cursor_mode.toggle();
     }
 
     let mut mouse_delta = Vec2::ZERO;
 
-    let in_dialog = active_dialog.in_use();
-    let lock = !in_dialog && (accept_input.mouse && state == ClickState::Held || *move_toggled);
+    // dialogs and the console need a free cursor, look mode resumes when they close
+    let suspended = active_dialog.in_use() || console.open;
+    let lock = !suspended
+        && (accept_input.mouse && state == ClickState::Held || *cursor_mode == CursorMode::Look);
 
     if lock {
         locks.0.insert("camera");
-        if !in_dialog {
+        if !suspended {
             cursor_locked.0 = true;
         }
 
@@ -196,23 +241,19 @@ pub fn update_camera(
         }
     } else {
         locks.0.remove("camera");
-        if !in_dialog {
+        if !suspended {
             cursor_locked.0 = false;
         }
     }
 
     if allow_cam_move {
-        if state == ClickState::Clicked {
-            *move_toggled = !*move_toggled;
-        }
-
         if accept_input.key {
             if key_input.pressed(options.key_roll_left) {
                 options.roll += dt * 1.0;
             } else if key_input.pressed(options.key_roll_right) {
                 options.roll -= dt * 1.0;
-            } else {
-                // decay roll if not in cinematic mode
+            } else if !photo_mode.active {
+                // decay roll if not in cinematic or photo mode
                 if options.roll > 0.0 {
                     options.roll = (options.roll - dt * 0.25).max(0.0);
                 } else {
@@ -224,12 +265,26 @@ pub fn update_camera(
         options.pitch = (options.pitch - mouse_delta.y * options.sensitivity / 1000.0)
             .clamp(-PI / 2.1, PI / 2.1);
         options.yaw -= mouse_delta.x * options.sensitivity / 1000.0;
-        if accept_input.mouse && !used_wheel.0 {
+        // photo mode zooms instead
+        if accept_input.mouse && !used_wheel.0 && !photo_mode.active {
             if let Some(event) = wheel_events.read().last() {
-                if (event.y > 0.0) == zoom_range.is_none() {
-                    options.distance = 0f32.max((options.distance - 0.05) * 0.9);
+                if zoom_range.is_some() {
+                    // cinematic zoom narrows the field of view
+                    if event.y > 0.0 {
+                        options.distance = (options.distance / 0.9) + 0.05;
+                    } else {
+                        options.distance = 0f32.max((options.distance - 0.05) * 0.9);
+                    }
                 } else {
-                    options.distance = 7000f32.min((options.distance / 0.9) + 0.05);
+                    let zoom = step_zoom(
+                        options.distance * CAMERA_BOOM,
+                        event.y > 0.0,
+                        &config.camera,
+                    );
+                    options.distance = zoom / CAMERA_BOOM;
+                    // kept for the next session without flagging a config change on every
+                    // wheel step, it's saved on exit
+                    config.bypass_change_detection().camera.zoom = zoom;
                 }
             }
         }
@@ -249,6 +304,29 @@ pub fn update_camera(
     }
 }
 
+// how far along `direction` from `origin` the camera can go and stay `BOUNDS_MARGIN` short of
+// anything that isn't loaded. unconstrained when the origin itself isn't loaded
+fn bounded_distance(
+    origin: Vec3,
+    direction: Vec3,
+    distance: f32,
+    loaded: impl Fn(Vec3) -> bool,
+) -> f32 {
+    if !loaded(origin) {
+        return distance;
+    }
+
+    let step = BOUNDS_STEP.max((distance + BOUNDS_MARGIN) / MAX_BOUNDS_CHECKS);
+    let mut checked = 0.0;
+    while checked < distance + BOUNDS_MARGIN {
+        checked += step;
+        if !loaded(origin + direction * checked) {
+            return (checked - step - BOUNDS_MARGIN).clamp(0.0, distance);
+        }
+    }
+    distance
+}
+
 #[allow(clippy::type_complexity)]
 pub fn update_camera_position(
     mut commands: Commands,
@@ -267,6 +345,7 @@ pub fn update_camera_position(
     mut scene_colliders: Query<(&RendererSceneContext, &mut SceneColliderData)>,
     mut prev_override: Local<Option<CameraOverride>>,
     gt_helper: TransformHelper,
+    (photo_mode, photo, config): (Res<PhotoMode>, Res<PhotoCamera>, Res<AppConfig>),
 ) {
     let (
         Ok((player_transform, dynamic_state)),
@@ -278,7 +357,18 @@ pub fn update_camera_position(
 
     let mut target_transform = *camera_transform;
 
-    if let Some(CameraOverride::Cinematic(cine)) = options.scene_override.as_ref() {
+    if photo_mode.active {
+        target_transform.translation = photo.translation;
+        target_transform.rotation =
+            Quat::from_euler(EulerRot::YXZ, options.yaw, options.pitch, options.roll);
+        let target_fov = FRAC_PI_4 * 1.25 / photo.zoom;
+        if let Projection::Perspective(PerspectiveProjection { ref mut fov, .. }) = &mut *projection
+        {
+            if *fov != target_fov {
+                *fov = target_fov;
+            }
+        };
+    } else if let Some(CameraOverride::Cinematic(cine)) = options.scene_override.as_ref() {
         let Ok(origin) = gt_helper.compute_global_transform(cine.origin) else {
             warn!("failed to get gt");
             return;
@@ -325,7 +415,9 @@ pub fn update_camera_position(
                 * distance.clamp(0.0, 0.5)
             + xz_plane;
 
-        let target_direction = target_transform.rotation.mul_vec3(Vec3::Z * 5.0 * distance);
+        let target_direction = target_transform
+            .rotation
+            .mul_vec3(Vec3::Z * CAMERA_BOOM * distance);
         let mut distance = target_direction.length();
         if target_direction.y + player_head.y < 0.1 {
             distance = distance * (player_head.y - 0.1) / -target_direction.y;
@@ -354,6 +446,20 @@ pub fn update_camera_position(
                     distance = distance.min(hit.toi - 0.1).max(0.0);
                 }
             }
+
+            // and keep out of the void, whichever is closer
+            if config.constrain_camera {
+                distance = distance.min(bounded_distance(
+                    player_head,
+                    target_direction,
+                    distance,
+                    |position| {
+                        containing_scene
+                            .get_parcel_position(position)
+                            .is_some_and(|scene| scene_colliders.contains(scene))
+                    },
+                ));
+            }
         }
 
         target_transform.translation = player_head + target_direction * distance;
@@ -422,3 +528,125 @@ pub fn update_cursor_lock(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_step_zoom() {
+        let settings = CameraSettings {
+            min_zoom: 0.0,
+            max_zoom: 10.0,
+            first_person_zoom: 1.0,
+            zoom: 5.0,
+        };
+
+        // zooming in reaches first person, and zooming out leaves it at the threshold
+        let mut zoom = 5.0;
+        let mut steps = 0;
+        while zoom > 0.0 {
+            let next = step_zoom(zoom, true, &settings);
+            assert!(next == 0.0 || next >= settings.first_person_zoom);
+            zoom = next;
+            steps += 1;
+            assert!(steps < 100);
+        }
+        assert_eq!(step_zoom(0.0, false, &settings), 1.0);
+
+        // and out no further than the max
+        for _ in 0..100 {
+            zoom = step_zoom(zoom, false, &settings);
+        }
+        assert_eq!(zoom, 10.0);
+
+        // a min above the threshold never goes to first person
+        let settings = CameraSettings {
+            min_zoom: 2.0,
+            ..settings
+        };
+        assert_eq!(step_zoom(2.0, true, &settings), 2.0);
+    }
+
+    #[test]
+    fn test_bounded_distance() {
+        // loaded up to `edge` along x
+        let bounded = |edge: f32, distance: f32| {
+            bounded_distance(Vec3::ZERO, Vec3::X, distance, |p| p.x < edge)
+        };
+
+        // stops short of the edge by the margin, to within a step
+        let d = bounded(3.0, 5.0);
+        assert!(d <= 3.0 - BOUNDS_MARGIN && d >= 3.0 - BOUNDS_MARGIN - BOUNDS_STEP);
+        // including an edge just beyond the full distance
+        assert!(bounded(5.2, 5.0) < 5.2 - BOUNDS_MARGIN);
+        // but never behind the origin
+        assert_eq!(bounded(0.3, 5.0), 0.0);
+        // and unconstrained with the edge well out of reach
+        assert_eq!(bounded(100.0, 5.0), 5.0);
+
+        // long distances take larger steps
+        let d = bounded(50.0, 100.0);
+        assert!(d <= 50.0 - BOUNDS_MARGIN && d >= 50.0 - BOUNDS_MARGIN - 100.5 / MAX_BOUNDS_CHECKS);
+
+        // with the origin itself unloaded there's nothing to stay inside
+        assert_eq!(bounded_distance(Vec3::ZERO, Vec3::X, 5.0, |_| false), 5.0);
+    }
+
+    #[test]
+    fn test_resync_after_reposition() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<Events<MouseMotion>>();
+        world.init_resource::<Events<MouseWheel>>();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world.insert_resource(CursorMode::Look);
+        world.init_resource::<AppConfig>();
+        world.insert_resource(ConsoleOpen { open: false });
+        world.init_resource::<AcceptInput>();
+        world.init_resource::<UsedScrollWheel>();
+        world.init_resource::<CursorLocked>();
+        world.init_resource::<CursorLocks>();
+        world.init_resource::<ActiveDialog>();
+        world.init_resource::<PhotoMode>();
+        let camera = world
+            .spawn((Transform::default(), PrimaryCamera::default()))
+            .id();
+
+        let mut system = IntoSystem::into_system(update_camera);
+        system.initialize(&mut world);
+        // runs the controller with a mouse delta, returning the orientation it is aiming for
+        let mut look = |world: &mut World, delta: Vec2| {
+            world.send_event(MouseMotion { delta });
+            system.run((), world);
+            system.apply_deferred(world);
+            world.resource_mut::<Events<MouseMotion>>().update();
+            let options = world.get::<PrimaryCamera>(camera).unwrap();
+            Quat::from_euler(EulerRot::YXZ, options.yaw, options.pitch, options.roll)
+        };
+        let reposition = |world: &mut World, rotation: Quat| {
+            world.get_mut::<Transform>(camera).unwrap().rotation = rotation;
+            world
+                .get_mut::<PrimaryCamera>(camera)
+                .unwrap()
+                .request_resync();
+        };
+
+        assert!(look(&mut world, Vec2::ZERO).angle_between(Quat::IDENTITY) < 1e-3);
+
+        // a small mouse move after the camera is moved turns it a little from where it is now
+        let moved = Quat::from_euler(EulerRot::YXZ, 2.0, -0.3, 0.0);
+        reposition(&mut world, moved);
+        assert!(look(&mut world, Vec2::new(2.0, 1.0)).angle_between(moved) < 0.05);
+
+        // while easing, from where it will end up
+        let target = Quat::from_euler(EulerRot::YXZ, -1.0, 0.4, 0.0);
+        reposition(&mut world, Quat::IDENTITY);
+        world.entity_mut(camera).insert(SystemTween {
+            target: Transform::from_rotation(target),
+            time: TRANSITION_TIME,
+        });
+        assert!(look(&mut world, Vec2::new(-2.0, 1.0)).angle_between(target) < 0.05);
+    }
+}
@@ -1,7 +1,11 @@
+pub mod auto_walk;
 pub mod camera;
 pub mod dynamics;
+pub mod photo_mode;
 pub mod player_input;
+pub mod recording;
 
+use auto_walk::AutoWalkPlugin;
 use bevy::{
     ecs::query::Has,
     prelude::*,
@@ -13,13 +17,18 @@ use camera::update_cursor_lock;
 use common::{
     anim_last_system,
     sets::SceneSets,
-    structs::{CursorLocks, PrimaryCamera, PrimaryUser, PRIMARY_AVATAR_LIGHT_LAYER_INDEX},
+    structs::{
+        AppConfig, CursorLocks, CursorMode, PrimaryCamera, PrimaryUser,
+        PRIMARY_AVATAR_LIGHT_LAYER_INDEX,
+    },
 };
 use console::DoAddConsoleCommand;
 use dynamics::{
     jump_cmd, no_clip, speed_cmd, JumpCommand, NoClipCommand, SpeedCommand, UserClipping,
 };
 use input_manager::should_accept_key;
+use photo_mode::PhotoModePlugin;
+use recording::RecordingPlugin;
 use scene_runner::{
     update_world::{
         avatar_modifier_area::PlayerModifiers,
@@ -73,16 +82,21 @@ impl Plugin for UserInputPlugin {
             ),
         );
         app.insert_resource(UserClipping(true))
-            .init_resource::<CursorLocks>();
+            .init_resource::<CursorLocks>()
+            .init_resource::<CursorMode>();
         app.add_console_command::<NoClipCommand, _>(no_clip);
         app.add_console_command::<SpeedCommand, _>(speed_cmd);
         app.add_console_command::<JumpCommand, _>(jump_cmd);
+        app.add_plugins(PhotoModePlugin);
+        app.add_plugins(AutoWalkPlugin);
+        app.add_plugins(RecordingPlugin);
     }
 }
 
 #[allow(clippy::type_complexity)]
 fn manage_player_visibility(
     camera: Query<&GlobalTransform, With<PrimaryCamera>>,
+    config: Res<AppConfig>,
     mut player: Query<
         (
             &GlobalTransform,
@@ -114,7 +128,8 @@ fn manage_player_visibility(
                 .length();
 
         #[allow(clippy::collapsible_else_if)]
-        if distance < 0.5 {
+        // always hidden with the camera in the head
+        if distance < config.camera.first_person_zoom.max(0.1) {
             layers.0 = layers
                 .0
                 .clone()
@@ -0,0 +1,401 @@
+// photo mode detaches the camera from the player so it can fly freely for screenshots. the
+// player stays where it is, the ui and name tags are hidden, and framing aids are drawn over
+// the view. toggled with `PHOTO_MODE_KEY` or `/photo`, escape also leaves. while active
+// `CAPTURE_KEY` saves a screenshot without the aids, and `GRID_KEY` and `LEVEL_KEY` toggle
+// the rule of thirds grid and the level indicator.
+
+use bevy::{
+    input::mouse::MouseWheel, prelude::*, render::view::screenshot::ScreenshotManager,
+    utils::HashMap, window::PrimaryWindow,
+};
+use bevy_console::ConsoleCommand;
+use common::{
+    sets::SceneSets,
+    structs::{CursorMode, PhotoMode, PrimaryCamera},
+    util::project_directories,
+};
+use console::DoAddConsoleCommand;
+use dcl_component::proto_components::sdk::components::common::InputAction;
+use input_manager::{AcceptInput, InputManager};
+use scene_runner::Toaster;
+use tween::SystemTween;
+use ui_core::scrollable::UsedScrollWheel;
+
+use crate::{camera::update_camera, TRANSITION_TIME};
+
+pub const PHOTO_MODE_KEY: KeyCode = KeyCode::F2;
+const CAPTURE_KEY: KeyCode = KeyCode::KeyP;
+const GRID_KEY: KeyCode = KeyCode::KeyC;
+const LEVEL_KEY: KeyCode = KeyCode::KeyL;
+
+// meters per second
+const FLY_SPEED: f32 = 6.0;
+const MAX_ZOOM: f32 = 8.0;
+// radians of roll that still count as level
+const LEVEL_TOLERANCE: f32 = 0.005;
+
+const AID_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.5);
+const LEVEL_COLOR: Color = Color::srgba(0.3, 1.0, 0.3, 0.8);
+
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhotoMode>();
+        app.init_resource::<PhotoCamera>();
+        app.add_systems(
+            Update,
+            (
+                (toggle_photo_mode, apply_photo_mode)
+                    .chain()
+                    .before(update_camera),
+                fly_photo_camera.after(update_camera),
+            )
+                .in_set(SceneSets::Input),
+        );
+        app.add_systems(
+            Update,
+            ((capture_photo, update_photo_overlay).chain(), hide_ui),
+        );
+        app.add_console_command::<PhotoCommand, _>(photo_command);
+    }
+}
+
+#[derive(Resource)]
+pub struct PhotoCamera {
+    pub translation: Vec3,
+    // divides the field of view
+    pub zoom: f32,
+    pub grid: bool,
+    pub level: bool,
+    // frames until a requested screenshot is done, the aids are hidden meanwhile
+    capture_frames: u8,
+    // restored on exit
+    cursor_mode: CursorMode,
+}
+
+impl Default for PhotoCamera {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            zoom: 1.0,
+            grid: true,
+            level: true,
+            capture_frames: 0,
+            cursor_mode: CursorMode::default(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct PhotoOverlay;
+
+#[derive(Component)]
+struct PhotoGrid;
+
+#[derive(Component)]
+struct PhotoLevel;
+
+/// toggle photo mode, a free camera for screenshots
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/photo")]
+struct PhotoCommand {
+    on: Option<bool>,
+}
+
+fn photo_command(mut input: ConsoleCommand<PhotoCommand>, mut photo_mode: ResMut<PhotoMode>) {
+    if let Some(Ok(command)) = input.take() {
+        photo_mode.active = command.on.unwrap_or(!photo_mode.active);
+        input.reply_ok(format!(
+            "photo mode {}",
+            if photo_mode.active { "on" } else { "off" }
+        ));
+    }
+}
+
+fn toggle_photo_mode(
+    keys: Res<ButtonInput<KeyCode>>,
+    accept_input: Res<AcceptInput>,
+    mut photo_mode: ResMut<PhotoMode>,
+) {
+    if !accept_input.key {
+        return;
+    }
+
+    if keys.just_pressed(PHOTO_MODE_KEY) {
+        photo_mode.active = !photo_mode.active;
+    } else if photo_mode.active && keys.just_pressed(KeyCode::Escape) {
+        photo_mode.active = false;
+    }
+}
+
+fn apply_photo_mode(
+    mut commands: Commands,
+    photo_mode: Res<PhotoMode>,
+    mut photo: ResMut<PhotoCamera>,
+    mut cursor_mode: ResMut<CursorMode>,
+    mut camera: Query<(Entity, &Transform, &mut PrimaryCamera)>,
+    overlay: Query<Entity, With<PhotoOverlay>>,
+    mut was_active: Local<bool>,
+) {
+    if photo_mode.active == *was_active {
+        return;
+    }
+    *was_active = photo_mode.active;
+
+    let Ok((camera_ent, transform, mut options)) = camera.get_single_mut() else {
+        return;
+    };
+
+    if photo_mode.active {
+        // start from the current view
+        photo.translation = transform.translation;
+        photo.zoom = 1.0;
+        photo.capture_frames = 0;
+        photo.cursor_mode = *cursor_mode;
+        *cursor_mode = CursorMode::Look;
+        spawn_overlay(&mut commands);
+    } else {
+        *cursor_mode = photo.cursor_mode;
+        for ent in overlay.iter() {
+            commands.entity(ent).despawn_recursive();
+        }
+        // ease back to the player, the target is updated in `update_camera_position`
+        commands.entity(camera_ent).try_insert(SystemTween {
+            target: *transform,
+            time: TRANSITION_TIME,
+        });
+        options.request_resync();
+    }
+}
+
+fn spawn_overlay(commands: &mut Commands) {
+    let line = |style: Style| NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            ..style
+        },
+        background_color: AID_COLOR.into(),
+        ..Default::default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..Default::default()
+                },
+                z_index: ZIndex::Global(i16::MAX as i32),
+                ..Default::default()
+            },
+            PhotoOverlay,
+        ))
+        .with_children(|c| {
+            for third in [100.0 / 3.0, 200.0 / 3.0] {
+                c.spawn((
+                    line(Style {
+                        left: Val::Percent(third),
+                        width: Val::Px(1.0),
+                        height: Val::Percent(100.0),
+                        ..Default::default()
+                    }),
+                    PhotoGrid,
+                ));
+                c.spawn((
+                    line(Style {
+                        top: Val::Percent(third),
+                        width: Val::Percent(100.0),
+                        height: Val::Px(1.0),
+                        ..Default::default()
+                    }),
+                    PhotoGrid,
+                ));
+            }
+            c.spawn((
+                line(Style {
+                    left: Val::Percent(35.0),
+                    top: Val::Percent(50.0),
+                    width: Val::Percent(30.0),
+                    height: Val::Px(2.0),
+                    ..Default::default()
+                }),
+                PhotoLevel,
+            ));
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fly_photo_camera(
+    photo_mode: Res<PhotoMode>,
+    mut photo: ResMut<PhotoCamera>,
+    camera: Query<&PrimaryCamera>,
+    input: InputManager,
+    accept_input: Res<AcceptInput>,
+    used_wheel: Res<UsedScrollWheel>,
+    mut wheel_events: EventReader<MouseWheel>,
+    time: Res<Time>,
+) {
+    if !photo_mode.active {
+        return;
+    }
+    let Ok(options) = camera.get_single() else {
+        return;
+    };
+
+    let mut axis = Vec3::ZERO;
+    for (action, direction) in [
+        (InputAction::IaForward, Vec3::NEG_Z),
+        (InputAction::IaBackward, Vec3::Z),
+        (InputAction::IaRight, Vec3::X),
+        (InputAction::IaLeft, Vec3::NEG_X),
+    ] {
+        if input.is_down(action) {
+            axis += direction;
+        }
+    }
+    // up and down stay vertical whichever way the camera faces
+    let mut movement = Quat::from_euler(EulerRot::YXZ, options.yaw, options.pitch, 0.0) * axis;
+    if input.is_down(InputAction::IaJump) {
+        movement += Vec3::Y;
+    }
+    if input.is_down(InputAction::IaWalk) {
+        movement -= Vec3::Y;
+    }
+    photo.translation += movement.normalize_or_zero() * FLY_SPEED * time.delta_seconds();
+    // stay above the ground
+    photo.translation.y = photo.translation.y.max(0.1);
+
+    if accept_input.mouse && !used_wheel.0 {
+        if let Some(event) = wheel_events.read().last() {
+            photo.zoom = if event.y > 0.0 {
+                (photo.zoom * 1.1).min(MAX_ZOOM)
+            } else {
+                (photo.zoom / 1.1).max(1.0)
+            };
+        }
+    }
+}
+
+fn capture_photo(
+    photo_mode: Res<PhotoMode>,
+    mut photo: ResMut<PhotoCamera>,
+    keys: Res<ButtonInput<KeyCode>>,
+    accept_input: Res<AcceptInput>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    mut toaster: Toaster,
+) {
+    if !photo_mode.active {
+        return;
+    }
+
+    if accept_input.key {
+        if keys.just_pressed(GRID_KEY) {
+            photo.grid = !photo.grid;
+        }
+        if keys.just_pressed(LEVEL_KEY) {
+            photo.level = !photo.level;
+        }
+    }
+
+    match photo.capture_frames {
+        0 => {
+            if accept_input.key && keys.just_pressed(CAPTURE_KEY) {
+                // hide the aids for a frame first
+                photo.capture_frames = 2;
+            }
+        }
+        2 => {
+            photo.capture_frames = 1;
+            let dir = project_directories().data_local_dir().join("photos");
+            let name = format!("photo-{}", chrono::Local::now().format("%Y%m%d-%H%M%S-%3f"));
+            let path = dir.join(name).with_extension("png");
+            let result = std::fs::create_dir_all(&dir)
+                .map_err(|e| e.to_string())
+                .and_then(|_| window.get_single().map_err(|e| e.to_string()))
+                .and_then(|window| {
+                    screenshots
+                        .save_screenshot_to_disk(window, &path)
+                        .map_err(|e| e.to_string())
+                });
+            match result {
+                Ok(()) => {
+                    info!("photo saved to {}", path.display());
+                    toaster.add_toast("photo", format!("Photo saved to {}", path.display()));
+                }
+                Err(e) => warn!("failed to save photo: {e}"),
+            }
+        }
+        _ => photo.capture_frames = 0,
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_photo_overlay(
+    photo: Res<PhotoCamera>,
+    camera: Query<&PrimaryCamera>,
+    mut grid: Query<&mut Visibility, (With<PhotoGrid>, Without<PhotoLevel>)>,
+    mut level: Query<(&mut Visibility, &mut Transform, &mut BackgroundColor), With<PhotoLevel>>,
+) {
+    let visibility = |show: bool| {
+        if show && photo.capture_frames == 0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    };
+
+    for mut vis in grid.iter_mut() {
+        vis.set_if_neq(visibility(photo.grid));
+    }
+
+    let roll = camera.get_single().map_or(0.0, |options| options.roll);
+    for (mut vis, mut transform, mut color) in level.iter_mut() {
+        vis.set_if_neq(visibility(photo.level));
+        // follows the horizon as the camera rolls
+        transform.rotation = Quat::from_rotation_z(roll);
+        let target = if roll.abs() < LEVEL_TOLERANCE {
+            LEVEL_COLOR
+        } else {
+            AID_COLOR
+        };
+        if color.0 != target {
+            color.0 = target;
+        }
+    }
+}
+
+// hide the window's root ui nodes while in photo mode, restoring them afterwards
+#[allow(clippy::type_complexity)]
+fn hide_ui(
+    photo_mode: Res<PhotoMode>,
+    mut roots: Query<
+        (Entity, &mut Visibility),
+        (
+            With<Node>,
+            Without<Parent>,
+            Without<TargetCamera>,
+            Without<PhotoOverlay>,
+        ),
+    >,
+    mut hidden: Local<HashMap<Entity, Visibility>>,
+) {
+    if photo_mode.active {
+        // checked every frame to catch ui spawned meanwhile
+        for (ent, mut vis) in roots.iter_mut() {
+            if *vis != Visibility::Hidden {
+                hidden.insert(ent, *vis);
+                *vis = Visibility::Hidden;
+            }
+        }
+    } else {
+        for (ent, prev) in hidden.drain() {
+            if let Ok((_, mut vis)) = roots.get_mut(ent) {
+                *vis = prev;
+            }
+        }
+    }
+}
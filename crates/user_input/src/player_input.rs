@@ -2,7 +2,7 @@ use bevy::{math::Vec3Swizzles, prelude::*};
 
 use common::{
     dynamics::PLAYER_GROUND_THRESHOLD,
-    structs::{AvatarControl, PrimaryCamera, PrimaryUser},
+    structs::{AvatarControl, PhotoMode, PrimaryCamera, PrimaryUser},
 };
 
 use avatar::AvatarDynamicState;
@@ -24,6 +24,7 @@ pub(crate) fn update_user_velocity(
     input: InputManager,
     mut tankiness: Local<f32>,
     time: Res<Time>,
+    photo_mode: Res<PhotoMode>,
 ) {
     let (Ok((player_transform, mut dynamic_state, user, maybe_modifiers)), Ok(camera_transform)) =
         (player.get_single_mut(), camera.get_single())
@@ -31,6 +32,13 @@ pub(crate) fn update_user_velocity(
         return;
     };
 
+    // the player stays put while the camera flies
+    if photo_mode.active {
+        dynamic_state.force = Vec2::ZERO;
+        dynamic_state.rotate = 0.0;
+        return;
+    }
+
     let user = maybe_modifiers
         .map(|m| m.combine(user))
         .unwrap_or_else(|| user.clone());
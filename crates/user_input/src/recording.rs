@@ -0,0 +1,315 @@
+// records the player's movement to a json file and plays it back, for demos and bug repros.
+// `/rec start` samples the player's transform `RECORD_RATE` times a second (and with
+// `--emotes`, the emotes it plays), `/rec stop` writes the file, and `/play <file>` moves the
+// player through the recording, interpolating between samples. any movement input stops
+// playback. with deterministic time a recording plays back exactly as it was made.
+
+use std::path::PathBuf;
+
+use avatar::{animate::PlayerEmote, AvatarDynamicState};
+use bevy::prelude::*;
+use bevy_console::ConsoleCommand;
+use common::{structs::PrimaryUser, util::project_directories};
+use console::DoAddConsoleCommand;
+use input_manager::InputManager;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auto_walk::{AutoWalk, MANUAL_ACTIONS},
+    camera::update_camera_position,
+    dynamics::update_user_position,
+};
+
+// samples per second
+const RECORD_RATE: f32 = 20.0;
+
+pub struct RecordingPlugin;
+
+impl Plugin for RecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MovementRecorder>();
+        app.add_systems(Update, record_movement);
+        app.add_systems(
+            PostUpdate,
+            play_recording
+                .after(update_user_position)
+                .before(update_camera_position),
+        );
+        app.add_console_command::<RecCommand, _>(rec_command);
+        app.add_console_command::<PlayCommand, _>(play_command);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MovementSample {
+    // seconds since the recording started
+    pub time: f32,
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    // an emote started at this sample
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emote: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct MovementRecording {
+    pub samples: Vec<MovementSample>,
+}
+
+impl MovementRecording {
+    pub fn duration(&self) -> f32 {
+        self.samples.last().map_or(0.0, |sample| sample.time)
+    }
+
+    // the interpolated transform at `time`, clamped to the recording
+    pub fn transform_at(&self, time: f32) -> Option<Transform> {
+        let next = self.samples.partition_point(|sample| sample.time <= time);
+        let from = &self.samples[next.checked_sub(1).unwrap_or_default()..];
+        let (a, b) = match from {
+            [] => return None,
+            [a] => (a, a),
+            [a, b, ..] => (a, b),
+        };
+        let span = b.time - a.time;
+        let t = if span > 0.0 {
+            ((time - a.time) / span).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        Some(Transform {
+            translation: Vec3::from(a.translation).lerp(Vec3::from(b.translation), t),
+            rotation: Quat::from_array(a.rotation).slerp(Quat::from_array(b.rotation), t),
+            ..Default::default()
+        })
+    }
+}
+
+struct ActiveRecording {
+    recording: MovementRecording,
+    started: f32,
+    emotes: bool,
+    last_emote: Option<String>,
+}
+
+struct ActivePlayback {
+    recording: MovementRecording,
+    started: f32,
+    // samples before this have been played
+    next_sample: usize,
+}
+
+#[derive(Resource, Default)]
+pub struct MovementRecorder {
+    recording: Option<ActiveRecording>,
+    playback: Option<ActivePlayback>,
+}
+
+impl MovementRecorder {
+    pub fn recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn playing(&self) -> bool {
+        self.playback.is_some()
+    }
+}
+
+/// record the player's movement
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/rec")]
+struct RecCommand {
+    #[command(subcommand)]
+    action: RecAction,
+}
+
+#[derive(clap::Subcommand)]
+enum RecAction {
+    /// start recording
+    Start {
+        /// also record emotes
+        #[arg(long)]
+        emotes: bool,
+    },
+    /// stop recording and save it, to the recordings folder by default
+    Stop {
+        file: Option<PathBuf>,
+    },
+}
+
+fn rec_command(
+    mut input: ConsoleCommand<RecCommand>,
+    mut recorder: ResMut<MovementRecorder>,
+    time: Res<Time>,
+) {
+    let Some(Ok(RecCommand { action })) = input.take() else {
+        return;
+    };
+
+    match action {
+        RecAction::Start { emotes } => {
+            recorder.recording = Some(ActiveRecording {
+                recording: MovementRecording::default(),
+                started: time.elapsed_seconds(),
+                emotes,
+                last_emote: None,
+            });
+            input.reply_ok("recording");
+        }
+        RecAction::Stop { file } => {
+            let Some(active) = recorder.recording.take() else {
+                input.reply_failed("not recording");
+                return;
+            };
+
+            let path = file.unwrap_or_else(|| {
+                let name = format!("recording-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+                project_directories()
+                    .data_local_dir()
+                    .join("recordings")
+                    .join(name)
+                    .with_extension("json")
+            });
+            let result = path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .map_err(|e| e.to_string())
+                .and_then(|_| serde_json::to_string(&active.recording).map_err(|e| e.to_string()))
+                .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+            match result {
+                Ok(()) => input.reply_ok(format!(
+                    "saved {:.1}s to {}",
+                    active.recording.duration(),
+                    path.display()
+                )),
+                Err(e) => input.reply_failed(format!("failed to save {}: {e}", path.display())),
+            }
+        }
+    }
+}
+
+/// play back a movement recording
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/play")]
+struct PlayCommand {
+    file: PathBuf,
+}
+
+fn play_command(
+    mut input: ConsoleCommand<PlayCommand>,
+    mut recorder: ResMut<MovementRecorder>,
+    mut auto_walk: ResMut<AutoWalk>,
+    time: Res<Time>,
+) {
+    let Some(Ok(PlayCommand { file })) = input.take() else {
+        return;
+    };
+
+    let recording = std::fs::read_to_string(&file)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            serde_json::from_str::<MovementRecording>(&json).map_err(|e| e.to_string())
+        });
+    match recording {
+        Ok(recording) if recording.samples.is_empty() => {
+            input.reply_failed(format!("{} is empty", file.display()));
+        }
+        Ok(recording) => {
+            auto_walk.stop();
+            input.reply_ok(format!("playing {:.1}s", recording.duration()));
+            recorder.playback = Some(ActivePlayback {
+                recording,
+                started: time.elapsed_seconds(),
+                next_sample: 0,
+            });
+        }
+        Err(e) => input.reply_failed(format!("failed to load {}: {e}", file.display())),
+    }
+}
+
+fn record_movement(
+    mut recorder: ResMut<MovementRecorder>,
+    player: Query<&Transform, With<PrimaryUser>>,
+    emote: PlayerEmote,
+    time: Res<Time>,
+) {
+    let Some(active) = recorder.recording.as_mut() else {
+        return;
+    };
+    let Ok(transform) = player.get_single() else {
+        return;
+    };
+
+    let elapsed = time.elapsed_seconds() - active.started;
+    let emote = if active.emotes {
+        let current = emote.current().map(|emote| emote.emote_urn.clone());
+        if current != active.last_emote {
+            active.last_emote.clone_from(&current);
+            current
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+    let recent = active
+        .recording
+        .samples
+        .last()
+        .is_some_and(|last| elapsed - last.time < 1.0 / RECORD_RATE);
+    if recent && emote.is_none() {
+        return;
+    }
+
+    active.recording.samples.push(MovementSample {
+        time: elapsed,
+        translation: transform.translation.to_array(),
+        rotation: transform.rotation.to_array(),
+        emote,
+    });
+}
+
+fn play_recording(
+    mut recorder: ResMut<MovementRecorder>,
+    mut player: Query<(&mut Transform, &mut AvatarDynamicState), With<PrimaryUser>>,
+    mut emote: PlayerEmote,
+    input: InputManager,
+    time: Res<Time>,
+) {
+    let Some(playback) = recorder.playback.as_mut() else {
+        return;
+    };
+    let Ok((mut transform, mut dynamic_state)) = player.get_single_mut() else {
+        return;
+    };
+
+    if MANUAL_ACTIONS.iter().any(|action| input.is_down(*action)) {
+        info!("playback stopped by movement input");
+        recorder.playback = None;
+        return;
+    }
+
+    let elapsed = time.elapsed_seconds() - playback.started;
+    let samples = &playback.recording.samples;
+    while let Some(sample) = samples.get(playback.next_sample) {
+        if sample.time > elapsed {
+            break;
+        }
+        if let Some(urn) = &sample.emote {
+            emote.play(urn.clone(), false);
+        }
+        playback.next_sample += 1;
+    }
+
+    // the recording overrides the player's own motion
+    if let Some(recorded) = playback.recording.transform_at(elapsed) {
+        transform.translation = recorded.translation;
+        transform.rotation = recorded.rotation;
+    }
+    dynamic_state.force = Vec2::ZERO;
+    dynamic_state.velocity = Vec3::ZERO;
+
+    if elapsed >= playback.recording.duration() {
+        info!("playback finished");
+        recorder.playback = None;
+    }
+}
@@ -0,0 +1,42 @@
+// camera clip planes.
+//
+// bevy uses an infinite reverse-z projection, so the far plane only limits what is drawn
+// (and the range shadows are computed over). depth precision depends on the near plane:
+// raising it reduces z-fighting on distant geometry at the cost of clipping very close
+// objects.
+//
+// world positions are absolute, with parcel (x, y) at (16x, -16y). genesis city spans
+// roughly +/-150 parcels, so positions stay within ~2.5km of the origin where f32
+// precision is better than 1mm, and precision loss is not currently visible. larger worlds
+// would need a floating origin. the plan for that:
+// - keep the authoritative player position as parcel + offset (an IVec2 and a Vec3)
+//   rather than a single Vec3, and expose a `WorldOrigin` resource holding the parcel the
+//   render space is centred on.
+// - when the player moves more than a threshold (e.g. 64 parcels) from the origin, shift
+//   the origin and offset the transform of every root entity (scene roots, avatars, the
+//   player, lights, ground) by the same amount in one system, before transform propagation.
+// - convert at the boundaries that use absolute positions: scene placement (parcel to
+//   world in initialize_scene), scene-facing transforms (the scene root already makes scene
+//   coordinates relative), comms positions (send and receive in absolute coordinates,
+//   converting with the origin), and the containing-scene parcel lookup.
+// - shadows, fog and imposters are camera-relative and need no changes.
+
+use bevy::prelude::*;
+use common::structs::{AppConfig, PrimaryCamera};
+
+pub fn apply_clip_planes(
+    config: Res<AppConfig>,
+    mut cameras: Query<&mut Projection, With<PrimaryCamera>>,
+) {
+    let near = config.graphics.camera_near.max(0.01);
+    let far = config.graphics.camera_far.max(near + 1.0);
+    for mut projection in cameras.iter_mut() {
+        let Projection::Perspective(perspective) = &mut *projection else {
+            continue;
+        };
+        if perspective.near != near || perspective.far != far {
+            perspective.near = near;
+            perspective.far = far;
+        }
+    }
+}
@@ -1,9 +1,14 @@
+mod clip_planes;
+
+use std::path::{Path, PathBuf};
+
 use bevy::{
     core_pipeline::Skybox,
     pbr::{wireframe::WireframePlugin, CascadeShadowConfigBuilder, DirectionalLightShadowMap},
     prelude::*,
     render::{
         render_asset::RenderAssetBytesPerFrame,
+        render_resource::TextureFormat,
         view::{Layer, RenderLayers},
     },
 };
@@ -13,14 +18,20 @@ use bevy_atmosphere::{
 };
 
 use bevy_console::ConsoleCommand;
+use clip_planes::apply_clip_planes;
 use common::{
     sets::SetupSets,
     structs::{
-        AppConfig, FogSetting, PrimaryCamera, PrimaryCameraRes, PrimaryUser, SceneLoadDistance,
-        ShadowSetting, GROUND_RENDERLAYER, PRIMARY_AVATAR_LIGHT_LAYER,
+        AppConfig, Cubemap, FogParams, FogSetting, PrimaryCamera, PrimaryCameraRes, PrimaryUser,
+        RealmSettings, SceneLoadDistance, ShadowSetting, SkyboxSetting, GROUND_RENDERLAYER,
+        PRIMARY_AVATAR_LIGHT_LAYER,
     },
 };
 use console::DoAddConsoleCommand;
+use ipfs::{ipfs_path::IpfsPath, CurrentRealm};
+
+// cubemap used when no custom skybox is set
+pub const DEFAULT_CUBEMAP: &str = "images/skybox/skybox_cubemap.png";
 
 pub struct VisualsPlugin {
     pub no_fog: bool,
@@ -30,16 +41,23 @@ impl Plugin for VisualsPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(DirectionalLightShadowMap { size: 4096 })
             .init_resource::<SceneGlobalLight>()
+            .init_resource::<SkyAmbient>()
             .insert_resource(AtmosphereModel::default())
             .add_plugins(AtmospherePlugin)
             .add_plugins(WireframePlugin)
-            .add_systems(Update, apply_global_light)
+            .add_systems(Update, (update_skybox, apply_skybox_image).chain())
+            .add_systems(Update, apply_global_light.after(apply_skybox_image))
             .add_systems(Update, move_ground)
+            .add_systems(
+                Update,
+                apply_clip_planes.run_if(|config: Res<AppConfig>| config.is_changed()),
+            )
             .add_systems(Startup, setup.in_set(SetupSets::Main))
             .insert_resource(RenderAssetBytesPerFrame::new(16777216));
 
         app.add_console_command::<ShadowConsoleCommand, _>(shadow_console_command);
         app.add_console_command::<FogConsoleCommand, _>(fog_console_command);
+        app.add_console_command::<FogParamsConsoleCommand, _>(fog_params_console_command);
     }
 }
 
@@ -54,10 +72,6 @@ fn setup(
 ) {
     info!("visuals::setup");
 
-    commands.entity(camera.0).try_insert(AtmosphereCamera {
-        render_layers: Some(RenderLayers::default()),
-    });
-
     commands.entity(camera.0).try_insert(FogSettings {
         color: Color::srgb(0.3, 0.2, 0.1),
         directional_light_color: Color::srgb(1.0, 1.0, 0.7),
@@ -82,6 +96,142 @@ fn setup(
     ));
 }
 
+// ambient tint derived from a custom skybox, used when the current scene doesn't set its
+// own lighting
+#[derive(Resource, Default)]
+struct SkyAmbient(Option<Color>);
+
+// the custom cubemap in use, if any. an explicit cubemap in the config takes priority over
+// one from the realm, otherwise the sky is the procedural atmosphere
+fn custom_cubemap<'a>(config: &'a AppConfig, realm: &'a CurrentRealm) -> Option<&'a str> {
+    match &config.graphics.skybox {
+        SkyboxSetting::Cubemap(path) => Some(path),
+        SkyboxSetting::Procedural => realm
+            .config
+            .skybox
+            .as_ref()
+            .and_then(|skybox| skybox.cubemap.as_deref()),
+    }
+}
+
+fn update_skybox(
+    mut commands: Commands,
+    config: Res<AppConfig>,
+    realm: Res<CurrentRealm>,
+    camera: Res<PrimaryCameraRes>,
+    asset_server: Res<AssetServer>,
+    cubemap: Option<ResMut<Cubemap>>,
+    mut sky_ambient: ResMut<SkyAmbient>,
+    mut current: Local<Option<Option<String>>>,
+) {
+    let Some(mut cubemap) = cubemap else {
+        return;
+    };
+    let Some(mut camera_commands) = commands.get_entity(camera.0) else {
+        return;
+    };
+
+    let source = custom_cubemap(&config, &realm).map(ToOwned::to_owned);
+    if current.as_ref() == Some(&source) {
+        return;
+    }
+
+    let image_handle = match &source {
+        None => {
+            info!("using procedural sky");
+            camera_commands.try_insert(AtmosphereCamera {
+                render_layers: Some(RenderLayers::default()),
+            });
+            asset_server.load(DEFAULT_CUBEMAP)
+        }
+        Some(source) => {
+            info!("using skybox {source}");
+            camera_commands.remove::<AtmosphereCamera>();
+            if source.starts_with("http") {
+                asset_server.load(PathBuf::from(&IpfsPath::new_from_url(source, "image")))
+            } else {
+                asset_server.load(source.clone())
+            }
+        }
+    };
+
+    if cubemap.image_handle != image_handle {
+        *cubemap = Cubemap {
+            is_loaded: false,
+            image_handle,
+        };
+    }
+    sky_ambient.0 = None;
+    *current = Some(source);
+}
+
+// once a new cubemap is loaded (and reinterpreted as a cube), show it on all cameras
+#[allow(clippy::too_many_arguments)]
+fn apply_skybox_image(
+    mut commands: Commands,
+    config: Res<AppConfig>,
+    realm: Res<CurrentRealm>,
+    camera: Res<PrimaryCameraRes>,
+    cubemap: Option<Res<Cubemap>>,
+    images: Res<Assets<Image>>,
+    mut skyboxes: Query<&mut Skybox>,
+    mut sky_ambient: ResMut<SkyAmbient>,
+) {
+    let Some(cubemap) = cubemap else {
+        return;
+    };
+    if !cubemap.is_changed() || !cubemap.is_loaded {
+        return;
+    }
+
+    for mut skybox in skyboxes.iter_mut() {
+        skybox.image = cubemap.image_handle.clone();
+    }
+
+    // a custom cubemap that couldn't be used as a cube is replaced by the default, so keep
+    // the procedural sky in that case
+    let is_default = cubemap
+        .image_handle
+        .path()
+        .is_some_and(|path| path.path() == Path::new(DEFAULT_CUBEMAP));
+    if is_default {
+        if let Some(mut camera_commands) = commands.get_entity(camera.0) {
+            camera_commands.try_insert(AtmosphereCamera {
+                render_layers: Some(RenderLayers::default()),
+            });
+        }
+    }
+
+    sky_ambient.0 = if !is_default && custom_cubemap(&config, &realm).is_some() {
+        images
+            .get(cubemap.image_handle.id())
+            .and_then(average_color)
+    } else {
+        None
+    };
+}
+
+// average color of an image, scaled so the brightest channel is 1
+fn average_color(image: &Image) -> Option<Color> {
+    if image.texture_descriptor.format != TextureFormat::Rgba8UnormSrgb {
+        return None;
+    }
+
+    let mut total = Vec3::ZERO;
+    let mut count = 0.0;
+    for pixel in image.data.chunks_exact(4) {
+        total += Vec3::new(pixel[0] as f32, pixel[1] as f32, pixel[2] as f32);
+        count += 1.0;
+    }
+    let average = total / count.max(1.0);
+    let max = average.max_element();
+    if max <= 0.0 {
+        return None;
+    }
+    let average = average / max;
+    Some(Color::srgb(average.x, average.y, average.z))
+}
+
 #[derive(Resource, Default, Clone, Debug)]
 pub struct SceneGlobalLight {
     pub source: Option<Entity>,
@@ -118,8 +268,10 @@ fn apply_global_light(
     >,
     scene_distance: Res<SceneLoadDistance>,
     scene_global_light: Res<SceneGlobalLight>,
+    sky_ambient: Res<SkyAmbient>,
     mut prev: Local<(f32, SceneGlobalLight)>,
     config: Res<AppConfig>,
+    realm_settings: Res<RealmSettings>,
 ) {
     let next_light = if prev.0 >= TRANSITION_TIME && prev.1.source == scene_global_light.source {
         scene_global_light.clone()
@@ -177,7 +329,11 @@ fn apply_global_light(
             layer = layer.union(&PRIMARY_AVATAR_LIGHT_LAYER);
         }
 
-        let (shadows_enabled, cascade_shadow_config) = match config.graphics.shadow_settings {
+        let shadow_settings = realm_settings
+            .overrides
+            .shadow_settings
+            .unwrap_or(config.graphics.shadow_settings);
+        let (shadows_enabled, cascade_shadow_config) = match shadow_settings {
             ShadowSetting::Off => (false, Default::default()),
             ShadowSetting::Low => (
                 true,
@@ -240,7 +396,20 @@ fn apply_global_light(
                 * 0.5
                 * skybox_brightness
                 / 2000.0;
-            let base_color = Color::from(base_color).with_alpha(1.0);
+            let params = &setting.graphics.fog_params;
+            let base_color = match params.color {
+                Some([r, g, b]) => Color::srgb(r, g, b),
+                None => Color::from(base_color).with_alpha(1.0),
+            };
+            let visibility = distance * 2.0 / params.density.max(0.01);
+            let falloff = if params.start.is_some() || params.end.is_some() {
+                FogFalloff::Linear {
+                    start: params.start.unwrap_or(0.0),
+                    end: params.end.unwrap_or(visibility),
+                }
+            } else {
+                FogFalloff::from_visibility_squared(visibility)
+            };
 
             fog.color = base_color;
             match setting.graphics.fog {
@@ -249,11 +418,11 @@ fn apply_global_light(
                     fog.directional_light_color = base_color;
                 }
                 FogSetting::Basic => {
-                    fog.falloff = FogFalloff::from_visibility_squared(distance * 2.0);
+                    fog.falloff = falloff;
                     fog.directional_light_color = base_color;
                 }
                 FogSetting::Atmospheric => {
-                    fog.falloff = FogFalloff::from_visibility_squared(distance * 2.0);
+                    fog.falloff = falloff;
                     fog.directional_light_color = next_light.dir_color;
                 }
             }
@@ -268,7 +437,11 @@ fn apply_global_light(
 
     ambient.brightness =
         next_light.ambient_brightness * config.graphics.ambient_brightness as f32 * 20.0;
-    ambient.color = next_light.ambient_color;
+    // scenes that set their own lighting keep their ambient color, otherwise it follows the sky
+    ambient.color = match (next_light.source, sky_ambient.0) {
+        (None, Some(sky_color)) => sky_color,
+        _ => next_light.ambient_color,
+    };
 
     if prev.1.source == scene_global_light.source {
         prev.0 += time.delta_seconds()
@@ -350,3 +523,37 @@ fn fog_console_command(
         ));
     }
 }
+
+/// set fog density, color (srgb) and linear start/end distances. with no arguments, resets to defaults
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/fog_params")]
+struct FogParamsConsoleCommand {
+    #[arg(long)]
+    density: Option<f32>,
+    #[arg(long, num_args = 3)]
+    color: Option<Vec<f32>>,
+    #[arg(long)]
+    start: Option<f32>,
+    #[arg(long)]
+    end: Option<f32>,
+}
+
+fn fog_params_console_command(
+    mut input: ConsoleCommand<FogParamsConsoleCommand>,
+    mut config: ResMut<AppConfig>,
+) {
+    if let Some(Ok(command)) = input.take() {
+        let mut params = FogParams::default();
+        if let Some(density) = command.density {
+            params.density = density.clamp(FogParams::MIN_DENSITY, FogParams::MAX_DENSITY);
+        }
+        if let Some([r, g, b]) = command.color.as_deref() {
+            params.color = Some([*r, *g, *b]);
+        }
+        params.start = command.start;
+        params.end = command.end;
+
+        config.graphics.fog_params = params;
+        input.reply_ok(format!("fog params: {params:?}"));
+    }
+}
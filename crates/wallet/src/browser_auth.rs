@@ -4,7 +4,10 @@ use common::{rpc::RPCSendableMessage, structs::ChainLink, util::AsH160};
 use ethers_core::types::{Signature, H160};
 use ethers_signers::{LocalWallet, Signer};
 use isahc::{config::Configurable, http::StatusCode, AsyncReadResponseExt, RequestExt};
-use std::{str::FromStr, time::Duration};
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
 
 use rand::thread_rng;
 use serde::{Deserialize, Serialize};
@@ -160,7 +163,7 @@ pub async fn remote_send_async(
         .map(|(_, payload)| payload)
 }
 
-fn get_ephemeral_message(ephemeral_address: &str, expiration: std::time::SystemTime) -> String {
+fn get_ephemeral_message(ephemeral_address: &str, expiration: SystemTime) -> String {
     let datetime: chrono::DateTime<chrono::Utc> = expiration.into();
     let formatted_time = datetime.format("%Y-%m-%dT%H:%M:%S%.3fZ");
     format!(
@@ -168,6 +171,20 @@ fn get_ephemeral_message(ephemeral_address: &str, expiration: std::time::SystemT
     )
 }
 
+// expiration of the ephemeral key in an auth chain, as signed in the ephemeral message
+pub fn auth_chain_expiration(auth: &[ChainLink]) -> Option<SystemTime> {
+    auth.iter()
+        .filter(|link| link.ty == "ECDSA_EPHEMERAL")
+        .flat_map(|link| link.payload.lines())
+        .find_map(|line| line.strip_prefix("Expiration:"))
+        .and_then(|exp| chrono::DateTime::<chrono::Utc>::from_str(exp.trim()).ok())
+        .map(Into::into)
+}
+
+pub fn auth_chain_expired(auth: &[ChainLink], now: SystemTime) -> bool {
+    auth_chain_expiration(auth).is_some_and(|expiration| now > expiration)
+}
+
 pub struct RemoteEphemeralRequest {
     pub code: Option<i32>,
     request_id: String,
@@ -175,10 +192,12 @@ pub struct RemoteEphemeralRequest {
     ephemeral_wallet: LocalWallet,
 }
 
-pub async fn init_remote_ephemeral_request() -> Result<RemoteEphemeralRequest, anyhow::Error> {
+pub async fn init_remote_ephemeral_request(
+    lifetime: Duration,
+) -> Result<RemoteEphemeralRequest, anyhow::Error> {
     let ephemeral_wallet = LocalWallet::new(&mut thread_rng());
     let ephemeral_address = format!("{:#x}", ephemeral_wallet.address());
-    let expiration = std::time::SystemTime::now() + std::time::Duration::from_secs(30 * 24 * 3600);
+    let expiration = SystemTime::now() + lifetime;
     let message = get_ephemeral_message(ephemeral_address.as_str(), expiration);
 
     let request = CreateRequest {
@@ -216,3 +235,40 @@ pub async fn finish_remote_ephemeral_request(
     };
     Ok((signer, ephemeral_wallet, vec![delegate], 1))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chain(expiration: SystemTime) -> Vec<ChainLink> {
+        vec![
+            ChainLink {
+                ty: "SIGNER".to_owned(),
+                payload: "0x1234".to_owned(),
+                signature: String::default(),
+            },
+            ChainLink {
+                ty: "ECDSA_EPHEMERAL".to_owned(),
+                payload: get_ephemeral_message("0x5678", expiration),
+                signature: String::default(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_auth_chain_expiry() {
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 3600);
+
+        assert!(auth_chain_expired(&chain(now - day), now));
+        assert!(!auth_chain_expired(&chain(now + day), now));
+
+        let expiration = auth_chain_expiration(&chain(now + day)).unwrap();
+        assert!(expiration
+            .duration_since(now + day - Duration::from_secs(1))
+            .is_ok());
+
+        // chains without an ephemeral link never expire
+        assert!(!auth_chain_expired(&[], now));
+    }
+}
@@ -46,8 +46,9 @@ impl Wallet {
         ))
     }
 
+    // clears the identity, waiting for any signing in progress to finish
     pub fn disconnect(&mut self) {
-        let mut write = self.0.try_write().unwrap();
+        let mut write = self.0.blocking_write();
         write.inner = None;
         write.root_address = None;
         write.delegates.clear();
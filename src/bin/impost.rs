@@ -8,6 +8,7 @@ use bevy::{
 };
 
 use common::{
+    config::read_config,
     rpc::RpcCall,
     sets::SetupSets,
     structs::{
@@ -40,10 +41,7 @@ fn main() {
         .map(|f: f32| f * 16.0)
         .unwrap_or(f32::MAX);
 
-    let base_config: AppConfig = std::fs::read(&config_file)
-        .ok()
-        .and_then(|f| serde_json::from_slice(&f).ok())
-        .unwrap_or_default();
+    let base_config: AppConfig = read_config(&config_file, &mut Vec::default()).unwrap_or_default();
 
     let final_config = AppConfig {
         server: args
@@ -1,7 +1,7 @@
 #![cfg_attr(not(feature = "console"), windows_subsystem = "windows")]
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-use std::{fs::File, io::Write, sync::OnceLock};
+use std::{fs::File, io::Write, sync::OnceLock, time::Duration};
 
 use analytics::{metrics::MetricsPlugin, segment_system::SegmentConfig};
 use build_time::build_time_utc;
@@ -18,7 +18,7 @@ use bevy::{
     core_pipeline::{
         bloom::BloomSettings,
         prepass::{DepthPrepass, NormalPrepass},
-        tonemapping::{DebandDither, Tonemapping},
+        tonemapping::DebandDither,
         Skybox,
     },
     diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
@@ -26,7 +26,7 @@ use bevy::{
     prelude::*,
     render::{
         render_resource::{TextureViewDescriptor, TextureViewDimension},
-        view::{ColorGrading, ColorGradingGlobal, ColorGradingSection, RenderLayers},
+        view::RenderLayers,
     },
     tasks::{IoTaskPool, Task},
     window::WindowResolution,
@@ -35,11 +35,12 @@ use bevy_console::ConsoleCommand;
 
 use collectibles::CollectiblesPlugin;
 use common::{
+    config::{read_config, ConfigPlugin},
     sets::SetupSets,
     structs::{
         AppConfig, AttachPoints, Cubemap, GraphicsSettings, IVec2Arg, PrimaryCamera,
         PrimaryCameraRes, PrimaryPlayerRes, PrimaryUser, SceneImposterBake, SceneLoadDistance,
-        Version, GROUND_RENDERLAYER,
+        Version, CAMERA_BOOM, GROUND_RENDERLAYER,
     },
     util::{config_file, project_directories, TaskExt, UtilsPlugin},
 };
@@ -47,6 +48,7 @@ use restricted_actions::{lookup_portable, RestrictedActionsPlugin};
 use scene_material::SceneBoundPlugin;
 use scene_runner::{
     automatic_testing::AutomaticTestingPlugin,
+    deterministic_time::DeterministicTime,
     initialize_scene::{PortableScenes, PortableSource, TestingData, PARCEL_SIZE},
     update_world::{mesh_collider::GroundCollider, NoGltf},
     OutOfWorld, SceneRunnerPlugin,
@@ -54,7 +56,12 @@ use scene_runner::{
 
 use av::AudioPlugin;
 use avatar::AvatarPlugin;
-use comms::{preview::PreviewMode, CommsPlugin};
+use comms::{
+    network_sim::{NetworkConditions, NetworkSimulator},
+    packet_log::{init_packet_log, PacketReplay},
+    preview::PreviewMode,
+    CommsPlugin,
+};
 use console::{ConsolePlugin, DoAddConsoleCommand};
 use input_manager::InputManagerPlugin;
 use ipfs::{IpfsAssetServer, IpfsIoPlugin};
@@ -120,21 +127,12 @@ fn main() {
     let mut app = App::new();
 
     let config_file = config_file();
-    let base_config: AppConfig = std::fs::read(&config_file)
-        .ok()
-        .and_then(|f| {
+    let base_config: AppConfig = read_config(&config_file, &mut warnings)
+        .map(|config| {
             infos.push(format!("config file loaded from {:?}", config_file));
-            serde_json::from_slice(&f)
-                .map_err(|e| warnings.push(format!("failed to parse config.json: {e}")))
-                .ok()
+            config
         })
-        .unwrap_or_else(|| {
-            warnings.push(format!(
-                "config file not found at {:?}, generating default",
-                config_file
-            ));
-            Default::default()
-        });
+        .unwrap_or_default();
 
     let final_config = AppConfig {
         server: args
@@ -229,6 +227,37 @@ fn main() {
 
     let is_preview = args.contains("--preview");
 
+    if let Ok(comms_log) = args.value_from_str::<_, String>("--comms_log") {
+        match init_packet_log(&comms_log) {
+            Ok(()) => infos.push(format!("logging comms packets to {comms_log}")),
+            Err(e) => warnings.push(format!("failed to start comms packet log: {e}")),
+        }
+    }
+    if let Ok(comms_replay) = args.value_from_str::<_, String>("--comms_replay") {
+        app.insert_resource(PacketReplay {
+            path: comms_replay.into(),
+        });
+    }
+    if let Ok(comms_sim) = args.value_from_str::<_, String>("--comms_sim") {
+        match comms_sim.parse::<NetworkConditions>() {
+            Ok(conditions) => {
+                app.insert_resource(NetworkSimulator::new(conditions));
+            }
+            Err(e) => warnings.push(format!("invalid --comms_sim conditions: {e}")),
+        }
+    }
+
+    // fixed frame time in seconds for reproducible runs, optionally starting paused
+    let fixed_dt = args.value_from_str::<_, f32>("--fixed_dt").ok();
+    let start_paused = args.contains("--paused");
+    if let Some(dt) = fixed_dt.filter(|dt| *dt > 0.0) {
+        app.insert_resource(DeterministicTime::new(
+            Duration::from_secs_f32(dt),
+            start_paused,
+        ));
+        infos.push(format!("using deterministic time with dt {dt}s"));
+    }
+
     let ui_scene: Option<String> = args.value_from_str("--ui").ok();
     if let Some(source) = ui_scene {
         app.add_systems(Update, spawn_system_ui_scene);
@@ -372,6 +401,7 @@ fn main() {
     app.configure_sets(Startup, SetupSets::Init.before(SetupSets::Main));
 
     app.add_plugins(UtilsPlugin)
+        .add_plugins(ConfigPlugin)
         .add_plugins(InputManagerPlugin)
         .add_plugins(SceneBoundPlugin)
         .add_plugins(SceneRunnerPlugin)
@@ -420,6 +450,7 @@ fn main() {
     app.add_console_command::<SceneDistanceCommand, _>(scene_distance);
     app.add_console_command::<SceneThreadsCommand, _>(scene_threads);
     app.add_console_command::<FpsCommand, _>(set_fps);
+    app.add_console_command::<ResetPostProcessingCommand, _>(reset_post_processing);
 
     info!("Bevy-Explorer version {}", version);
 
@@ -472,7 +503,7 @@ fn setup(
         .insert(attach_points)
         .id();
 
-    let skybox = asset_server.load("images/skybox/skybox_cubemap.png");
+    let skybox = asset_server.load(visuals::DEFAULT_CUBEMAP);
 
     // add a camera
     let camera_id = commands
@@ -482,33 +513,13 @@ fn setup(
                     hdr: true,
                     ..Default::default()
                 },
-                tonemapping: Tonemapping::TonyMcMapface,
+                tonemapping: config.graphics.post_processing.tonemapping.into(),
                 deband_dither: DebandDither::Enabled,
-                color_grading: ColorGrading {
-                    // exposure: -0.5,
-                    // gamma: 1.5,
-                    // pre_saturation: 1.0,
-                    // post_saturation: 1.0,
-                    global: ColorGradingGlobal {
-                        exposure: -0.5,
-                        ..default()
-                    },
-                    shadows: ColorGradingSection {
-                        gamma: 0.75,
-                        ..Default::default()
-                    },
-                    midtones: ColorGradingSection {
-                        gamma: 0.75,
-                        ..Default::default()
-                    },
-                    highlights: ColorGradingSection {
-                        gamma: 0.75,
-                        ..Default::default()
-                    },
-                },
+                color_grading: config.graphics.post_processing.color_grading(),
                 projection: PerspectiveProjection {
                     // projection: OrthographicProjection {
-                    far: 100000.0,
+                    near: config.graphics.camera_near,
+                    far: config.graphics.camera_far,
                     ..Default::default()
                 }
                 .into(),
@@ -519,7 +530,10 @@ fn setup(
                 ..BloomSettings::OLD_SCHOOL
             },
             ShadowFilteringMethod::Gaussian,
-            PrimaryCamera::default(),
+            PrimaryCamera {
+                distance: config.camera.zoom / CAMERA_BOOM,
+                ..Default::default()
+            },
             DepthPrepass,
             NormalPrepass,
             Skybox {
@@ -548,11 +562,24 @@ fn asset_loaded(
         let image = images.get_mut(&cubemap.image_handle).unwrap();
         // NOTE: PNGs do not have any metadata that could indicate they contain a cubemap texture,
         // so they appear as one texture. The following code reconfigures the texture as necessary.
-        image.reinterpret_stacked_2d_as_array(image.height() / image.width());
-        image.texture_view_descriptor = Some(TextureViewDescriptor {
-            dimension: Some(TextureViewDimension::Cube),
-            ..default()
-        });
+        // the image may already have been reconfigured if it was used as the skybox before
+        if image.texture_view_descriptor.is_none() {
+            // a cubemap is 6 square faces stacked vertically
+            if image.height() != image.width() * 6 {
+                warn!(
+                    "skybox image must be 6 square faces stacked vertically, got {}x{}. using the procedural sky",
+                    image.width(),
+                    image.height()
+                );
+                cubemap.image_handle = asset_server.load(visuals::DEFAULT_CUBEMAP);
+                return;
+            }
+            image.reinterpret_stacked_2d_as_array(6);
+            image.texture_view_descriptor = Some(TextureViewDescriptor {
+                dimension: Some(TextureViewDimension::Cube),
+                ..default()
+            });
+        }
 
         cubemap.is_loaded = true;
     }
@@ -614,7 +641,7 @@ fn scene_distance(
     }
 }
 
-// set thread count
+// set thread count, or show the current thread usage
 #[derive(clap::Parser, ConsoleCommand)]
 #[command(name = "/scene_threads")]
 struct SceneThreadsCommand {
@@ -623,9 +650,19 @@ struct SceneThreadsCommand {
 
 fn scene_threads(mut input: ConsoleCommand<SceneThreadsCommand>, mut config: ResMut<AppConfig>) {
     if let Some(Ok(command)) = input.take() {
-        let threads = command.threads.unwrap_or(4);
-        config.scene_threads = threads;
-        input.reply_ok("scene simultaneous thread count set to {threads}");
+        let Some(threads) = command.threads else {
+            input.reply_ok(format!(
+                "{} scene threads, up to {} running simultaneously",
+                dcl::scene_thread_count(),
+                config.scene_threads
+            ));
+            return;
+        };
+        config.scene_threads = threads.max(1);
+        input.reply_ok(format!(
+            "scene simultaneous thread count set to {}",
+            config.scene_threads
+        ));
     }
 }
 
@@ -644,6 +681,21 @@ fn set_fps(mut input: ConsoleCommand<FpsCommand>, mut config: ResMut<AppConfig>)
     }
 }
 
+// reset brightness, contrast, gamma and tonemapping to defaults
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/reset_post_processing")]
+struct ResetPostProcessingCommand;
+
+fn reset_post_processing(
+    mut input: ConsoleCommand<ResetPostProcessingCommand>,
+    mut config: ResMut<AppConfig>,
+) {
+    if let Some(Ok(_)) = input.take() {
+        config.graphics.post_processing = Default::default();
+        input.reply_ok("post-processing settings reset");
+    }
+}
+
 #[derive(Resource)]
 pub struct SystemScene {
     pub source: Option<String>,